@@ -0,0 +1,1537 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::error_handler::ErrorHandler;
+use crate::parser::{BinaryOp, Closure, Expr, Function, Literal, MatchArm, Pattern, Program, Stmt, Type, UnaryOp};
+use crate::iterators::RANGE_METHODS;
+use crate::type_checker::{MethodTarget, TypeChecker, MUTATING_METHODS, ORDERING};
+
+// Méthodes prédéfinies d'Option et de Result qui consomment leur receveur
+const CONSUMING_METHODS: [&str; 5] = ["unwrap", "expect", "unwrap_or", "unwrap_err", "map"];
+
+// Vérification simplifiée des emprunts, après la monomorphisation. Chaque corps de fonction est parcouru
+// dans l'ordre d'évaluation en numérotant les événements (accès aux lieux, emprunts, utilisations des
+// variables) ; un emprunt conservé par une variable reste actif jusqu'à la dernière utilisation de
+// celle-ci, ou pendant toute une boucle s'il la traverse. Sont rejetés : la modification d'une variable
+// non `mut` ou à travers une référence `&`, les accès incompatibles avec un emprunt actif (`&mut` exclusif,
+// `&` partagé) et les références qui survivent à la variable qu'elles désignent.
+// Le même parcours suit les déplacements : un lieu dont le type n'est pas `Copy`, utilisé par valeur
+// (initialisation, affectation, argument, receveur `self`, valeur renvoyée), est déplacé. Il reste
+// inutilisable jusqu'à sa réaffectation, dès qu'il l'est sur l'un des chemins d'exécution (branche d'un
+// `if`, itération précédente d'une boucle).
+
+// Étape du chemin d'un lieu à partir de sa variable
+#[derive(Clone, PartialEq)]
+enum Projection {
+    Field(String),
+    Index,      // Élément d'un tableau ou d'un vecteur : l'indice n'est pas connu, tous se chevauchent
+    Deref(bool), // Déréférencement d'une référence (vrai) ou d'une Box possédée (faux)
+}
+
+// Lieu désigné par une expression : variable, champ, élément, valeur pointée
+#[derive(Clone)]
+struct Place {
+    var: usize,
+    path: Vec<Projection>,
+    through: Option<bool>, // Atteint à travers des références : toutes `&mut` (vrai) ou non
+    display: String,
+}
+
+struct Variable {
+    name: String,
+    mutable: bool,
+    deferred: bool, // `let x;` : la première affectation est une initialisation
+    depth: usize,   // Profondeur du bloc de déclaration
+    declared: usize,
+}
+
+// Emprunt d'un lieu : temporaire (le temps d'un appel ou d'une instruction), ou conservé par une variable
+// jusqu'à ce qu'elle soit réaffectée
+struct Borrow {
+    place: Place,
+    mutable: bool,
+    holder: Option<usize>,
+    start: usize,
+    end: Option<usize>,
+    line: usize,
+}
+
+#[derive(Clone, Copy)]
+enum Access {
+    Read,
+    Write,
+    Borrow(bool),
+    Move,
+}
+
+struct AccessEvent {
+    place: Place,
+    kind: Access,
+    pos: usize,
+    line: usize,
+}
+
+// Déplacement d'un lieu, à la ligne indiquée
+#[derive(Clone)]
+struct Moved {
+    place: Place,
+    pos: usize,
+    line: usize,
+}
+
+// État des déplacements en un point du corps : lieux déplacés sur au moins un des chemins qui y mènent,
+// et accessibilité du point (faux après un `return`)
+#[derive(Clone)]
+struct MoveState {
+    moved: Vec<Moved>,
+    reachable: bool,
+}
+
+// États des déplacements aux `break` et aux `continue` d'une boucle en cours de vérification
+#[derive(Default)]
+struct LoopJumps {
+    breaks: Vec<MoveState>,
+    continues: Vec<MoveState>,
+}
+
+// Emprunts transportés par une valeur : un emprunt temporaire, ou ceux conservés par une variable
+#[derive(Clone, Copy)]
+enum Flow {
+    Temp(usize),
+    Held(usize),
+}
+
+// Résultat de l'analyse d'une expression : un lieu, ou une valeur calculée et ses emprunts
+enum Operand {
+    Place(Place),
+    Value(Vec<Flow>),
+}
+
+// Corps en cours de vérification (fonction ou fermeture) : ses variables locales commencent à `first_local`
+struct Frame {
+    return_type: Type,
+    first_local: usize,
+    mutated: HashSet<usize>, // Variables englobantes modifiées par le corps d'une fermeture
+}
+
+struct Scope {
+    variables: Vec<usize>,
+    saved_types: HashMap<String, Type>,
+}
+
+pub struct BorrowChecker<'a> {
+    error_handler: &'a ErrorHandler,
+    types: TypeChecker<'a>,
+    variables: Vec<Variable>,
+    scopes: Vec<Scope>,
+    mentions: Vec<Vec<usize>>, // Positions des utilisations de chaque variable
+    kills: Vec<Vec<usize>>,    // Positions des réaffectations de chaque variable
+    borrows: Vec<Borrow>,
+    accesses: Vec<AccessEvent>,
+    loops: Vec<(usize, usize)>,
+    jumps: Vec<LoopJumps>,
+    scope_ends: Vec<(usize, Vec<usize>, usize)>, // (position, variables du bloc, profondeur)
+    temps: Vec<usize>,
+    frames: Vec<Frame>,
+    state: MoveState,
+    errors: Vec<(usize, usize, String)>, // (position, ligne, message)
+    pos: usize,
+    line: usize,
+}
+
+impl<'a> BorrowChecker<'a> {
+    pub fn new(error_handler: &'a ErrorHandler) -> Self {
+        BorrowChecker {
+            error_handler,
+            types: TypeChecker::new(error_handler),
+            variables: Vec::new(),
+            scopes: Vec::new(),
+            mentions: Vec::new(),
+            kills: Vec::new(),
+            borrows: Vec::new(),
+            accesses: Vec::new(),
+            loops: Vec::new(),
+            jumps: Vec::new(),
+            scope_ends: Vec::new(),
+            temps: Vec::new(),
+            frames: Vec::new(),
+            state: MoveState { moved: Vec::new(), reachable: true },
+            errors: Vec::new(),
+            pos: 0,
+            line: 0,
+        }
+    }
+
+    pub fn check(mut self, program: &Program) -> Result<(), usize> {
+        self.types.collect_declarations(program)?;
+
+        for function in &program.functions {
+            self.check_function(function, None)?;
+        }
+
+        // Méthodes des blocs impl, y compris les méthodes par défaut des traits
+        for impl_block in &program.impls {
+            let concrete = self.types.type_from_name(&impl_block.type_name);
+            for method in &impl_block.methods {
+                self.check_function(method, concrete.clone())?;
+            }
+            if let Some(trait_name) = &impl_block.trait_name {
+                let trait_def = self.types.traits[trait_name].clone();
+                for declared in trait_def.methods.iter().filter(|m| m.has_default) {
+                    if !impl_block.methods.iter().any(|m| m.name == declared.function.name) {
+                        self.check_function(&declared.function, concrete.clone())?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn check_function(&mut self, function: &Function, self_type: Option<Type>) -> Result<(), usize> {
+        self.variables.clear();
+        self.mentions.clear();
+        self.kills.clear();
+        self.borrows.clear();
+        self.accesses.clear();
+        self.loops.clear();
+        self.jumps.clear();
+        self.scope_ends.clear();
+        self.temps.clear();
+        self.state = MoveState { moved: Vec::new(), reachable: true };
+        self.errors.clear();
+        self.types.self_type = self_type;
+        self.types.variables.clear();
+        self.types.current_line = function.line;
+        self.line = function.line;
+
+        let return_type = self.types.resolve_type(&function.return_type.clone().unwrap_or(Type::Void));
+        self.frames.push(Frame { return_type, first_local: 0, mutated: HashSet::new() });
+        self.open_scope();
+        for (name, typ) in &function.params {
+            let typ = self.types.resolve_type(typ);
+            self.types.variables.insert(name.clone(), typ);
+            self.declare(name, function.mutable_params.contains(name), false);
+        }
+        for stmt in &function.body {
+            self.stmt(stmt)?;
+        }
+        self.close_scope();
+        self.frames.pop();
+
+        self.report()
+    }
+
+    // Signale la première erreur dans l'ordre d'évaluation, une fois le corps entièrement parcouru
+    fn report(&mut self) -> Result<(), usize> {
+        let mut errors = std::mem::take(&mut self.errors);
+
+        for access in &self.accesses {
+            let conflict = self.borrows.iter().find(|borrow| {
+                let incompatible = match access.kind {
+                    Access::Read | Access::Borrow(false) => borrow.mutable,
+                    Access::Write | Access::Borrow(true) | Access::Move => true,
+                };
+                incompatible && Self::overlap(&borrow.place, &access.place) && self.live(borrow, access.pos)
+            });
+            if let Some(borrow) = conflict {
+                let place = &access.place.display;
+                let message = match (access.kind, borrow.mutable) {
+                    (Access::Borrow(true), true) => format!("impossible d'emprunter `{}` comme mutable plus d'une fois à la fois", place),
+                    (Access::Borrow(true), false) => format!("impossible d'emprunter `{}` comme mutable, car il est aussi emprunté comme immuable", place),
+                    (Access::Borrow(false), _) => format!("impossible d'emprunter `{}` comme immuable, car il est aussi emprunté comme mutable", place),
+                    (Access::Write, _) => format!("impossible d'affecter à `{}`, car il est emprunté", place),
+                    (Access::Read, _) => format!("impossible d'utiliser `{}`, car il est emprunté comme mutable", place),
+                    (Access::Move, _) => format!("impossible de déplacer `{}`, car il est emprunté", place),
+                };
+                errors.push((access.pos, access.line, format!("{} (emprunt à la ligne {})", message, borrow.line)));
+            }
+        }
+
+        // Une référence conservée par une variable d'un bloc englobant ne doit plus servir après la fin
+        // du bloc de la variable empruntée
+        for (end, variables, depth) in &self.scope_ends {
+            for borrow in &self.borrows {
+                let Some(holder) = borrow.holder else { continue };
+                if variables.contains(&borrow.place.var) && borrow.place.through.is_none()
+                    && self.variables[holder].depth < *depth && self.live(borrow, *end) {
+                    errors.push((*end, borrow.line, format!("`{}` ne vit pas assez longtemps : il est encore emprunté par `{}` après la fin de son bloc",
+                        self.variables[borrow.place.var].name, self.variables[holder].name)));
+                }
+            }
+        }
+
+        match errors.into_iter().min_by_key(|(pos, _, _)| *pos) {
+            Some((_, line, message)) => {
+                self.error_handler.report_error(line, &message);
+                Err(line)
+            },
+            None => Ok(()),
+        }
+    }
+
+    // Deux lieux se chevauchent si l'un contient l'autre
+    fn overlap(a: &Place, b: &Place) -> bool {
+        a.var == b.var && a.path.iter().zip(&b.path).all(|pair| match pair {
+            (Projection::Field(x), Projection::Field(y)) => x == y,
+            _ => true,
+        })
+    }
+
+    // Un emprunt temporaire est actif entre sa création et sa fin ; un emprunt conservé par une variable
+    // l'est tant que celle-ci est encore utilisée ensuite sans avoir été réaffectée entre-temps, y compris
+    // à l'itération suivante d'une boucle
+    fn live(&self, borrow: &Borrow, at: usize) -> bool {
+        let holder = match borrow.holder {
+            None => return borrow.start < at && borrow.end.is_none_or(|end| at < end),
+            Some(holder) => holder,
+        };
+        let mentions = &self.mentions[holder];
+        // Réaffectation de la variable dans l'intervalle ]from, to[
+        let reassigned = |from: usize, to: usize| self.kills[holder].iter().any(|&kill| from < kill && kill < to);
+        let used_after = |from: usize| mentions.iter().any(|&used| used > from && !reassigned(from, used));
+
+        if borrow.start < at && !reassigned(borrow.start, at) && used_after(at) {
+            return true;
+        }
+        self.loops.iter().any(|&(start, end)| {
+            if at < start || at > end {
+                return false;
+            }
+            if borrow.start < at {
+                // Retour au début de la boucle, puis utilisation avant d'atteindre `at`
+                !reassigned(borrow.start, end) && mentions.iter().any(|&used| used >= start && used <= at && !reassigned(start, used))
+            } else {
+                // Emprunt créé plus loin dans la boucle, conservé par une variable qui lui survit
+                borrow.start <= end && self.variables[holder].declared < start
+                    && !reassigned(borrow.start, end) && !reassigned(start, at) && used_after(at)
+            }
+        })
+    }
+
+    fn tick(&mut self) -> usize {
+        self.pos += 1;
+        self.pos
+    }
+
+    fn open_scope(&mut self) {
+        self.scopes.push(Scope { variables: Vec::new(), saved_types: self.types.variables.clone() });
+    }
+
+    fn close_scope(&mut self) {
+        let depth = self.scopes.len();
+        let scope = self.scopes.pop().expect("portée ouverte");
+        let end = self.tick();
+        self.state.moved.retain(|moved| !scope.variables.contains(&moved.place.var));
+        self.scope_ends.push((end, scope.variables, depth));
+        self.types.variables = scope.saved_types;
+    }
+
+    fn declare(&mut self, name: &str, mutable: bool, deferred: bool) -> usize {
+        let declared = self.tick();
+        self.variables.push(Variable { name: name.to_string(), mutable, deferred, depth: self.scopes.len(), declared });
+        self.mentions.push(Vec::new());
+        self.kills.push(Vec::new());
+        let id = self.variables.len() - 1;
+        self.scopes.last_mut().expect("portée ouverte").variables.push(id);
+        id
+    }
+
+    fn lookup(&self, name: &str) -> Option<usize> {
+        self.scopes.iter().rev()
+            .flat_map(|scope| scope.variables.iter().rev())
+            .find(|&&id| self.variables[id].name == name)
+            .copied()
+    }
+
+    fn mention(&mut self, var: usize) {
+        let pos = self.tick();
+        self.mentions[var].push(pos);
+    }
+
+    fn error(&mut self, message: String) {
+        let pos = self.tick();
+        self.errors.push((pos, self.line, message));
+    }
+
+    // Types dont les valeurs peuvent contenir des références (et donc conserver des emprunts)
+    fn carries_ref(&self, typ: &Type, visited: &mut Vec<String>) -> bool {
+        match typ {
+            Type::Ref(_, _) | Type::Closure(_, _) | Type::Iterator(_) | Type::Guard(_, _) | Type::Entry(_, _) => true,
+            Type::Vec(inner) | Type::Option(inner) | Type::Box(inner) | Type::Array(inner, _) | Type::Range(inner, _)
+            | Type::Rc(inner) | Type::RefCell(inner) | Type::HashSet(inner) => {
+                self.carries_ref(inner, visited)
+            },
+            Type::Result(ok, err) | Type::HashMap(ok, err) => self.carries_ref(ok, visited) || self.carries_ref(err, visited),
+            Type::Tuple(elements) => elements.iter().any(|element| self.carries_ref(element, visited)),
+            Type::Named(name) if !visited.contains(name) => {
+                visited.push(name.clone());
+                let fields: Vec<Type> = match (self.types.structs.get(name), self.types.enums.get(name)) {
+                    (Some(def), _) => def.fields.iter().map(|(_, field)| field.clone()).collect(),
+                    (None, Some(def)) => def.variants.iter().flat_map(|(_, fields)| fields.clone()).collect(),
+                    (None, None) => Vec::new(),
+                };
+                fields.iter().any(|field| self.carries_ref(field, visited))
+            },
+            _ => false,
+        }
+    }
+
+    fn carries(&self, typ: &Type) -> bool {
+        self.carries_ref(typ, &mut Vec::new())
+    }
+
+    // Termine les emprunts temporaires transportés par une valeur qui ne les conserve pas
+    fn release(&mut self, flows: &[Flow]) {
+        let pos = self.tick();
+        for flow in flows {
+            if let Flow::Temp(id) = flow {
+                self.borrows[*id].end.get_or_insert(pos);
+            }
+        }
+    }
+
+    // Termine les emprunts temporaires créés depuis `mark` (fin d'instruction ou de condition)
+    fn release_temps(&mut self, mark: usize) {
+        let pos = self.tick();
+        for id in self.temps.drain(mark..) {
+            self.borrows[id].end.get_or_insert(pos);
+        }
+    }
+
+    // Valeur d'un appel : elle conserve les emprunts de ses arguments si son type contient des références
+    fn carried(&mut self, expr: &Expr, flows: Vec<Flow>) -> Result<Vec<Flow>, usize> {
+        let typ = self.types.expr_type(expr)?;
+        if self.carries(&typ) {
+            Ok(flows)
+        } else {
+            self.release(&flows);
+            Ok(Vec::new())
+        }
+    }
+
+    // La variable `holder` conserve désormais les emprunts transportés par sa nouvelle valeur
+    fn hold(&mut self, flows: &[Flow], holder: usize) {
+        let start = self.tick();
+        let mut held = Vec::new();
+        for flow in flows {
+            match *flow {
+                Flow::Temp(id) => held.push(id),
+                Flow::Held(var) if var != holder => {
+                    held.extend((0..self.borrows.len()).filter(|&id| self.borrows[id].holder == Some(var) && self.borrows[id].end.is_none()));
+                },
+                Flow::Held(_) => {},
+            }
+        }
+        for id in held {
+            let borrow = &self.borrows[id];
+            let copy = Borrow { place: borrow.place.clone(), mutable: borrow.mutable, holder: Some(holder), start, end: None, line: borrow.line };
+            self.borrows.push(copy);
+        }
+    }
+
+    // Réaffectation d'une variable : les emprunts qu'elle conservait prennent fin
+    fn kill(&mut self, var: usize) {
+        let pos = self.tick();
+        self.kills[var].push(pos);
+        for borrow in self.borrows.iter_mut().filter(|borrow| borrow.holder == Some(var)) {
+            borrow.end.get_or_insert(pos);
+        }
+    }
+
+    fn access(&mut self, place: &Place, kind: Access) {
+        let pos = self.tick();
+        match kind {
+            Access::Write => self.reinitialize(place),
+            _ => self.check_moved(place, kind),
+        }
+        if let Access::Move = kind {
+            self.state.moved.push(Moved { place: place.clone(), pos, line: self.line });
+        }
+        self.accesses.push(AccessEvent { place: place.clone(), kind, pos, line: self.line });
+    }
+
+    // Types dont les valeurs sont copiées au lieu d'être déplacées ; les références `&mut` (réempruntées),
+    // les fermetures et les itérateurs sont traités comme tels
+    fn is_copy(&self, typ: &Type) -> bool {
+        match typ {
+            Type::F32 | Type::F64 | Type::Void | Type::Ref(_, _) | Type::Closure(_, _) | Type::Iterator(_) => true,
+            Type::Tuple(elements) => elements.iter().all(|element| self.is_copy(element)),
+            Type::Array(element, _) | Type::Option(element) => self.is_copy(element),
+            Type::Result(ok, err) => self.is_copy(ok) && self.is_copy(err),
+            _ => self.types.satisfies(typ, "Copy"),
+        }
+    }
+
+    // Utilisation par valeur d'un lieu qui n'est pas Copy : seule une valeur possédée peut être déplacée
+    fn move_place(&mut self, place: &Place) {
+        let message = if place.through.is_some() {
+            format!("impossible de déplacer `{}`, qui est derrière une référence", place.display)
+        } else if place.path.contains(&Projection::Index) {
+            format!("impossible de déplacer l'élément indexé `{}` : empruntez-le ou clonez-le", place.display)
+        } else {
+            self.access(place, Access::Move);
+            return;
+        };
+        self.access(place, Access::Read);
+        self.error(message);
+    }
+
+    // Un lieu déplacé, même en partie, n'est plus utilisable
+    fn check_moved(&mut self, place: &Place, kind: Access) {
+        if let Some(moved) = self.state.moved.iter().find(|moved| Self::overlap(&moved.place, place)) {
+            let message = Self::moved_message(place, kind, moved, false);
+            self.error(message);
+        }
+    }
+
+    fn moved_message(place: &Place, kind: Access, moved: &Moved, previous_iteration: bool) -> String {
+        let usage = match kind {
+            Access::Borrow(_) => "emprunt",
+            _ => "utilisation",
+        };
+        let iteration = if previous_iteration { ", lors d'une itération précédente de la boucle" } else { "" };
+        // Déplacer la valeur d'une Box déplace la Box entière
+        let fields = |place: &Place| place.path.iter().filter(|projection| matches!(projection, Projection::Field(_))).count();
+        if fields(&moved.place) > fields(place) {
+            format!("{} de la valeur partiellement déplacée `{}` (`{}` déplacé à la ligne {}{})",
+                usage, place.display, moved.place.display, moved.line, iteration)
+        } else {
+            format!("{} de la valeur déplacée `{}` (déplacée à la ligne {}{})", usage, moved.place.display, moved.line, iteration)
+        }
+    }
+
+    // Une affectation réinitialise le lieu et ses parties déplacées ; affecter à une partie d'une valeur
+    // déplacée est refusé
+    fn reinitialize(&mut self, place: &Place) {
+        let inside = self.state.moved.iter().find(|moved| moved.place.path.len() < place.path.len() && Self::overlap(&moved.place, place));
+        if let Some(moved) = inside {
+            let message = format!("impossible d'affecter à `{}`, qui fait partie de la valeur déplacée `{}` (déplacée à la ligne {})",
+                place.display, moved.place.display, moved.line);
+            self.error(message);
+        }
+        self.state.moved.retain(|moved| place.path.len() > moved.place.path.len() || !Self::overlap(place, &moved.place));
+    }
+
+    // Jonction de deux chemins d'exécution : un lieu est déplacé s'il l'est sur l'un des chemins atteignables
+    fn merge(&mut self, other: MoveState) {
+        if !other.reachable {
+            return;
+        }
+        if !self.state.reachable {
+            self.state = other;
+            return;
+        }
+        for moved in other.moved {
+            if !self.state.moved.iter().any(|known| known.pos == moved.pos) {
+                self.state.moved.push(moved);
+            }
+        }
+    }
+
+    // Un lieu déplacé dans le corps d'une boucle et non réaffecté avant sa fin l'est encore à l'itération
+    // suivante : ses utilisations depuis le début de la boucle, avant réaffectation, sont refusées
+    fn check_loop_moves(&mut self, start: usize) {
+        if !self.state.reachable {
+            return;
+        }
+        for moved in self.state.moved.iter().filter(|moved| moved.pos > start) {
+            let reinitialized = |at: usize| self.accesses.iter().any(|write| {
+                matches!(write.kind, Access::Write) && start < write.pos && write.pos < at
+                    && write.place.path.len() <= moved.place.path.len() && Self::overlap(&write.place, &moved.place)
+            });
+            let used = self.accesses.iter().find(|access| {
+                !matches!(access.kind, Access::Write) && start < access.pos && access.pos <= moved.pos
+                    && Self::overlap(&access.place, &moved.place) && !reinitialized(access.pos)
+            });
+            if let Some(access) = used {
+                self.errors.push((access.pos, access.line, Self::moved_message(&access.place, access.kind, moved, true)));
+            }
+        }
+    }
+
+    fn new_temp(&mut self, place: Place, mutable: bool) -> usize {
+        self.borrows.push(Borrow { place, mutable, holder: None, start: self.pos, end: None, line: self.line });
+        let id = self.borrows.len() - 1;
+        self.temps.push(id);
+        id
+    }
+
+    // Emprunt d'un lieu (`&x`, `&mut x`, receveur `&self`/`&mut self`) ; à travers une référence, la
+    // valeur transporte aussi les emprunts de la variable déréférencée
+    fn borrow_place(&mut self, place: Place, mutable: bool) -> Vec<Flow> {
+        if mutable {
+            self.check_mutable(&place, Access::Borrow(true));
+        }
+        self.access(&place, Access::Borrow(mutable));
+        let var = place.var;
+        let through = place.through.is_some();
+        let id = self.new_temp(place, mutable);
+        let mut flows = vec![Flow::Temp(id)];
+        if through {
+            flows.push(Flow::Held(var));
+        }
+        flows
+    }
+
+    // Une modification exige une variable `mut`, ou un chemin composé uniquement de références `&mut`
+    fn check_mutable(&mut self, place: &Place, kind: Access) {
+        let writes = matches!(kind, Access::Write);
+        let variable = &self.variables[place.var];
+        let message = match place.through {
+            Some(true) => None,
+            Some(false) if writes => Some(format!("impossible d'affecter à `{}`, qui est derrière une référence `&`", place.display)),
+            Some(false) => Some(format!("impossible d'emprunter `{}` comme mutable, car il est derrière une référence `&`", place.display)),
+            None if variable.mutable || (writes && variable.deferred && place.path.is_empty()) => None,
+            None if writes && place.path.is_empty() => Some(format!("impossible d'affecter deux fois à la variable immuable `{}`", place.display)),
+            None if writes => Some(format!("impossible d'affecter à `{}` : la variable `{}` n'est pas déclarée `mut`", place.display, variable.name)),
+            None => Some(format!("impossible d'emprunter `{}` comme mutable : la variable `{}` n'est pas déclarée `mut`", place.display, variable.name)),
+        };
+        if place.through.is_none() {
+            for frame in self.frames.iter_mut().filter(|frame| place.var < frame.first_local) {
+                frame.mutated.insert(place.var);
+            }
+        }
+        if let Some(message) = message {
+            self.error(message);
+        }
+    }
+
+    fn open_frame(&mut self, return_type: Type) {
+        let first_local = self.variables.len();
+        self.frames.push(Frame { return_type, first_local, mutated: HashSet::new() });
+    }
+
+    // Une valeur renvoyée ne peut pas emprunter une variable locale du corps qui se termine
+    fn check_return(&mut self, flows: &[Flow]) {
+        let frame = self.frames.last().expect("corps en cours");
+        if !self.carries(&frame.return_type) {
+            return;
+        }
+        let first_local = frame.first_local;
+        let returned: Vec<usize> = (0..self.borrows.len()).filter(|&id| {
+            let borrow = &self.borrows[id];
+            flows.iter().any(|flow| match *flow {
+                Flow::Temp(temp) => temp == id,
+                Flow::Held(var) => borrow.holder == Some(var) && borrow.end.is_none(),
+            })
+        }).collect();
+        for id in returned {
+            let place = &self.borrows[id].place;
+            if place.through.is_none() && place.var >= first_local {
+                let message = format!("impossible de renvoyer une référence à la variable locale `{}`", self.variables[place.var].name);
+                self.error(message);
+                return;
+            }
+        }
+    }
+
+    // Déclare les variables d'un motif ; celles dont le type contient des références conservent les
+    // emprunts de la valeur filtrée
+    fn bind(&mut self, pattern: &Pattern, typ: &Type, flows: &[Flow], deferred: bool) -> Result<(), usize> {
+        self.types.bind_pattern(pattern, typ)?;
+        for (name, mutable) in pattern.bindings() {
+            let var = self.declare(&name, mutable, deferred);
+            let var_type = self.types.variables[&name].clone();
+            if self.carries(&var_type) {
+                self.hold(flows, var);
+            }
+        }
+        Ok(())
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) -> Result<(), usize> {
+        let mark = self.temps.len();
+
+        match stmt {
+            Stmt::Let(pattern, initializer, var_type, line) => {
+                self.set_line(*line);
+                let typ = match (var_type, initializer) {
+                    (Some(var_type), _) => self.types.resolve_type(var_type),
+                    (None, Some(initializer)) => self.types.expr_type(initializer)?,
+                    (None, None) => Type::I32,
+                };
+                match initializer {
+                    Some(initializer) => {
+                        let (flows, place) = self.scrutinee(initializer)?;
+                        self.bind(pattern, &typ, &flows, false)?;
+                        if let Some(place) = place {
+                            self.destructure(&place, pattern, &typ);
+                        }
+                    },
+                    None => self.bind(pattern, &typ, &[], true)?,
+                }
+            },
+            Stmt::Expression(expr, line) => {
+                self.set_line(*line);
+                self.expr(expr)?;
+            },
+            Stmt::Return(expr, line) => {
+                self.set_line(*line);
+                if let Some(expr) = expr {
+                    let flows = self.expr(expr)?;
+                    self.check_return(&flows);
+                }
+                self.state.reachable = false;
+            },
+            Stmt::Break(line) | Stmt::Continue(line) => {
+                self.set_line(*line);
+                let jumps = self.jumps.last_mut().expect("boucle en cours");
+                let state = self.state.clone();
+                if let Stmt::Break(_) = stmt {
+                    jumps.breaks.push(state);
+                } else {
+                    jumps.continues.push(state);
+                }
+                self.state.reachable = false;
+            },
+            Stmt::Print(format, _, line) => {
+                self.set_line(*line);
+                for arg in &format.args {
+                    self.inspect(arg)?;
+                }
+            },
+            Stmt::Block(stmts) => {
+                self.open_scope();
+                for stmt in stmts {
+                    self.stmt(stmt)?;
+                }
+                self.close_scope();
+            },
+            Stmt::If(condition, then_branch, else_branch, line) => {
+                self.set_line(*line);
+                self.condition(condition)?;
+                let skipped = self.state.clone();
+                self.stmt(then_branch)?;
+                self.otherwise(skipped, else_branch.as_deref())?;
+            },
+            Stmt::Match(value, arms, line) => {
+                self.set_line(*line);
+                let value_type = self.types.expr_type(value)?;
+                let (flows, place) = self.scrutinee(value)?;
+                let start = self.state.clone();
+                let mut ends = Vec::new();
+                for arm in arms {
+                    self.state = start.clone();
+                    self.arm(arm, &value_type, &flows, place.as_ref(), mark)?;
+                    ends.push(std::mem::replace(&mut self.state, MoveState { moved: Vec::new(), reachable: false }));
+                }
+                for end in ends {
+                    self.merge(end);
+                }
+            },
+            Stmt::While(condition, body, line) => {
+                self.set_line(*line);
+                let start = self.tick();
+                self.jumps.push(LoopJumps::default());
+                self.condition(condition)?;
+                let exit = self.state.clone();
+                self.stmt(body)?;
+                self.end_loop(start, exit);
+            },
+            Stmt::For(pattern, iterable, body, line) => {
+                // L'itérable reste emprunté pendant toute la boucle
+                self.set_line(*line);
+                let item_type = self.types.iteration_type(iterable)?;
+                let flows = self.expr(iterable)?;
+                let start = self.tick();
+                self.jumps.push(LoopJumps::default());
+                let exit = self.state.clone();
+                self.open_scope();
+                self.bind(pattern, &item_type, &flows, false)?;
+                self.stmt(body)?;
+                self.close_scope();
+                self.end_loop(start, exit);
+            },
+        }
+
+        self.release_temps(mark);
+        Ok(())
+    }
+
+    fn set_line(&mut self, line: usize) {
+        self.line = line;
+        self.types.current_line = line;
+    }
+
+    // Les temporaires d'une condition sont libérés avant la branche exécutée
+    fn condition(&mut self, condition: &Expr) -> Result<(), usize> {
+        let mark = self.temps.len();
+        self.expr(condition)?;
+        self.release_temps(mark);
+        Ok(())
+    }
+
+    // Branche `else` éventuelle, partant de l'état où la branche principale n'est pas prise, puis jonction
+    fn otherwise(&mut self, skipped: MoveState, else_branch: Option<&Stmt>) -> Result<(), usize> {
+        match else_branch {
+            Some(else_branch) => {
+                let taken = std::mem::replace(&mut self.state, skipped);
+                self.stmt(else_branch)?;
+                self.merge(taken);
+            },
+            None => self.merge(skipped),
+        }
+        Ok(())
+    }
+
+    // Fin d'une boucle : on en sort depuis l'état de sa condition ou d'un `break`, après un nombre
+    // quelconque d'itérations ; un `continue` revient au début comme la fin du corps
+    fn end_loop(&mut self, start: usize, exit: MoveState) {
+        let jumps = self.jumps.pop().expect("boucle en cours");
+        for state in jumps.continues {
+            self.merge(state);
+        }
+        self.check_loop_moves(start);
+        let end = self.tick();
+        self.loops.push((start, end));
+        self.merge(exit);
+        for state in jumps.breaks {
+            self.merge(state);
+        }
+    }
+
+    // Bras d'un match : les variables du motif conservent les emprunts de la valeur filtrée, le corps
+    // ne voit plus ses temporaires. Un lieu filtré n'est déplacé que par les bras dont le motif
+    // correspond, une fois la garde évaluée ; `_` ne le lit même pas
+    fn arm(&mut self, arm: &MatchArm, value_type: &Type, flows: &[Flow], place: Option<&Place>, mark: usize) -> Result<(), usize> {
+        self.open_scope();
+        self.bind(&arm.pattern, value_type, flows, false)?;
+        if let Some(guard) = &arm.guard {
+            self.condition(guard)?;
+        }
+        if let (Some(place), false) = (place, arm.pattern == Pattern::Wildcard) {
+            self.destructure(place, &arm.pattern, value_type);
+        }
+        self.release_temps(mark);
+        self.stmt(&arm.body)?;
+        self.close_scope();
+        Ok(())
+    }
+
+    fn exprs(&mut self, exprs: &[Expr]) -> Result<Vec<Flow>, usize> {
+        let mut flows = Vec::new();
+        for expr in exprs {
+            flows.extend(self.expr(expr)?);
+        }
+        Ok(flows)
+    }
+
+    // Valeur filtrée par un motif : un lieu n'est lu ou déplacé qu'une fois le motif lié (`destructure`)
+    fn scrutinee(&mut self, value: &Expr) -> Result<(Vec<Flow>, Option<Place>), usize> {
+        Ok(match self.place(value)? {
+            Operand::Place(place) => (vec![Flow::Held(place.var)], Some(place)),
+            Operand::Value(flows) => (flows, None),
+        })
+    }
+
+    // Seules les parties d'un lieu que le motif lie par valeur et qui ne sont pas Copy sont déplacées ;
+    // sinon le lieu est seulement lu
+    fn destructure(&mut self, place: &Place, pattern: &Pattern, typ: &Type) {
+        let mut parts = Vec::new();
+        self.moved_parts(place, pattern, typ, &mut parts);
+        if parts.is_empty() {
+            self.access(place, Access::Read);
+        }
+        for part in parts {
+            self.move_place(&part);
+        }
+    }
+
+    fn moved_parts(&self, place: &Place, pattern: &Pattern, typ: &Type, parts: &mut Vec<Place>) {
+        match (pattern, typ) {
+            (Pattern::Tuple(patterns), Type::Tuple(elements)) => {
+                for (index, (pattern, element)) in patterns.iter().zip(elements).enumerate() {
+                    let mut part = place.clone();
+                    part.path.push(Projection::Field(index.to_string()));
+                    part.display = format!("{}.{}", place.display, index);
+                    self.moved_parts(&part, pattern, element, parts);
+                }
+            },
+            (Pattern::Binding(_, _), _) if !self.is_copy(typ) => parts.push(place.clone()),
+            (Pattern::Variant(_, Some(payload)), _) if payload.bindings().iter().any(|(name, _)| !self.is_copy(&self.types.variables[name])) => {
+                parts.push(place.clone());
+            },
+            _ => {},
+        }
+    }
+
+    // Valeur empruntée le temps de son utilisation : argument de println!/format!/write!, opérande d'une
+    // comparaison
+    fn inspect(&mut self, expr: &Expr) -> Result<Vec<Flow>, usize> {
+        match expr {
+            Expr::Variable(_) | Expr::FieldAccess(_, _) | Expr::TupleIndex(_, _) | Expr::Index(_, _) | Expr::Unary(UnaryOp::Deref, _) => {
+                self.read(expr, false)
+            },
+            _ => self.expr(expr),
+        }
+    }
+
+    // Utilisation d'un lieu : par valeur, il est déplacé si son type n'est pas Copy
+    fn read(&mut self, expr: &Expr, by_value: bool) -> Result<Vec<Flow>, usize> {
+        match self.place(expr)? {
+            Operand::Place(place) => {
+                let typ = self.types.expr_type(expr)?;
+                if !by_value {
+                    self.access(&place, Access::Borrow(false));
+                } else if self.is_copy(&typ) {
+                    self.access(&place, Access::Read);
+                } else {
+                    self.move_place(&place);
+                }
+                Ok(vec![Flow::Held(place.var)])
+            },
+            Operand::Value(flows) => Ok(flows),
+        }
+    }
+
+    fn inspect_all(&mut self, exprs: &[Expr]) -> Result<Vec<Flow>, usize> {
+        let mut flows = Vec::new();
+        for expr in exprs {
+            flows.extend(self.inspect(expr)?);
+        }
+        Ok(flows)
+    }
+
+    fn expr(&mut self, expr: &Expr) -> Result<Vec<Flow>, usize> {
+        match expr {
+            Expr::Variable(_) | Expr::FieldAccess(_, _) | Expr::TupleIndex(_, _) | Expr::Index(_, _) | Expr::Unary(UnaryOp::Deref, _) => {
+                self.read(expr, true)
+            },
+            Expr::Unary(op @ (UnaryOp::Ref | UnaryOp::RefMut), operand) => match self.place(operand)? {
+                Operand::Place(place) => Ok(self.borrow_place(place, matches!(op, UnaryOp::RefMut))),
+                Operand::Value(flows) => Ok(flows),
+            },
+            Expr::Unary(UnaryOp::Negate, operand) => {
+                self.expr(operand)?;
+                Ok(Vec::new())
+            },
+            // Les opérateurs arithmétiques prennent leurs opérandes par valeur, les comparaisons par référence
+            Expr::Binary(left, op, right) => {
+                let mut flows = Vec::new();
+                for operand in [left, right] {
+                    flows.extend(match op {
+                        BinaryOp::Add | BinaryOp::Subtract | BinaryOp::Multiply | BinaryOp::Divide | BinaryOp::Modulo => self.expr(operand)?,
+                        _ => self.inspect(operand)?,
+                    });
+                }
+                self.release(&flows);
+                Ok(Vec::new())
+            },
+            Expr::Literal(Literal::Array(elements)) | Expr::VecNew(elements) | Expr::Tuple(elements) => self.exprs(elements),
+            Expr::Literal(Literal::ArrayRepeat(element, _)) => self.expr(element),
+            Expr::VecRepeat(element, count) => {
+                let flows = self.expr(element)?;
+                let count_flows = self.expr(count)?;
+                self.release(&count_flows);
+                Ok(flows)
+            },
+            Expr::Literal(_) => Ok(Vec::new()),
+            Expr::StructLiteral(_, fields) => {
+                let mut flows = Vec::new();
+                for (_, value) in fields {
+                    flows.extend(self.expr(value)?);
+                }
+                Ok(flows)
+            },
+            Expr::Range(start, end, _) => {
+                let mut flows = Vec::new();
+                for bound in [start, end].into_iter().flatten() {
+                    flows.extend(self.expr(bound)?);
+                }
+                Ok(flows)
+            },
+            // Appel d'une fonction nommée, ou d'une variable locale appelable
+            Expr::FunctionCall(name, _, args) => {
+                let params = match self.lookup(name) {
+                    Some(_) => self.types.callable_signature(&self.types.expr_type(&Expr::Variable(name.clone()))?)?.0,
+                    None => self.function_params(name)?,
+                };
+                let mut flows = self.arguments(args, &params)?;
+                if self.lookup(name).is_some() {
+                    flows.extend(self.expr(&Expr::Variable(name.clone()))?);
+                }
+                self.carried(expr, flows)
+            },
+            Expr::Call(callee, args) => {
+                let params = self.types.callable_signature(&self.types.expr_type(callee)?)?.0;
+                let mut flows = self.arguments(args, &params)?;
+                flows.extend(self.expr(callee)?);
+                self.carried(expr, flows)
+            },
+            Expr::MethodCall(receiver, method_name, _, args) => {
+                let mut flows = self.exprs(args)?;
+                let receiver_type = self.types.expr_type(receiver)?;
+                let by_ref = self.receiver_mode(&receiver_type, method_name)?;
+                let target = self.receiver(receiver, &receiver_type, by_ref)?;
+                // `v.push(&x)` : le receveur modifié conserve les emprunts des arguments
+                if by_ref == Some(true) && self.carries(Self::pointee(&receiver_type)) {
+                    self.store(&target, &flows);
+                }
+                flows.extend(target);
+                self.carried(expr, flows)
+            },
+            Expr::Format(format) => {
+                let flows = self.inspect_all(&format.args)?;
+                self.release(&flows);
+                Ok(Vec::new())
+            },
+            Expr::Write(destination, format) => {
+                let flows = self.inspect_all(&format.args)?;
+                self.release(&flows);
+                let destination_type = self.types.expr_type(destination)?;
+                let flows = self.receiver(destination, &destination_type, Some(true))?;
+                self.release(&flows);
+                Ok(Vec::new())
+            },
+            Expr::Try(operand) => {
+                let flows = self.expr(operand)?;
+                self.carried(expr, flows)
+            },
+            Expr::Assign(target, value) => {
+                self.assign(target, value)?;
+                Ok(Vec::new())
+            },
+            // `lieu += valeur` lit puis modifie le lieu, qui doit donc être initialisé et modifiable
+            Expr::CompoundAssign(target, _, value) => {
+                let flows = self.expr(value)?;
+                self.release(&flows);
+                if let Operand::Place(place) = self.place(target)? {
+                    self.check_mutable(&place, Access::Write);
+                    self.access(&place, Access::Read);
+                    self.access(&place, Access::Write);
+                }
+                Ok(Vec::new())
+            },
+            Expr::Closure(closure) => self.closure(closure),
+        }
+    }
+
+    // Lieu désigné par une expression ; les autres expressions sont évaluées comme des valeurs
+    fn place(&mut self, expr: &Expr) -> Result<Operand, usize> {
+        match expr {
+            Expr::Variable(name) => match self.lookup(name) {
+                Some(var) => {
+                    self.mention(var);
+                    Ok(Operand::Place(Place { var, path: Vec::new(), through: None, display: name.clone() }))
+                },
+                // Fonction nommée
+                None => Ok(Operand::Value(Vec::new())),
+            },
+            Expr::FieldAccess(base, field) => self.project(base, Projection::Field(field.clone()), &format!(".{}", field)),
+            Expr::TupleIndex(base, index) => self.project(base, Projection::Field(index.to_string()), &format!(".{}", index)),
+            Expr::Index(base, index) => {
+                let operand = self.project(base, Projection::Index, "[_]")?;
+                let flows = self.expr(index)?;
+                self.release(&flows);
+                Ok(operand)
+            },
+            Expr::Unary(UnaryOp::Deref, operand) => {
+                let operand_type = self.types.expr_type(operand)?;
+                match self.place(operand)? {
+                    Operand::Place(mut place) => {
+                        match operand_type {
+                            Type::Ref(_, mutable) | Type::Guard(_, mutable) => {
+                                place.path.push(Projection::Deref(true));
+                                place.through = Some(place.through.unwrap_or(true) && mutable);
+                            },
+                            // La valeur d'un Rc est partagée : elle ne se modifie pas et ne se déplace pas
+                            Type::Rc(_) => {
+                                place.path.push(Projection::Deref(true));
+                                place.through = Some(false);
+                            },
+                            Type::Box(_) => place.path.push(Projection::Deref(false)),
+                            _ => {},
+                        }
+                        place.display = format!("*{}", place.display);
+                        Ok(Operand::Place(place))
+                    },
+                    value => Ok(value),
+                }
+            },
+            _ => Ok(Operand::Value(self.expr(expr)?)),
+        }
+    }
+
+    // Champ ou élément d'un lieu, après déréférencement automatique des références et des Box
+    fn project(&mut self, base: &Expr, projection: Projection, suffix: &str) -> Result<Operand, usize> {
+        let base_type = self.types.expr_type(base)?;
+        match self.place(base)? {
+            Operand::Place(mut place) => {
+                Self::autoderef(&mut place, &base_type);
+                place.path.push(projection);
+                if place.display.starts_with('*') {
+                    place.display = format!("({})", place.display);
+                }
+                place.display.push_str(suffix);
+                Ok(Operand::Place(place))
+            },
+            value => Ok(value),
+        }
+    }
+
+    fn autoderef(place: &mut Place, typ: &Type) {
+        let mut typ = typ;
+        loop {
+            match typ {
+                Type::Ref(inner, mutable) | Type::Guard(inner, mutable) => {
+                    place.path.push(Projection::Deref(true));
+                    place.through = Some(place.through.unwrap_or(true) && *mutable);
+                    typ = inner;
+                },
+                Type::Rc(inner) => {
+                    place.path.push(Projection::Deref(true));
+                    place.through = Some(false);
+                    typ = inner;
+                },
+                Type::Box(inner) => {
+                    place.path.push(Projection::Deref(false));
+                    typ = inner;
+                },
+                _ => break,
+            }
+        }
+    }
+
+    // Mode de passage du receveur d'une méthode : par valeur (None), `&self` ou `&mut self`. Parmi les
+    // méthodes prédéfinies, celles qui consomment une Option, un Result, un vecteur ou une plage, et
+    // celles d'un Ordering, le prennent par valeur
+    fn receiver_mode(&self, receiver_type: &Type, method_name: &str) -> Result<Option<bool>, usize> {
+        let self_param = match self.types.resolve_method(receiver_type, method_name)? {
+            MethodTarget::Static(info) => info.sig.params.first().cloned(),
+            MethodTarget::Dynamic(trait_name, index) => {
+                self.types.traits[&trait_name].methods[index].function.params.first().map(|(_, typ)| typ.clone())
+            },
+            MethodTarget::Builtin => {
+                let consumes = match receiver_type {
+                    Type::Option(_) | Type::Result(_, _) => CONSUMING_METHODS.contains(&method_name),
+                    Type::Vec(_) | Type::Array(_, _) | Type::HashMap(_, _) | Type::HashSet(_) => method_name == "into_iter",
+                    Type::Entry(_, _) => true,
+                    Type::Named(name) => name == ORDERING,
+                    Type::Range(_, _) => !RANGE_METHODS.contains(&method_name),
+                    _ => false,
+                };
+                return Ok(if consumes { None } else { Some(MUTATING_METHODS.contains(&method_name)) });
+            },
+        };
+        Ok(match self_param {
+            Some(Type::Ref(_, mutable)) => Some(mutable),
+            _ => None,
+        })
+    }
+
+    fn receiver(&mut self, receiver: &Expr, receiver_type: &Type, by_ref: Option<bool>) -> Result<Vec<Flow>, usize> {
+        let Some(mutable) = by_ref else {
+            return self.expr(receiver);
+        };
+        match self.place(receiver)? {
+            Operand::Place(mut place) => {
+                let owned = place.path.len();
+                Self::autoderef(&mut place, receiver_type);
+                if place.path.len() > owned {
+                    place.display = format!("*{}", place.display);
+                }
+                Ok(self.borrow_place(place, mutable))
+            },
+            Operand::Value(flows) => Ok(flows),
+        }
+    }
+
+    // Arguments d'un appel : la valeur désignée par un paramètre `&mut` dont le type contient des références
+    // conserve les emprunts des autres arguments
+    fn arguments(&mut self, args: &[Expr], params: &[Type]) -> Result<Vec<Flow>, usize> {
+        let mut each = Vec::new();
+        for arg in args {
+            each.push(self.expr(arg)?);
+        }
+        for (index, param) in params.iter().enumerate().take(each.len()) {
+            let Type::Ref(inner, true) = param else { continue };
+            if !self.carries(inner) {
+                continue;
+            }
+            let others: Vec<Flow> = each.iter().enumerate().filter(|(other, _)| *other != index).flat_map(|(_, flows)| flows.clone()).collect();
+            self.store(&each[index].clone(), &others);
+        }
+        Ok(each.into_iter().flatten().collect())
+    }
+
+    // Paramètres d'une fonction de l'utilisateur ; les fonctions prédéfinies ne rangent pas leurs arguments
+    // à travers une référence `&mut`
+    fn function_params(&self, name: &str) -> Result<Vec<Type>, usize> {
+        let user = match name.split_once("::") {
+            _ if self.types.enum_variant(name).is_some() => false,
+            Some((type_name, _)) => type_name == "Self" || self.types.structs.contains_key(type_name) || self.types.enums.contains_key(type_name),
+            None => self.types.functions.contains_key(name),
+        };
+        if user {
+            Ok(self.types.lookup_function(name)?.0.params)
+        } else {
+            Ok(Vec::new())
+        }
+    }
+
+    // Valeur rangée à travers une référence `&mut` : le lieu référencé conserve ses emprunts. Quand ce lieu
+    // est inconnu (référence reçue en paramètre), il survit au corps en cours, qui ne peut donc pas y ranger
+    // un emprunt de l'une de ses variables locales
+    fn store(&mut self, target: &[Flow], flows: &[Flow]) {
+        let mut holders = Vec::new();
+        for flow in target {
+            match *flow {
+                Flow::Temp(id) => {
+                    let borrow = &self.borrows[id];
+                    if borrow.mutable && borrow.place.through.is_none() {
+                        holders.push(borrow.place.var);
+                    }
+                },
+                Flow::Held(var) => {
+                    let referents: Vec<usize> = self.borrows.iter()
+                        .filter(|borrow| borrow.holder == Some(var) && borrow.end.is_none() && borrow.mutable && borrow.place.through.is_none())
+                        .map(|borrow| borrow.place.var)
+                        .collect();
+                    if referents.is_empty() {
+                        self.check_escape(flows, var);
+                    }
+                    holders.push(var);
+                    holders.extend(referents);
+                },
+            }
+        }
+        holders.sort_unstable();
+        holders.dedup();
+        for holder in holders {
+            self.hold(flows, holder);
+        }
+    }
+
+    fn check_escape(&mut self, flows: &[Flow], target: usize) {
+        let first_local = self.frames.last().expect("corps en cours").first_local;
+        let stored = (0..self.borrows.len()).find(|&id| {
+            let borrow = &self.borrows[id];
+            let carried = flows.iter().any(|flow| match *flow {
+                Flow::Temp(temp) => temp == id,
+                Flow::Held(var) => borrow.holder == Some(var) && borrow.end.is_none(),
+            });
+            carried && borrow.place.through.is_none() && borrow.place.var >= first_local
+        });
+        if let Some(id) = stored {
+            let message = format!("`{}` ne vit pas assez longtemps : il est rangé dans la valeur référencée par `{}`, qui lui survit",
+                self.variables[self.borrows[id].place.var].name, self.variables[target].name);
+            self.error(message);
+        }
+    }
+
+    fn pointee(typ: &Type) -> &Type {
+        match typ {
+            Type::Ref(inner, _) | Type::Box(inner) => Self::pointee(inner),
+            _ => typ,
+        }
+    }
+
+    fn assign(&mut self, target: &Expr, value: &Expr) -> Result<(), usize> {
+        // `let r;` n'a pas de type déclaré : c'est la valeur affectée qui peut contenir des références
+        let value_type = self.types.expr_type(value)?;
+        let flows = self.expr(value)?;
+
+        // Une variable réaffectée n'est pas utilisée : ses anciens emprunts prennent fin ici
+        let place = match target {
+            Expr::Variable(name) => match self.lookup(name) {
+                Some(var) => Place { var, path: Vec::new(), through: None, display: name.clone() },
+                None => return Ok(()),
+            },
+            _ => match self.place(target)? {
+                Operand::Place(place) => place,
+                Operand::Value(_) => return Ok(()),
+            },
+        };
+        self.check_mutable(&place, Access::Write);
+        self.access(&place, Access::Write);
+        if self.carries(&value_type) {
+            if place.path.is_empty() {
+                self.kill(place.var);
+            }
+            self.hold(&flows, place.var);
+        }
+        Ok(())
+    }
+
+    // Une fermeture emprunte ses captures (mutablement si son corps les modifie), ou les déplace avec
+    // `move` ou si son corps les déplace ; elle conserve aussi les emprunts des références capturées
+    fn closure(&mut self, closure: &Closure) -> Result<Vec<Flow>, usize> {
+        let return_type = closure.return_type.as_ref().map(|typ| self.types.resolve_type(typ)).unwrap_or(Type::Void);
+        let outer = self.state.clone();
+        let start = self.tick();
+        self.open_frame(return_type);
+        self.open_scope();
+        for (name, typ) in &closure.params {
+            if let Some(typ) = typ {
+                let typ = self.types.resolve_type(typ);
+                self.types.variables.insert(name.clone(), typ);
+            }
+            self.declare(name, false, false);
+        }
+        for stmt in &closure.body {
+            self.stmt(stmt)?;
+        }
+        self.close_scope();
+        let frame = self.frames.pop().expect("corps de la fermeture");
+        // Les déplacements du corps ont lieu à chaque appel : pour la fonction englobante, c'est la
+        // création de la fermeture qui déplace les captures
+        let inner = std::mem::replace(&mut self.state, outer);
+
+        let mut flows = Vec::new();
+        for name in &closure.captures {
+            let Some(var) = self.lookup(name) else { continue };
+            let place = Place { var, path: Vec::new(), through: None, display: name.clone() };
+            let moved = closure.is_move || inner.moved.iter().any(|moved| moved.pos > start && moved.place.var == var);
+            if moved && !self.is_copy(&self.types.variables[name]) {
+                self.move_place(&place);
+            } else if !closure.is_move {
+                flows.extend(self.borrow_place(place, frame.mutated.contains(&var)));
+            }
+            self.mention(var);
+            flows.push(Flow::Held(var));
+        }
+        Ok(flows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::Lexer;
+    use crate::monomorphizer::Monomorphizer;
+    use crate::parser::Parser;
+
+    // Vérifie les emprunts d'un programme correctement typé ; renvoie la ligne de la première erreur
+    fn check(source: &str) -> Result<(), usize> {
+        let error_handler = ErrorHandler::new("test.rs".to_string());
+        let tokens = Lexer::new(source, &error_handler).tokenize().expect("analyse lexicale");
+        let program = Parser::new(tokens, &error_handler).parse().expect("analyse syntaxique");
+        let program = Monomorphizer::new(&error_handler).monomorphize(program).expect("typage");
+        BorrowChecker::new(&error_handler).check(&program)
+    }
+
+    #[test]
+    fn accepts_disjoint_borrows() {
+        assert_eq!(check("fn main() {
+            let mut v: Vec<i32> = Vec::new();
+            v.push(1);
+            let first = &v[0];
+            println!(\"{}\", first);
+            v.push(2);
+        }"), Ok(()));
+    }
+
+    #[test]
+    fn rejects_mutation_while_shared() {
+        assert_eq!(check("fn main() {
+            let mut v: Vec<i32> = Vec::new();
+            v.push(1);
+            let first = &v[0];
+            v.push(2);
+            println!(\"{}\", first);
+        }"), Err(5));
+    }
+
+    #[test]
+    fn rejects_reference_outliving_block() {
+        assert_eq!(check("fn main() {
+            let r;
+            {
+                let x = 1;
+                r = &x;
+            }
+            println!(\"{}\", r);
+        }"), Err(5));
+    }
+
+    #[test]
+    fn rejects_returned_local_reference() {
+        assert_eq!(check("fn f() -> &'static i32 {
+            let x = 1;
+            return &x;
+        }
+        fn main() { f(); }"), Err(3));
+    }
+
+    #[test]
+    fn rejects_assignment_to_immutable() {
+        assert_eq!(check("fn main() {
+            let x = 1;
+            x = 2;
+            println!(\"{}\", x);
+        }"), Err(3));
+    }
+
+    #[test]
+    fn rejects_push_of_short_lived_reference() {
+        assert_eq!(check("fn main() {
+            let mut v: Vec<&i32> = Vec::new();
+            {
+                let x = 1;
+                v.push(&x);
+            }
+            println!(\"{:?}\", v);
+        }"), Err(5));
+    }
+
+    #[test]
+    fn rejects_short_lived_reference_stored_through_parameter() {
+        assert_eq!(check("fn store<'a>(v: &mut Vec<&'a i32>, x: &'a i32) {
+            v.push(x);
+        }
+        fn main() {
+            let mut v: Vec<&i32> = Vec::new();
+            {
+                let x = 1;
+                store(&mut v, &x);
+            }
+            println!(\"{:?}\", v);
+        }"), Err(8));
+    }
+
+    #[test]
+    fn rejects_mutation_of_vector_borrowed_by_pushed_reference() {
+        assert_eq!(check("fn main() {
+            let mut v: Vec<i32> = Vec::new();
+            v.push(1);
+            let mut refs: Vec<&i32> = Vec::new();
+            refs.push(&v[0]);
+            v.push(2);
+            println!(\"{:?}\", refs);
+        }"), Err(6));
+    }
+
+    #[test]
+    fn rejects_local_stored_into_parameter() {
+        assert_eq!(check("fn fill(v: &mut Vec<&i32>) {
+            let local = 3;
+            v.push(&local);
+        }
+        fn main() {
+            let mut v: Vec<&i32> = Vec::new();
+            fill(&mut v);
+        }"), Err(3));
+    }
+
+    #[test]
+    fn accepts_references_stored_for_their_lifetime() {
+        assert_eq!(check("struct Holder<'a> { items: Vec<&'a i32> }
+        impl<'a> Holder<'a> {
+            fn add(&mut self, x: &'a i32) { self.items.push(x); }
+        }
+        fn main() {
+            let a = 1;
+            let mut h = Holder { items: Vec::new() };
+            h.add(&a);
+            let mut v: Vec<&i32> = Vec::new();
+            let r = &mut v;
+            r.push(&a);
+            println!(\"{:?} {:?}\", h.items, v);
+        }"), Ok(()));
+    }
+
+    #[test]
+    fn rejects_use_after_move() {
+        assert_eq!(check("fn main() {
+            let s = String::from(\"a\");
+            let t = s;
+            println!(\"{} {}\", s, t);
+        }"), Err(4));
+    }
+
+    #[test]
+    fn rejects_move_in_loop() {
+        assert_eq!(check("fn consume(s: String) { println!(\"{}\", s); }
+        fn main() {
+            let s = String::from(\"a\");
+            let mut i = 0;
+            while i < 2 {
+                consume(s);
+                i = i + 1;
+            }
+        }"), Err(6));
+    }
+
+    #[test]
+    fn accepts_move_before_break() {
+        assert_eq!(check("fn consume(s: String) { println!(\"{}\", s); }
+        fn main() {
+            let s = String::from(\"a\");
+            let mut i = 0;
+            while i < 2 {
+                i = i + 1;
+                consume(s);
+                break;
+            }
+        }"), Ok(()));
+    }
+
+    #[test]
+    fn rejects_move_before_continue() {
+        assert_eq!(check("fn consume(s: String) { println!(\"{}\", s); }
+        fn main() {
+            let s = String::from(\"a\");
+            for i in 0..2 {
+                if i == 0 {
+                    consume(s);
+                    continue;
+                }
+            }
+        }"), Err(6));
+    }
+
+    #[test]
+    fn rejects_use_after_conditional_move() {
+        assert_eq!(check("fn consume(s: String) { println!(\"{}\", s); }
+        fn main() {
+            let s = String::from(\"a\");
+            if s.len() > 3 {
+                consume(s);
+            }
+            println!(\"{}\", s);
+        }"), Err(7));
+    }
+
+    #[test]
+    fn accepts_reassignment_after_move() {
+        assert_eq!(check("fn main() {
+            let mut s = String::from(\"a\");
+            let t = s;
+            s = String::from(\"b\");
+            println!(\"{} {}\", s, t);
+        }"), Ok(()));
+    }
+
+    #[test]
+    fn rejects_move_out_of_index() {
+        assert_eq!(check("fn main() {
+            let mut v: Vec<String> = Vec::new();
+            v.push(String::from(\"a\"));
+            let s = v[0];
+            println!(\"{}\", s);
+        }"), Err(4));
+    }
+
+    #[test]
+    fn accepts_clone_instead_of_move() {
+        assert_eq!(check("#[derive(Clone)]
+        struct Person { name: String, tags: Vec<String> }
+        fn main() {
+            let mut v: Vec<String> = Vec::new();
+            v.push(String::from(\"a\"));
+            let s = v[0].clone();
+            let p = Person { name: s.clone(), tags: v.clone() };
+            let q = p.clone();
+            println!(\"{} {} {} {:?} {:?}\", s, p.name, q.name, v, p.tags);
+        }"), Ok(()));
+    }
+
+    #[test]
+    fn rejects_clone_of_moved_value() {
+        assert_eq!(check("fn main() {
+            let s = String::from(\"a\");
+            let t = s;
+            let u = s.clone();
+            println!(\"{} {}\", t, u);
+        }"), Err(4));
+    }
+}
@@ -0,0 +1,142 @@
+use std::collections::HashSet;
+
+use crate::parser::{Closure, Expr, Literal, Pattern, Stmt};
+
+// Noms libres du corps d'une fermeture, dans l'ordre de leur première utilisation : ni paramètres,
+// ni variables déclarées dans le corps. La monomorphisation ne garde que les variables locales
+// englobantes, qui deviennent les captures de la fermeture.
+pub fn free_variables(closure: &Closure) -> Vec<String> {
+    let mut walker = Walker { scopes: Vec::new(), free: Vec::new() };
+    walker.closure(closure);
+    walker.free
+}
+
+struct Walker {
+    scopes: Vec<HashSet<String>>,
+    free: Vec<String>,
+}
+
+impl Walker {
+    fn closure(&mut self, closure: &Closure) {
+        self.scopes.push(closure.params.iter().map(|(name, _)| name.clone()).collect());
+        for stmt in &closure.body {
+            self.stmt(stmt);
+        }
+        self.scopes.pop();
+    }
+
+    fn bind(&mut self, pattern: &Pattern) {
+        let scope = self.scopes.last_mut().expect("portée ouverte");
+        scope.extend(pattern.names());
+    }
+
+    fn use_name(&mut self, name: &str) {
+        let bound = self.scopes.iter().any(|scope| scope.contains(name));
+        if !bound && !self.free.iter().any(|free| free == name) {
+            self.free.push(name.to_string());
+        }
+    }
+
+    // Corps d'une branche ou d'une boucle : les liaisons de son motif (boucle for) ne valent que dans ce corps
+    fn scoped(&mut self, pattern: Option<&Pattern>, body: &Stmt) {
+        self.scopes.push(HashSet::new());
+        if let Some(pattern) = pattern {
+            self.bind(pattern);
+        }
+        self.stmt(body);
+        self.scopes.pop();
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::Expression(expr, _) | Stmt::Return(Some(expr), _) => self.expr(expr),
+            Stmt::Return(None, _) | Stmt::Break(_) | Stmt::Continue(_) => {},
+            Stmt::Let(pattern, initializer, _, _) => {
+                if let Some(initializer) = initializer {
+                    self.expr(initializer);
+                }
+                self.bind(pattern);
+            },
+            Stmt::Block(stmts) => {
+                self.scopes.push(HashSet::new());
+                for stmt in stmts {
+                    self.stmt(stmt);
+                }
+                self.scopes.pop();
+            },
+            Stmt::If(condition, then_branch, else_branch, _) => {
+                self.expr(condition);
+                self.scoped(None, then_branch);
+                if let Some(else_branch) = else_branch {
+                    self.scoped(None, else_branch);
+                }
+            },
+            Stmt::Match(value, arms, _) => {
+                self.expr(value);
+                for arm in arms {
+                    self.scopes.push(HashSet::new());
+                    self.bind(&arm.pattern);
+                    if let Some(guard) = &arm.guard {
+                        self.expr(guard);
+                    }
+                    self.stmt(&arm.body);
+                    self.scopes.pop();
+                }
+            },
+            Stmt::While(condition, body, _) => {
+                self.expr(condition);
+                self.scoped(None, body);
+            },
+            Stmt::For(pattern, value, body, _) => {
+                self.expr(value);
+                self.scoped(Some(pattern), body);
+            },
+            Stmt::Print(format, _, _) => self.exprs(&format.args),
+        }
+    }
+
+    fn exprs(&mut self, exprs: &[Expr]) {
+        for expr in exprs {
+            self.expr(expr);
+        }
+    }
+
+    fn expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Variable(name) => self.use_name(name),
+            // Appel d'une fonction nommée, ou d'une variable locale appelable
+            Expr::FunctionCall(name, _, args) => {
+                self.use_name(name);
+                self.exprs(args);
+            },
+            Expr::Closure(closure) => self.closure(closure),
+            Expr::Binary(left, _, right) | Expr::CompoundAssign(left, _, right) | Expr::Index(left, right) | Expr::Assign(left, right) | Expr::VecRepeat(left, right) => {
+                self.expr(left);
+                self.expr(right);
+            },
+            Expr::Unary(_, operand) | Expr::FieldAccess(operand, _) | Expr::TupleIndex(operand, _) | Expr::Try(operand)
+            | Expr::Literal(Literal::ArrayRepeat(operand, _)) => self.expr(operand),
+            Expr::VecNew(elements) | Expr::Tuple(elements) | Expr::Literal(Literal::Array(elements)) => self.exprs(elements),
+            Expr::MethodCall(receiver, _, _, args) | Expr::Call(receiver, args) => {
+                self.expr(receiver);
+                self.exprs(args);
+            },
+            Expr::StructLiteral(_, fields) => {
+                for (_, value) in fields {
+                    self.expr(value);
+                }
+            },
+            Expr::Range(start, end, _) => {
+                for bound in [start, end].into_iter().flatten() {
+                    self.expr(bound);
+                }
+            },
+            Expr::Format(format) => self.exprs(&format.args),
+            Expr::Write(destination, format) => {
+                self.expr(destination);
+                self.exprs(&format.args);
+            },
+            Expr::Literal(_) => {},
+        }
+    }
+}
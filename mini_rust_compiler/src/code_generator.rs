@@ -5,37 +5,147 @@ use std::process::Command;
 use std::collections::HashMap;
 
 use crate::error_handler::ErrorHandler;
-use crate::parser::{Program, Function, Stmt, Expr, BinaryOp, Literal, Type};
+use crate::parser::{Program, Function, Stmt, Stream, Expr, BinaryOp, UnaryOp, Literal, Type, Pattern, Closure};
+use crate::type_checker::{TypeChecker, MethodTarget, PARSE_INT_ERROR, VARIANTS};
+use crate::runtime;
+use crate::format::{FormatArgs, Piece, Spec, Count, Kind, Align};
+use crate::iterators::{IteratorMethod, Source, Adapter, Consumer};
 
 // Structure pour stocker les informations sur les variables
 #[derive(Clone)]
 struct VarInfo {
     offset: usize,
     var_type: Type,
+    indirect: bool, // L'emplacement contient l'adresse de la valeur (variable capturée par une fermeture)
 }
 
+// Variable capturée par une fermeture : (nom, type, capture par valeur avec `move`)
+type Capture = (String, Type, bool);
+
+// Corps de fermeture restant à émettre : (étiquette, fermeture, captures, type `Self` englobant)
+type PendingClosure = (String, Closure, Vec<Capture>, Option<Type>);
+
+// Emplacement d'une valeur filtrée par un motif
+#[derive(Clone, Copy)]
+enum Place {
+    Frame(usize),   // Valeur rangée à rbp-n
+    Pointer(usize), // Valeur dont l'adresse est rangée à rbp-n
+}
+
+// Itérateur fondu : `setup` s'exécute une fois avant la boucle, `next` range l'élément suivant à rbp-item
+// ou saute à l'étiquette de fin quand l'itérateur est épuisé
+struct FusedIterator {
+    setup: String,
+    next: String,
+    item: usize,
+    item_type: Type,
+}
+
+// Portée de destruction : un bloc (ses variables) ou une instruction (ses temporaires), avec les valeurs
+// qu'elle possède (emplacement rbp-n et type), détruites dans l'ordre inverse de leur enregistrement
+struct DropScope {
+    block: bool,
+    values: Vec<(usize, Type)>,
+}
+
+// Boucle en cours de génération : étiquettes visées par `continue` et `break`, nombre de portées de
+// destruction ouvertes avant son corps, et destructions propres à la boucle avant d'en sortir par `break`
+struct LoopTarget {
+    next_label: String,
+    end_label: String,
+    depth: usize,
+    exit_drops: String,
+}
+
+// Registres des arguments entiers (convention System V AMD64)
+const ARG_REGISTERS: [&str; 6] = ["rdi", "rsi", "rdx", "rcx", "r8", "r9"];
+
+// Un vecteur est un triplet (pointeur vers le tas, longueur, capacité)
+const VEC_SIZE: usize = 24;
+const VEC_LEN: usize = 8;
+const VEC_CAP: usize = 16;
+
+// En-tête de l'environnement d'une fermeture : compteur de références, glue de destruction des captures
+const CLOSURE_ENV_HEADER: usize = 16;
+
+// En-tête d'une vtable : drop_in_place, taille, alignement, puis les méthodes du trait
+const VTABLE_HEADER_SIZE: usize = 24;
+
+// Entrée d'une table de hachage : état (0 libre, 1 occupée), empreinte, puis la clé et la valeur
+const MAP_KEY: usize = 16;
+
 pub struct CodeGenerator<'a> {
     error_handler: &'a ErrorHandler,
     current_function: Option<String>,
     label_counter: usize,  // Utile pour générer des étiquettes uniques
     variable_info: HashMap<String, VarInfo>,
-    function_params: HashMap<String, Vec<String>>,
-    format_labels: HashMap<(String, usize), String>,
+    types: TypeChecker<'a>,                         // Déclarations et typage des expressions
+    vtables: Vec<(String, String)>,                 // Paires (type, trait) dont la vtable est émise
+    string_literals: HashMap<String, String>,       // Chaînes littérales émises dans .rodata, par contenu
+    debug_functions: HashMap<String, String>,       // Fonction d'affichage {:?} de chaque type composé, par type
+    pending_debug: Vec<Type>,                       // Types dont la fonction d'affichage {:?} reste à émettre
+    pending_closures: Vec<PendingClosure>,          // Corps des fermetures rencontrées, émis après les fonctions
+    drop_functions: HashMap<String, String>,        // Glue de destruction de chaque type possédant des ressources, par type
+    pending_drops: Vec<Type>,                       // Types dont la glue de destruction reste à émettre
+    clone_functions: HashMap<String, String>,       // Glue de copie en profondeur de chaque type possédant des ressources, par type
+    pending_clones: Vec<Type>,                      // Types dont la glue de copie reste à émettre
+    compare_functions: HashMap<String, String>,     // Fonction de comparaison de chaque type trié qui n'est pas un entier, par type
+    pending_compares: Vec<Type>,                    // Types dont la fonction de comparaison reste à émettre
+    drop_scopes: Vec<DropScope>,                    // Portées ouvertes de la fonction courante, la plus interne en dernier
+    loops: Vec<LoopTarget>,                         // Boucles englobant l'instruction en cours, la plus interne en dernier
+    frame_size: usize,           // Taille courante du cadre de pile de la fonction
+    return_type: Type,
+    return_slot: Option<usize>,  // Pointeur de retour caché (valeurs de plus de 16 octets)
+    source_name: String,         // Fichier source, cité par les messages de panique
+    uses_bounds_check: bool,     // Les routines de panique doivent être émises
+    uses_vec_runtime: bool,      // Les routines des vecteurs et tableaux doivent être émises
+    uses_string_runtime: bool,   // Les routines des chaînes doivent être émises
+    uses_format_runtime: bool,   // Les routines d'écriture formatée doivent être émises
+    uses_map_runtime: bool,      // Les routines des tables de hachage doivent être émises
+    owns_values: bool,           // La fonction courante possède des valeurs à détruire : son cadre est mis à zéro
+    release: String,             // Remise à zéro de la source du dernier pointeur Box lu (déplacement)
+    leak_check: bool,            // Compilation de débogage : les allocations restantes sont signalées à la sortie
 }
 
 impl<'a> CodeGenerator<'a> {
-    pub fn new(error_handler: &'a ErrorHandler) -> Self {
-        CodeGenerator { 
+    pub fn new(error_handler: &'a ErrorHandler, release: bool) -> Self {
+        CodeGenerator {
             error_handler,
             current_function: None,
             label_counter: 0,
             variable_info: HashMap::new(),
-            function_params: HashMap::new(),
-            format_labels: HashMap::new(),
+            types: TypeChecker::new(error_handler),
+            vtables: Vec::new(),
+            string_literals: HashMap::new(),
+            debug_functions: HashMap::new(),
+            pending_debug: Vec::new(),
+            pending_closures: Vec::new(),
+            drop_functions: HashMap::new(),
+            pending_drops: Vec::new(),
+            clone_functions: HashMap::new(),
+            pending_clones: Vec::new(),
+            compare_functions: HashMap::new(),
+            pending_compares: Vec::new(),
+            drop_scopes: Vec::new(),
+            loops: Vec::new(),
+            frame_size: 0,
+            return_type: Type::Void,
+            return_slot: None,
+            source_name: String::new(),
+            uses_bounds_check: false,
+            uses_vec_runtime: false,
+            uses_string_runtime: false,
+            uses_format_runtime: false,
+            uses_map_runtime: false,
+            owns_values: false,
+            release: String::new(),
+            leak_check: !release,
         }
     }
-    
+
     pub fn generate(&mut self, program: Program, source_path: &str) -> Result<String, usize> {
+        self.source_name = source_path.to_string();
+
         // Générer le code assembleur pour le programme
         let asm_code = self.generate_asm_code(&program)?;
         
@@ -117,7 +227,12 @@ impl<'a> CodeGenerator<'a> {
         
         Ok(stem)
     }
-    
+
+    // Signale une erreur à la ligne courante et renvoie la valeur d'erreur
+    fn error<T>(&self, message: &str) -> Result<T, usize> {
+        self.types.error(message)
+    }
+
     fn type_size(&self, typ: &Type) -> usize {
         match typ {
             Type::I8 => 1,
@@ -125,934 +240,5346 @@ impl<'a> CodeGenerator<'a> {
             Type::I32 => 4,
             Type::I64 => 8,
             Type::I128 => 16,
+            Type::U8 => 1,
             Type::F32 => 4,
             Type::F64 => 8,
-            Type::String => 8, // Stocké comme un pointeur
-            Type::Vec(_) => 8, // Stocké comme un pointeur vers la structure du vecteur
+            Type::Bool => 1,
+            Type::Char => 4, // Valeur scalaire Unicode
+            Type::String | Type::Vec(_) => VEC_SIZE,
+            // Entrées, nombre de clés et capacité, comme un Vec (voir `runtime::MAP`)
+            Type::HashMap(_, _) | Type::HashSet(_) => VEC_SIZE,
+            // Adresse de la table, puis clé possédée
+            Type::Entry(key, value) => self.fields_layout(&[Type::Ref(Box::new(Type::HashMap(key.clone(), value.clone())), true), (**key).clone()]).1,
+            Type::Option(inner) => self.fields_layout(&[Type::Bool, (**inner).clone()]).1,
+            Type::Result(ok, err) => self.result_layout(ok, err).1,
             Type::Void => 0,
+            Type::Named(name) if self.types.enums.contains_key(name) => self.enum_layout(name).1,
+            Type::Named(name) => self.struct_layout(name).1,
+            Type::Tuple(elements) => self.fields_layout(elements).1,
+            Type::Array(element, length) => self.type_size(element) * length,
+            // Pointeur large (données, longueur) vers une tranche ou une chaîne
+            Type::Ref(inner, _) if matches!(**inner, Type::Slice(_) | Type::Str) => 16,
+            // Pointeur large (données, vtable) vers un objet trait
+            Type::Ref(inner, _) | Type::Box(inner) if matches!(**inner, Type::Dyn(_)) => 16,
+            Type::Ref(_, _) | Type::Box(_) | Type::FnPtr(_, _) => 8,
+            // Pointeur vers la valeur, précédée sur le tas de son compteur de références
+            Type::Rc(_) => 8,
+            // Pointeur vers la valeur empruntée, précédée dans la cellule de son état d'emprunt
+            Type::Guard(_, _) => 8,
+            // État d'emprunt (0 libre, n lectures en cours, -1 écriture en cours), puis la valeur
+            Type::RefCell(inner) => self.fields_layout(&[Type::I64, (**inner).clone()]).1,
+            // Adresse du code, puis adresse de l'environnement des captures (nulle s'il est vide)
+            Type::Closure(_, _) => 16,
+            Type::Dyn(_) | Type::Slice(_) | Type::Str => 0, // Types non dimensionnés
+            Type::Generic(_, _) => 0, // Remplacé par son instance lors de la monomorphisation
+            Type::Iterator(_) => 0, // Jamais matérialisé : fondu dans la boucle qui le consomme
+            Type::Range(element, _) => self.fields_layout(&[(**element).clone(), (**element).clone()]).1,
+        }
+    }
+
+    fn type_align(&self, typ: &Type) -> usize {
+        match typ {
+            Type::Named(name) if self.types.enums.contains_key(name) => self.enum_layout(name).2,
+            Type::Named(name) => self.struct_layout(name).2,
+            Type::Tuple(elements) => self.fields_layout(elements).2,
+            Type::Option(inner) => self.fields_layout(&[Type::Bool, (**inner).clone()]).2,
+            Type::Result(ok, err) => self.result_layout(ok, err).2,
+            Type::RefCell(_) => 8,
+            Type::Array(element, _) | Type::Range(element, _) => self.type_align(element),
+            Type::Void | Type::Dyn(_) | Type::Slice(_) | Type::Str | Type::Iterator(_) => 1,
+            _ => self.type_size(typ).clamp(1, 8),
+        }
+    }
+
+    // Les agrégats sont manipulés par adresse : rax contient un pointeur vers la valeur
+    fn is_aggregate(&self, typ: &Type) -> bool {
+        match typ {
+            Type::Named(name) => self.types.structs.contains_key(name) || self.types.enums.contains_key(name),
+            Type::Tuple(elements) => !elements.is_empty(),
+            Type::Array(_, _) | Type::Vec(_) | Type::Option(_) | Type::Result(_, _) | Type::String | Type::Closure(_, _)
+            | Type::Range(_, _) | Type::RefCell(_) | Type::HashMap(_, _) | Type::HashSet(_) | Type::Entry(_, _) => true,
+            Type::Ref(inner, _) => matches!(**inner, Type::Dyn(_) | Type::Slice(_) | Type::Str),
+            Type::Box(inner) => matches!(**inner, Type::Dyn(_)),
+            _ => false,
+        }
+    }
+
+    // Décalage de chaque champ, taille totale et alignement d'une structure
+    fn struct_layout(&self, name: &str) -> (Vec<(String, Type, usize)>, usize, usize) {
+        let Some(def) = self.types.structs.get(name) else {
+            return (Vec::new(), 0, 1);
+        };
+
+        // Une structure qui implémente Drop porte après ses champs un octet indiquant qu'elle est initialisée
+        let mut types: Vec<Type> = def.fields.iter().map(|(_, field_type)| field_type.clone()).collect();
+        if self.has_drop_impl(name) {
+            types.push(Type::Bool);
+        }
+        let (offsets, size, align) = self.fields_layout(&types);
+        let fields = def.fields.iter().zip(offsets)
+            .map(|((field_name, field_type), offset)| (field_name.clone(), field_type.clone(), offset))
+            .collect();
+        (fields, size, align)
+    }
+
+    // Énumération : un octet d'étiquette (rang de la variante plus un, nul pour une valeur déplacée) suivi
+    // des champs de la variante, placés comme ceux d'un tuple ; renvoie les décalages des champs de chaque
+    // variante, la taille et l'alignement
+    fn enum_layout(&self, name: &str) -> (Vec<Vec<usize>>, usize, usize) {
+        let mut variants = Vec::new();
+        let (mut size, mut align) = (1, 1);
+        for (_, fields) in &self.types.enums[name].variants {
+            let types: Vec<Type> = std::iter::once(Type::U8).chain(fields.iter().cloned()).collect();
+            let (offsets, variant_size, variant_align) = self.fields_layout(&types);
+            variants.push(offsets[1..].to_vec());
+            size = size.max(variant_size);
+            align = align.max(variant_align);
+        }
+        (variants, size.next_multiple_of(align), align)
+    }
+
+    // Rang de la variante désignée par un motif ou un constructeur, et le type et le décalage de chacun
+    // de ses champs
+    fn variant_fields(&self, path: &str) -> (usize, Vec<(Type, usize)>) {
+        let Some((enum_name, index, fields)) = self.types.enum_variant(path) else {
+            unreachable!("variante inconnue : {}", path)
+        };
+        let offsets = self.enum_layout(&enum_name).0.swap_remove(index);
+        (index, fields.into_iter().zip(offsets).collect())
+    }
+
+    // Motifs liant chacun des champs d'une variante : le motif unique d'une variante à un champ, sinon
+    // les éléments du tuple qui suit son nom
+    fn field_patterns(payload: &Pattern, count: usize) -> Vec<&Pattern> {
+        match payload {
+            Pattern::Tuple(patterns) if count > 1 => patterns.iter().collect(),
+            _ => vec![payload],
+        }
+    }
+
+    // Décalage de l'octet d'initialisation d'une structure qui implémente Drop, placé après ses champs
+    fn drop_flag(&self, name: &str) -> usize {
+        let mut types: Vec<Type> = self.types.structs[name].fields.iter().map(|(_, field_type)| field_type.clone()).collect();
+        types.push(Type::Bool);
+        self.fields_layout(&types).0[types.len() - 1]
+    }
+
+    // Champs placés dans l'ordre de déclaration, chacun aligné sur son type (structures et tuples)
+    fn fields_layout(&self, types: &[Type]) -> (Vec<usize>, usize, usize) {
+        let mut offsets = Vec::new();
+        let mut offset: usize = 0;
+        let mut align = 1;
+
+        for field_type in types {
+            let field_align = self.type_align(field_type);
+            offset = offset.next_multiple_of(field_align);
+            offsets.push(offset);
+            offset += self.type_size(field_type);
+            align = align.max(field_align);
         }
+
+        (offsets, offset.next_multiple_of(align), align)
+    }
+
+    // Result<T, E> : un octet d'étiquette (1 = Ok) suivi de la valeur ou de l'erreur, au même décalage ;
+    // renvoie ce décalage, la taille et l'alignement
+    fn result_layout(&self, ok: &Type, err: &Type) -> (usize, usize, usize) {
+        let align = self.type_align(ok).max(self.type_align(err));
+        let payload = self.type_size(ok).max(self.type_size(err));
+        (align, (align + payload).next_multiple_of(align), align)
+    }
+
+    // Clé et valeur (absente pour un HashSet) d'une table
+    fn table_parts(table: &Type) -> (Type, Option<Type>) {
+        match table {
+            Type::HashMap(key, value) => ((**key).clone(), Some((**value).clone())),
+            Type::HashSet(element) => ((**element).clone(), None),
+            _ => unreachable!("table attendue : {}", table),
+        }
+    }
+
+    // Entrée d'une table : état, empreinte, clé rangée sur 8 octets (ou en String), puis la valeur ;
+    // renvoie le décalage de la valeur et la taille de l'entrée
+    fn map_layout(&self, table: &Type) -> (usize, usize) {
+        let (key, value) = Self::table_parts(table);
+        let stored_key = if key == Type::String { Type::String } else { Type::I64 };
+        let mut fields = vec![Type::I64, Type::I64, stored_key];
+        fields.extend(value);
+        let (offsets, size, _) = self.fields_layout(&fields);
+        (offsets.get(3).copied().unwrap_or(size), size)
     }
-    
+
     fn align_to_8_bytes(size: usize) -> usize {
         (size + 7) & !7  // Arrondir au multiple de 8 supérieur
     }
-    
+
+    // Réserve un emplacement dans le cadre de pile et renvoie son décalage par rapport à rbp
+    fn alloc_slot(&mut self, size: usize) -> usize {
+        self.frame_size = Self::align_to_8_bytes(self.frame_size + size.max(8));
+        self.frame_size
+    }
+
+    fn new_label(&mut self, prefix: &str) -> String {
+        let label = format!("{}_{}", prefix, self.label_counter);
+        self.label_counter += 1;
+        label
+    }
+
+    fn vtable_label(type_name: &str, trait_name: &str) -> String {
+        format!("vtable_{}_{}", type_name, trait_name)
+    }
+
     fn generate_asm_code(&mut self, program: &Program) -> Result<String, usize> {
-        let mut code = String::new();
-        
-        // Préparation - Collecter tous les paramètres de fonction
+        self.types.collect_declarations(program)?;
+
+        let mut text = String::new();
+
+        // Fonctions libres (main comprise)
         for function in &program.functions {
-            let param_names: Vec<String> = function.params.iter()
-                .map(|(name, _)| name.clone())
-                .collect();
-            self.function_params.insert(function.name.clone(), param_names);
+            let label = function.name.clone();
+            text.push_str(&self.generate_function(function, &label, None, &[])?);
         }
-        
+
+        // Méthodes des blocs impl, y compris les méthodes par défaut des traits
+        for impl_block in &program.impls {
+            let concrete = self.types.type_from_name(&impl_block.type_name);
+            let trait_name = impl_block.trait_name.as_deref();
+
+            for method in &impl_block.methods {
+                let label = TypeChecker::method_label(&impl_block.type_name, trait_name, &method.name);
+                text.push_str(&self.generate_function(method, &label, concrete.clone(), &[])?);
+            }
+
+            if let Some(trait_name) = trait_name {
+                let trait_def = self.types.traits[trait_name].clone();
+                for declared in trait_def.methods.iter().filter(|m| m.has_default) {
+                    if impl_block.methods.iter().any(|m| m.name == declared.function.name) {
+                        continue;
+                    }
+                    let label = TypeChecker::method_label(&impl_block.type_name, Some(trait_name), &declared.function.name);
+                    text.push_str(&self.generate_function(&declared.function, &label, concrete.clone(), &[])?);
+                }
+            }
+        }
+
+        // Corps des fermetures, chacun pouvant en contenir d'autres
+        while let Some((label, closure, captures, self_type)) = self.pending_closures.pop() {
+            let function = Function {
+                name: label.clone(),
+                type_params: Vec::new(),
+                params: closure.params.iter().map(|(name, typ)| (name.clone(), typ.clone().unwrap_or(Type::Void))).collect(),
+                mutable_params: Vec::new(),
+                return_type: closure.return_type.clone(),
+                body: closure.body.clone(),
+                line: closure.line,
+            };
+            text.push_str(&self.generate_function(&function, &label, self_type, &captures)?);
+        }
+
+        // Fonctions d'affichage {:?} des types composés, chacune pouvant en demander d'autres
+        while let Some(typ) = self.pending_debug.pop() {
+            text.push_str(&self.generate_debug_function(&typ)?);
+        }
+
+        // Fonctions de comparaison de sort(), chacune pouvant en demander d'autres
+        while let Some(typ) = self.pending_compares.pop() {
+            text.push_str(&self.generate_compare_function(&typ));
+        }
+
+        // Glues de copie en profondeur, chacune pouvant en demander d'autres
+        while let Some(typ) = self.pending_clones.pop() {
+            text.push_str(&self.generate_clone_function(&typ));
+        }
+
+        // Glues de destruction, chacune pouvant en demander d'autres ; celle d'un type converti en objet
+        // trait est désignée par sa vtable
+        for (type_name, _) in self.vtables.clone() {
+            let concrete = self.types.type_from_name(&type_name).unwrap_or(Type::Void);
+            if self.needs_drop(&concrete) {
+                self.drop_function(&concrete);
+            }
+        }
+        while let Some(typ) = self.pending_drops.pop() {
+            text.push_str(&self.generate_drop_function(&typ));
+        }
+
+        // Les routines des vecteurs et des chaînes peuvent paniquer
+        if self.uses_vec_runtime {
+            self.uses_bounds_check = true;
+        }
+
+        let mut code = String::new();
+        if !self.leak_check {
+            code.push_str(runtime::MEMORY_RELEASE);
+        }
+
         // En-tête assembleur
         code.push_str("section .data\n");
-        
+
         // Constantes et variables globales
-        code.push_str("    format_integer db \"%d\", 0\n");
-        code.push_str("    format_string db \"%s\", 0\n");
-        code.push_str("    newline db 10, 0\n");
-        
-        // Constantes pour println!
-        // Premier passage pour générer des étiquettes stables et les stocker dans un dictionnaire
-        self.format_labels.clear();
-        for function in &program.functions {
-            self.process_function_for_format_labels(function);
+        code.push_str("    bool_true db \"true\", 0\n");
+        code.push_str("    bool_false db \"false\", 0\n");
+        if self.uses_bounds_check {
+            code.push_str(&format!("    panic_source db {}, 0\n", Self::asm_string(&self.source_name)));
+            code.push_str(runtime::PANIC_DATA);
         }
-        
-        // Maintenant générons les définitions de chaînes avec les étiquettes stables
-        for function in &program.functions {
-            self.generate_format_strings_for_function(function, &mut code);
+        if self.uses_string_runtime {
+            code.push_str(runtime::STRING_DATA);
         }
-        
+        if self.leak_check {
+            code.push_str(runtime::MEMORY_DATA);
+        }
+
+        if !self.vtables.is_empty() || !self.string_literals.is_empty() {
+            code.push_str("\nsection .rodata\n");
+        }
+
+        // Contenu des chaînes littérales (sans zéro final : leur longueur accompagne le pointeur)
+        let mut literals: Vec<(&String, &String)> = self.string_literals.iter().collect();
+        literals.sort_by(|a, b| a.1.cmp(b.1));
+        for (value, label) in literals {
+            code.push_str(&format!("    {} db {}\n", label, Self::asm_string(value)));
+        }
+
+        // Une vtable par paire (type, trait) utilisée comme objet trait
+        if !self.vtables.is_empty() {
+            for (type_name, trait_name) in self.vtables.clone() {
+                let concrete = self.types.type_from_name(&type_name).unwrap_or(Type::Void);
+                code.push_str("    align 8\n");
+                code.push_str(&format!("{}:\n", Self::vtable_label(&type_name, &trait_name)));
+                match self.drop_functions.get(&concrete.to_string()) {
+                    Some(label) => code.push_str(&format!("    dq {}  ; drop_in_place\n", label)),
+                    None => code.push_str("    dq 0  ; drop_in_place (aucune glue de destruction)\n"),
+                }
+                code.push_str(&format!("    dq {}  ; taille\n", self.type_size(&concrete)));
+                code.push_str(&format!("    dq {}  ; alignement\n", self.type_align(&concrete)));
+                for method in &self.types.traits[&trait_name].methods {
+                    code.push_str(&format!("    dq {}\n", TypeChecker::method_label(&type_name, Some(&trait_name), &method.function.name)));
+                }
+            }
+        }
+
         // Section de code
         code.push_str("\nsection .text\n");
         // Ne pas définir _start quand on utilise gcc comme éditeur de liens
-        code.push_str("    extern printf\n");
         code.push_str("    extern exit\n");
+        code.push_str("    extern malloc\n");
+        code.push_str("    extern realloc\n");
+        code.push_str("    extern free\n");
+        code.push_str("    extern dprintf\n");
+        code.push_str("    extern memcpy\n");
+        code.push_str("    extern memmove\n");
+        code.push_str("    extern memcmp\n");
+        code.push_str("    extern qsort\n");
+        code.push_str("    extern memset\n");
+        code.push_str("    extern strlen\n");
+        code.push_str("    extern fwrite\n");
+        code.push_str("    extern fflush\n");
+        code.push_str("    extern memchr\n");
+        code.push_str("    extern free\n");
+        code.push_str("    extern stdout\n");
+        code.push_str("    extern stderr\n");
         code.push_str("    global main\n\n");  // Définir main comme global pour l'édition de liens
-        
-        // Implémentation des fonctions (sauf main qui sera traité spécialement)
-        for function in &program.functions {
-            if function.name != "main" {  // Générer toutes les fonctions sauf main
-                self.variable_info.clear();
-                code.push_str(&self.generate_function(function)?);
-            }
+
+        code.push_str(&text);
+
+        if self.leak_check {
+            code.push_str(runtime::MEMORY);
         }
-        
-        // Traitement spécial pour main pour qu'il suive la convention d'appel C
-        if let Some(main_function) = program.functions.iter().find(|f| f.name == "main") {
-            self.variable_info.clear();
-            self.current_function = Some("main".to_string());
-            
-            code.push_str("main:\n");
-            code.push_str("    push rbp\n");
-            code.push_str("    mov rbp, rsp\n");
-            
-            // Count all variables including loop variables
-            let mut total_vars = 0;
-            self.count_all_variables(&main_function.body, &mut total_vars);
-            
-            // Allouer plus d'espace pour les vecteurs et les variables temporaires
-            let local_vars_size = ((total_vars + 2) * 8) + 128; // Extra 128 bytes for vector storage
-            
-            println!("Found {} total variables, allocating {} bytes (including vector space)", total_vars, local_vars_size);
-            
-            if local_vars_size > 0 {
-                // Ensure stack is aligned to 16 bytes (required by System V ABI)
-                let aligned_size = ((local_vars_size + 15) / 16) * 16;
-                code.push_str(&format!("    sub rsp, {}\n", aligned_size));
-            }
-            
-            // Enregistrer les décalages des variables locales
-            let mut offset = 0;
-            self.assign_variable_offsets(&main_function.body, &mut offset);
-            
-            // Debug: Print variable assignments
-            println!("Variable assignments:");
-            for (name, info) in &self.variable_info {
-                println!("  {} -> offset {}", name, info.offset);
-            }
-            
-            // Générer le code du corps de la fonction main
-            for (i, stmt) in main_function.body.iter().enumerate() {
-                let stmt_code = self.generate_statement(stmt, i)?;
-                code.push_str(&stmt_code);
-            }
-            
-            // Épilogue - retourner 0
-            code.push_str("\n    ; Épilogue de main avec valeur de retour 0\n");
-            code.push_str("    mov eax, 0\n");  // Valeur de retour 0 pour indiquer la réussite
-            code.push_str("    mov rsp, rbp\n");
-            code.push_str("    pop rbp\n");
-            code.push_str("    ret\n\n");
-            
-            self.current_function = None;
+        if self.uses_bounds_check {
+            code.push_str(runtime::PANIC);
         }
-        
+        if self.uses_vec_runtime {
+            code.push_str(runtime::VEC);
+        }
+        if self.uses_string_runtime {
+            code.push_str(runtime::STRING);
+        }
+        if self.uses_map_runtime {
+            code.push_str(runtime::MAP);
+        }
+        if self.uses_format_runtime {
+            code.push_str(runtime::FORMAT);
+        }
+
         Ok(code)
     }
-    
-    fn generate_function(&mut self, function: &Function) -> Result<String, usize> {
+
+    // Les captures d'une fermeture sont lues dans son environnement, dont l'adresse arrive dans r10
+    fn generate_function(&mut self, function: &Function, label: &str, self_type: Option<Type>, captures: &[Capture]) -> Result<String, usize> {
         self.current_function = Some(function.name.clone());
+        self.types.self_type = self_type;
         self.variable_info.clear();
-        
-        let mut code = String::new();
-        
-        // Étiquette de la fonction
-        code.push_str(&format!("{}:\n", function.name));
-        
-        // Prologue de la fonction
-        code.push_str("    push rbp\n");
-        code.push_str("    mov rbp, rsp\n");
-        
-        // Enregistrer les paramètres de la fonction
-        if let Some(param_names) = self.function_params.get(&function.name) {
-            let registers = ["rdi", "rsi", "rdx", "rcx", "r8", "r9"];
-            
-            // Calculer l'espace nécessaire pour les variables locales et les paramètres
-            let total_local_vars = function.body.iter()
-                .filter(|stmt| matches!(stmt, Stmt::Let(_, _, _, _)))
-                .count();
-            
-            let stack_size = (total_local_vars + param_names.len()) * 8;
-            if stack_size > 0 {
-                code.push_str(&format!("    sub rsp, {}\n", stack_size));
+        self.types.variables.clear();
+        self.frame_size = 0;
+        self.types.current_line = function.line;
+        self.return_type = self.types.resolve_type(&function.return_type.clone().unwrap_or(Type::Void));
+        self.return_slot = None;
+        self.owns_values = false;
+        self.drop_scopes.clear();
+        self.loops.clear();
+        self.push_scope(true);
+
+        let is_main = label == "main";
+        let mut body = String::new();
+        if is_main {
+            match &self.return_type {
+                Type::Void => {},
+                Type::Result(ok, err) if **ok == Type::Void => self.types.check_debug(err, &mut Vec::new())?,
+                other => return self.error(&format!("`main` doit renvoyer `()` ou `Result<(), E>`, pas `{}`", self.types.display_type(other))),
             }
-            
-            // Sauvegarder les paramètres sur la pile
-            for (i, param_name) in param_names.iter().enumerate() {
-                let offset = (i + 1) * 8;
-                self.variable_info.insert(param_name.clone(), VarInfo {
-                    offset,
-                    var_type: Type::I32, // Default type for parameters
-                });
-                
-                if i < registers.len() {
-                    code.push_str(&format!("    mov QWORD [rbp-{}], {}\n", offset, registers[i]));
+        }
+
+        // Environnement d'une fermeture : chaque capture désigne sa variable (par référence) ou sa copie
+        // dans l'environnement (par valeur)
+        if !captures.is_empty() {
+            let env = self.alloc_slot(8);
+            body.push_str(&format!("    mov QWORD [rbp-{}], r10  ; Environnement\n", env));
+            let types: Vec<Type> = captures.iter().map(|(_, typ, by_move)| self.capture_field_type(typ, *by_move)).collect();
+            let (offsets, _, _) = self.fields_layout(&types);
+            for ((name, typ, by_move), offset) in captures.iter().zip(offsets) {
+                let slot = self.alloc_slot(8);
+                body.push_str(&format!("    mov rax, QWORD [rbp-{}]\n", env));
+                if *by_move {
+                    body.push_str(&format!("    add rax, {}\n", CLOSURE_ENV_HEADER + offset));
                 } else {
-                    // Les paramètres supplémentaires sont déjà sur la pile
-                    // Note: ceci est simplifié, il faudrait gérer la convention d'appel correctement
-                    code.push_str(&format!("    ; Paramètre {} sur la pile\n", param_name));
+                    body.push_str(&format!("    mov rax, QWORD [rax+{}]\n", CLOSURE_ENV_HEADER + offset));
                 }
+                body.push_str(&format!("    mov QWORD [rbp-{}], rax  ; Capture {}\n", slot, name));
+                self.declare_variable(name, slot, typ.clone());
+                self.variable_info.get_mut(name).expect("capture déclarée").indirect = true;
             }
-            
-            // Maintenant, réserver de l'espace pour les variables locales
-            let mut current_offset = param_names.len() * 8;
-            
-            for stmt in &function.body {
-                if let Stmt::Let(name, _, _, var_type) = stmt {
-                    let var_size = self.type_size(var_type);
-                    current_offset = Self::align_to_8_bytes(current_offset + var_size);
-                    
-                    self.variable_info.insert(name.clone(), VarInfo { 
-                        offset: current_offset, 
-                        var_type: var_type.clone() 
-                    });
-                }
-            }
-        } else {
-            // Pas de paramètres, juste gérer les variables locales
-            let local_vars_size = function.body.iter()
-                .filter(|stmt| matches!(stmt, Stmt::Let(_, _, _, _)))
-                .count() * 8;
-            
-            if local_vars_size > 0 {
-                code.push_str(&format!("    sub rsp, {}\n", local_vars_size));
+        }
+
+        // Valeur de retour de plus de 16 octets : l'appelant fournit l'adresse dans rdi
+        let mut incoming = 0;
+        if self.is_aggregate(&self.return_type) && self.type_size(&self.return_type) > 16 {
+            let slot = self.alloc_slot(8);
+            body.push_str(&format!("    mov QWORD [rbp-{}], rdi  ; Pointeur de retour caché\n", slot));
+            self.return_slot = Some(slot);
+            incoming = 1;
+        }
+
+        // Enregistrer les paramètres de la fonction
+        for (param_name, param_type) in &function.params {
+            let param_type = self.types.resolve_type(param_type);
+            let size = self.type_size(&param_type);
+            let offset = self.alloc_slot(size);
+
+            let source = if incoming < ARG_REGISTERS.len() {
+                ARG_REGISTERS[incoming].to_string()
+            } else {
+                // Les paramètres supplémentaires sont sur la pile, au-dessus de l'adresse de retour
+                body.push_str(&format!("    mov rax, QWORD [rbp+{}]  ; Paramètre {} sur la pile\n", 16 + (incoming - ARG_REGISTERS.len()) * 8, param_name));
+                "rax".to_string()
+            };
+            incoming += 1;
+
+            if self.is_aggregate(&param_type) {
+                // Les agrégats sont passés par pointeur vers une copie faite par l'appelant
+                body.push_str(&format!("    ; Copie du paramètre {}\n", param_name));
+                body.push_str(&Self::copy_memory(&source, &format!("rbp-{}", offset), size));
+            } else {
+                body.push_str(&format!("    mov QWORD [rbp-{}], {}  ; Paramètre {}\n", offset, source, param_name));
             }
-            
-            // Enregistrer les décalages des variables locales
-            let mut offset = 0;
-            for stmt in &function.body {
-                if let Stmt::Let(name, _, _, var_type) = stmt {
-                    let var_size = self.type_size(var_type);
-                    offset = Self::align_to_8_bytes(offset + var_size);
-                    
-                    self.variable_info.insert(name.clone(), VarInfo { 
-                        offset, 
-                        var_type: var_type.clone() 
-                    });
-                }
+
+            // Les paramètres sont détruits après les variables locales, comme s'ils étaient déclarés en premier
+            if self.needs_drop(&param_type) {
+                self.own(offset, param_type.clone());
             }
+            self.declare_variable(param_name, offset, param_type);
         }
-        
+
         // Corps de la fonction
-        for (i, stmt) in function.body.iter().enumerate() {
-            let stmt_code = self.generate_statement(stmt, i)?;
-            code.push_str(&stmt_code);
+        for stmt in &function.body {
+            body.push_str(&self.generate_statement(stmt)?);
         }
-        
+        let drops = self.pop_scope();
+
+        let mut code = String::new();
+
+        // Étiquette de la fonction
+        code.push_str(&format!("{}:\n", label));
+
+        // Prologue de la fonction (la pile reste alignée sur 16 octets pour les appels)
+        let frame_size = self.frame_size.next_multiple_of(16);
+        code.push_str("    push rbp\n");
+        code.push_str("    mov rbp, rsp\n");
+        code.push_str(&format!("    sub rsp, {}\n", frame_size));
+        if self.owns_values {
+            // Cadre mis à zéro : une valeur possédée pas encore initialisée n'a rien à détruire
+            let clear_label = self.new_label("L_frame_clear");
+            code.push_str(&format!("    mov r11, {}\n", frame_size));
+            code.push_str(&format!("{}:\n", clear_label));
+            code.push_str("    mov QWORD [rsp+r11-8], 0\n");
+            code.push_str("    sub r11, 8\n");
+            code.push_str(&format!("    jnz {}\n", clear_label));
+        }
+
+        code.push_str(&body);
+
         // Épilogue par défaut si aucun return explicite n'est trouvé
         code.push_str("\n    ; Épilogue de la fonction\n");
+        code.push_str(&drops);
+        if is_main {
+            if self.leak_check {
+                code.push_str("    call __leak_report\n");
+            }
+            code.push_str("    mov eax, 0\n");  // Valeur de retour 0 pour indiquer la réussite
+        }
         code.push_str("    mov rsp, rbp\n");
         code.push_str("    pop rbp\n");
         code.push_str("    ret\n\n");
-        
+
         self.current_function = None;
+        self.types.self_type = None;
         Ok(code)
     }
-    
-    fn generate_statement(&mut self, stmt: &Stmt, index: usize) -> Result<String, usize> {
-        let mut code = String::new();
-        
-        match stmt {
-            Stmt::Let(name, initializer, _mutable, _var_type) => {
-                let var_info = self.variable_info.get(name).cloned().unwrap_or_else(|| {
-                    self.error_handler.report_error(0, &format!("Variable non trouvée: {}", name));
-                    VarInfo { offset: 8 * (index + 1), var_type: Type::I32 }
-                });
-                
-                code.push_str(&format!("\n    ; Variable declaration: {}\n", name));
-                
-                if let Some(init_expr) = initializer {
-                    // Évaluer l'expression et la stocker dans rax
-                    code.push_str(&self.generate_expr_code(init_expr)?);
-                    // Stocker la valeur à l'emplacement approprié
-                    code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", var_info.offset));
+
+    // Rend une variable visible pour la génération de code et pour le typage
+    fn declare_variable(&mut self, name: &str, offset: usize, var_type: Type) {
+        self.types.variables.insert(name.to_string(), var_type.clone());
+        self.variable_info.insert(name.to_string(), VarInfo { offset, var_type, indirect: false });
+    }
+
+    // Adresse de l'octet `offset` d'une valeur filtrée, dans rax
+    fn place_address(place: Place, offset: usize) -> String {
+        match place {
+            Place::Frame(slot) => format!("    lea rax, [rbp-{}]\n", slot - offset),
+            Place::Pointer(slot) => format!("    mov rax, QWORD [rbp-{}]\n    add rax, {}\n", slot, offset),
+        }
+    }
+
+    // Évalue la valeur filtrée par `if let`/`while let` et la range dans un emplacement propre, pour que
+    // les variables du motif ne désignent pas la variable filtrée elle-même. Une valeur temporaire possédant
+    // des ressources est déplacée dans cet emplacement, possédé par la portée courante ; pour un lieu, la copie
+    // ne possède rien et l'adresse du lieu est aussi renvoyée, pour en retirer les parties liées par valeur.
+    fn generate_scrutinee(&mut self, value: &Expr, typ: &Type) -> Result<(String, Place, Option<usize>), usize> {
+        let slot = self.alloc_slot(self.type_size(typ));
+        if !self.needs_drop(typ) {
+            let mut code = self.generate_expr_code(value)?;
+            code.push_str(&self.store_to(typ, &format!("rbp-{}", slot)));
+            return Ok((code, Place::Frame(slot), None));
+        }
+
+        if let Expr::Variable(_) | Expr::FieldAccess(_, _) | Expr::TupleIndex(_, _) | Expr::Index(_, _) | Expr::Unary(UnaryOp::Deref, _) = value {
+            let address = self.alloc_slot(8);
+            let mut code = self.generate_address(value)?;
+            code.push_str(&format!("    mov QWORD [rbp-{}], rax  ; Lieu filtré\n", address));
+            code.push_str(&self.load_from(typ, "rax"));
+            code.push_str(&self.store_to(typ, &format!("rbp-{}", slot)));
+            return Ok((code, Place::Frame(slot), Some(address)));
+        }
+
+        let mut code = self.generate_owned(value, typ)?;
+        code.push_str(&self.store_to(typ, &format!("rbp-{}", slot)));
+        self.own(slot, typ.clone());
+        Ok((code, Place::Frame(slot), None))
+    }
+
+    // Parties d'une valeur filtrée liées par valeur et possédant des ressources : (décalage, type)
+    fn moved_parts(&self, pattern: &Pattern, typ: &Type, offset: usize, parts: &mut Vec<(usize, Type)>) {
+        match (pattern, typ) {
+            (Pattern::Binding(_, _), _) if self.needs_drop(typ) => parts.push((offset, typ.clone())),
+            (Pattern::Tuple(patterns), Type::Tuple(elements)) => {
+                let (offsets, _, _) = self.fields_layout(elements);
+                for ((pattern, element), element_offset) in patterns.iter().zip(elements).zip(offsets) {
+                    self.moved_parts(pattern, element, offset + element_offset, parts);
                 }
             },
-            Stmt::Return(expr) => {
-                code.push_str("\n    ; Return statement\n");
-                
-                if let Some(ret_expr) = expr {
-                    // Évaluer l'expression de retour et la mettre dans rax
-                    code.push_str(&self.generate_expr_code(ret_expr)?);
-                }
-                
-                // Épilogue de la fonction
-                code.push_str("    mov rsp, rbp\n");
-                code.push_str("    pop rbp\n");
-                code.push_str("    ret\n");
-            },
-            Stmt::Println(args) => {
-                if args.is_empty() {
-                    code.push_str("\n    ; println! (newline only)\n");
-                    code.push_str("    mov rdi, newline\n");
-                    code.push_str("    call printf\n");
-                } else if let Expr::Literal(Literal::String(format_str)) = &args[0] {
-                    let unknown_str = "unknown".to_string();
-                    let func_name = self.current_function.as_ref().unwrap_or(&unknown_str);
-                    
-                    code.push_str(&format!("\n    ; println!(\"{}\", ...)\n", format_str));
-                    
-                    // Stocker l'étiquette dans une variable avant de faire les appels qui empruntent self de manière mutable
-                    let label_value = if let Some(label) = self.format_labels.get(&(func_name.clone(), index)) {
-                        label.clone()
-                    } else {
-                        self.error_handler.report_error(0, &format!("Étiquette de format non trouvée pour println! à l'index {}", index));
-                        return Err(0);
-                    };
-                    
-                    // Évaluer tous les arguments en premier et les sauvegarder sur la pile
-                    // Attention: on empile en ordre inverse pour faciliter leur récupération
-                    for arg in args.iter().skip(1).rev() {
-                        code.push_str("\n    ; Évaluation d'un argument\n");
-                        code.push_str(&self.generate_expr_code(arg)?);
-                        code.push_str("    push rax  ; Sauvegarde de l'argument sur la pile\n");
-                    }
-                    
-                    // Registres dans l'ordre standard de la convention d'appel System V AMD64 ABI
-                    let registers = ["rsi", "rdx", "rcx", "r8", "r9"];
-                    
-                    // Récupérer les arguments dans l'ordre correct
-                    code.push_str("\n    ; Configuration des registres pour printf\n");
-                    for (i, _) in args.iter().skip(1).enumerate() {
-                        if i < registers.len() {
-                            code.push_str(&format!("    pop {}  ; Argument {}\n", registers[i], i + 1));
-                        } else {
-                            // Arguments supplémentaires restent sur la pile pour printf
-                            break;
-                        }
-                    }
-                    
-                    // Charger l'adresse du format en dernier pour ne pas l'écraser
-                    code.push_str(&format!("    lea rdi, [rel {}]  ; Format string\n", label_value));
-                    
-                    // Appel à printf
-                    code.push_str("    xor eax, eax  ; Pas de flottants\n");
-                    code.push_str("    call printf\n");
-                }
+            (Pattern::Variant(name, Some(payload)), Type::Option(inner)) if name == "Some" => {
+                let payload_offset = self.fields_layout(&[Type::Bool, (**inner).clone()]).0[1];
+                self.moved_parts(payload, inner, offset + payload_offset, parts);
             },
-            Stmt::Expression(expr) => {
-                code.push_str("\n    ; Expression statement\n");
-                code.push_str(&self.generate_expr_code(expr)?);
-                // Le résultat est ignoré
+            (Pattern::Variant(name, Some(payload)), Type::Result(ok, err)) => {
+                let payload_offset = self.result_layout(ok, err).0;
+                self.moved_parts(payload, if name == "Ok" { ok } else { err }, offset + payload_offset, parts);
             },
-            Stmt::For(var_name, range_start, range_end, body) => {
-                code.push_str("\n    ; For loop\n");
-                
-                // Create unique labels for loop control
-                let cond_label = format!("L_for_cond_{}", self.label_counter);
-                let end_label = format!("L_for_end_{}", self.label_counter);
-                self.label_counter += 1;
-                
-                // Get the variable offset (should already be assigned)
-                let var_offset = if let Some(var_info) = self.variable_info.get(var_name) {
-                    var_info.offset
-                } else {
-                    self.error_handler.report_error(0, &format!("Loop variable {} not found in variable_info", var_name));
-                    return Err(0);
-                };
-                
-                println!("For loop: variable {} at offset {}", var_name, var_offset);
-                
-                // Initialize loop variable with range_start
-                code.push_str(&self.generate_expr_code(range_start)?);
-                code.push_str(&format!("    mov DWORD [rbp-{}], eax  ; Initialize loop variable\n", var_offset));
-                
-                // Condition check - compare to range_end
-                code.push_str(&format!("{}:\n", cond_label));
-                code.push_str(&format!("    mov eax, DWORD [rbp-{}]  ; Load counter\n", var_offset));
-                code.push_str("    push rax\n"); // Save counter
-                code.push_str(&self.generate_expr_code(range_end)?); // Calculate end value
-                code.push_str("    mov ecx, eax  ; Move end value to ecx\n");
-                code.push_str("    pop rax\n");      // Restore counter to rax
-                code.push_str("    cmp eax, ecx  ; Compare counter with end\n");
-                code.push_str(&format!("    jge {}  ; Exit if counter >= end\n", end_label));
-                
-                // Body of the loop
-                if let Stmt::Block(stmts) = &**body {
-                    for (i, stmt) in stmts.iter().enumerate() {
-                        // Use nested indexing for proper statement label management
-                        let nested_index = index * 100 + i + 1;
-                        code.push_str(&self.generate_statement(stmt, nested_index)?);
-                    }
+            (Pattern::Variant(name, Some(payload)), Type::Named(_)) => {
+                let (_, fields) = self.variant_fields(name);
+                for (pattern, (field, field_offset)) in Self::field_patterns(payload, fields.len()).into_iter().zip(fields) {
+                    self.moved_parts(pattern, &field, offset + field_offset, parts);
                 }
-                
-                // Increment counter
-                code.push_str(&format!("    mov eax, DWORD [rbp-{}]  ; Load counter for increment\n", var_offset));
-                code.push_str("    add eax, 1  ; Increment\n");
-                code.push_str(&format!("    mov DWORD [rbp-{}], eax  ; Store incremented value\n", var_offset));
-                code.push_str(&format!("    jmp {}  ; Jump back to condition\n", cond_label));
-                
-                // End of loop
-                code.push_str(&format!("{}:\n", end_label));
             },
-            Stmt::If(condition, then_stmt, else_stmt) => {
-                code.push_str("\n    ; If statement\n");
-                
-                // Create unique labels for if control flow
-                let else_label = format!("L_if_else_{}", self.label_counter);
-                let end_label = format!("L_if_end_{}", self.label_counter);
-                self.label_counter += 1;
-                
-                // Generate condition evaluation
-                code.push_str(&self.generate_expr_code(condition)?);
-                
-                // Test if condition is false (0)
-                code.push_str("    test rax, rax  ; Test if condition is zero\n");
-                code.push_str(&format!("    jz {}  ; Jump to else if condition is false\n", 
-                    if else_stmt.is_some() { &else_label } else { &end_label }));
-                
-                // Generate then branch
-                code.push_str("\n    ; Then branch\n");
-                if let Stmt::Block(stmts) = &**then_stmt {
-                    for (i, stmt) in stmts.iter().enumerate() {
-                        let nested_index = index * 100 + i + 1;
-                        code.push_str(&self.generate_statement(stmt, nested_index)?);
-                    }
-                } else {
-                    let nested_index = index * 100 + 1;
-                    code.push_str(&self.generate_statement(then_stmt, nested_index)?);
+            _ => {},
+        }
+    }
+
+    // Après un filtrage réussi, les parties liées par valeur sont déplacées hors du lieu filtré (dont
+    // l'adresse est rangée à rbp-`address`) : la copie filtrée à rbp-`slot` les possède désormais
+    fn take_moved_parts(&mut self, pattern: &Pattern, typ: &Type, slot: usize, address: usize) -> String {
+        let mut parts = Vec::new();
+        self.moved_parts(pattern, typ, 0, &mut parts);
+        let mut code = String::new();
+        for (offset, part_type) in parts {
+            code.push_str(&format!("    mov rax, QWORD [rbp-{}]\n", address));
+            code.push_str(&self.vacate(&part_type, "rax", offset));
+            self.own(slot - offset, part_type);
+        }
+        code
+    }
+
+    // Arbre de décision d'un motif (déjà vérifié) : teste les étiquettes des variantes et les littéraux
+    // de la valeur de type `typ` située à l'octet `offset` de `place`, en sautant à `fail` au premier
+    // échec, puis lie les variables. Sous une référence (`by_ref`), les variables reçoivent l'adresse de
+    // leur élément ; sinon elles désignent directement leur élément, sans copie
+    fn generate_pattern_match(&mut self, pattern: &Pattern, typ: &Type, place: Place, offset: usize, by_ref: Option<bool>, fail: &str) -> Result<String, usize> {
+        let mut code = String::new();
+
+        match (pattern, typ) {
+            (Pattern::Wildcard, _) => {},
+            (Pattern::Or(alternatives), _) => {
+                // Chaque alternative ayant échoué passe à la suivante ; aucune ne lie de variable
+                let matched = self.new_label("L_or_matched");
+                let (last, others) = alternatives.split_last().expect("au moins deux alternatives");
+                for alternative in others {
+                    let next = self.new_label("L_or_next");
+                    code.push_str(&self.generate_pattern_match(alternative, typ, place, offset, by_ref, &next)?);
+                    code.push_str(&format!("    jmp {}\n{}:\n", matched, next));
                 }
-                
-                // Jump to end after then branch (skip else)
-                if else_stmt.is_some() {
-                    code.push_str(&format!("    jmp {}  ; Skip else branch\n", end_label));
+                code.push_str(&self.generate_pattern_match(last, typ, place, offset, by_ref, fail)?);
+                code.push_str(&format!("{}:\n", matched));
+            },
+            (Pattern::Binding(name, _), _) => match (place, by_ref) {
+                (Place::Frame(slot), None) => self.declare_variable(name, slot - offset, typ.clone()),
+                (_, Some(mutable)) => {
+                    let slot = self.alloc_slot(8);
+                    code.push_str(&Self::place_address(place, offset));
+                    code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", slot));
+                    self.declare_variable(name, slot, Type::Ref(Box::new(typ.clone()), mutable));
+                },
+                (Place::Pointer(_), None) => {
+                    let slot = self.alloc_slot(self.type_size(typ));
+                    code.push_str(&Self::place_address(place, offset));
+                    code.push_str(&self.load_from(typ, "rax"));
+                    code.push_str(&self.store_to(typ, &format!("rbp-{}", slot)));
+                    self.declare_variable(name, slot, typ.clone());
+                },
+            },
+            (Pattern::Ref(pattern), Type::Ref(inner, _)) => {
+                // &p : la valeur désignée est filtrée, ses variables en sont des copies
+                let slot = self.alloc_slot(8);
+                code.push_str(&Self::place_address(place, offset));
+                code.push_str("    mov rax, QWORD [rax]\n");
+                code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", slot));
+                code.push_str(&self.generate_pattern_match(pattern, inner, Place::Pointer(slot), 0, None, fail)?);
+            },
+            (_, Type::Ref(inner, mutable)) => {
+                // Motif non liant sur une référence : on filtre la valeur pointée
+                let slot = self.alloc_slot(8);
+                code.push_str(&Self::place_address(place, offset));
+                code.push_str("    mov rax, QWORD [rax]\n");
+                code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", slot));
+                let by_ref = Some(by_ref.unwrap_or(true) && *mutable);
+                code.push_str(&self.generate_pattern_match(pattern, inner, Place::Pointer(slot), 0, by_ref, fail)?);
+            },
+            (Pattern::Tuple(patterns), Type::Tuple(elements)) => {
+                let (offsets, _, _) = self.fields_layout(elements);
+                for ((pattern, element), element_offset) in patterns.iter().zip(elements).zip(offsets) {
+                    code.push_str(&self.generate_pattern_match(pattern, element, place, offset + element_offset, by_ref, fail)?);
                 }
-                
-                // Generate else branch if it exists
-                if let Some(else_stmt) = else_stmt {
-                    code.push_str(&format!("{}:\n", else_label));
-                    code.push_str("    ; Else branch\n");
-                    
-                    if let Stmt::Block(stmts) = &**else_stmt {
-                        for (i, stmt) in stmts.iter().enumerate() {
-                            let nested_index = index * 100 + i + 2; // +2 to differentiate from then branch
-                            code.push_str(&self.generate_statement(stmt, nested_index)?);
-                        }
-                    } else {
-                        let nested_index = index * 100 + 2;
-                        code.push_str(&self.generate_statement(else_stmt, nested_index)?);
-                    }
+            },
+            (Pattern::Variant(name, payload), Type::Option(_) | Type::Result(_, _)) => {
+                let (payload_offset, payload_type) = match typ {
+                    Type::Option(inner) => (self.fields_layout(&[Type::Bool, (**inner).clone()]).0[1], (**inner).clone()),
+                    Type::Result(ok, err) => {
+                        let (payload_offset, _, _) = self.result_layout(ok, err);
+                        (payload_offset, if name == "Ok" { (**ok).clone() } else { (**err).clone() })
+                    },
+                    _ => unreachable!(),
+                };
+                let tag = matches!(name.as_str(), "Some" | "Ok") as u8;
+                code.push_str(&Self::place_address(place, offset));
+                code.push_str(&format!("    cmp BYTE [rax], {}  ; Variante {}\n", tag, name));
+                code.push_str(&format!("    jne {}\n", fail));
+                if let Some(payload) = payload {
+                    code.push_str(&self.generate_pattern_match(payload, &payload_type, place, offset + payload_offset, by_ref, fail)?);
                 }
-                
-                // End label
-                code.push_str(&format!("{}:\n", end_label));
+            },
+            (Pattern::Variant(name, payload), Type::Named(_)) => {
+                let (index, fields) = self.variant_fields(name);
+                code.push_str(&Self::place_address(place, offset));
+                code.push_str(&format!("    cmp BYTE [rax], {}  ; Variante {}\n", index + 1, name));
+                code.push_str(&format!("    jne {}\n", fail));
+                if let Some(payload) = payload {
+                    for (pattern, (field, field_offset)) in Self::field_patterns(payload, fields.len()).into_iter().zip(fields) {
+                        code.push_str(&self.generate_pattern_match(pattern, &field, place, offset + field_offset, by_ref, fail)?);
+                    }
+                }
+            },
+            (Pattern::Int(_) | Pattern::Bool(_) | Pattern::Char(_), _) => {
+                let value = match pattern {
+                    Pattern::Int(value) => *value,
+                    Pattern::Bool(value) => *value as i64,
+                    Pattern::Char(value) => *value as i64,
+                    _ => unreachable!(),
+                };
+                code.push_str(&Self::place_address(place, offset));
+                code.push_str(&self.load_from(typ, "rax"));
+                code.push_str(&format!("    mov rcx, {}  ; Motif {}\n", value, pattern));
+                code.push_str("    cmp rax, rcx\n");
+                code.push_str(&format!("    jne {}\n", fail));
+            },
+            _ => {},
+        }
+
+        Ok(code)
+    }
+
+    fn generate_block(&mut self, stmts: &[Stmt]) -> Result<String, usize> {
+        // Les variables déclarées dans le bloc ne sont visibles que dans celui-ci
+        let saved_scope = (self.variable_info.clone(), self.types.variables.clone());
+        let mut code = String::new();
+        self.push_scope(true);
+
+        for stmt in stmts {
+            code.push_str(&self.generate_statement(stmt)?);
+        }
+
+        // Les variables du bloc sont détruites à sa sortie, dans l'ordre inverse de leur déclaration
+        code.push_str(&self.pop_scope());
+        (self.variable_info, self.types.variables) = saved_scope;
+        Ok(code)
+    }
+
+    // Condition d'un `if` ou d'un `while` : ses valeurs temporaires sont détruites avant le saut
+    fn generate_condition(&mut self, condition: &Expr) -> Result<String, usize> {
+        self.push_scope(false);
+        let mut code = self.generate_expr_code(condition)?;
+        let drops = self.pop_scope();
+        if !drops.is_empty() {
+            let value = self.alloc_slot(8);
+            code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", value));
+            code.push_str(&drops);
+            code.push_str(&format!("    mov rax, QWORD [rbp-{}]\n", value));
+        }
+        Ok(code)
+    }
+
+    fn generate_statement(&mut self, stmt: &Stmt) -> Result<String, usize> {
+        let mut code = String::new();
+        // Les valeurs temporaires de l'instruction sont détruites à sa fin
+        self.push_scope(false);
+
+        match stmt {
+            Stmt::Let(pattern, initializer, var_type, line) => {
+                self.types.current_line = *line;
+
+                let var_type = match (var_type, initializer) {
+                    (Some(var_type), _) => {
+                        let var_type = self.types.resolve_type(var_type);
+                        self.types.check_type(&var_type, *line)?;
+                        var_type
+                    },
+                    (None, Some(init_expr)) => self.types.expr_type(init_expr)?,
+                    (None, None) => Type::I32,
+                };
+
+                if let Type::Dyn(trait_name) = &var_type {
+                    return self.error(&format!("le type `dyn {}` n'a pas de taille connue : utilisez `&dyn {}` ou `Box<dyn {}>`", trait_name, trait_name, trait_name));
+                }
+                self.types.check_pattern(pattern, &var_type)?;
+
+                let size = self.type_size(&var_type);
+                let offset = self.alloc_slot(size);
+
+                code.push_str(&format!("\n    ; Variable declaration: {}\n", pattern));
+
+                let destructured_place = !matches!(pattern, Pattern::Binding(_, _)) && self.needs_drop(&var_type) && matches!(initializer,
+                    Some(Expr::Variable(_) | Expr::FieldAccess(_, _) | Expr::TupleIndex(_, _) | Expr::Index(_, _) | Expr::Unary(UnaryOp::Deref, _)));
+                if let (Some(init_expr), true) = (initializer, destructured_place) {
+                    // Déstructuration d'un lieu : seules les parties liées par valeur le quittent, les autres
+                    // restent utilisables
+                    let address = self.alloc_slot(8);
+                    code.push_str(&self.generate_address(init_expr)?);
+                    code.push_str(&format!("    mov QWORD [rbp-{}], rax  ; Lieu déstructuré\n", address));
+                    code.push_str(&self.load_from(&var_type, "rax"));
+                    code.push_str(&self.store_to(&var_type, &format!("rbp-{}", offset)));
+                    code.push_str(&self.take_moved_parts(pattern, &var_type, offset, address));
+                    let parts = std::mem::take(&mut self.drop_scopes.last_mut().expect("portée ouverte").values);
+                    for (slot, typ) in parts {
+                        self.own_in_block(slot, typ);
+                    }
+                } else if let Some(init_expr) = initializer {
+                    // Évaluer l'expression (valeur ou adresse dans rax)
+                    code.push_str(&self.generate_owned(init_expr, &var_type)?);
+                    // Stocker la valeur à l'emplacement approprié
+                    code.push_str(&self.store_to(&var_type, &format!("rbp-{}", offset)));
+
+                    // `let r = &valeur_temporaire;` : la valeur vit aussi longtemps que la variable
+                    if let Expr::Unary(UnaryOp::Ref | UnaryOp::RefMut, _) = init_expr {
+                        let temporaries = std::mem::take(&mut self.drop_scopes.last_mut().expect("portée ouverte").values);
+                        for (slot, typ) in temporaries {
+                            self.own_in_block(slot, typ);
+                        }
+                    }
+                }
+                if self.needs_drop(&var_type) && !destructured_place {
+                    self.own_in_block(offset, var_type.clone());
+                }
+
+                // Les variables ne sont visibles qu'après l'initialisation (masquage) ; le motif est
+                // irréfutable, l'étiquette d'échec n'est jamais utilisée
+                code.push_str(&self.generate_pattern_match(pattern, &var_type, Place::Frame(offset), 0, None, "")?);
+            },
+            Stmt::Return(expr, line) => {
+                self.types.current_line = *line;
+                code.push_str("\n    ; Return statement\n");
+
+                if let Some(ret_expr) = expr {
+                    // Évaluer l'expression de retour et la mettre dans rax
+                    let return_type = self.return_type.clone();
+                    code.push_str(&self.generate_owned(ret_expr, &return_type)?);
+                }
+                code.push_str(&self.generate_return()?);
+            },
+            Stmt::Break(line) | Stmt::Continue(line) => {
+                // Les valeurs créées depuis l'entrée dans le corps de la boucle sont détruites avant le saut
+                self.types.current_line = *line;
+                let target = self.loops.last().expect("boucle en cours");
+                let (depth, exit_drops) = (target.depth, target.exit_drops.clone());
+                let (keyword, label) = match stmt {
+                    Stmt::Break(_) => ("break", target.end_label.clone()),
+                    _ => ("continue", target.next_label.clone()),
+                };
+                code.push_str(&format!("\n    ; {}\n", keyword));
+                code.push_str(&self.drop_from(depth));
+                if keyword == "break" {
+                    code.push_str(&exit_drops);
+                }
+                code.push_str(&format!("    jmp {}\n", label));
+            },
+            Stmt::Print(format, stream, line) => {
+                self.types.current_line = *line;
+                code.push_str("\n    ; Écriture formatée\n");
+                let (format_code, buffer) = self.generate_format(format, None)?;
+                code.push_str(&format_code);
+                code.push_str(&format!("    mov rdi, QWORD [rbp-{}]\n", buffer));
+                code.push_str(match stream {
+                    Stream::Stdout => "    mov rsi, QWORD [rel stdout]\n",
+                    Stream::Stderr => "    mov rsi, QWORD [rel stderr]\n",
+                });
+                code.push_str("    call __fmt_write\n");
+            },
+            Stmt::Expression(expr, line) => {
+                self.types.current_line = *line;
+                code.push_str("\n    ; Expression statement\n");
+                code.push_str(&self.generate_expr_code(expr)?);
+                // Le résultat est ignoré
+            },
+            Stmt::Block(stmts) => {
+                code.push_str(&self.generate_block(stmts)?);
+            },
+            Stmt::For(pattern, iterable, body, line) => {
+                self.types.current_line = *line;
+                let element_type = self.types.iteration_type(iterable)?;
+                self.types.check_pattern(pattern, &element_type)?;
+                code.push_str(&self.generate_iterator_loop(pattern, iterable, body)?);
+            },
+            Stmt::While(condition, body, line) => {
+                self.types.current_line = *line;
+                code.push_str("\n    ; While loop\n");
+
+                let cond_label = format!("L_while_cond_{}", self.label_counter);
+                let end_label = format!("L_while_end_{}", self.label_counter);
+                self.label_counter += 1;
+
+                code.push_str(&format!("{}:\n", cond_label));
+                code.push_str(&self.generate_condition(condition)?);
+                code.push_str("    test rax, rax  ; Test if condition is zero\n");
+                code.push_str(&format!("    jz {}  ; Exit loop if condition is false\n", end_label));
+
+                let depth = self.drop_scopes.len();
+                let target = LoopTarget { next_label: cond_label.clone(), end_label: end_label.clone(), depth, exit_drops: String::new() };
+                code.push_str(&self.generate_loop_body(body, target)?);
+                code.push_str(&format!("    jmp {}  ; Jump back to condition\n", cond_label));
+                code.push_str(&format!("{}:\n", end_label));
+            },
+            Stmt::Match(value, arms, line) => {
+                self.types.current_line = *line;
+                let value_type = self.types.expr_type(value)?;
+                code.push_str("\n    ; Match\n");
+
+                // Les bras sont essayés dans l'ordre ; le match étant exhaustif, le dernier échec est impossible
+                let end_label = self.new_label("L_match_end");
+                let (value_code, place, source) = self.generate_scrutinee(value, &value_type)?;
+                code.push_str(&value_code);
+                for arm in arms {
+                    self.types.check_pattern(&arm.pattern, &value_type)?;
+                    let next_label = self.new_label("L_match_next");
+                    code.push_str(&format!("    ; {} =>\n", arm.pattern));
+
+                    // Les variables du motif n'existent que dans la garde et le corps du bras
+                    let saved_scope = (self.variable_info.clone(), self.types.variables.clone());
+                    code.push_str(&self.generate_pattern_match(&arm.pattern, &value_type, place, 0, None, &next_label)?);
+                    if let Some(guard) = &arm.guard {
+                        code.push_str(&self.generate_condition(guard)?);
+                        code.push_str("    test rax, rax\n");
+                        code.push_str(&format!("    jz {}  ; Garde fausse\n", next_label));
+                    }
+                    // Les parties liées par valeur sont détruites à la fin du bras
+                    self.push_scope(false);
+                    if let (Place::Frame(slot), Some(address)) = (place, source) {
+                        code.push_str(&self.take_moved_parts(&arm.pattern, &value_type, slot, address));
+                    }
+                    code.push_str(&self.generate_statement(&arm.body)?);
+                    code.push_str(&self.pop_scope());
+                    (self.variable_info, self.types.variables) = saved_scope;
+                    code.push_str(&format!("    jmp {}\n", end_label));
+                    code.push_str(&format!("{}:\n", next_label));
+                }
+                code.push_str(&format!("{}:\n", end_label));
+            },
+            Stmt::If(condition, then_stmt, else_stmt, line) => {
+                self.types.current_line = *line;
+                code.push_str("\n    ; If statement\n");
+
+                // Create unique labels for if control flow
+                let else_label = format!("L_if_else_{}", self.label_counter);
+                let end_label = format!("L_if_end_{}", self.label_counter);
+                self.label_counter += 1;
+
+                // Generate condition evaluation
+                code.push_str(&self.generate_condition(condition)?);
+
+                // Test if condition is false (0)
+                code.push_str("    test rax, rax  ; Test if condition is zero\n");
+                code.push_str(&format!("    jz {}  ; Jump to else if condition is false\n",
+                    if else_stmt.is_some() { &else_label } else { &end_label }));
+
+                // Generate then branch
+                code.push_str("\n    ; Then branch\n");
+                code.push_str(&self.generate_statement(then_stmt)?);
+
+                // Jump to end after then branch (skip else)
+                if else_stmt.is_some() {
+                    code.push_str(&format!("    jmp {}  ; Skip else branch\n", end_label));
+                }
+
+                // Generate else branch if it exists
+                if let Some(else_stmt) = else_stmt {
+                    code.push_str(&format!("{}:\n", else_label));
+                    code.push_str("    ; Else branch\n");
+                    code.push_str(&self.generate_statement(else_stmt)?);
+                }
+
+                // End label
+                code.push_str(&format!("{}:\n", end_label));
+            },
+        }
+
+        code.push_str(&self.pop_scope());
+        Ok(code)
+    }
+
+    // Renvoie la valeur de rax (adresse pour un agrégat) puis quitte la fonction, après avoir détruit les
+    // valeurs qu'elle possède encore. main renvoie le code de sortie du programme : 0, ou 1 après avoir écrit
+    // `Error: {:?}` sur la sortie d'erreur pour un `Err`
+    fn generate_return(&mut self) -> Result<String, usize> {
+        let return_type = self.return_type.clone();
+        let mut code = String::new();
+
+        let drops = self.drop_all();
+        if !drops.is_empty() && self.is_aggregate(&return_type) {
+            // Un agrégat peut désigner une partie d'une valeur détruite : il est d'abord copié
+            let size = self.type_size(&return_type);
+            let value = self.alloc_slot(size);
+            code.push_str(&Self::copy_memory("rax", &format!("rbp-{}", value), size));
+            code.push_str(&drops);
+            code.push_str(&format!("    lea rax, [rbp-{}]  ; Valeur renvoyée\n", value));
+        } else if !drops.is_empty() {
+            let value = self.alloc_slot(8);
+            code.push_str(&format!("    mov QWORD [rbp-{}], rax  ; Valeur renvoyée\n", value));
+            code.push_str(&drops);
+            code.push_str(&format!("    mov rax, QWORD [rbp-{}]\n", value));
+        }
+
+        if self.current_function.as_deref() == Some("main") && self.types.self_type.is_none() {
+            if let Type::Result(ok, err) = &return_type {
+                code.push_str(&self.generate_main_error(ok, err)?);
+            }
+            if self.leak_check {
+                code.push_str("    call __leak_report\n");
+            }
+            code.push_str("    mov eax, 0\n");
+        } else if self.is_aggregate(&return_type) {
+            let size = self.type_size(&return_type);
+            if let Some(slot) = self.return_slot {
+                // Copie vers la zone fournie par l'appelant
+                code.push_str(&format!("    mov rdx, QWORD [rbp-{}]  ; Pointeur de retour caché\n", slot));
+                code.push_str(&Self::copy_memory("rax", "rdx", size));
+                code.push_str("    mov rax, rdx\n");
+            } else {
+                // Jusqu'à 16 octets : retour dans rax:rdx
+                if size > 8 {
+                    code.push_str("    mov rdx, QWORD [rax+8]\n");
+                }
+                code.push_str("    mov rax, QWORD [rax]\n");
+            }
+        }
+
+        // Épilogue de la fonction
+        code.push_str("    mov rsp, rbp\n");
+        code.push_str("    pop rbp\n");
+        code.push_str("    ret\n");
+        Ok(code)
+    }
+
+    // `Err` renvoyé par main (adresse dans rax) : message d'erreur et code de sortie 1
+    fn generate_main_error(&mut self, ok: &Type, err: &Type) -> Result<String, usize> {
+        self.uses_vec_runtime = true;
+        self.uses_string_runtime = true;
+        self.uses_format_runtime = true;
+        let ok_label = self.new_label("L_main_ok");
+        let (result, value, formatter) = (self.alloc_slot(8), self.alloc_slot(8), self.alloc_slot(8));
+        let mut code = String::from("    cmp BYTE [rax], 0\n");
+        code.push_str(&format!("    jne {}\n", ok_label));
+        code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", result));
+        code.push_str(&format!("    add rax, {}\n", self.result_layout(ok, err).0));
+        code.push_str(&format!("    mov QWORD [rbp-{}], rax  ; Erreur renvoyée\n", value));
+        code.push_str(&self.generate_message_buffer(formatter));
+        code.push_str(&self.generate_format_text(&format!("QWORD [rbp-{}]", formatter), "Error: "));
+        code.push_str(&format!("    mov rax, QWORD [rbp-{}]\n", value));
+        code.push_str(&self.generate_debug_value(err, &format!("rbp-{}", formatter), &Self::debug_spec(), "0", "0")?);
+        code.push_str(&self.generate_format_text(&format!("QWORD [rbp-{}]", formatter), "\n"));
+        code.push_str(&format!("    mov rdi, QWORD [rbp-{}]\n", formatter));
+        code.push_str("    mov rsi, QWORD [rel stderr]\n");
+        code.push_str("    call __fmt_write\n");
+        if self.needs_drop(err) {
+            let result_type = Type::Result(Box::new(ok.clone()), Box::new(err.clone()));
+            code.push_str(&format!("    mov rdi, QWORD [rbp-{}]\n", result));
+            code.push_str(&format!("    call {}  ; Destruction de l'erreur\n", self.drop_function(&result_type)));
+        }
+        if self.leak_check {
+            code.push_str("    call __leak_report\n");
+        }
+        code.push_str("    mov eax, 1\n");
+        code.push_str("    mov rsp, rbp\n");
+        code.push_str("    pop rbp\n");
+        code.push_str("    ret\n");
+        code.push_str(&format!("{}:\n", ok_label));
+        Ok(code)
+    }
+
+    // String vide dont l'adresse est rangée à rbp-`formatter`, pour composer un message
+    fn generate_message_buffer(&mut self, formatter: usize) -> String {
+        let string = self.alloc_slot(VEC_SIZE);
+        let mut code = String::new();
+        for field in [0, VEC_LEN, VEC_CAP] {
+            code.push_str(&format!("    mov QWORD [rbp-{}], 0\n", string - field));
+        }
+        code.push_str(&format!("    lea rax, [rbp-{}]\n", string));
+        code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", formatter));
+        code
+    }
+
+    // Panique au message composé : le &str dont l'en-tête est pointé par [rbp-`message`] (expect), le texte
+    // `text`, puis la forme {:?} de la valeur de type `typ` pointée par [rbp-`value`]
+    fn generate_panic_message(&mut self, message: Option<usize>, text: &str, value: Option<(&Type, usize)>) -> Result<String, usize> {
+        self.uses_bounds_check = true;
+        self.uses_vec_runtime = true;
+        self.uses_string_runtime = true;
+        self.uses_format_runtime = true;
+        let formatter = self.alloc_slot(8);
+        let buffer = format!("QWORD [rbp-{}]", formatter);
+        let mut code = self.generate_message_buffer(formatter);
+        if let Some(message) = message {
+            code.push_str(&format!("    mov rax, QWORD [rbp-{}]\n", message));
+            code.push_str("    mov rsi, QWORD [rax]\n");
+            code.push_str("    mov rdx, QWORD [rax+8]\n");
+            code.push_str(&format!("    mov rdi, {}\n", buffer));
+            code.push_str("    mov ecx, 1\n");
+            code.push_str("    call __vec_extend\n");
+        }
+        if !text.is_empty() {
+            code.push_str(&self.generate_format_text(&buffer, text));
+        }
+        if let Some((typ, value)) = value {
+            self.types.check_debug(typ, &mut Vec::new())?;
+            code.push_str(&format!("    mov rax, QWORD [rbp-{}]\n", value));
+            code.push_str(&self.generate_debug_value(typ, &format!("rbp-{}", formatter), &Self::debug_spec(), "0", "0")?);
+        }
+        code.push_str(&format!("    mov edi, {}\n", self.types.current_line));
+        code.push_str("    lea rsi, [rel panic_message]\n");
+        code.push_str(&format!("    mov rax, {}\n", buffer));
+        code.push_str("    mov rdx, QWORD [rax+8]\n");
+        code.push_str("    mov rcx, QWORD [rax]\n");
+        code.push_str("    call __panic\n");
+        Ok(code)
+    }
+
+    // Boucle for sur une collection ou une chaîne d'itérateurs : l'élément suivant est calculé en tête
+    // de boucle, puis lié au motif
+    fn generate_iterator_loop(&mut self, pattern: &Pattern, iterable: &Expr, body: &Stmt) -> Result<String, usize> {
+        let (next_label, end_label) = (self.new_label("L_for_next"), self.new_label("L_for_end"));
+        let iterator = self.generate_iterator(iterable, &end_label)?;
+        let mut code = String::from("\n    ; For loop sur un itérateur\n");
+        code.push_str(&iterator.setup);
+        code.push_str(&format!("{}:\n", next_label));
+        code.push_str(&iterator.next);
+
+        // Les variables de la boucle n'existent que dans le corps
+        let saved_scope = (self.variable_info.clone(), self.types.variables.clone());
+        // L'élément reçu par valeur est détruit à la fin de chaque tour, et en sortant par `break`
+        let continue_label = self.new_label("L_for_continue");
+        let item_drop = if self.needs_drop(&iterator.item_type) {
+            self.drop_at(&iterator.item_type, &format!("rbp-{}", iterator.item))
+        } else {
+            String::new()
+        };
+        let target = LoopTarget { next_label: continue_label.clone(), end_label: end_label.clone(), depth: self.drop_scopes.len(), exit_drops: item_drop.clone() };
+        let body_code = self.generate_pattern_match(pattern, &iterator.item_type, Place::Frame(iterator.item), 0, None, &end_label)
+            .and_then(|binding| Ok(binding + &self.generate_loop_body(body, target)?));
+        (self.variable_info, self.types.variables) = saved_scope;
+        code.push_str(&body_code?);
+        code.push_str(&format!("{}:\n", continue_label));
+        code.push_str(&item_drop);
+
+        code.push_str(&format!("    jmp {}\n", next_label));
+        code.push_str(&format!("{}:\n", end_label));
+        Ok(code)
+    }
+
+    // Corps d'une boucle, où break et continue visent `target`
+    fn generate_loop_body(&mut self, body: &Stmt, target: LoopTarget) -> Result<String, usize> {
+        self.loops.push(target);
+        let code = self.generate_statement(body);
+        self.loops.pop();
+        code
+    }
+
+    // Fond une chaîne d'itérateurs, ou une collection parcourue comme par une boucle for (par valeur, ou
+    // par référence à travers une référence) : la source puis chaque adaptateur ajoutent leurs
+    // instructions au calcul de l'élément suivant, qui saute à `done` quand l'itérateur est épuisé
+    fn generate_iterator(&mut self, iterator: &Expr, done: &str) -> Result<FusedIterator, usize> {
+        if let Expr::MethodCall(receiver, method_name, _, args) = iterator {
+            let receiver_type = self.types.expr_type(receiver)?;
+            match IteratorMethod::from_name(method_name) {
+                // rev() parcourt sa source depuis la fin
+                Some(IteratorMethod::Adapter(Adapter::Rev)) => return self.generate_source(receiver, true, done),
+                Some(IteratorMethod::Adapter(adapter)) if TypeChecker::iterator_item(&receiver_type, method_name).is_some() => {
+                    return self.generate_adapter(receiver, adapter, args, done);
+                },
+                _ => {},
+            }
+        }
+        self.generate_source(iterator, false, done)
+    }
+
+    // Source d'une chaîne d'itérateurs : plage, méthode d'une collection, d'une table ou d'une chaîne,
+    // ou collection
+    fn generate_source(&mut self, source: &Expr, reversed: bool, done: &str) -> Result<FusedIterator, usize> {
+        let item_type = self.types.iteration_type(source)?;
+        let source_type = self.types.expr_type(source)?;
+        if let Type::Range(_, _) = source_type {
+            return self.generate_range_iterator(source, reversed, done);
+        }
+        if let Type::HashMap(_, _) | Type::HashSet(_) = TypeChecker::builtin_receiver(&source_type, "iter") {
+            return self.generate_map_iterator(source, None, item_type, reversed, done);
+        }
+
+        let Expr::MethodCall(receiver, method_name, _, args) = source else {
+            return self.generate_sequence_iterator(source, item_type, reversed, done);
+        };
+        if let (Type::HashMap(_, _) | Type::HashSet(_), Some(IteratorMethod::Source(part)))
+            = (TypeChecker::builtin_receiver(&self.types.expr_type(receiver)?, method_name), IteratorMethod::from_name(method_name)) {
+            return self.generate_map_iterator(receiver, Some(part), item_type, reversed, done);
+        }
+        match IteratorMethod::from_name(method_name) {
+            Some(IteratorMethod::Source(Source::Chars | Source::Bytes | Source::Split)) => {
+                // Les morceaux de la chaîne sont rassemblés dans un vecteur, parcouru par valeur puis libéré
+                let mut setup = self.generate_string_method(receiver, method_name, &[], args)?;
+                setup.push_str(&self.hold(&Type::Vec(Box::new(item_type.clone()))));
+                setup.push_str(&format!("    mov rdx, QWORD [rax+{}]  ; Longueur\n", VEC_LEN));
+                setup.push_str("    mov rax, QWORD [rax]  ; Adresse des éléments\n");
+                Ok(self.sequence_iterator(setup, &item_type.clone(), item_type, reversed, done))
+            },
+            Some(IteratorMethod::Source(_)) => self.generate_sequence_iterator(receiver, item_type, reversed, done),
+            _ => self.generate_sequence_iterator(source, item_type, reversed, done),
+        }
+    }
+
+    // Table parcourue entrée par entrée, dans l'ordre de ses entrées : par référence (clé, valeur, ou les
+    // deux), ou par valeur après avoir été déplacée dans un emplacement de la boucle, chaque entrée en
+    // étant retirée à son tour
+    fn generate_map_iterator(&mut self, table_expr: &Expr, part: Option<Source>, item_type: Type, reversed: bool, done: &str) -> Result<FusedIterator, usize> {
+        if reversed {
+            return self.error("rev() n'est pas disponible sur le parcours d'une table, dont l'ordre n'est pas défini");
+        }
+        let table_type = self.types.expr_type(table_expr)?;
+        let table = TypeChecker::builtin_receiver(&table_type, "iter").clone();
+        let value = Self::table_parts(&table).1;
+        let (value_offset, slot_size) = self.map_layout(&table);
+        let by_value = item_type == TypeChecker::table_item(&table, None);
+
+        let (address, index) = (self.alloc_slot(8), self.alloc_slot(8));
+        let item = self.alloc_slot(self.type_size(&item_type));
+        let mut setup = String::from("    ; Parcours d'une table\n");
+        if by_value {
+            let moved = self.alloc_slot(VEC_SIZE);
+            setup.push_str(&self.generate_owned(table_expr, &table)?);
+            setup.push_str(&self.store_to(&table, &format!("rbp-{}", moved)));
+            self.own(moved, table.clone());
+            setup.push_str(&format!("    lea rax, [rbp-{}]\n", moved));
+        } else {
+            setup.push_str(&self.generate_receiver(table_expr)?.0);
+        }
+        setup.push_str(&format!("    mov QWORD [rbp-{}], rax  ; Adresse de la table\n", address));
+        setup.push_str(&format!("    mov QWORD [rbp-{}], 0  ; Index\n", index));
+
+        let mut next = String::new();
+        if by_value && self.needs_drop(&item_type) {
+            // L'élément précédent, s'il n'a pas été déplacé, est détruit avant d'être remplacé
+            self.own(item, item_type.clone());
+            next.push_str(&self.drop_at(&item_type, &format!("rbp-{}", item)));
+        }
+        let retry = self.new_label("L_map_next");
+        next.push_str(&format!("{}:\n", retry));
+        next.push_str(&format!("    mov rcx, QWORD [rbp-{}]\n", index));
+        next.push_str(&format!("    mov rdx, QWORD [rbp-{}]\n", address));
+        next.push_str(&format!("    cmp rcx, QWORD [rdx+{}]\n", VEC_CAP));
+        next.push_str(&format!("    jae {}\n", done));
+        next.push_str(&format!("    inc QWORD [rbp-{}]\n", index));
+        next.push_str(&format!("    imul rax, rcx, {}\n", slot_size));
+        next.push_str("    add rax, QWORD [rdx]\n");
+        next.push_str(&format!("    cmp QWORD [rax], 0  ; Entrée libre\n    je {}\n", retry));
+        match (part, &value) {
+            (Some(Source::Keys), _) | (_, None) if !by_value => {
+                next.push_str(&format!("    add rax, {}\n", MAP_KEY));
+                next.push_str(&format!("    mov QWORD [rbp-{}], rax  ; Référence à la clé\n", item));
+            },
+            (Some(Source::Values), _) => {
+                next.push_str(&format!("    add rax, {}\n", value_offset));
+                next.push_str(&format!("    mov QWORD [rbp-{}], rax  ; Référence à la valeur\n", item));
+            },
+            (_, Some(_)) if !by_value => {
+                next.push_str(&format!("    lea rcx, [rax+{}]\n", MAP_KEY));
+                next.push_str(&format!("    mov QWORD [rbp-{}], rcx  ; Référence à la clé\n", item));
+                next.push_str(&format!("    add rax, {}\n", value_offset));
+                next.push_str(&format!("    mov QWORD [rbp-{}], rax  ; Référence à la valeur\n", item - 8));
+            },
+            _ => {
+                // L'entrée est retirée de la table déplacée : libre, elle ne sera pas détruite avec elle
+                let parts: Vec<(Type, usize)> = match &item_type {
+                    Type::Tuple(elements) => elements.iter().cloned().zip(self.fields_layout(elements).0).collect(),
+                    element => vec![(element.clone(), 0)],
+                };
+                next.push_str("    mov rcx, rax\n");
+                for ((part_type, offset), stored) in parts.iter().zip([MAP_KEY, value_offset]) {
+                    next.push_str(&self.load_from(part_type, &format!("rcx+{}", stored)));
+                    next.push_str(&self.store_to(part_type, &format!("rbp-{}", item - offset)));
+                }
+                next.push_str("    mov QWORD [rcx], 0\n");
+            },
+        }
+        Ok(FusedIterator { setup, next, item, item_type })
+    }
+
+    // Plage a..b ou a..=b : les bornes sont évaluées une seule fois, puis l'entier courant est comparé à
+    // la borne atteinte en dernier (la fin, ou le début en sens inverse)
+    fn generate_range_iterator(&mut self, range: &Expr, reversed: bool, done: &str) -> Result<FusedIterator, usize> {
+        let Type::Range(element, inclusive) = self.types.expr_type(range)? else { unreachable!() };
+        let (current, limit) = (self.alloc_slot(8), self.alloc_slot(8));
+        let item = self.alloc_slot(self.type_size(&element));
+        let (first, last) = if reversed { (limit, current) } else { (current, limit) };
+
+        let mut setup = String::from("    ; Plage\n");
+        if let Expr::Range(Some(start), Some(end), _) = range {
+            setup.push_str(&self.generate_expr_code(start)?);
+            setup.push_str(&format!("    mov QWORD [rbp-{}], rax  ; Début\n", first));
+            setup.push_str(&self.generate_expr_code(end)?);
+            setup.push_str(&format!("    mov QWORD [rbp-{}], rax  ; Fin\n", last));
+        } else {
+            let (offsets, _, _) = self.fields_layout(&[(*element).clone(), (*element).clone()]);
+            let address = self.alloc_slot(8);
+            setup.push_str(&self.generate_expr_code(range)?);
+            setup.push_str(&format!("    mov QWORD [rbp-{}], rax\n", address));
+            setup.push_str(&self.load_from(&element, "rax"));
+            setup.push_str(&format!("    mov QWORD [rbp-{}], rax  ; Début\n", first));
+            setup.push_str(&format!("    mov rax, QWORD [rbp-{}]\n", address));
+            setup.push_str(&self.load_from(&element, &format!("rax+{}", offsets[1])));
+            setup.push_str(&format!("    mov QWORD [rbp-{}], rax  ; Fin\n", last));
+        }
+        if reversed && !inclusive {
+            setup.push_str(&format!("    dec QWORD [rbp-{}]  ; Dernier élément\n", current));
+        }
+
+        let exhausted = match (reversed, inclusive) {
+            (false, false) => "jge",
+            (false, true) => "jg",
+            (true, _) => "jl",
+        };
+        let mut next = format!("    mov rax, QWORD [rbp-{}]\n", current);
+        next.push_str(&format!("    cmp rax, QWORD [rbp-{}]\n", limit));
+        next.push_str(&format!("    {} {}\n", exhausted, done));
+        next.push_str(&self.store_to(&element, &format!("rbp-{}", item)));
+        next.push_str(&format!("    {} QWORD [rbp-{}]\n", if reversed { "dec" } else { "inc" }, current));
+        Ok(FusedIterator { setup, next, item, item_type: *element })
+    }
+
+    // Vecteur, tableau ou tranche parcouru élément par élément
+    fn generate_sequence_iterator(&mut self, sequence: &Expr, item_type: Type, reversed: bool, done: &str) -> Result<FusedIterator, usize> {
+        let (Type::Array(stored_type, _) | Type::Vec(stored_type) | Type::Slice(stored_type)) = self.types.indexed_type(sequence)?.0 else {
+            unreachable!()
+        };
+        let sequence_type = self.types.expr_type(sequence)?;
+        let mut setup = String::new();
+        if item_type == *stored_type && self.needs_drop(&stored_type) && matches!(sequence_type, Type::Vec(_) | Type::Array(_, _)) {
+            // Collection parcourue par valeur dont les éléments possèdent des ressources : elle est déplacée
+            // dans un emplacement de la boucle, chaque élément en est retiré à son tour, et la boucle
+            // détruit ce qui reste de la collection
+            let size = self.type_size(&sequence_type);
+            let moved = self.alloc_slot(size);
+            setup.push_str(&self.generate_owned(sequence, &sequence_type)?);
+            setup.push_str(&self.store_to(&sequence_type, &format!("rbp-{}", moved)));
+            self.own(moved, sequence_type.clone());
+            match sequence_type {
+                Type::Array(_, length) => {
+                    setup.push_str(&format!("    lea rax, [rbp-{}]\n", moved));
+                    setup.push_str(&format!("    mov edx, {}\n", length));
+                },
+                _ => setup.push_str(&format!("    mov rdx, QWORD [rbp-{}]\n    mov rax, QWORD [rbp-{}]\n", moved - VEC_LEN, moved)),
+            }
+        } else if let (Type::Array(_, length), true) = (&sequence_type, item_type == *stored_type) {
+            // Un tableau parcouru par valeur est d'abord copié : le corps peut modifier l'original
+            let size = self.type_size(&sequence_type);
+            let copy = self.alloc_slot(size);
+            setup.push_str(&self.generate_expr_code(sequence)?);
+            setup.push_str(&Self::copy_memory("rax", &format!("rbp-{}", copy), size));
+            setup.push_str(&format!("    lea rax, [rbp-{}]\n", copy));
+            setup.push_str(&format!("    mov edx, {}\n", length));
+        } else {
+            setup.push_str(&self.generate_sequence(sequence)?);
+        }
+        Ok(self.sequence_iterator(setup, &stored_type, item_type, reversed, done))
+    }
+
+    // Parcours par index des éléments dont `setup` laisse l'adresse dans rax et le nombre dans rdx ;
+    // l'élément produit est une copie de l'élément rangé, ou une référence à celui-ci
+    fn sequence_iterator(&mut self, mut setup: String, stored_type: &Type, item_type: Type, reversed: bool, done: &str) -> FusedIterator {
+        let (data, length, index) = (self.alloc_slot(8), self.alloc_slot(8), self.alloc_slot(8));
+        let item = self.alloc_slot(self.type_size(&item_type));
+        setup.push_str(&format!("    mov QWORD [rbp-{}], rax  ; Adresse des éléments\n", data));
+        setup.push_str(&format!("    mov QWORD [rbp-{}], rdx  ; Longueur\n", length));
+
+        let mut next = String::new();
+        if reversed {
+            // L'index part de la longueur et désigne l'élément qui le précède
+            setup.push_str(&format!("    mov QWORD [rbp-{}], rdx  ; Index\n", index));
+            next.push_str(&format!("    cmp QWORD [rbp-{}], 0\n", index));
+            next.push_str(&format!("    jle {}\n", done));
+            next.push_str(&format!("    dec QWORD [rbp-{}]\n", index));
+            next.push_str(&format!("    mov rcx, QWORD [rbp-{}]\n", index));
+        } else {
+            setup.push_str(&format!("    mov QWORD [rbp-{}], 0  ; Index\n", index));
+            next.push_str(&format!("    mov rcx, QWORD [rbp-{}]\n", index));
+            next.push_str(&format!("    cmp rcx, QWORD [rbp-{}]\n", length));
+            next.push_str(&format!("    jge {}\n", done));
+            next.push_str(&format!("    inc QWORD [rbp-{}]\n", index));
+        }
+        if item_type == *stored_type && self.needs_drop(stored_type) {
+            // L'élément précédent, s'il n'a pas été déplacé, est détruit avant d'être remplacé
+            self.own(item, item_type.clone());
+            next.push_str(&self.drop_at(stored_type, &format!("rbp-{}", item)));
+            next.push_str(&format!("    mov rcx, QWORD [rbp-{}]\n", index));
+            if !reversed {
+                next.push_str("    dec rcx\n");
+            }
+        }
+        next.push_str(&format!("    mov rax, QWORD [rbp-{}]\n", data));
+        next.push_str(&Self::element_address(self.type_size(stored_type)));
+        if item_type == *stored_type && self.needs_drop(stored_type) {
+            // L'élément est retiré de la collection déplacée
+            next.push_str("    mov rcx, rax\n");
+            next.push_str(&self.load_from(stored_type, "rcx"));
+            next.push_str(&self.store_to(stored_type, &format!("rbp-{}", item)));
+            next.push_str(&Self::zero_memory("rcx", self.type_size(stored_type)));
+        } else if item_type == *stored_type {
+            next.push_str(&self.load_from(stored_type, "rax"));
+            next.push_str(&self.store_to(stored_type, &format!("rbp-{}", item)));
+        } else {
+            next.push_str(&format!("    mov QWORD [rbp-{}], rax  ; Référence à l'élément\n", item));
+        }
+        FusedIterator { setup, next, item, item_type }
+    }
+
+    // Adaptateur appliqué à l'itérateur `receiver`
+    fn generate_adapter(&mut self, receiver: &Expr, adapter: Adapter, args: &[Expr], done: &str) -> Result<FusedIterator, usize> {
+        let mut iterator = self.generate_iterator(receiver, done)?;
+        match adapter {
+            Adapter::Map => {
+                let (callee_code, callee, is_closure, _, output) = self.generate_callee(&args[0])?;
+                iterator.setup.push_str(&callee_code);
+                let result = self.alloc_slot(self.type_size(&output));
+                if self.needs_drop(&output) {
+                    // Le résultat précédent, s'il n'a pas été déplacé, est détruit avant d'être remplacé
+                    self.own(result, output.clone());
+                    iterator.next.push_str(&self.drop_at(&output, &format!("rbp-{}", result)));
+                }
+                let call = self.generate_item_call(callee, is_closure, &[(iterator.item_type.clone(), iterator.item)], &output);
+                iterator.next.push_str(&call);
+                iterator.next.push_str(&self.store_to(&output, &format!("rbp-{}", result)));
+                iterator.item = result;
+                iterator.item_type = output;
+            },
+            Adapter::Filter => {
+                // Le prédicat reçoit une référence à l'élément ; un élément refusé fait passer au suivant
+                let (callee_code, callee, is_closure, _, _) = self.generate_callee(&args[0])?;
+                iterator.setup.push_str(&callee_code);
+                let reference = self.alloc_slot(8);
+                iterator.setup.push_str(&format!("    lea rax, [rbp-{}]\n", iterator.item));
+                iterator.setup.push_str(&format!("    mov QWORD [rbp-{}], rax  ; Référence à l'élément filtré\n", reference));
+                let retry = self.new_label("L_filter");
+                let reference_type = Type::Ref(Box::new(iterator.item_type.clone()), false);
+                let call = self.generate_item_call(callee, is_closure, &[(reference_type, reference)], &Type::Bool);
+                iterator.next = format!("{}:\n{}{}    test al, al\n    jz {}\n", retry, iterator.next, call, retry);
+            },
+            Adapter::Enumerate => {
+                let counter = self.alloc_slot(8);
+                iterator.setup.push_str(&format!("    mov QWORD [rbp-{}], 0  ; Index d'enumerate\n", counter));
+                let index = self.alloc_slot(4);
+                iterator.next.push_str(&format!("    mov rax, QWORD [rbp-{}]\n", counter));
+                iterator.next.push_str(&format!("    mov DWORD [rbp-{}], eax\n", index));
+                iterator.next.push_str(&format!("    inc QWORD [rbp-{}]\n", counter));
+                iterator = self.pair_items(iterator, (Type::I32, index), String::new(), true);
+            },
+            Adapter::Zip => {
+                let other = self.generate_iterator(&args[0], done)?;
+                iterator.next.push_str(&other.next);
+                iterator = self.pair_items(iterator, (other.item_type, other.item), other.setup, false);
+            },
+            Adapter::Take => {
+                let remaining = self.alloc_slot(8);
+                iterator.setup.push_str(&self.generate_expr_code(&args[0])?);
+                iterator.setup.push_str(&format!("    mov QWORD [rbp-{}], rax  ; Éléments restants\n", remaining));
+                iterator.next = format!("    cmp QWORD [rbp-{}], 0\n    jle {}\n    dec QWORD [rbp-{}]\n{}", remaining, done, remaining, iterator.next);
+            },
+            Adapter::Skip => {
+                let skipped = self.alloc_slot(8);
+                iterator.setup.push_str(&self.generate_expr_code(&args[0])?);
+                iterator.setup.push_str(&format!("    mov QWORD [rbp-{}], rax  ; Éléments à sauter\n", skipped));
+                let (retry, kept) = (self.new_label("L_skip"), self.new_label("L_skip_done"));
+                iterator.next = format!("{}:\n{}    cmp QWORD [rbp-{}], 0\n    jle {}\n    dec QWORD [rbp-{}]\n    jmp {}\n{}:\n",
+                    retry, iterator.next, skipped, kept, skipped, retry, kept);
+            },
+            Adapter::StepBy => {
+                // Après chaque élément produit, les step - 1 suivants sont sautés
+                let (step, skipped) = (self.alloc_slot(8), self.alloc_slot(8));
+                let valid = self.new_label("L_step_valid");
+                iterator.setup.push_str(&self.generate_expr_code(&args[0])?);
+                iterator.setup.push_str(&format!("    mov QWORD [rbp-{}], rax  ; Pas\n", step));
+                iterator.setup.push_str(&format!("    cmp QWORD [rbp-{}], 0\n    jg {}\n", step, valid));
+                iterator.setup.push_str(&self.generate_panic_message(None, "assertion failed: step != 0", None)?);
+                iterator.setup.push_str(&format!("{}:\n    mov QWORD [rbp-{}], 0\n", valid, skipped));
+                let (retry, kept) = (self.new_label("L_step"), self.new_label("L_step_done"));
+                iterator.next = format!("{}:\n{}    cmp QWORD [rbp-{}], 0\n    jle {}\n    dec QWORD [rbp-{}]\n    jmp {}\n{}:\n",
+                    retry, iterator.next, skipped, kept, skipped, retry, kept);
+                iterator.next.push_str(&format!("    mov rax, QWORD [rbp-{}]\n    dec rax\n", step));
+                iterator.next.push_str(&format!("    mov QWORD [rbp-{}], rax\n", skipped));
+            },
+            Adapter::Rev => unreachable!(), // Appliqué à la source par generate_iterator
+        }
+        Ok(iterator)
+    }
+
+    // Élément de `iterator` apparié à un autre élément dans un tuple, construit après le calcul des deux ;
+    // l'autre élément vient en premier pour enumerate, en second pour zip
+    fn pair_items(&mut self, mut iterator: FusedIterator, other: (Type, usize), other_setup: String, other_first: bool) -> FusedIterator {
+        let mut items = [(iterator.item_type.clone(), iterator.item), other];
+        if other_first {
+            items.reverse();
+        }
+        let elements: Vec<Type> = items.iter().map(|(typ, _)| typ.clone()).collect();
+        let (offsets, size, _) = self.fields_layout(&elements);
+        let tuple = self.alloc_slot(size);
+        let tuple_type = Type::Tuple(elements);
+        iterator.setup.push_str(&other_setup);
+        if self.needs_drop(&tuple_type) {
+            self.own(tuple, tuple_type.clone());
+            iterator.next.push_str(&self.drop_at(&tuple_type, &format!("rbp-{}", tuple)));
+        }
+        for ((typ, slot), offset) in items.iter().zip(offsets) {
+            iterator.next.push_str(&self.load_from(typ, &format!("rbp-{}", slot)));
+            iterator.next.push_str(&self.store_to(typ, &format!("rbp-{}", tuple - offset)));
+            // Un élément possédant des ressources est déplacé dans le tuple
+            if self.needs_drop(typ) {
+                iterator.next.push_str(&Self::zero_memory(&format!("rbp-{}", slot), self.type_size(typ)));
+            }
+        }
+        iterator.item = tuple;
+        iterator.item_type = tuple_type;
+        iterator
+    }
+
+    // Appelle la fonction rangée par generate_callee avec des éléments rangés dans le cadre, passés comme
+    // des arguments ordinaires (copie pour un agrégat) ; le résultat est dans rax
+    fn generate_item_call(&mut self, callee: usize, is_closure: bool, items: &[(Type, usize)], output: &Type) -> String {
+        let mut code = String::new();
+        let mut slots = Vec::new();
+        for (typ, slot) in items {
+            code.push_str(&self.load_from(typ, &format!("rbp-{}", slot)));
+            if self.is_aggregate(typ) {
+                let size = self.type_size(typ);
+                let copy = self.alloc_slot(size);
+                code.push_str(&Self::copy_memory("rax", &format!("rbp-{}", copy), size));
+                code.push_str(&format!("    lea rax, [rbp-{}]\n", copy));
+            }
+            // Un élément possédant des ressources est déplacé vers la fonction appelée
+            if self.needs_drop(typ) {
+                code.push_str(&Self::zero_memory(&format!("rbp-{}", slot), self.type_size(typ)));
+            }
+            let argument = self.alloc_slot(8);
+            code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", argument));
+            slots.push(argument);
+        }
+        code.push_str(&Self::load_callee(callee, is_closure));
+        self.emit_call(&mut code, &slots, "r11", output);
+        code
+    }
+
+    // Valeur scalaire d'un élément rangé à `addr`, à travers ses références, dans rax
+    fn load_scalar(&self, typ: &Type, addr: &str) -> String {
+        self.load_from(typ, addr) + &self.deref_scalar(typ)
+    }
+
+    // Remplace dans rax une référence (éventuellement multiple) à un scalaire par la valeur désignée
+    fn deref_scalar(&self, typ: &Type) -> String {
+        let (value_type, derefs) = TypeChecker::scalar_pointee(typ);
+        if derefs == 0 {
+            return String::new();
+        }
+        let mut code = String::new();
+        for _ in 1..derefs {
+            code.push_str("    mov rax, QWORD [rax]\n");
+        }
+        code.push_str(&self.load_from(value_type, "rax"));
+        code
+    }
+
+    // Consommateur d'un itérateur : une boucle fondue qui calcule le résultat
+    fn generate_iterator_consumer(&mut self, obj_expr: &Expr, method_name: &str, type_args: &[Type], args: &[Expr]) -> Result<String, usize> {
+        let Some(IteratorMethod::Consumer(consumer)) = IteratorMethod::from_name(method_name) else {
+            return self.error(&format!(
+                "l'itérateur produit par `{}()` doit être parcouru par une boucle `for` ou consommé sur place (sum, count, collect...)", method_name));
+        };
+        let item_type = TypeChecker::iterator_item(&self.types.expr_type(obj_expr)?, method_name).unwrap();
+        let result_type = self.types.iterator_method_type(&item_type, method_name, type_args, args)?;
+        let (next_label, done) = (self.new_label("L_iter_next"), self.new_label("L_iter_done"));
+        let iterator = self.generate_iterator(obj_expr, &done)?;
+        let item = format!("rbp-{}", iterator.item);
+
+        let mut code = format!("\n    ; Itérateur consommé par {}()\n", method_name);
+        code.push_str(&iterator.setup);
+        let (mut step, mut finish) = (String::new(), String::new());
+        match consumer {
+            Consumer::Sum | Consumer::Product | Consumer::Count => {
+                let total = self.alloc_slot(8);
+                let initial = if consumer == Consumer::Product { 1 } else { 0 };
+                code.push_str(&format!("    mov QWORD [rbp-{}], {}\n", total, initial));
+                match consumer {
+                    Consumer::Count => step.push_str(&format!("    inc QWORD [rbp-{}]\n", total)),
+                    Consumer::Sum => {
+                        step.push_str(&self.load_scalar(&iterator.item_type, &item));
+                        step.push_str(&format!("    add QWORD [rbp-{}], rax\n", total));
+                    },
+                    _ => {
+                        step.push_str(&self.load_scalar(&iterator.item_type, &item));
+                        step.push_str(&format!("    imul rax, QWORD [rbp-{}]\n", total));
+                        step.push_str(&format!("    mov QWORD [rbp-{}], rax\n", total));
+                    },
+                }
+                // Le total est ramené à la largeur de son type
+                finish.push_str(&format!("    mov rax, QWORD [rbp-{}]\n", total));
+                finish.push_str(&self.store_to(&result_type, &format!("rbp-{}", total)));
+                finish.push_str(&self.load_from(&result_type, &format!("rbp-{}", total)));
+            },
+            Consumer::Min | Consumer::Max => {
+                // Option de l'élément retenu : max garde le dernier des maximums, min le premier des minimums
+                let (offsets, size, _) = self.fields_layout(&[Type::Bool, item_type.clone()]);
+                let (option, candidate) = (self.alloc_slot(size), self.alloc_slot(8));
+                let (best, keep) = (format!("rbp-{}", option - offsets[1]), self.new_label("L_iter_keep"));
+                code.push_str(&format!("    mov BYTE [rbp-{}], 0\n", option));
+                let take = self.new_label("L_iter_take");
+                step.push_str(&self.load_scalar(&iterator.item_type, &item));
+                step.push_str(&format!("    mov QWORD [rbp-{}], rax\n", candidate));
+                step.push_str(&format!("    cmp BYTE [rbp-{}], 0\n", option));
+                step.push_str(&format!("    je {}\n", take));
+                step.push_str(&self.load_scalar(&iterator.item_type, &best));
+                step.push_str(&format!("    cmp QWORD [rbp-{}], rax\n", candidate));
+                step.push_str(&format!("    {} {}\n", if consumer == Consumer::Max { "jl" } else { "jge" }, keep));
+                step.push_str(&format!("{}:\n", take));
+                step.push_str(&format!("    mov BYTE [rbp-{}], 1\n", option));
+                step.push_str(&self.load_from(&iterator.item_type, &item));
+                step.push_str(&self.store_to(&iterator.item_type, &best));
+                step.push_str(&format!("{}:\n", keep));
+                finish.push_str(&format!("    lea rax, [rbp-{}]\n", option));
+            },
+            Consumer::Collect => {
+                let collection = self.alloc_slot(VEC_SIZE);
+                for field in [0, VEC_LEN, VEC_CAP] {
+                    code.push_str(&format!("    mov QWORD [rbp-{}], 0\n", collection - field));
+                }
+                self.uses_vec_runtime = true;
+                if result_type == Type::String {
+                    self.uses_string_runtime = true;
+                    step.push_str(&self.load_from(&iterator.item_type, &item));
+                    step.push_str("    mov esi, eax\n");
+                    step.push_str(&format!("    lea rdi, [rbp-{}]\n", collection));
+                    step.push_str("    call __string_push\n");
+                } else {
+                    // Ajout en fin de vecteur, comme push()
+                    let element_size = self.type_size(&iterator.item_type);
+                    step.push_str(&format!("    lea rdi, [rbp-{}]\n    mov esi, 1\n    mov edx, {}\n", collection, element_size));
+                    step.push_str("    call __vec_reserve\n");
+                    step.push_str(&format!("    mov rcx, QWORD [rbp-{}]\n", collection - VEC_LEN));
+                    step.push_str(&format!("    mov rax, QWORD [rbp-{}]\n", collection));
+                    step.push_str(&Self::element_address(element_size));
+                    step.push_str("    mov rdx, rax\n");
+                    step.push_str(&self.load_from(&iterator.item_type, &item));
+                    step.push_str(&self.store_to(&iterator.item_type, "rdx"));
+                    if self.needs_drop(&iterator.item_type) {
+                        step.push_str(&Self::zero_memory(&item, element_size));
+                    }
+                    step.push_str(&format!("    inc QWORD [rbp-{}]\n", collection - VEC_LEN));
+                }
+                finish.push_str(&format!("    lea rax, [rbp-{}]\n", collection));
+            },
+            Consumer::Fold => {
+                let accumulator = self.alloc_slot(self.type_size(&result_type));
+                code.push_str(&self.generate_owned(&args[0], &result_type)?);
+                code.push_str(&self.store_to(&result_type, &format!("rbp-{}", accumulator)));
+                let (callee_code, callee, is_closure, _, _) = self.generate_callee(&args[1])?;
+                code.push_str(&callee_code);
+                let items = [(result_type.clone(), accumulator), (iterator.item_type.clone(), iterator.item)];
+                step.push_str(&self.generate_item_call(callee, is_closure, &items, &result_type));
+                step.push_str(&self.store_to(&result_type, &format!("rbp-{}", accumulator)));
+                finish.push_str(&self.load_from(&result_type, &format!("rbp-{}", accumulator)));
+            },
+            Consumer::Any | Consumer::All | Consumer::ForEach => {
+                // any et all s'arrêtent au premier élément qui décide du résultat
+                let found = self.alloc_slot(1);
+                code.push_str(&format!("    mov BYTE [rbp-{}], {}\n", found, (consumer == Consumer::All) as u8));
+                let (callee_code, callee, is_closure, _, output) = self.generate_callee(&args[0])?;
+                code.push_str(&callee_code);
+                step.push_str(&self.generate_item_call(callee, is_closure, &[(iterator.item_type.clone(), iterator.item)], &output));
+                if consumer != Consumer::ForEach {
+                    let (jump, decided) = if consumer == Consumer::Any { ("jz", 1) } else { ("jnz", 0) };
+                    step.push_str(&format!("    test al, al\n    {} {}\n", jump, next_label));
+                    step.push_str(&format!("    mov BYTE [rbp-{}], {}\n    jmp {}\n", found, decided, done));
+                }
+                finish.push_str(&format!("    movzx eax, BYTE [rbp-{}]\n", found));
+            },
+        }
+
+        code.push_str(&format!("{}:\n", next_label));
+        code.push_str(&iterator.next);
+        code.push_str(&step);
+        code.push_str(&format!("    jmp {}\n", next_label));
+        code.push_str(&format!("{}:\n", done));
+        code.push_str(&finish);
+        Ok(code)
+    }
+
+    // Chaîne littérale au format NASM ; les guillemets et caractères de contrôle sont émis en octets
+    fn asm_string(value: &str) -> String {
+        let mut parts = Vec::new();
+        let mut current = String::new();
+
+        for c in value.chars() {
+            if c == '"' || (c as u32) < 32 {
+                if !current.is_empty() {
+                    parts.push(format!("\"{}\"", current));
+                    current.clear();
+                }
+                parts.push((c as u32).to_string());
+            } else {
+                current.push(c);
+            }
+        }
+        if !current.is_empty() || parts.is_empty() {
+            parts.push(format!("\"{}\"", current));
+        }
+
+        parts.join(", ")
+    }
+
+    // Copie `size` octets de [src] vers [dst] (adresses de la forme "rax" ou "rbp-16") via r10 ;
+    // au-delà de 64 octets, la copie passe par `rep movsb` en préservant rsi, rdi et rcx
+    fn copy_memory(src: &str, dst: &str, size: usize) -> String {
+        let mut code = String::new();
+        let mut offset = 0;
+
+        if size > 64 {
+            code.push_str("    push rsi\n");
+            code.push_str("    push rdi\n");
+            code.push_str("    push rcx\n");
+            code.push_str(&format!("    lea rsi, [{}]\n", src));
+            code.push_str(&format!("    lea rdi, [{}]\n", dst));
+            code.push_str(&format!("    mov rcx, {}\n", size));
+            code.push_str("    rep movsb\n");
+            code.push_str("    pop rcx\n");
+            code.push_str("    pop rdi\n");
+            code.push_str("    pop rsi\n");
+            return code;
+        }
+
+        for (width, reg, keyword) in [(8, "r10", "QWORD"), (4, "r10d", "DWORD"), (2, "r10w", "WORD"), (1, "r10b", "BYTE")] {
+            while offset + width <= size {
+                code.push_str(&format!("    mov {}, {} [{}+{}]\n", reg, keyword, src, offset));
+                code.push_str(&format!("    mov {} [{}+{}], {}\n", keyword, dst, offset, reg));
+                offset += width;
+            }
+        }
+
+        code
+    }
+
+    // Charge dans rax la valeur située à `addr` (ou son adresse pour un agrégat)
+    fn load_from(&self, typ: &Type, addr: &str) -> String {
+        if self.is_aggregate(typ) {
+            return format!("    lea rax, [{}]\n", addr);
+        }
+
+        match self.type_size(typ) {
+            0 => String::new(),
+            1 if *typ == Type::U8 => format!("    movzx eax, BYTE [{}]\n", addr),
+            1 => format!("    movsx rax, BYTE [{}]\n", addr),
+            2 => format!("    movsx rax, WORD [{}]\n", addr),
+            4 => format!("    movsxd rax, DWORD [{}]\n", addr),
+            _ => format!("    mov rax, QWORD [{}]\n", addr),
+        }
+    }
+
+    // Range la valeur de rax (ou copie l'agrégat pointé par rax) à `addr`, qui ne doit pas utiliser rax
+    fn store_to(&self, typ: &Type, addr: &str) -> String {
+        if self.is_aggregate(typ) {
+            return Self::copy_memory("rax", addr, self.type_size(typ));
+        }
+
+        match self.type_size(typ) {
+            0 => String::new(),
+            1 => format!("    mov BYTE [{}], al\n", addr),
+            2 => format!("    mov WORD [{}], ax\n", addr),
+            4 => format!("    mov DWORD [{}], eax\n", addr),
+            _ => format!("    mov QWORD [{}], rax\n", addr),
+        }
+    }
+
+    // Remet à zéro `size` octets à [dst] ; au-delà de 64 octets, par `rep stosb` en préservant rax, rcx et rdi
+    fn zero_memory(dst: &str, size: usize) -> String {
+        let mut code = String::new();
+        let mut offset = 0;
+
+        if size > 64 {
+            code.push_str("    push rdi\n");
+            code.push_str("    push rcx\n");
+            code.push_str("    push rax\n");
+            code.push_str(&format!("    lea rdi, [{}]\n", dst));
+            code.push_str("    xor eax, eax\n");
+            code.push_str(&format!("    mov rcx, {}\n", size));
+            code.push_str("    rep stosb\n");
+            code.push_str("    pop rax\n");
+            code.push_str("    pop rcx\n");
+            code.push_str("    pop rdi\n");
+            return code;
+        }
+
+        for (width, keyword) in [(8, "QWORD"), (4, "DWORD"), (2, "WORD"), (1, "BYTE")] {
+            while offset + width <= size {
+                code.push_str(&format!("    mov {} [{}+{}], 0\n", keyword, dst, offset));
+                offset += width;
+            }
+        }
+
+        code
+    }
+
+    // La structure implémente Drop : son destructeur est appelé avant la destruction de ses champs
+    fn has_drop_impl(&self, name: &str) -> bool {
+        self.types.trait_impls.contains(&(name.to_string(), "Drop".to_string()))
+    }
+
+    // Le type possède des ressources à libérer : mémoire du tas, environnement de fermeture ou destructeur
+    fn needs_drop(&self, typ: &Type) -> bool {
+        match typ {
+            Type::String | Type::Vec(_) | Type::Box(_) | Type::Closure(_, _) | Type::Rc(_) | Type::Guard(_, _) => true,
+            Type::HashMap(_, _) | Type::HashSet(_) => true,
+            Type::Option(inner) | Type::Array(inner, _) | Type::RefCell(inner) | Type::Entry(inner, _) => self.needs_drop(inner),
+            Type::Result(ok, err) => self.needs_drop(ok) || self.needs_drop(err),
+            Type::Tuple(elements) => elements.iter().any(|element| self.needs_drop(element)),
+            Type::Named(name) if self.types.enums.contains_key(name) => {
+                self.types.enums[name].variants.iter().flat_map(|(_, fields)| fields).any(|field| self.needs_drop(field))
+            },
+            Type::Named(name) => self.has_drop_impl(name)
+                || self.struct_layout(name).0.iter().any(|(_, field_type, _)| self.needs_drop(field_type)),
+            _ => false,
+        }
+    }
+
+    // Valeur possédant des ressources mais copiée plutôt que déplacée : une fermeture est Copy, ses copies
+    // partagent son environnement, dont le compteur de références est incrémenté
+    fn is_shared(&self, typ: &Type) -> bool {
+        match typ {
+            Type::Closure(_, _) => true,
+            Type::Option(inner) | Type::Array(inner, _) => self.is_shared(inner),
+            Type::Result(ok, err) => [ok, err].iter().all(|part| !self.needs_drop(part) || self.is_shared(part)),
+            Type::Tuple(elements) => elements.iter().all(|element| !self.needs_drop(element) || self.is_shared(element)),
+            _ => false,
+        }
+    }
+
+    // Nouvelle copie de la valeur partagée située à [base+offset] : chaque environnement de fermeture
+    // qu'elle contient gagne une référence (rcx est modifié)
+    fn retain_code(&mut self, typ: &Type, base: &str, offset: usize) -> String {
+        let mut code = String::new();
+        match typ {
+            Type::Closure(_, _) => {
+                let skip = self.new_label("L_retain");
+                code.push_str(&format!("    mov rcx, QWORD [{}+{}]\n", base, offset + 8));
+                code.push_str(&format!("    test rcx, rcx\n    jz {}\n", skip));
+                code.push_str("    inc QWORD [rcx]  ; Environnement partagé\n");
+                code.push_str(&format!("{}:\n", skip));
+            },
+            Type::Option(inner) => {
+                let payload = self.fields_layout(&[Type::Bool, (**inner).clone()]).0[1];
+                let skip = self.new_label("L_retain");
+                code.push_str(&format!("    cmp BYTE [{}+{}], 0\n    je {}\n", base, offset, skip));
+                code.push_str(&self.retain_code(inner, base, offset + payload));
+                code.push_str(&format!("{}:\n", skip));
+            },
+            Type::Result(ok, err) => {
+                let payload = self.result_layout(ok, err).0;
+                let (err_label, skip) = (self.new_label("L_retain_err"), self.new_label("L_retain"));
+                code.push_str(&format!("    cmp BYTE [{}+{}], 1\n    jne {}\n", base, offset, err_label));
+                code.push_str(&self.retain_code(ok, base, offset + payload));
+                code.push_str(&format!("    jmp {}\n{}:\n", skip, err_label));
+                code.push_str(&self.retain_code(err, base, offset + payload));
+                code.push_str(&format!("{}:\n", skip));
+            },
+            Type::Tuple(elements) => {
+                let (offsets, _, _) = self.fields_layout(elements);
+                for (element, element_offset) in elements.iter().zip(offsets) {
+                    code.push_str(&self.retain_code(element, base, offset + element_offset));
+                }
+            },
+            Type::Array(element, length) if self.needs_drop(element) => {
+                let size = self.type_size(element);
+                for i in 0..*length {
+                    code.push_str(&self.retain_code(element, base, offset + i * size));
+                }
+            },
+            _ => {},
+        }
+        code
+    }
+
+    // Déplace l'agrégat pointé par rax vers un emplacement neuf, dont l'adresse remplace rax : la source est
+    // remise à zéro, ce qui rend sa destruction sans effet. Une valeur partagée est simplement copiée.
+    fn move_value(&mut self, typ: &Type) -> String {
+        if self.is_shared(typ) {
+            return self.retain_code(typ, "rax", 0);
+        }
+        let size = self.type_size(typ);
+        let slot = self.alloc_slot(size);
+        let mut code = Self::copy_memory("rax", &format!("rbp-{}", slot), size);
+        code.push_str(&Self::zero_memory("rax", size));
+        code.push_str(&format!("    lea rax, [rbp-{}]\n", slot));
+        code
+    }
+
+    // La valeur située à [base+offset] vient d'être copiée vers son nouveau propriétaire : elle est remise
+    // à zéro, ou gagne une référence si elle est partagée (rcx est modifié)
+    fn vacate(&mut self, typ: &Type, base: &str, offset: usize) -> String {
+        if self.is_shared(typ) {
+            self.retain_code(typ, base, offset)
+        } else {
+            Self::zero_memory(&format!("{}+{}", base, offset), self.type_size(typ))
+        }
+    }
+
+    // Évalue `expr` comme une valeur déplacée vers `target` (variable, argument, champ, valeur renvoyée) :
+    // un agrégat possédant des ressources est copié puis remis à zéro à sa source, la source d'un pointeur
+    // Box est remise à zéro après sa lecture
+    fn generate_owned(&mut self, expr: &Expr, target: &Type) -> Result<String, usize> {
+        let source = self.types.expr_type(expr)?;
+        if !self.needs_drop(&source) {
+            return self.generate_coerced_expr(expr, target);
+        }
+        self.release.clear();
+        let mut code = self.generate_coerced_expr(expr, target)?;
+        let release = std::mem::take(&mut self.release);
+        if !self.is_aggregate(&source) {
+            code.push_str(&release);
+        }
+        if self.is_aggregate(target) {
+            code.push_str(&self.move_value(target));
+        }
+        Ok(code)
+    }
+
+    // Lecture par valeur du lieu `addr` : pour un pointeur Box, la remise à zéro de sa source est préparée
+    // dans `release`, pour le cas où la valeur lue serait déplacée
+    fn load_place(&mut self, typ: &Type, addr: &str) -> String {
+        let mut code = String::new();
+        if self.needs_drop(typ) && !self.is_aggregate(typ) {
+            if addr.starts_with("rbp-") {
+                self.release = format!("    mov QWORD [{}], 0\n", addr);
+            } else {
+                let slot = self.alloc_slot(8);
+                code.push_str(&format!("    lea rcx, [{}]\n", addr));
+                code.push_str(&format!("    mov QWORD [rbp-{}], rcx  ; Source du pointeur\n", slot));
+                self.release = format!("    mov rcx, QWORD [rbp-{}]\n    mov QWORD [rcx], 0\n", slot);
+            }
+        }
+        code.push_str(&self.load_from(typ, addr));
+        code
+    }
+
+    // Une valeur neuve (résultat d'un appel, valeur construite) est rangée dans un emplacement possédé par
+    // l'instruction courante : elle y est détruite à la fin de l'instruction, sauf si elle a été déplacée
+    fn hold(&mut self, typ: &Type) -> String {
+        if !self.needs_drop(typ) || self.drop_scopes.is_empty() {
+            return String::new();
+        }
+        let size = self.type_size(typ);
+        let slot = self.alloc_slot(size);
+        self.own(slot, typ.clone());
+        if self.is_aggregate(typ) {
+            let mut code = Self::copy_memory("rax", &format!("rbp-{}", slot), size);
+            code.push_str(&format!("    lea rax, [rbp-{}]  ; Valeur temporaire\n", slot));
+            code
+        } else {
+            self.release = format!("    mov QWORD [rbp-{}], 0\n", slot);
+            format!("    mov QWORD [rbp-{}], rax  ; Valeur temporaire\n", slot)
+        }
+    }
+
+    fn push_scope(&mut self, block: bool) {
+        self.drop_scopes.push(DropScope { block, values: Vec::new() });
+    }
+
+    // La valeur rangée à rbp-`slot` est possédée par la portée la plus interne
+    fn own(&mut self, slot: usize, typ: Type) {
+        self.owns_values = true;
+        self.drop_scopes.last_mut().expect("portée ouverte").values.push((slot, typ));
+    }
+
+    // La variable rangée à rbp-`slot` est possédée par le bloc qui la déclare
+    fn own_in_block(&mut self, slot: usize, typ: Type) {
+        self.owns_values = true;
+        let scope = self.drop_scopes.iter_mut().rev().find(|scope| scope.block).expect("bloc ouvert");
+        scope.values.push((slot, typ));
+    }
+
+    // Ferme la portée la plus interne : ses valeurs sont détruites dans l'ordre inverse de leur création
+    fn pop_scope(&mut self) -> String {
+        let scope = self.drop_scopes.pop().expect("portée ouverte");
+        self.drop_values(&scope.values)
+    }
+
+    // Avant de quitter la fonction, toutes ses valeurs sont détruites, en commençant par la portée la plus interne
+    fn drop_all(&mut self) -> String {
+        self.drop_from(0)
+    }
+
+    // Valeurs des portées ouvertes depuis les `depth` premières, détruites avant un saut qui les quitte
+    fn drop_from(&mut self, depth: usize) -> String {
+        let values: Vec<(usize, Type)> = self.drop_scopes[depth..].iter().flat_map(|scope| scope.values.clone()).collect();
+        self.drop_values(&values)
+    }
+
+    fn drop_values(&mut self, values: &[(usize, Type)]) -> String {
+        values.iter().rev().map(|(slot, typ)| self.drop_at(typ, &format!("rbp-{}", slot))).collect()
+    }
+
+    // Appelle la glue de destruction du type sur la valeur située à `addr`
+    fn drop_at(&mut self, typ: &Type, addr: &str) -> String {
+        let label = self.drop_function(typ);
+        format!("    lea rdi, [{}]\n    call {}  ; Destruction de {}\n", addr, label, self.types.display_type(typ))
+    }
+
+    // Évalue `expr` en appliquant les coercitions vers `target` (&T -> &dyn Trait, Box<T> -> Box<dyn Trait>)
+    fn generate_coerced_expr(&mut self, expr: &Expr, target: &Type) -> Result<String, usize> {
+        let source = self.types.expr_type(expr)?;
+
+        // Fonction nommée attendue comme fermeture : environnement vide ; fermeture sans capture
+        // attendue comme pointeur de fonction : son code
+        match (target, &source) {
+            (Type::Closure(_, _), Type::FnPtr(_, _)) => {
+                let value = self.alloc_slot(16);
+                let mut code = self.generate_expr_code(expr)?;
+                code.push_str(&format!("    mov QWORD [rbp-{}], rax  ; Code\n", value));
+                code.push_str(&format!("    mov QWORD [rbp-{}], 0  ; Aucune capture\n", value - 8));
+                code.push_str(&format!("    lea rax, [rbp-{}]\n", value));
+                return Ok(code);
+            },
+            (Type::FnPtr(_, _), Type::Closure(_, _)) => {
+                let mut code = self.generate_expr_code(expr)?;
+                code.push_str("    mov rax, QWORD [rax]  ; Code de la fermeture\n");
+                return Ok(code);
+            },
+            _ => {},
+        }
+
+        if let (Type::Tuple(target_elements), Type::Tuple(source_elements)) = (target, &source) {
+            if target_elements != source_elements && target_elements.len() == source_elements.len() {
+                return self.generate_tuple_conversion(expr, target_elements, source_elements);
+            }
+        }
+        if let (Type::Array(target_element, _), Expr::Literal(Literal::Array(_) | Literal::ArrayRepeat(_, _))) = (target, expr) {
+            return self.generate_array(expr, target_element);
+        }
+        if let (Type::Vec(target_element), Expr::VecNew(_) | Expr::VecRepeat(_, _)) = (target, expr) {
+            return self.generate_vec_literal(expr, target_element);
+        }
+
+        let (target_inner, source_inner) = match (target, &source) {
+            (Type::Ref(target_inner, target_mut), Type::Ref(source_inner, source_mut)) if !*target_mut || *source_mut => (target_inner, source_inner),
+            (Type::Box(target_inner), Type::Box(source_inner)) => (target_inner, source_inner),
+            (Type::Ref(target_inner, _), _) | (Type::Box(target_inner), _) if matches!(**target_inner, Type::Dyn(_)) => {
+                return self.error(&format!("types incompatibles : attendu `{}`, trouvé `{}`", target, source));
+            },
+            _ => return self.generate_expr_code(expr),
+        };
+
+        // Emprunt d'un vecteur, d'un tableau ou d'une String comme tranche : pointeur large (données, longueur)
+        if let Type::Slice(_) | Type::Str = &**target_inner {
+            let fat = self.alloc_slot(16);
+            let mut code = self.generate_expr_code(expr)?;
+            code.push_str(&format!("    ; Coercition {} -> {}\n", source, target));
+            match &**source_inner {
+                Type::Vec(_) | Type::String => {
+                    code.push_str(&format!("    mov rcx, QWORD [rax+{}]\n", VEC_LEN));
+                    code.push_str("    mov rax, QWORD [rax]\n");
+                },
+                Type::Array(_, length) => code.push_str(&format!("    mov ecx, {}\n", length)),
+                _ => return Ok(code),
+            }
+            code.push_str(&format!("    mov QWORD [rbp-{}], rax  ; Pointeur vers les éléments\n", fat));
+            code.push_str(&format!("    mov QWORD [rbp-{}], rcx  ; Longueur\n", fat - VEC_LEN));
+            code.push_str(&format!("    lea rax, [rbp-{}]\n", fat));
+            return Ok(code);
+        }
+
+        // Coercition par déréférencement : `&Box<T>` ou `&&T` attendu comme `&T` (parcours d'une liste chaînée)
+        if let Type::Box(pointee) | Type::Ref(pointee, _) = &**source_inner {
+            if pointee == target_inner && !matches!(**pointee, Type::Dyn(_) | Type::Slice(_) | Type::Str) {
+                let mut code = self.generate_expr_code(expr)?;
+                code.push_str("    mov rax, QWORD [rax]  ; Coercition par déréférencement\n");
+                return Ok(code);
+            }
+        }
+
+        let trait_name = match (&**target_inner, &**source_inner) {
+            (Type::Dyn(target_trait), Type::Dyn(source_trait)) if target_trait == source_trait => return self.generate_expr_code(expr),
+            (Type::Dyn(trait_name), _) => trait_name.clone(),
+            _ => return self.generate_expr_code(expr),
+        };
+
+        // Coercition vers un objet trait : construction du pointeur large (données, vtable)
+        let type_name = source_inner.to_string();
+        self.types.check_object_safety(&trait_name, self.types.current_line)?;
+        if !self.types.trait_impls.contains(&(type_name.clone(), trait_name.clone())) {
+            return self.error(&format!("le trait `{}` n'est pas implémenté pour `{}`", trait_name, type_name));
+        }
+
+        if !self.vtables.contains(&(type_name.clone(), trait_name.clone())) {
+            self.vtables.push((type_name.clone(), trait_name.clone()));
+        }
+
+        let fat = self.alloc_slot(16);
+        let mut code = self.generate_expr_code(expr)?;
+        code.push_str(&format!("    ; Coercition {} -> {}\n", source, target));
+        code.push_str(&format!("    mov QWORD [rbp-{}], rax  ; Pointeur vers les données\n", fat));
+        code.push_str(&format!("    lea rcx, [rel {}]\n", Self::vtable_label(&type_name, &trait_name)));
+        code.push_str(&format!("    mov QWORD [rbp-{}], rcx  ; Pointeur vers la vtable\n", fat - 8));
+        code.push_str(&format!("    lea rax, [rbp-{}]\n", fat));
+        Ok(code)
+    }
+
+    // Construit un tuple selon la disposition de `target` (les littéraux entiers d'un tuple sont des i32 :
+    // (1, 2) devient un (i64, i64) si c'est le type attendu)
+    fn generate_tuple_conversion(&mut self, expr: &Expr, target: &[Type], source: &[Type]) -> Result<String, usize> {
+        let (target_offsets, size, _) = self.fields_layout(target);
+        let base = self.alloc_slot(size);
+        let mut code = format!("    ; Conversion vers le tuple {}\n", Type::Tuple(target.to_vec()));
+
+        if let Expr::Tuple(elements) = expr {
+            // Tuple littéral : chaque élément est converti vers son type attendu
+            for ((element, element_type), offset) in elements.iter().zip(target).zip(target_offsets) {
+                code.push_str(&self.generate_owned(element, element_type)?);
+                code.push_str(&self.store_to(element_type, &format!("rbp-{}", base - offset)));
+            }
+        } else {
+            // Tuple déjà construit : les entiers sont relus puis rangés à leur nouvelle taille
+            let (source_offsets, _, _) = self.fields_layout(source);
+            code.push_str(&self.generate_expr_code(expr)?);
+            let source_slot = self.alloc_slot(8);
+            code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", source_slot));
+            for (i, (target_type, source_type)) in target.iter().zip(source).enumerate() {
+                if target_type != source_type && (self.is_aggregate(target_type) || self.is_aggregate(source_type)) {
+                    return self.error(&format!("types incompatibles : attendu `{}`, trouvé `{}`",
+                        self.types.display_type(&Type::Tuple(target.to_vec())), self.types.display_type(&Type::Tuple(source.to_vec()))));
+                }
+                code.push_str(&format!("    mov rax, QWORD [rbp-{}]\n", source_slot));
+                code.push_str(&self.load_from(source_type, &format!("rax+{}", source_offsets[i])));
+                code.push_str(&self.store_to(target_type, &format!("rbp-{}", base - target_offsets[i])));
+            }
+            let source_type = Type::Tuple(source.to_vec());
+            if self.needs_drop(&source_type) {
+                // Les éléments ont été déplacés vers le nouveau tuple
+                code.push_str(&format!("    mov rax, QWORD [rbp-{}]\n", source_slot));
+                code.push_str(&self.vacate(&source_type, "rax", 0));
+            }
+        }
+
+        code.push_str(&format!("    lea rax, [rbp-{}]\n", base));
+        Ok(code)
+    }
+
+    // Calcule dans rax l'adresse d'une expression (lieu mémoire, ou temporaire pour une valeur)
+    fn generate_address(&mut self, expr: &Expr) -> Result<String, usize> {
+        let mut code = String::new();
+
+        match expr {
+            Expr::Variable(name) => {
+                match self.variable_info.get(name) {
+                    Some(var_info) if var_info.indirect => code.push_str(&format!("    mov rax, QWORD [rbp-{}]  ; Adresse de {}\n", var_info.offset, name)),
+                    Some(var_info) => code.push_str(&format!("    lea rax, [rbp-{}]  ; Adresse de {}\n", var_info.offset, name)),
+                    None => return self.error(&format!("Variable non trouvée: {}", name)),
+                }
+            },
+            Expr::FieldAccess(base, field_name) => {
+                let struct_name = self.types.struct_of(base)?.0.to_string();
+                let (fields, _, _) = self.struct_layout(&struct_name);
+                let offset = match fields.iter().find(|(name, _, _)| name == field_name) {
+                    Some((_, _, offset)) => *offset,
+                    None => return self.error(&format!("la structure `{}` n'a pas de champ `{}`", struct_name, field_name)),
+                };
+                let (_, derefs) = self.types.struct_of(base)?;
+                code.push_str(&self.generate_base_address(base, derefs)?);
+                if offset > 0 {
+                    code.push_str(&format!("    add rax, {}  ; Champ {}\n", offset, field_name));
+                }
+            },
+            Expr::TupleIndex(base, index) => {
+                let (elements, derefs) = self.types.tuple_of(base)?;
+                self.types.expr_type(expr)?; // Indice hors limites
+                let (offsets, _, _) = self.fields_layout(&elements);
+                code.push_str(&self.generate_base_address(base, derefs)?);
+                if offsets[*index] > 0 {
+                    code.push_str(&format!("    add rax, {}  ; Élément {}\n", offsets[*index], index));
+                }
+            },
+            Expr::Unary(UnaryOp::Deref, inner) => {
+                // La valeur du pointeur est l'adresse désignée
+                code.push_str(&self.generate_expr_code(inner)?);
+            },
+            Expr::Index(_, index_expr) if matches!(**index_expr, Expr::Range(_, _, _)) => {
+                // L'« adresse » d'une tranche v[a..b] est celle de son pointeur large
+                code.push_str(&self.generate_slice(expr)?);
+            },
+            Expr::Index(base, index_expr) => {
+                let element_type = self.types.expr_type(expr)?;
+                let (data, length) = (self.alloc_slot(8), self.alloc_slot(8));
+                code.push_str(&self.generate_sequence(base)?);
+                code.push_str(&format!("    mov QWORD [rbp-{}], rax  ; Adresse des éléments\n", data));
+                code.push_str(&format!("    mov QWORD [rbp-{}], rdx  ; Longueur\n", length));
+                code.push_str(&self.generate_expr_code(index_expr)?);
+                code.push_str("    mov rcx, rax\n");
+                code.push_str(&format!("    mov r8, QWORD [rbp-{}]\n", length));
+                code.push_str(&self.bounds_check("r8"));
+                code.push_str(&format!("    mov rax, QWORD [rbp-{}]\n", data));
+                code.push_str(&Self::element_address(self.type_size(&element_type)));
+            },
+            _ => {
+                let typ = self.types.expr_type(expr)?;
+                code.push_str(&self.generate_expr_code(expr)?);
+                if !self.is_aggregate(&typ) {
+                    // Valeur temporaire : on la range dans le cadre pour en prendre l'adresse
+                    let slot = self.alloc_slot(8);
+                    code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", slot));
+                    code.push_str(&format!("    lea rax, [rbp-{}]\n", slot));
+                }
+            },
+        }
+
+        Ok(code)
+    }
+
+    // Vérifie que l'index contenu dans rcx est inférieur à `length` (comparaison non signée :
+    // un index négatif est aussi hors limites), sinon le programme panique
+    fn bounds_check(&mut self, length: &str) -> String {
+        let ok_label = self.new_label("L_bounds_ok");
+        self.uses_bounds_check = true;
+
+        let mut code = String::new();
+        code.push_str(&format!("    cmp rcx, {}  ; Vérification des limites\n", length));
+        code.push_str(&format!("    jb {}\n", ok_label));
+        code.push_str(&format!("    mov rdx, rcx\n    mov rsi, {}\n", length));
+        code.push_str(&format!("    mov rdi, {}\n", self.types.current_line));
+        code.push_str("    call __panic_bounds_check\n");
+        code.push_str(&format!("{}:\n", ok_label));
+        code
+    }
+
+    // Adresse de l'élément d'index rcx d'un tableau dont l'adresse est dans rax
+    fn element_address(element_size: usize) -> String {
+        match element_size {
+            1 | 2 | 4 | 8 => format!("    lea rax, [rax+rcx*{}]  ; Adresse de l'élément\n", element_size),
+            _ => format!("    imul rcx, rcx, {}\n    add rax, rcx  ; Adresse de l'élément\n", element_size),
+        }
+    }
+
+    // Construit un tableau littéral ([a, b, c] ou [valeur; N]) dans son propre emplacement du cadre
+    fn generate_array(&mut self, expr: &Expr, element_type: &Type) -> Result<String, usize> {
+        let element_size = self.type_size(element_type);
+        let mut code = String::new();
+
+        match expr {
+            Expr::Literal(Literal::Array(elements)) => {
+                let base = self.alloc_slot(element_size * elements.len());
+                code.push_str(&format!("    ; Tableau littéral [{}; {}]\n", element_type, elements.len()));
+                for (i, element) in elements.iter().enumerate() {
+                    code.push_str(&self.generate_owned(element, element_type)?);
+                    code.push_str(&self.store_to(element_type, &format!("rbp-{}", base - i * element_size)));
+                }
+                code.push_str(&format!("    lea rax, [rbp-{}]\n", base));
+            },
+            Expr::Literal(Literal::ArrayRepeat(value, length)) => {
+                let base = self.alloc_slot(element_size * length);
+                code.push_str(&format!("    ; Tableau [valeur; {}]\n", length));
+                code.push_str(&self.generate_owned(value, element_type)?);
+                if *length > 0 {
+                    let value_slot = self.alloc_slot(8);
+                    let loop_label = self.new_label("L_array_fill");
+                    code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", value_slot));
+                    code.push_str(&format!("    lea rdx, [rbp-{}]\n", base));
+                    code.push_str(&format!("    mov rcx, {}\n", length));
+                    code.push_str(&format!("{}:\n", loop_label));
+                    code.push_str(&format!("    mov rax, QWORD [rbp-{}]\n", value_slot));
+                    code.push_str(&self.store_to(element_type, "rdx"));
+                    code.push_str(&format!("    add rdx, {}\n", element_size));
+                    code.push_str("    dec rcx\n");
+                    code.push_str(&format!("    jnz {}\n", loop_label));
+                }
+                code.push_str(&format!("    lea rax, [rbp-{}]\n", base));
+            },
+            _ => return self.generate_expr_code(expr),
+        }
+
+        Ok(code)
+    }
+
+    // Construit le vecteur de vec![a, b, c] ou de vec![valeur; n] : un tampon alloué à la taille exacte
+    // reçoit les éléments déplacés, ou des copies de la valeur répétée, détruite à la fin de l'instruction
+    fn generate_vec_literal(&mut self, expr: &Expr, element_type: &Type) -> Result<String, usize> {
+        let element_size = self.type_size(element_type);
+        let base = self.alloc_slot(VEC_SIZE);
+        let mut code = String::new();
+
+        match expr {
+            Expr::VecNew(elements) => {
+                code.push_str(&format!("    ; vec![..] de {} éléments\n", elements.len()));
+                code.push_str(&format!("    mov QWORD [rbp-{}], 0\n", base));
+                if !elements.is_empty() && element_size > 0 {
+                    code.push_str(&format!("    mov rdi, {}\n", element_size * elements.len()));
+                    code.push_str("    call __alloc\n");
+                    code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", base));
+                }
+                for field in [VEC_LEN, VEC_CAP] {
+                    code.push_str(&format!("    mov QWORD [rbp-{}], {}\n", base - field, elements.len()));
+                }
+                for (i, element) in elements.iter().enumerate() {
+                    code.push_str(&self.generate_owned(element, element_type)?);
+                    code.push_str(&format!("    mov rdx, QWORD [rbp-{}]\n", base));
+                    code.push_str(&self.store_to(element_type, &format!("rdx+{}", i * element_size)));
+                }
+            },
+            Expr::VecRepeat(value, count) => {
+                let value_slot = self.alloc_slot(element_size.max(8));
+                let (next, end) = (self.alloc_slot(8), self.alloc_slot(8));
+                let length_label = self.new_label("L_vec_length");
+                let fill_label = self.new_label("L_vec_fill");
+                let end_label = self.new_label("L_vec_fill_end");
+                code.push_str("    ; vec![valeur; n]\n");
+                code.push_str(&self.generate_owned(value, element_type)?);
+                code.push_str(&self.store_to(element_type, &format!("rbp-{}", value_slot)));
+                if self.needs_drop(element_type) && !self.drop_scopes.is_empty() {
+                    self.own(value_slot, element_type.clone());
+                }
+                code.push_str(&self.generate_expr_code(count)?);
+                code.push_str("    test rax, rax\n");
+                code.push_str(&format!("    jns {}\n", length_label));
+                code.push_str("    xor eax, eax  ; Longueur négative : vecteur vide\n");
+                code.push_str(&format!("{}:\n", length_label));
+                code.push_str(&format!("    mov QWORD [rbp-{}], 0\n", base));
+                code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", base - VEC_LEN));
+                code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", base - VEC_CAP));
+                code.push_str(&format!("    imul rax, rax, {}\n", element_size));
+                code.push_str("    test rax, rax\n");
+                code.push_str(&format!("    jz {}\n", end_label));
+                code.push_str(&format!("    mov QWORD [rbp-{}], rax  ; Taille du tampon\n", end));
+                code.push_str("    mov rdi, rax\n");
+                code.push_str("    call __alloc\n");
+                code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", base));
+                code.push_str(&format!("    mov QWORD [rbp-{}], rax  ; Prochain élément\n", next));
+                code.push_str(&format!("    add QWORD [rbp-{}], rax  ; Fin du tampon\n", end));
+                code.push_str(&format!("{}:\n", fill_label));
+                if self.needs_drop(element_type) {
+                    code.push_str(&format!("    lea rdi, [rbp-{}]\n", value_slot));
+                    code.push_str(&format!("    mov rsi, QWORD [rbp-{}]\n", next));
+                    code.push_str(&format!("    call {}\n", self.clone_function(element_type)));
+                } else {
+                    code.push_str(&self.load_from(element_type, &format!("rbp-{}", value_slot)));
+                    code.push_str(&format!("    mov rdx, QWORD [rbp-{}]\n", next));
+                    code.push_str(&self.store_to(element_type, "rdx"));
+                }
+                code.push_str(&format!("    add QWORD [rbp-{}], {}\n", next, element_size));
+                code.push_str(&format!("    mov rax, QWORD [rbp-{}]\n", next));
+                code.push_str(&format!("    cmp rax, QWORD [rbp-{}]\n", end));
+                code.push_str(&format!("    jb {}\n", fill_label));
+                code.push_str(&format!("{}:\n", end_label));
+            },
+            _ => return self.generate_expr_code(expr),
+        }
+
+        code.push_str(&format!("    lea rax, [rbp-{}]\n", base));
+        Ok(code)
+    }
+
+    // Adresse de la structure ou du tuple désigné par `base`, après `derefs` déréférencements automatiques
+    fn generate_base_address(&mut self, base: &Expr, derefs: usize) -> Result<String, usize> {
+        if derefs == 0 {
+            return self.generate_address(base);
+        }
+
+        let mut code = self.generate_expr_code(base)?;
+        for _ in 1..derefs {
+            code.push_str("    mov rax, QWORD [rax]  ; Déréférencement automatique\n");
+        }
+        Ok(code)
+    }
+
+    // Éléments d'un tableau, d'un vecteur, d'une tranche ou octets d'une chaîne (à travers les références) :
+    // adresse du premier élément dans rax, longueur dans rdx
+    fn generate_sequence(&mut self, base: &Expr) -> Result<String, usize> {
+        let (base_type, derefs) = self.types.indexed_type(base)?;
+        let mut code = String::new();
+
+        match base_type {
+            Type::Array(_, length) => {
+                code.push_str(&self.generate_base_address(base, derefs)?);
+                code.push_str(&format!("    mov edx, {}  ; Longueur du tableau\n", length));
+            },
+            Type::Vec(_) | Type::Slice(_) | Type::String | Type::Str => {
+                // Un vecteur ou une String commence, comme un pointeur large, par (pointeur, longueur)
+                if derefs == 0 && matches!(base_type, Type::Slice(_) | Type::Str) {
+                    code.push_str(&self.generate_slice(base)?);
+                } else {
+                    code.push_str(&self.generate_base_address(base, derefs)?);
+                }
+                code.push_str(&format!("    mov rdx, QWORD [rax+{}]  ; Longueur\n", VEC_LEN));
+                code.push_str("    mov rax, QWORD [rax]  ; Adresse des éléments\n");
+            },
+            _ => unreachable!(),
+        }
+        Ok(code)
+    }
+
+    // Tranche base[début..fin] : pointeur large (données, longueur) construit dans le cadre, adresse dans rax.
+    // Les bornes absentes valent 0 et la longueur ; elles sont vérifiées comme le fait Rust, et doivent
+    // en plus tomber entre deux caractères UTF-8 pour une chaîne.
+    fn generate_slice(&mut self, expr: &Expr) -> Result<String, usize> {
+        let Expr::Index(base, range) = expr else { unreachable!() };
+        let Expr::Range(start, end, inclusive) = &**range else { unreachable!() };
+        let (element_size, check) = match self.types.expr_type(expr)? {
+            Type::Slice(element_type) => (self.type_size(&element_type), "__slice_check"),
+            _ => {
+                self.uses_string_runtime = true;
+                (1, "__str_slice_check")
+            },
+        };
+        self.uses_vec_runtime = true;
+        self.uses_bounds_check = true;
+
+        let (data, length, first) = (self.alloc_slot(8), self.alloc_slot(8), self.alloc_slot(8));
+        let fat = self.alloc_slot(16);
+        let mut code = String::from("    ; Tranche\n");
+        code.push_str(&self.generate_sequence(base)?);
+        code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", data));
+        code.push_str(&format!("    mov QWORD [rbp-{}], rdx\n", length));
+        match start {
+            Some(start) => code.push_str(&self.generate_expr_code(start)?),
+            None => code.push_str("    xor eax, eax\n"),
+        }
+        code.push_str(&format!("    mov QWORD [rbp-{}], rax  ; Début\n", first));
+        match end {
+            Some(end) => code.push_str(&self.generate_expr_code(end)?),
+            None => code.push_str(&format!("    mov rax, QWORD [rbp-{}]\n", length)),
+        }
+        if *inclusive {
+            code.push_str("    inc rax  ; Fin incluse\n");
+        }
+        code.push_str(&format!("    mov QWORD [rbp-{}], rax  ; Fin\n", fat - VEC_LEN));
+        code.push_str("    mov rsi, rax\n");
+        code.push_str(&format!("    mov rdi, QWORD [rbp-{}]\n", first));
+        code.push_str(&format!("    mov rdx, QWORD [rbp-{}]\n", length));
+        code.push_str(&format!("    mov ecx, {}\n", self.types.current_line));
+        code.push_str(&format!("    mov r8d, {}\n", end.is_some() as i32));
+        code.push_str(&format!("    mov r9, QWORD [rbp-{}]\n", data));
+        code.push_str(&format!("    call {}\n", check));
+
+        code.push_str(&format!("    mov rcx, QWORD [rbp-{}]\n", first));
+        code.push_str(&format!("    sub QWORD [rbp-{}], rcx  ; Longueur de la tranche\n", fat - VEC_LEN));
+        code.push_str(&format!("    mov rax, QWORD [rbp-{}]\n", data));
+        code.push_str(&Self::element_address(element_size));
+        code.push_str(&format!("    mov QWORD [rbp-{}], rax  ; Début de la tranche\n", fat));
+        code.push_str(&format!("    lea rax, [rbp-{}]\n", fat));
+        Ok(code)
+    }
+
+    // Évalue les arguments et les range dans des emplacements de 64 bits prêts à être passés
+    fn generate_arguments(&mut self, args: &[Expr], params: &[Type], code: &mut String) -> Result<Vec<usize>, usize> {
+        if args.len() != params.len() {
+            return self.error(&format!("{} argument(s) attendu(s), {} fourni(s)", params.len(), args.len()));
+        }
+
+        let mut slots = Vec::new();
+        for (arg, param_type) in args.iter().zip(params) {
+            if self.needs_drop(param_type) {
+                // Argument déplacé : l'appelé en devient propriétaire
+                code.push_str(&self.generate_owned(arg, param_type)?);
+            } else if self.is_aggregate(param_type) {
+                code.push_str(&self.generate_coerced_expr(arg, param_type)?);
+                // L'appelé reçoit un pointeur vers une copie de l'agrégat
+                let size = self.type_size(param_type);
+                let copy = self.alloc_slot(size);
+                code.push_str(&Self::copy_memory("rax", &format!("rbp-{}", copy), size));
+                code.push_str(&format!("    lea rax, [rbp-{}]\n", copy));
+            } else {
+                code.push_str(&self.generate_coerced_expr(arg, param_type)?);
+            }
+            let slot = self.alloc_slot(8);
+            code.push_str(&format!("    mov QWORD [rbp-{}], rax  ; Argument\n", slot));
+            slots.push(slot);
+        }
+
+        Ok(slots)
+    }
+
+    // Charge les arguments, appelle `target` et laisse le résultat dans rax (adresse pour un agrégat).
+    // Pour un appel indirect, `target` peut utiliser r11, qui n'est pas modifié ici.
+    fn emit_call(&mut self, code: &mut String, arg_slots: &[usize], target: &str, return_type: &Type) {
+        let mut values: Vec<String> = arg_slots.iter().map(|slot| format!("QWORD [rbp-{}]", slot)).collect();
+
+        let aggregate_return = self.is_aggregate(return_type);
+        let return_size = self.type_size(return_type);
+        let mut return_buffer = None;
+        if aggregate_return && return_size > 16 {
+            // Pointeur de retour caché en premier argument
+            let buffer = self.alloc_slot(return_size);
+            code.push_str(&format!("    lea rax, [rbp-{}]\n", buffer));
+            let slot = self.alloc_slot(8);
+            code.push_str(&format!("    mov QWORD [rbp-{}], rax  ; Pointeur de retour caché\n", slot));
+            values.insert(0, format!("QWORD [rbp-{}]", slot));
+            return_buffer = Some(buffer);
+        }
+
+        // Arguments au-delà du sixième : sur la pile, en ordre inverse
+        let stack_args = values.len().saturating_sub(ARG_REGISTERS.len());
+        let padding = if stack_args % 2 == 1 { 8 } else { 0 };
+        if padding > 0 {
+            code.push_str("    sub rsp, 8  ; Alignement de la pile\n");
+        }
+        for value in values.iter().skip(ARG_REGISTERS.len()).rev() {
+            code.push_str(&format!("    push {}\n", value));
+        }
+        for (register, value) in ARG_REGISTERS.iter().zip(&values) {
+            code.push_str(&format!("    mov {}, {}\n", register, value));
+        }
+
+        code.push_str(&format!("    call {}\n", target));
+
+        if stack_args > 0 {
+            code.push_str(&format!("    add rsp, {}\n", stack_args * 8 + padding));
+        }
+
+        if let Some(buffer) = return_buffer {
+            code.push_str(&format!("    lea rax, [rbp-{}]  ; Valeur de retour\n", buffer));
+        } else if aggregate_return {
+            // Agrégat de 16 octets au plus renvoyé dans rax:rdx
+            let buffer = self.alloc_slot(16);
+            code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", buffer));
+            code.push_str(&format!("    mov QWORD [rbp-{}], rdx\n", buffer - 8));
+            code.push_str(&format!("    lea rax, [rbp-{}]  ; Valeur de retour\n", buffer));
+        }
+    }
+
+    // Adresse du receveur d'une méthode dans rax, après déréférencement automatique
+    fn generate_receiver(&mut self, obj_expr: &Expr) -> Result<(String, Type), usize> {
+        let mut typ = self.types.expr_type(obj_expr)?;
+
+        match typ.clone() {
+            Type::Ref(inner, _) | Type::Box(inner) | Type::Rc(inner) | Type::Guard(inner, _) if !matches!(*inner, Type::Dyn(_)) => {
+                let mut code = self.generate_expr_code(obj_expr)?;
+                typ = *inner;
+                while let Type::Ref(inner, _) | Type::Box(inner) | Type::Rc(inner) | Type::Guard(inner, _) = typ.clone() {
+                    if matches!(*inner, Type::Dyn(_)) {
+                        break;
+                    }
+                    // rax pointe déjà sur un pointeur large vers une tranche ou une chaîne
+                    if !matches!(*inner, Type::Slice(_) | Type::Str) {
+                        code.push_str("    mov rax, QWORD [rax]  ; Déréférencement automatique\n");
+                    }
+                    typ = *inner;
+                }
+                Ok((code, typ))
+            },
+            _ => Ok((self.generate_address(obj_expr)?, typ)),
+        }
+    }
+
+    fn generate_method_call(&mut self, obj_expr: &Expr, method_name: &str, type_args: &[Type], args: &[Expr]) -> Result<String, usize> {
+        let obj_type = self.types.expr_type(obj_expr)?;
+        let mut code = String::new();
+
+        match self.types.resolve_method(&obj_type, method_name)? {
+            MethodTarget::Static(info) => {
+                code.push_str(&format!("\n    ; Appel de méthode: {}\n", info.sig.label));
+                let (receiver_code, receiver_type) = self.generate_receiver(obj_expr)?;
+                code.push_str(&receiver_code);
+
+                // &self / &mut self reçoivent l'adresse ; self reçoit une copie de la valeur
+                let self_param = info.sig.params[0].clone();
+                if !matches!(self_param, Type::Ref(_, _)) && !self.is_aggregate(&receiver_type) {
+                    self.release.clear();
+                    code.push_str(&self.load_place(&receiver_type, "rax"));
+                    code.push_str(&std::mem::take(&mut self.release));
+                }
+                let self_slot = self.alloc_slot(8);
+                code.push_str(&format!("    mov QWORD [rbp-{}], rax  ; Receveur\n", self_slot));
+
+                let mut slots = vec![self_slot];
+                slots.extend(self.generate_arguments(args, &info.sig.params[1..], &mut code)?);
+                if self.is_aggregate(&self_param) {
+                    // Copie du receveur passé par valeur
+                    let size = self.type_size(&self_param);
+                    let copy = self.alloc_slot(size);
+                    code.push_str(&format!("    mov rax, QWORD [rbp-{}]\n", self_slot));
+                    code.push_str(&Self::copy_memory("rax", &format!("rbp-{}", copy), size));
+                    if self.needs_drop(&self_param) {
+                        // Le receveur est déplacé dans la méthode
+                        code.push_str(&self.vacate(&self_param, "rax", 0));
+                    }
+                    code.push_str(&format!("    lea rax, [rbp-{}]\n", copy));
+                    code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", self_slot));
+                }
+
+                self.emit_call(&mut code, &slots, &info.sig.label, &info.sig.return_type);
+            },
+            MethodTarget::Dynamic(trait_name, index) => {
+                let declared = self.types.traits[&trait_name].methods[index].function.clone();
+                if !matches!(declared.params[0].1, Type::Ref(_, _)) {
+                    return self.error(&format!(
+                        "impossible d'appeler `{}` sur `dyn {}` : la méthode prend `self` par valeur", method_name, trait_name));
+                }
+
+                code.push_str(&format!("\n    ; Appel dynamique: {}::{} via la vtable\n", trait_name, method_name));
+                let (receiver_code, _) = self.generate_receiver(obj_expr)?;
+                code.push_str(&receiver_code);
+
+                // rax pointe sur le pointeur large (données, vtable)
+                let self_slot = self.alloc_slot(8);
+                let vtable_slot = self.alloc_slot(8);
+                code.push_str("    mov rcx, QWORD [rax+8]\n");
+                code.push_str(&format!("    mov QWORD [rbp-{}], rcx  ; Vtable\n", vtable_slot));
+                code.push_str("    mov rcx, QWORD [rax]\n");
+                code.push_str(&format!("    mov QWORD [rbp-{}], rcx  ; Données (self)\n", self_slot));
+
+                let params: Vec<Type> = declared.params.iter().skip(1).map(|(_, typ)| typ.clone()).collect();
+                let mut slots = vec![self_slot];
+                slots.extend(self.generate_arguments(args, &params, &mut code)?);
+
+                let return_type = declared.return_type.clone().unwrap_or(Type::Void);
+                code.push_str(&format!("    mov r11, QWORD [rbp-{}]\n", vtable_slot));
+                let target = format!("[r11+{}]", VTABLE_HEADER_SIZE + index * 8);
+                self.emit_call(&mut code, &slots, &target, &return_type);
+            },
+            MethodTarget::Builtin if TypeChecker::iterator_item(&obj_type, method_name).is_some()
+                || matches!(IteratorMethod::from_name(method_name), Some(IteratorMethod::Source(_))) => {
+                code.push_str(&self.generate_iterator_consumer(obj_expr, method_name, type_args, args)?);
+            },
+            MethodTarget::Builtin => {
+                let receiver = TypeChecker::builtin_receiver(&obj_type, method_name);
+                if method_name == "clone" {
+                    code.push_str(&self.generate_clone(obj_expr)?);
+                } else if method_name == "cmp" {
+                    code.push_str(&self.generate_cmp(obj_expr, &args[0])?);
+                } else if let Type::Named(_) = receiver {
+                    code.push_str(&self.generate_ordering_method(obj_expr, method_name, args)?);
+                } else if let Type::RefCell(_) = receiver {
+                    code.push_str(&self.generate_cell_method(obj_expr, method_name)?);
+                } else if let Type::Option(_) = receiver {
+                    code.push_str(&self.generate_option_method(obj_expr, method_name, args)?);
+                } else if let Type::Result(_, _) = receiver {
+                    code.push_str(&self.generate_result_method(obj_expr, method_name, args)?);
+                } else if let Type::String | Type::Str = receiver {
+                    code.push_str(&self.generate_string_method(obj_expr, method_name, type_args, args)?);
+                } else if let Type::Char = receiver {
+                    code.push_str(&self.generate_char_method(obj_expr, method_name, args)?);
+                } else if let Type::Range(_, _) = receiver {
+                    code.push_str(&self.generate_range_method(obj_expr, method_name, args)?);
+                } else if let Type::HashMap(_, _) | Type::HashSet(_) | Type::Entry(_, _) = receiver {
+                    code.push_str(&self.generate_map_method(obj_expr, method_name, args)?);
+                } else {
+                    code.push_str(&self.generate_vec_method(obj_expr, method_name, args)?);
+                }
+            },
+        }
+
+        Ok(code)
+    }
+
+    fn generate_expr_code(&mut self, expr: &Expr) -> Result<String, usize> {
+        let mut code = String::new();
+
+        match expr {
+            Expr::Literal(Literal::Int(value)) => {
+                code.push_str(&format!("    mov rax, {}\n", value));
+            },
+            Expr::Literal(Literal::Bool(value)) => {
+                code.push_str(&format!("    mov rax, {}  ; {}\n", *value as i32, value));
+            },
+            Expr::Literal(Literal::String(value)) => {
+                // &str : pointeur large (données dans .rodata, longueur en octets)
+                let label = self.string_literal_label(value);
+                let fat = self.alloc_slot(16);
+                code.push_str(&format!("    lea rax, [rel {}]\n", label));
+                code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", fat));
+                code.push_str(&format!("    mov QWORD [rbp-{}], {}  ; Longueur\n", fat - VEC_LEN, value.len()));
+                code.push_str(&format!("    lea rax, [rbp-{}]\n", fat));
+            },
+            Expr::Literal(Literal::Char(value)) => {
+                code.push_str(&format!("    mov eax, {}  ; {:?}\n", *value as u32, value));
+            },
+            Expr::Literal(Literal::Array(_) | Literal::ArrayRepeat(_, _)) => {
+                let array_type = self.types.expr_type(expr)?;
+                let Type::Array(element_type, _) = &array_type else { unreachable!() };
+                code.push_str(&self.generate_array(expr, element_type)?);
+                code.push_str(&self.hold(&array_type));
+            },
+            Expr::Range(start, end, _) => {
+                // Valeur de plage : ses deux bornes, rangées comme les champs d'une structure
+                let Type::Range(element, _) = self.types.expr_type(expr)? else { unreachable!() };
+                let (offsets, size, _) = self.fields_layout(&[(*element).clone(), (*element).clone()]);
+                let range = self.alloc_slot(size);
+                for (bound, offset) in [start, end].into_iter().flatten().zip(offsets) {
+                    code.push_str(&self.generate_expr_code(bound)?);
+                    code.push_str(&self.store_to(&element, &format!("rbp-{}", range - offset)));
+                }
+                code.push_str(&format!("    lea rax, [rbp-{}]\n", range));
+            },
+            Expr::Format(format) => {
+                code.push_str("    ; format!\n");
+                let (format_code, buffer) = self.generate_format(format, None)?;
+                code.push_str(&format_code);
+                code.push_str(&format!("    mov rax, QWORD [rbp-{}]  ; String produite\n", buffer));
+                code.push_str(&self.hold(&Type::String));
+            },
+            Expr::Write(destination, format) => {
+                // L'écriture dans une String réussit toujours : Ok(())
+                let result_type = self.types.expr_type(expr)?;
+                code.push_str("    ; write!\n");
+                let (format_code, _) = self.generate_format(format, Some(destination))?;
+                code.push_str(&format_code);
+                let result = self.alloc_slot(self.type_size(&result_type));
+                code.push_str(&format!("    mov BYTE [rbp-{}], 1  ; Ok(())\n", result));
+                code.push_str(&format!("    lea rax, [rbp-{}]\n", result));
+            },
+            Expr::VecNew(elements) if elements.is_empty() => {
+                // Vec::new() n'alloue rien : pointeur nul, longueur et capacité nulles
+                let base = self.alloc_slot(VEC_SIZE);
+                code.push_str("    ; Vec::new()\n");
+                for field in [0, VEC_LEN, VEC_CAP] {
+                    code.push_str(&format!("    mov QWORD [rbp-{}], 0\n", base - field));
+                }
+                code.push_str(&format!("    lea rax, [rbp-{}]\n", base));
+            },
+            Expr::VecNew(_) | Expr::VecRepeat(_, _) => {
+                let vec_type = self.types.expr_type(expr)?;
+                let Type::Vec(element_type) = &vec_type else { unreachable!() };
+                code.push_str(&self.generate_vec_literal(expr, element_type)?);
+                code.push_str(&self.hold(&vec_type));
+            },
+            Expr::Index(_, _) => {
+                let element_type = self.types.expr_type(expr)?;
+                code.push_str(&self.generate_address(expr)?);
+                code.push_str("    ; Lecture de l'élément indexé\n");
+                code.push_str(&self.load_place(&element_type, "rax"));
+            },
+            Expr::MethodCall(obj_expr, method_name, type_args, args) => {
+                code.push_str(&self.generate_method_call(obj_expr, method_name, type_args, args)?);
+                // Le résultat est une valeur neuve, sauf la valeur contenue désignée dans le receveur par
+                // unwrap() et ses variantes
+                let obj_type = self.types.expr_type(obj_expr)?;
+                let receiver = TypeChecker::builtin_receiver(&obj_type, method_name);
+                let projection = matches!(receiver, Type::Option(_) | Type::Result(_, _))
+                    && matches!(method_name.as_str(), "unwrap" | "expect" | "unwrap_err");
+                if !projection {
+                    code.push_str(&self.hold(&self.types.expr_type(expr)?));
+                }
+            },
+            Expr::Variable(name) => {
+                // Récupérer les informations sur la variable
+                if let Some(var_info) = self.variable_info.get(name) {
+                    // Charger la variable selon son type (adresse pour un agrégat)
+                    code.push_str(&format!("    ; Load variable {}\n", name));
+                    let var_info = var_info.clone();
+                    if var_info.indirect {
+                        code.push_str(&format!("    mov rax, QWORD [rbp-{}]\n", var_info.offset));
+                        code.push_str(&self.load_place(&var_info.var_type, "rax"));
+                    } else {
+                        code.push_str(&self.load_place(&var_info.var_type, &format!("rbp-{}", var_info.offset)));
+                    }
+                } else {
+                    // Fonction nommée utilisée comme valeur : son adresse
+                    self.types.expr_type(expr)?;
+                    let (sig, _) = self.types.lookup_function(name)?;
+                    code.push_str(&format!("    lea rax, [rel {}]  ; Adresse de la fonction {}\n", sig.label, name));
+                }
+            },
+            Expr::FieldAccess(_, field_name) => {
+                let field_type = self.types.expr_type(expr)?;
+                code.push_str(&self.generate_address(expr)?);
+                code.push_str(&format!("    ; Lecture du champ {}\n", field_name));
+                code.push_str(&self.load_place(&field_type, "rax"));
+            },
+            Expr::TupleIndex(_, index) => {
+                let element_type = self.types.expr_type(expr)?;
+                code.push_str(&self.generate_address(expr)?);
+                code.push_str(&format!("    ; Lecture de l'élément {}\n", index));
+                code.push_str(&self.load_place(&element_type, "rax"));
+            },
+            Expr::Tuple(elements) => {
+                let tuple_type = self.types.expr_type(expr)?;
+                let Type::Tuple(types) = &tuple_type else {
+                    // Valeur unité : rien à calculer
+                    return Ok(code);
+                };
+
+                let (offsets, size, _) = self.fields_layout(types);
+                let base = self.alloc_slot(size);
+                code.push_str(&format!("    ; Construction du tuple {}\n", tuple_type));
+                for ((element, element_type), offset) in elements.iter().zip(types).zip(offsets) {
+                    code.push_str(&self.generate_owned(element, element_type)?);
+                    code.push_str(&self.store_to(element_type, &format!("rbp-{}", base - offset)));
+                }
+                code.push_str(&format!("    lea rax, [rbp-{}]\n", base));
+                code.push_str(&self.hold(&tuple_type));
+            },
+            Expr::StructLiteral(name, field_values) => {
+                let struct_type = self.types.resolve_type(&Type::Named(name.clone()));
+                let struct_name = struct_type.to_string();
+                if !self.types.structs.contains_key(&struct_name) {
+                    return self.error(&format!("Structure inconnue: {}", struct_name));
+                }
+
+                let (fields, size, _) = self.struct_layout(&struct_name);
+                for (field_name, _, _) in &fields {
+                    if !field_values.iter().any(|(name, _)| name == field_name) {
+                        return self.error(&format!("champ `{}` manquant dans l'initialisation de `{}`", field_name, struct_name));
+                    }
+                }
+
+                let base = self.alloc_slot(size);
+                code.push_str(&format!("    ; Construction de {}\n", struct_name));
+
+                // Les champs sont évalués dans l'ordre d'écriture
+                for (field_name, value) in field_values {
+                    let (field_type, offset) = match fields.iter().find(|(name, _, _)| name == field_name) {
+                        Some((_, field_type, offset)) => (field_type.clone(), *offset),
+                        None => return self.error(&format!("la structure `{}` n'a pas de champ `{}`", struct_name, field_name)),
+                    };
+                    code.push_str(&self.generate_owned(value, &field_type)?);
+                    code.push_str(&self.store_to(&field_type, &format!("rbp-{}", base - offset)));
+                }
+                if self.has_drop_impl(&struct_name) {
+                    code.push_str(&format!("    mov BYTE [rbp-{}], 1  ; Valeur initialisée\n", base - self.drop_flag(&struct_name)));
+                }
+
+                code.push_str(&format!("    lea rax, [rbp-{}]\n", base));
+                code.push_str(&self.hold(&struct_type));
+            },
+            Expr::Assign(place, value) => {
+                let place_type = self.types.expr_type(place)?;
+                code.push_str("    ; Affectation\n");
+                code.push_str(&self.generate_owned(value, &place_type)?);
+                let value_slot = self.alloc_slot(8);
+                code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", value_slot));
+                code.push_str(&self.generate_address(place)?);
+                if self.needs_drop(&place_type) {
+                    // L'ancienne valeur est détruite avant d'être remplacée
+                    let address = self.alloc_slot(8);
+                    code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", address));
+                    code.push_str(&self.drop_at(&place_type, "rax"));
+                    code.push_str(&format!("    mov rax, QWORD [rbp-{}]\n", address));
+                }
+                code.push_str("    mov rdx, rax\n");
+                code.push_str(&format!("    mov rax, QWORD [rbp-{}]\n", value_slot));
+                code.push_str(&self.store_to(&place_type, "rdx"));
+            },
+            Expr::CompoundAssign(place, _, value) if self.types.expr_type(place)? == Type::String => {
+                // `s += "..."` : les octets de la valeur sont ajoutés à la chaîne désignée
+                self.uses_vec_runtime = true;
+                let address = self.alloc_slot(8);
+                code.push_str("    ; Ajout à une chaîne\n");
+                code.push_str(&self.generate_address(place)?);
+                code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", address));
+                code.push_str(&self.generate_string_header(value)?);
+                code.push_str(&format!("    mov rsi, QWORD [rax]\n    mov rdx, QWORD [rax+{}]\n", VEC_LEN));
+                code.push_str(&format!("    mov rdi, QWORD [rbp-{}]\n", address));
+                code.push_str("    mov ecx, 1\n");
+                code.push_str("    call __vec_extend\n");
+            },
+            Expr::CompoundAssign(place, op, value) => {
+                // La valeur est évaluée d'abord, puis l'adresse du lieu, une seule fois
+                let place_type = self.types.expr_type(place)?;
+                code.push_str("    ; Affectation composée\n");
+                code.push_str(&self.generate_expr_code(value)?);
+                code.push_str(&self.deref_scalar(&self.types.expr_type(value)?));
+                let value_slot = self.alloc_slot(8);
+                code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", value_slot));
+                code.push_str(&self.generate_address(place)?);
+                let address = self.alloc_slot(8);
+                code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", address));
+                code.push_str(&self.load_from(&place_type, "rax"));
+                code.push_str(&format!("    mov rcx, QWORD [rbp-{}]\n", value_slot));
+                code.push_str(Self::binary_operation(op));
+                code.push_str(&format!("    mov rdx, QWORD [rbp-{}]\n", address));
+                code.push_str(&self.store_to(&place_type, "rdx"));
+            },
+            Expr::Unary(op, operand) => {
+                match op {
+                    UnaryOp::Negate => {
+                        code.push_str(&self.generate_expr_code(operand)?);
+                        code.push_str("    neg rax\n");
+                    },
+                    UnaryOp::Ref | UnaryOp::RefMut => {
+                        code.push_str(&self.generate_address(operand)?);
+                    },
+                    UnaryOp::Deref => {
+                        let target_type = self.types.expr_type(expr)?;
+                        code.push_str(&self.generate_expr_code(operand)?);
+                        code.push_str(&self.load_place(&target_type, "rax"));
+                    },
+                }
+            },
+            Expr::Binary(left, op, right) if TypeChecker::string_derefs(&self.types.expr_type(left)?).is_some() => {
+                code.push_str(&self.generate_string_operation(expr, left, op, right)?);
+            },
+            Expr::Binary(left, op, right) => {
+                // Évaluer d'abord l'opérande gauche ; une référence à un scalaire opère sur la valeur désignée
+                code.push_str(&self.generate_expr_code(left)?);
+                code.push_str(&self.deref_scalar(&self.types.expr_type(left)?));
+                // Sauvegarder le résultat
+                let left_slot = self.alloc_slot(8);
+                code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", left_slot));
+                // Évaluer ensuite l'opérande droite
+                code.push_str(&self.generate_expr_code(right)?);
+                code.push_str(&self.deref_scalar(&self.types.expr_type(right)?));
+                // Opérande droite dans rcx, gauche dans rax
+                code.push_str("    mov rcx, rax\n");
+                code.push_str(&format!("    mov rax, QWORD [rbp-{}]\n", left_slot));
+
+                // Effectuer l'opération
+                code.push_str(Self::binary_operation(op));
+            },
+            Expr::FunctionCall(callee, _, args) if VARIANTS.contains(&callee.as_str()) => {
+                code.push_str(&self.generate_variant(expr, callee, args)?);
+            },
+            Expr::FunctionCall(callee, _, args) if self.types.enum_variant(callee).is_some() => {
+                code.push_str(&self.generate_enum_variant(expr, callee, args)?);
+            },
+            Expr::Try(operand) => {
+                code.push_str(&self.generate_try(operand)?);
+            },
+            Expr::Closure(closure) => {
+                code.push_str(&self.generate_closure(closure)?);
+                code.push_str(&self.hold(&self.types.expr_type(expr)?));
+            },
+            Expr::Call(callee, args) => {
+                code.push_str(&self.generate_indirect_call(callee, args)?);
+                code.push_str(&self.hold(&self.types.expr_type(expr)?));
+            },
+            Expr::FunctionCall(callee, _, args) => {
+                if callee == "Box::new" {
+                    return self.generate_box_new(args);
+                }
+                if matches!(callee.as_str(), "Rc::new" | "RefCell::new" | "Rc::clone" | "Rc::strong_count") {
+                    return self.generate_shared_call(callee, args);
+                }
+                if callee == "String::new" {
+                    // Comme Vec::new(), String::new() n'alloue rien
+                    let base = self.alloc_slot(VEC_SIZE);
+                    code.push_str("    ; String::new()\n");
+                    for field in [0, VEC_LEN, VEC_CAP] {
+                        code.push_str(&format!("    mov QWORD [rbp-{}], 0\n", base - field));
+                    }
+                    code.push_str(&format!("    lea rax, [rbp-{}]\n", base));
+                    return Ok(code);
+                }
+                if callee == "HashMap::new" || callee == "HashSet::new" {
+                    // Table vide sans entrées allouées : la première insertion la fait grandir
+                    let base = self.alloc_slot(VEC_SIZE);
+                    code.push_str(&format!("    ; {}()\n", callee));
+                    for field in [0, VEC_LEN, VEC_CAP] {
+                        code.push_str(&format!("    mov QWORD [rbp-{}], 0\n", base - field));
+                    }
+                    code.push_str(&format!("    lea rax, [rbp-{}]\n", base));
+                    return Ok(code);
+                }
+                if callee == "String::from" {
+                    code.push_str(&self.generate_string_method(&args[0], "to_string", &[], &[])?);
+                    code.push_str(&self.hold(&Type::String));
+                    return Ok(code);
+                }
+                if callee == "drop" && !self.types.functions.contains_key("drop") {
+                    // drop(valeur) : la valeur est déplacée puis détruite aussitôt
+                    let value_type = self.types.expr_type(&args[0])?;
+                    code.push_str("    ; drop()\n");
+                    code.push_str(&self.generate_owned(&args[0], &value_type)?);
+                    if self.needs_drop(&value_type) {
+                        if !self.is_aggregate(&value_type) {
+                            let value = self.alloc_slot(8);
+                            code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", value));
+                            code.push_str(&format!("    lea rax, [rbp-{}]\n", value));
+                        }
+                        code.push_str(&self.drop_at(&value_type, "rax"));
+                    }
+                    return Ok(code);
+                }
+
+                code.push_str(&format!("\n    ; Appel de fonction: {}()\n", callee));
+
+                let (sig, _) = self.types.lookup_function(callee)?;
+                let slots = self.generate_arguments(args, &sig.params, &mut code)?;
+                self.emit_call(&mut code, &slots, &sig.label, &sig.return_type);
+
+                // Le résultat est déjà dans rax
+                code.push_str(&self.hold(&sig.return_type));
+            },
+        }
+
+        Ok(code)
+    }
+
+    // Opération binaire entière entre rax (gauche) et rcx (droite), résultat dans rax
+    fn binary_operation(op: &BinaryOp) -> &'static str {
+        match op {
+            BinaryOp::Add => "    add rax, rcx\n",
+            BinaryOp::Subtract => "    sub rax, rcx\n",
+            BinaryOp::Multiply => "    imul rax, rcx\n",
+            // cqo étend le signe de rax dans rdx pour la division ; le reste est dans rdx
+            BinaryOp::Divide => "    cqo\n    idiv rcx\n",
+            BinaryOp::Modulo => "    cqo\n    idiv rcx\n    mov rax, rdx\n",
+            BinaryOp::Equal => "    cmp rax, rcx\n    sete al\n    movzx rax, al\n",
+            BinaryOp::NotEqual => "    cmp rax, rcx\n    setne al\n    movzx rax, al\n",
+            BinaryOp::Less => "    cmp rax, rcx\n    setl al\n    movzx rax, al\n",
+            BinaryOp::LessEqual => "    cmp rax, rcx\n    setle al\n    movzx rax, al\n",
+            BinaryOp::Greater => "    cmp rax, rcx\n    setg al\n    movzx rax, al\n",
+            BinaryOp::GreaterEqual => "    cmp rax, rcx\n    setge al\n    movzx rax, al\n",
+        }
+    }
+
+    // Champ de l'environnement d'une fermeture : l'adresse de la variable, ou sa valeur avec `move`
+    fn capture_field_type(&self, typ: &Type, by_move: bool) -> Type {
+        if by_move {
+            typ.clone()
+        } else {
+            Type::Ref(Box::new(typ.clone()), true)
+        }
+    }
+
+    // Fermeture : son environnement est alloué sur le tas et rempli des captures ; la valeur produite
+    // est la paire (code, environnement), et le corps est émis plus tard sous sa propre étiquette.
+    // L'environnement commence par son compteur de références et la glue de destruction de ses captures.
+    fn generate_closure(&mut self, closure: &Closure) -> Result<String, usize> {
+        let label = self.new_label("__closure");
+        let mut captures = Vec::new();
+        for name in &closure.captures {
+            match self.types.variables.get(name) {
+                Some(typ) => captures.push((name.clone(), typ.clone(), closure.is_move)),
+                None => return self.error(&format!("Variable non trouvée: {}", name)),
+            }
+        }
+        let types: Vec<Type> = captures.iter().map(|(_, typ, by_move)| self.capture_field_type(typ, *by_move)).collect();
+        let (offsets, size, _) = self.fields_layout(&types);
+
+        let (value, env) = (self.alloc_slot(16), self.alloc_slot(8));
+        let mut code = format!("    ; Fermeture {}\n", label);
+        if size > 0 {
+            let env_type = Type::Tuple(types.clone());
+            code.push_str(&format!("    mov rdi, {}\n", CLOSURE_ENV_HEADER + size));
+            code.push_str("    call __alloc\n");
+            code.push_str("    mov QWORD [rax], 1  ; Une seule référence\n");
+            if self.needs_drop(&env_type) {
+                code.push_str(&format!("    lea rcx, [rel {}]\n", self.drop_function(&env_type)));
+                code.push_str("    mov QWORD [rax+8], rcx  ; Destruction des captures\n");
+            } else {
+                code.push_str("    mov QWORD [rax+8], 0\n");
+            }
+        } else {
+            code.push_str("    xor eax, eax  ; Aucune capture\n");
+        }
+        code.push_str(&format!("    mov QWORD [rbp-{}], rax  ; Environnement\n", env));
+        for ((name, typ, by_move), offset) in captures.iter().zip(offsets) {
+            let variable = Expr::Variable(name.clone());
+            if *by_move {
+                code.push_str(&self.generate_owned(&variable, typ)?);
+                code.push_str(&format!("    mov rdx, QWORD [rbp-{}]\n", env));
+                code.push_str(&self.store_to(typ, &format!("rdx+{}", CLOSURE_ENV_HEADER + offset)));
+            } else {
+                code.push_str(&self.generate_address(&variable)?);
+                code.push_str(&format!("    mov rdx, QWORD [rbp-{}]\n", env));
+                code.push_str(&format!("    mov QWORD [rdx+{}], rax  ; &{}\n", CLOSURE_ENV_HEADER + offset, name));
+            }
+        }
+        code.push_str(&format!("    lea rax, [rel {}]\n", label));
+        code.push_str(&format!("    mov QWORD [rbp-{}], rax  ; Code\n", value));
+        code.push_str(&format!("    mov rax, QWORD [rbp-{}]\n", env));
+        code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", value - 8));
+        code.push_str(&format!("    lea rax, [rbp-{}]\n", value));
+
+        self.pending_closures.push((label, closure.clone(), captures, self.types.self_type.clone()));
+        Ok(code)
+    }
+
+    // Évalue une valeur appelable et range dans un emplacement l'adresse de sa fermeture, ou l'adresse
+    // du code pour un pointeur de fonction ; renvoie aussi sa signature
+    fn generate_callee(&mut self, callee: &Expr) -> Result<(String, usize, bool, Vec<Type>, Type), usize> {
+        let mut typ = self.types.expr_type(callee)?;
+        let (params, return_type) = self.types.callable_signature(&typ)?;
+        let mut code = self.generate_expr_code(callee)?;
+
+        // Une référence vers une fermeture vaut déjà l'adresse de la fermeture
+        let mut pointers: usize = 0;
+        while let Type::Ref(inner, _) | Type::Box(inner) = typ {
+            pointers += 1;
+            typ = *inner;
+        }
+        let is_closure = matches!(typ, Type::Closure(_, _));
+        let loads = if is_closure { pointers.saturating_sub(1) } else { pointers };
+        for _ in 0..loads {
+            code.push_str("    mov rax, QWORD [rax]\n");
+        }
+        let slot = self.alloc_slot(8);
+        code.push_str(&format!("    mov QWORD [rbp-{}], rax  ; Fonction appelée\n", slot));
+        Ok((code, slot, is_closure, params, return_type))
+    }
+
+    // Cible d'un appel indirect dans r11 ; l'environnement d'une fermeture est passé dans r10
+    fn load_callee(slot: usize, is_closure: bool) -> String {
+        if is_closure {
+            format!("    mov rax, QWORD [rbp-{}]\n    mov r11, QWORD [rax]\n    mov r10, QWORD [rax+8]\n", slot)
+        } else {
+            format!("    mov r11, QWORD [rbp-{}]\n", slot)
+        }
+    }
+
+    // Appel d'une fermeture ou d'un pointeur de fonction : `call r11`
+    fn generate_indirect_call(&mut self, callee: &Expr, args: &[Expr]) -> Result<String, usize> {
+        let (mut code, slot, is_closure, params, return_type) = self.generate_callee(callee)?;
+        code.insert_str(0, "\n    ; Appel indirect\n");
+        let slots = self.generate_arguments(args, &params, &mut code)?;
+        code.push_str(&Self::load_callee(slot, is_closure));
+        self.emit_call(&mut code, &slots, "r11", &return_type);
+        Ok(code)
+    }
+
+    // Box::new(valeur) : copie la valeur dans un bloc alloué sur le tas
+    // Variante d'Option ou de Result construite dans un emplacement du cadre : étiquette puis valeur
+    fn generate_variant(&mut self, expr: &Expr, name: &str, args: &[Expr]) -> Result<String, usize> {
+        let typ = self.types.expr_type(expr)?;
+        let (tag, payload_type, offset) = match &typ {
+            Type::Option(inner) => (name == "Some", (**inner).clone(), self.fields_layout(&[Type::Bool, (**inner).clone()]).0[1]),
+            Type::Result(ok, err) => {
+                let payload_type = if name == "Ok" { ok } else { err };
+                (name == "Ok", (**payload_type).clone(), self.result_layout(ok, err).0)
+            },
+            _ => unreachable!(),
+        };
+
+        let base = self.alloc_slot(self.type_size(&typ));
+        let mut code = format!("    ; {}\n", name);
+        if let Some(arg) = args.first() {
+            code.push_str(&self.generate_owned(arg, &payload_type)?);
+            code.push_str(&self.store_to(&payload_type, &format!("rbp-{}", base - offset)));
+        }
+        code.push_str(&format!("    mov BYTE [rbp-{}], {}\n", base, tag as u8));
+        code.push_str(&format!("    lea rax, [rbp-{}]\n", base));
+        code.push_str(&self.hold(&typ));
+        Ok(code)
+    }
+
+    // Variante d'une énumération construite dans un emplacement du cadre : ses champs puis son étiquette
+    fn generate_enum_variant(&mut self, expr: &Expr, name: &str, args: &[Expr]) -> Result<String, usize> {
+        let typ = self.types.expr_type(expr)?;
+        let (index, fields) = self.variant_fields(name);
+
+        let base = self.alloc_slot(self.type_size(&typ));
+        let mut code = format!("    ; {}\n", name);
+        for (arg, (field, offset)) in args.iter().zip(fields) {
+            code.push_str(&self.generate_owned(arg, &field)?);
+            code.push_str(&self.store_to(&field, &format!("rbp-{}", base - offset)));
+        }
+        code.push_str(&format!("    mov BYTE [rbp-{}], {}\n", base, index + 1));
+        code.push_str(&format!("    lea rax, [rbp-{}]\n", base));
+        code.push_str(&self.hold(&typ));
+        Ok(code)
+    }
+
+    // expr? : la valeur de Some ou de Ok, sinon la fonction renvoie aussitôt None ou l'erreur
+    fn generate_try(&mut self, operand: &Expr) -> Result<String, usize> {
+        let operand_type = self.types.expr_type(operand)?;
+        let return_type = self.return_type.clone();
+        let (payload_type, payload) = match &operand_type {
+            Type::Option(inner) => ((**inner).clone(), self.fields_layout(&[Type::Bool, (**inner).clone()]).0[1]),
+            Type::Result(ok, err) => ((**ok).clone(), self.result_layout(ok, err).0),
+            other => return self.error(&format!(
+                "l'opérateur `?` s'applique à un `Result` ou à une `Option`, pas à `{}`", self.types.display_type(other))),
+        };
+
+        let success = self.new_label("L_try_ok");
+        let mut code = self.generate_expr_code(operand)?;
+        code.push_str("    ; Opérateur ?\n");
+        code.push_str("    cmp BYTE [rax], 0\n");
+        code.push_str(&format!("    jne {}\n", success));
+
+        let failure = self.alloc_slot(self.type_size(&return_type));
+        code.push_str(&format!("    mov BYTE [rbp-{}], 0\n", failure));
+        if let (Type::Result(_, err), Type::Result(return_ok, return_err)) = (&operand_type, &return_type) {
+            // L'erreur est recopiée à son décalage dans le Result renvoyé
+            let target = self.result_layout(return_ok, return_err).0;
+            // puis remise à zéro dans l'opérande, qui ne la possède plus
+            code.push_str(&format!("    lea rcx, [rax+{}]\n", payload));
+            code.push_str(&Self::copy_memory("rcx", &format!("rbp-{}", failure - target), self.type_size(err)));
+            if self.needs_drop(err) {
+                code.push_str(&self.vacate(err, "rax", payload));
+            }
+        }
+        code.push_str(&format!("    lea rax, [rbp-{}]\n", failure));
+        code.push_str(&self.generate_return()?);
+
+        code.push_str(&format!("{}:\n", success));
+        code.push_str(&self.load_place(&payload_type, &format!("rax+{}", payload)));
+        Ok(code)
+    }
+
+    fn generate_box_new(&mut self, args: &[Expr]) -> Result<String, usize> {
+        if args.len() != 1 {
+            return self.error("Box::new attend exactement un argument");
+        }
+
+        let value_type = self.types.expr_type(&args[0])?;
+        let size = self.type_size(&value_type);
+        let mut code = String::new();
+
+        code.push_str("    ; Box::new\n");
+        code.push_str(&self.generate_owned(&args[0], &value_type)?);
+        let value_slot = self.alloc_slot(8);
+        code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", value_slot));
+        code.push_str(&format!("    mov rdi, {}\n", size.max(1)));
+        code.push_str("    call __alloc\n");
+        code.push_str("    mov rdx, rax\n");
+        code.push_str(&format!("    mov rax, QWORD [rbp-{}]\n", value_slot));
+        code.push_str(&self.store_to(&value_type, "rdx"));
+        code.push_str("    mov rax, rdx  ; Pointeur vers la valeur sur le tas\n");
+        code.push_str(&self.hold(&Type::Box(Box::new(value_type))));
+
+        Ok(code)
+    }
+
+    // Rc::new place la valeur sur le tas derrière son compteur de références ; RefCell::new la range dans
+    // la pile derrière son état d'emprunt ; Rc::clone et Rc::strong_count lisent le compteur
+    fn generate_shared_call(&mut self, callee: &str, args: &[Expr]) -> Result<String, usize> {
+        let [arg] = args else {
+            return self.error(&format!("{}() attend exactement un argument", callee));
+        };
+        let arg_type = self.types.expr_type(arg)?;
+        let mut code = format!("    ; {}\n", callee);
+
+        match callee {
+            "Rc::new" => {
+                code.push_str(&self.generate_owned(arg, &arg_type)?);
+                let value_slot = self.alloc_slot(8);
+                code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", value_slot));
+                code.push_str(&format!("    mov rdi, {}\n", 8 + self.type_size(&arg_type)));
+                code.push_str("    call __alloc\n");
+                code.push_str("    mov QWORD [rax], 1  ; Références fortes\n");
+                code.push_str("    lea rdx, [rax+8]\n");
+                code.push_str(&format!("    mov rax, QWORD [rbp-{}]\n", value_slot));
+                code.push_str(&self.store_to(&arg_type, "rdx"));
+                code.push_str("    mov rax, rdx  ; Pointeur vers la valeur partagée\n");
+                code.push_str(&self.hold(&Type::Rc(Box::new(arg_type))));
+            },
+            "RefCell::new" => {
+                let cell_type = Type::RefCell(Box::new(arg_type.clone()));
+                code.push_str(&self.generate_owned(arg, &arg_type)?);
+                let cell = self.alloc_slot(self.type_size(&cell_type));
+                code.push_str(&format!("    lea rdx, [rbp-{}]\n", cell - 8));
+                code.push_str(&self.store_to(&arg_type, "rdx"));
+                code.push_str(&format!("    mov QWORD [rbp-{}], 0  ; Aucun emprunt\n", cell));
+                code.push_str(&format!("    lea rax, [rbp-{}]\n", cell));
+                code.push_str(&self.hold(&cell_type));
             },
             _ => {
-                self.error_handler.report_error(0, &format!("Type d'instruction non pris en charge: {:?}", stmt));
-                return Err(0);
+                code.push_str(&self.generate_expr_code(arg)?);
+                code.push_str("    mov rax, QWORD [rax]  ; Rc désigné\n");
+                if callee == "Rc::clone" {
+                    code.push_str("    inc QWORD [rax-8]  ; Références fortes\n");
+                    let Type::Ref(rc_type, _) = arg_type else { unreachable!() };
+                    code.push_str(&self.hold(&rc_type));
+                } else {
+                    code.push_str("    mov rax, QWORD [rax-8]  ; Références fortes\n");
+                }
+            },
+        }
+        Ok(code)
+    }
+
+    // Méthodes borrow et borrow_mut d'une RefCell, dont l'état d'emprunt est vérifié à l'exécution :
+    // le Ref ou RefMut obtenu désigne la valeur, et sa destruction met fin à l'emprunt
+    fn generate_cell_method(&mut self, obj_expr: &Expr, method_name: &str) -> Result<String, usize> {
+        let mut code = format!("    ; {}()\n", method_name);
+        let (receiver_code, _) = self.generate_receiver(obj_expr)?;
+        code.push_str(&receiver_code);
+        self.uses_bounds_check = true;
+        let free = self.new_label("L_borrow_ok");
+        if method_name == "borrow" {
+            code.push_str("    cmp QWORD [rax], 0\n");
+            code.push_str(&format!("    jge {}\n", free));
+        } else {
+            code.push_str("    cmp QWORD [rax], 0\n");
+            code.push_str(&format!("    je {}\n", free));
+        }
+        let message = if method_name == "borrow" { "panic_already_mutably_borrowed" } else { "panic_already_borrowed" };
+        code.push_str(&format!("    mov edi, {}\n", self.types.current_line));
+        code.push_str(&format!("    lea rsi, [rel {}]\n", message));
+        code.push_str("    call __panic\n");
+        code.push_str(&format!("{}:\n", free));
+        if method_name == "borrow" {
+            code.push_str("    inc QWORD [rax]  ; Emprunt partagé\n");
+        } else {
+            code.push_str("    mov QWORD [rax], -1  ; Emprunt exclusif\n");
+        }
+        code.push_str("    add rax, 8  ; Valeur empruntée\n");
+        Ok(code)
+    }
+
+    // Étiquette .rodata d'une chaîne littérale, partagée entre toutes ses occurrences
+    fn string_literal_label(&mut self, value: &str) -> String {
+        let count = self.string_literals.len();
+        self.string_literals.entry(value.to_string()).or_insert_with(|| format!("str_{}", count)).clone()
+    }
+
+    // Construit le texte d'un format analysé à la suite de la String `destination`, ou dans un tampon
+    // neuf ayant la même disposition ; renvoie le code et l'emplacement contenant l'adresse du tampon.
+    // La destination puis chaque argument sont évalués une seule fois, avant que le texte ne soit assemblé.
+    fn generate_format(&mut self, format: &FormatArgs, destination: Option<&Expr>) -> Result<(String, usize), usize> {
+        self.uses_vec_runtime = true;
+        self.uses_string_runtime = true;
+        self.uses_format_runtime = true;
+
+        // Les arguments ont été vérifiés par la monomorphisation ; une référence à un scalaire est
+        // formatée comme la valeur désignée
+        let declared = format.args.iter().map(|arg| self.types.expr_type(arg)).collect::<Result<Vec<_>, _>>()?;
+        let types: Vec<Type> = declared.iter().map(|typ| TypeChecker::scalar_pointee(typ).0.clone()).collect();
+
+        // Une String s'évalue en son adresse, un &mut String en l'adresse de la String désignée
+        let mut code = String::new();
+        let buffer = self.alloc_slot(8);
+        if let Some(destination) = destination {
+            code.push_str("\n    ; Destination de l'écriture\n");
+            code.push_str(&self.generate_expr_code(destination)?);
+            if matches!(self.types.expr_type(destination)?, Type::Ref(inner, _) if TypeChecker::is_formatter(&inner)) {
+                code.push_str("    mov rax, QWORD [rax]  ; String du Formatter\n");
+            }
+        } else {
+            let string = self.alloc_slot(VEC_SIZE);
+            for field in [0, VEC_LEN, VEC_CAP] {
+                code.push_str(&format!("    mov QWORD [rbp-{}], 0\n", string - field));
+            }
+            code.push_str(&format!("    lea rax, [rbp-{}]\n", string));
+        }
+        code.push_str(&format!("    mov QWORD [rbp-{}], rax  ; Adresse du tampon\n", buffer));
+
+        // Valeur de chaque argument dans son emplacement ; une chaîne y range son pointeur puis sa longueur
+        let mut slots = Vec::new();
+        for ((arg, typ), declared) in format.args.iter().zip(&types).zip(&declared) {
+            let slot = self.alloc_slot(16);
+            if TypeChecker::string_derefs(typ).is_some() {
+                code.push_str("\n    ; Évaluation d'une chaîne\n");
+                code.push_str(&self.generate_string_header(arg)?);
+                code.push_str("    mov rcx, QWORD [rax]\n");
+                code.push_str(&format!("    mov QWORD [rbp-{}], rcx  ; Octets\n", slot));
+                code.push_str(&format!("    mov rcx, QWORD [rax+{}]\n", VEC_LEN));
+                code.push_str(&format!("    mov QWORD [rbp-{}], rcx  ; Longueur\n", slot - VEC_LEN));
+            } else {
+                code.push_str("\n    ; Évaluation d'un argument\n");
+                code.push_str(&self.generate_expr_code(arg)?);
+                code.push_str(&self.deref_scalar(declared));
+                if *typ == Type::Named(PARSE_INT_ERROR.to_string()) {
+                    code.push_str("    movzx eax, BYTE [rax]  ; Cause de l'erreur\n");
+                }
+                code.push_str(&format!("    mov QWORD [rbp-{}], rax  ; Sauvegarde de l'argument\n", slot));
+            }
+            slots.push(slot);
+        }
+
+        for piece in &format.pieces {
+            let (index, spec) = match piece {
+                Piece::Text(text) => {
+                    code.push_str(&self.generate_format_text(&format!("QWORD [rbp-{}]", buffer), text));
+                    continue;
+                },
+                Piece::Argument(index, spec) => (*index, spec),
+            };
+            let (typ, slot) = (&types[index], slots[index]);
+            let count = |count: &Option<Count>, default: &str| match count {
+                Some(Count::Literal(value)) => value.to_string(),
+                Some(Count::Argument(index)) => format!("QWORD [rbp-{}]", slots[*index]),
+                None => default.to_string(),
+            };
+            let (width, precision) = (count(&spec.width, "0"), count(&spec.precision, "-1"));
+            code.push_str(&format!("\n    ; Argument {} affiché avec {}\n", index, spec.kind.placeholder()));
+
+            // {:?} : une chaîne se lit dans l'en-tête (pointeur, longueur) de son emplacement, un agrégat
+            // à l'adresse qui y est rangée, un scalaire dans l'emplacement lui-même
+            // Implémentation de Display écrite par l'utilisateur : fmt(&valeur, f), où le Formatter
+            // est l'emplacement contenant l'adresse du tampon
+            let (pointee, derefs) = Self::pointee(typ);
+            if let (Kind::Display, Some(label)) = (spec.kind, self.types.user_fmt(pointee, "Display")) {
+                code.push_str(&self.load_format_argument(typ, slot));
+                for _ in 0..derefs {
+                    code.push_str("    mov rax, QWORD [rax]  ; Déréférencement\n");
+                }
+                code.push_str("    mov rdi, rax\n");
+                code.push_str(&format!("    lea rsi, [rbp-{}]  ; Formatter\n", buffer));
+                code.push_str(&format!("    call {}\n", label));
+                continue;
+            }
+
+            if spec.kind == Kind::Debug {
+                let value_type = if TypeChecker::string_derefs(typ).is_some() { Type::Str } else { typ.clone() };
+                code.push_str(&self.load_format_argument(typ, slot));
+                let formatter = format!("rbp-{}", buffer);
+                code.push_str(&self.generate_debug_value(&value_type, &formatter, &Self::format_spec_bits(spec).to_string(), &width, "0")?);
+                continue;
+            }
+
+            if TypeChecker::is_integer(typ) {
+                code.push_str(&format!("    mov rax, QWORD [rbp-{}]\n", slot));
+                // Les bases autres que 10 affichent la représentation non signée de la largeur du type
+                code.push_str(match (typ, spec.kind) {
+                    (Type::U8, _) | (Type::I8, Kind::LowerHex | Kind::UpperHex | Kind::Binary | Kind::Octal) => "    movzx eax, al\n",
+                    (Type::I16, Kind::LowerHex | Kind::UpperHex | Kind::Binary | Kind::Octal) => "    movzx eax, ax\n",
+                    (Type::I32, Kind::LowerHex | Kind::UpperHex | Kind::Binary | Kind::Octal) => "    mov eax, eax\n",
+                    (Type::I8, _) => "    movsx rax, al\n",
+                    (Type::I16, _) => "    movsx rax, ax\n",
+                    (Type::I32, _) => "    movsxd rax, eax\n",
+                    _ => "",
+                });
+                code.push_str("    mov rsi, rax\n");
+                code.push_str(&format!("    mov rdx, {}  ; Spécification\n", Self::format_spec_bits(spec)));
+                code.push_str(&format!("    mov rcx, {}  ; Largeur\n", width));
+                code.push_str(&format!("    mov rdi, QWORD [rbp-{}]\n", buffer));
+                code.push_str("    call __fmt_int\n");
+                continue;
+            }
+
+            // Texte : chaîne, booléen, caractère encodé en UTF-8 ou message d'un ParseIntError
+            match typ {
+                Type::Bool => {
+                    code.push_str(&format!("    mov rax, QWORD [rbp-{}]\n", slot));
+                    code.push_str("    lea rsi, [rel bool_true]\n");
+                    code.push_str("    lea rdx, [rel bool_false]\n");
+                    code.push_str("    test rax, rax\n");
+                    code.push_str("    cmovz rsi, rdx\n");
+                    code.push_str("    mov edx, 5\n");
+                    code.push_str("    sub rdx, rax  ; 4 octets pour true, 5 pour false\n");
+                },
+                Type::Char => {
+                    code.push_str(&format!("    mov edi, DWORD [rbp-{}]\n", slot));
+                    code.push_str(&format!("    lea rsi, [rbp-{}]\n", slot - 8));
+                    code.push_str("    call __utf8_encode\n");
+                    code.push_str(&format!("    lea rsi, [rbp-{}]\n", slot - 8));
+                    code.push_str("    mov rdx, rax\n");
+                },
+                Type::Named(_) => {
+                    code.push_str(&format!("    mov rax, QWORD [rbp-{}]\n", slot));
+                    code.push_str("    lea rcx, [rel parse_error_messages]\n");
+                    code.push_str("    mov rdi, QWORD [rcx+rax*8]\n");
+                    code.push_str(&format!("    mov QWORD [rbp-{}], rdi\n", slot - 8));
+                    code.push_str("    call strlen\n");
+                    code.push_str(&format!("    mov rsi, QWORD [rbp-{}]\n", slot - 8));
+                    code.push_str("    mov rdx, rax\n");
+                },
+                _ => {
+                    code.push_str(&format!("    mov rsi, QWORD [rbp-{}]\n", slot));
+                    code.push_str(&format!("    mov rdx, QWORD [rbp-{}]\n", slot - VEC_LEN));
+                },
+            }
+            code.push_str(&format!("    mov rcx, {}  ; Spécification\n", Self::format_spec_bits(spec)));
+            code.push_str(&format!("    mov r8, {}  ; Largeur\n", width));
+            code.push_str(&format!("    mov r9, {}  ; Précision\n", precision));
+            code.push_str(&format!("    mov rdi, QWORD [rbp-{}]\n", buffer));
+            code.push_str("    call __fmt_str\n");
+        }
+        Ok((code, buffer))
+    }
+
+    // Ajoute un texte littéral au tampon d'un format, dont l'adresse est dans l'opérande `buffer`
+    fn generate_format_text(&mut self, buffer: &str, text: &str) -> String {
+        let label = self.string_literal_label(text);
+        let mut code = format!("    mov rdi, {}\n", buffer);
+        code.push_str(&format!("    lea rsi, [rel {}]\n", label));
+        code.push_str(&format!("    mov edx, {}\n", text.len()));
+        code.push_str("    mov ecx, 1\n");
+        code.push_str("    call __vec_extend\n");
+        code
+    }
+
+    // Spécification condensée telle que la lisent `__fmt_int` et `__fmt_str` (voir runtime::FORMAT)
+    fn format_spec_bits(spec: &Spec) -> u64 {
+        let base = match spec.kind {
+            Kind::LowerHex | Kind::UpperHex => 16,
+            Kind::Binary => 2,
+            Kind::Octal => 8,
+            Kind::Display | Kind::Debug => 10,
+        };
+        let align = match spec.align {
+            None => 0,
+            Some(Align::Left) => 1,
+            Some(Align::Right) => 2,
+            Some(Align::Center) => 3,
+        };
+        base | ((spec.kind == Kind::UpperHex) as u64) << 8 | (spec.plus as u64) << 9 | (spec.alternate as u64) << 10
+            | (spec.zero as u64) << 11 | align << 16 | (spec.fill as u64) << 32
+    }
+
+    // Spécification de {:?} sans option
+    fn debug_spec() -> String {
+        let spec = Spec { fill: ' ', align: None, plus: false, alternate: false, zero: false, width: None, precision: None, kind: Kind::Debug };
+        Self::format_spec_bits(&spec).to_string()
+    }
+
+    // Adresse dans rax d'un argument de format évalué dans son emplacement : une chaîne y a rangé son en-tête
+    // (pointeur, longueur), un agrégat son adresse, un scalaire ou un pointeur sa valeur
+    fn load_format_argument(&self, typ: &Type, slot: usize) -> String {
+        if self.is_aggregate(typ) && TypeChecker::string_derefs(typ).is_none() && *typ != Type::Named(PARSE_INT_ERROR.to_string()) {
+            format!("    mov rax, QWORD [rbp-{}]\n", slot)
+        } else {
+            format!("    lea rax, [rbp-{}]\n", slot)
+        }
+    }
+
+    // Type désigné à travers les références et les pointeurs, et nombre de déréférencements pour l'atteindre
+    fn pointee(typ: &Type) -> (&Type, usize) {
+        match typ {
+            Type::Ref(inner, _) | Type::Box(inner) | Type::Rc(inner) | Type::Guard(inner, _) if !matches!(**inner, Type::Str | Type::Slice(_) | Type::Dyn(_)) => {
+                let (pointee, derefs) = Self::pointee(inner);
+                (pointee, derefs + 1)
+            },
+            _ => (typ, 0),
+        }
+    }
+
+    // Forme {:?} de la valeur de type `typ` dont l'adresse est dans rax, ajoutée au tampon dont l'adresse
+    // est rangée à `formatter` (le Formatter passé aux implémentations de Debug de l'utilisateur).
+    // Les feuilles (entiers, booléens, caractères, chaînes) sont écrites sur place ; les types composés
+    // appellent leur fonction d'affichage, qui reçoit la spécification, la largeur et la profondeur
+    // d'indentation de la forme {:#?}.
+    fn generate_debug_value(&mut self, typ: &Type, formatter: &str, spec: &str, width: &str, depth: &str) -> Result<String, usize> {
+        let buffer = &format!("QWORD [{}]", formatter);
+        let mut code = String::new();
+        if let Some(label) = self.types.user_fmt(typ, "Debug") {
+            code.push_str("    mov rdi, rax\n");
+            code.push_str(&format!("    lea rsi, [{}]  ; Formatter\n", formatter));
+            code.push_str(&format!("    call {}\n", label));
+            return Ok(code);
+        }
+        match typ {
+            _ if TypeChecker::is_integer(typ) => {
+                code.push_str(&self.load_from(typ, "rax"));
+                code.push_str("    mov rsi, rax\n");
+                code.push_str(&format!("    mov rdx, {}  ; Spécification\n", spec));
+                code.push_str(&format!("    mov rcx, {}  ; Largeur\n", width));
+                code.push_str(&format!("    mov rdi, {}\n", buffer));
+                code.push_str("    call __fmt_int\n");
+            },
+            Type::Bool => {
+                code.push_str("    movzx eax, BYTE [rax]\n");
+                code.push_str("    lea rsi, [rel bool_true]\n");
+                code.push_str("    lea rdx, [rel bool_false]\n");
+                code.push_str("    test rax, rax\n");
+                code.push_str("    cmovz rsi, rdx\n");
+                code.push_str("    mov edx, 5\n");
+                code.push_str("    sub rdx, rax  ; 4 octets pour true, 5 pour false\n");
+                code.push_str(&format!("    mov rcx, {}  ; Spécification\n", spec));
+                code.push_str(&format!("    mov r8, {}  ; Largeur\n", width));
+                code.push_str("    mov r9, -1\n");
+                code.push_str(&format!("    mov rdi, {}\n", buffer));
+                code.push_str("    call __fmt_str\n");
+            },
+            Type::Char => {
+                code.push_str("    mov esi, DWORD [rax]\n");
+                code.push_str(&format!("    mov rdi, {}\n", buffer));
+                code.push_str("    call __fmt_debug_char\n");
+            },
+            // Une String et un &str commencent par le pointeur vers les octets puis la longueur
+            Type::String | Type::Str => {
+                code.push_str("    mov rsi, QWORD [rax]\n");
+                code.push_str("    mov rdx, QWORD [rax+8]\n");
+                code.push_str("    mov ecx, 34  ; Guillemets\n");
+                code.push_str(&format!("    mov rdi, {}\n", buffer));
+                code.push_str("    call __fmt_debug_escape\n");
+            },
+            Type::Ref(inner, _) if **inner == Type::Str => {
+                code.push_str(&self.generate_debug_value(inner, formatter, spec, width, depth)?);
+            },
+            Type::Ref(inner, _) | Type::Box(inner) | Type::Rc(inner) | Type::Guard(inner, _) if !matches!(**inner, Type::Slice(_)) => {
+                code.push_str("    mov rax, QWORD [rax]  ; Déréférencement\n");
+                code.push_str(&self.generate_debug_value(inner, formatter, spec, width, depth)?);
+            },
+            Type::Void => code.push_str(&self.generate_format_text(buffer, "()")),
+            Type::Tuple(elements) if elements.is_empty() => code.push_str(&self.generate_format_text(buffer, "()")),
+            _ => {
+                let label = self.debug_function(typ);
+                code.push_str("    mov rsi, rax\n");
+                code.push_str(&format!("    mov rdi, {}\n", buffer));
+                code.push_str(&format!("    mov rdx, {}  ; Spécification\n", spec));
+                code.push_str(&format!("    mov rcx, {}  ; Largeur\n", width));
+                code.push_str(&format!("    mov r8, {}  ; Profondeur\n", depth));
+                code.push_str(&format!("    call {}\n", label));
+            },
+        }
+        Ok(code)
+    }
+
+    // Étiquette de la fonction d'affichage {:?} d'un type composé, émise une seule fois à la fin du programme
+    fn debug_function(&mut self, typ: &Type) -> String {
+        if let Some(label) = self.debug_functions.get(&typ.to_string()) {
+            return label.clone();
+        }
+        let label = format!("__debug_{}", self.debug_functions.len());
+        self.debug_functions.insert(typ.to_string(), label.clone());
+        self.pending_debug.push(typ.clone());
+        label
+    }
+
+    // Fonction d'affichage {:?} d'un type composé : tampon dans rdi, adresse de la valeur dans rsi,
+    // spécification dans rdx, largeur dans rcx et profondeur d'indentation dans r8.
+    // Ils restent dans rbx, r12, r13, r14 et r15 ; [rbp-48] compte les éléments d'une séquence ou d'une table,
+    // [rbp-56] contient la profondeur des valeurs contenues et [rbp-72] l'adresse du tampon (Formatter).
+    fn generate_debug_function(&mut self, typ: &Type) -> Result<String, usize> {
+        let label = self.debug_functions[&typ.to_string()].clone();
+        let mut code = format!("\n; Affichage {{:?}} de {}\n{}:\n", self.types.display_type(typ), label);
+        code.push_str("    push rbp\n");
+        code.push_str("    mov rbp, rsp\n");
+        for register in ["rbx", "r12", "r13", "r14", "r15"] {
+            code.push_str(&format!("    push {}\n", register));
+        }
+        code.push_str("    sub rsp, 40\n");
+        code.push_str("    mov rbx, rdi\n");
+        code.push_str("    mov QWORD [rbp-72], rdi\n");
+        code.push_str("    mov r12, rsi\n");
+        code.push_str("    mov r13, rdx\n");
+        code.push_str("    mov r14, rcx\n");
+        code.push_str("    mov r15, r8\n");
+        code.push_str("    lea rax, [r8+1]\n");
+        code.push_str("    mov QWORD [rbp-56], rax\n");
+
+        match typ {
+            Type::Named(name) if self.types.enums.contains_key(name) => {
+                // Nom de la variante présente, suivi de ses champs comme un tuple : Cons(1, Nil)
+                let variants = self.types.enums[name].variants.clone();
+                let end_label = self.new_label("debug_enum_end");
+                for (index, ((variant, fields), offsets)) in variants.iter().zip(self.enum_layout(name).0).enumerate() {
+                    let next_label = self.new_label("debug_variant");
+                    code.push_str(&format!("    cmp BYTE [r12], {}\n", index + 1));
+                    code.push_str(&format!("    jne {}\n", next_label));
+                    if fields.is_empty() {
+                        code.push_str(&self.generate_format_text("rbx", variant));
+                    } else {
+                        code.push_str(&self.generate_format_text("rbx", &format!("{}(", variant)));
+                        for (i, (field, offset)) in fields.iter().zip(offsets).enumerate() {
+                            code.push_str(&self.debug_separator(if i == 0 { "" } else { ", " }, if i == 0 { "" } else { "," }, "QWORD [rbp-56]"));
+                            code.push_str(&format!("    lea rax, [r12+{}]\n", offset));
+                            code.push_str(&self.generate_debug_value(field, "rbp-72", "r13", "r14", "QWORD [rbp-56]")?);
+                        }
+                        code.push_str(&self.debug_separator("", ",", "r15"));
+                        code.push_str(&self.generate_format_text("rbx", ")"));
+                    }
+                    code.push_str(&format!("    jmp {}\n", end_label));
+                    code.push_str(&format!("{}:\n", next_label));
+                }
+                code.push_str(&format!("{}:\n", end_label));
+            },
+            Type::Named(name) => {
+                // Nom écrit par l'utilisateur : forme générique d'une instance, dernier segment d'un chemin
+                let generic = self.types.instances.get(name).map_or(name.as_str(), |(generic, _)| generic.as_str());
+                let display = generic.rsplit("::").next().unwrap_or(generic).to_string();
+                let fields = self.struct_layout(name).0;
+                if fields.is_empty() {
+                    code.push_str(&self.generate_format_text("rbx", &display));
+                } else {
+                    code.push_str(&self.generate_format_text("rbx", &format!("{} {{", display)));
+                    for (i, (field_name, field_type, offset)) in fields.iter().enumerate() {
+                        code.push_str(&self.debug_separator(if i == 0 { " " } else { ", " }, if i == 0 { "" } else { "," }, "QWORD [rbp-56]"));
+                        code.push_str(&self.generate_format_text("rbx", &format!("{}: ", field_name)));
+                        if name == PARSE_INT_ERROR {
+                            // La cause est une variante d'énumération, affichée par son nom
+                            code.push_str(&format!("    movzx eax, BYTE [r12+{}]\n", offset));
+                            code.push_str("    lea rcx, [rel parse_error_kinds]\n");
+                            code.push_str("    mov rdi, QWORD [rcx+rax*8]\n");
+                            code.push_str("    mov QWORD [rbp-64], rdi\n");
+                            code.push_str("    call strlen\n");
+                            code.push_str("    mov rsi, QWORD [rbp-64]\n");
+                            code.push_str("    mov rdx, rax\n");
+                            code.push_str("    mov ecx, 1\n");
+                            code.push_str("    mov rdi, rbx\n");
+                            code.push_str("    call __vec_extend\n");
+                        } else {
+                            code.push_str(&format!("    lea rax, [r12+{}]\n", offset));
+                            code.push_str(&self.generate_debug_value(field_type, "rbp-72", "r13", "r14", "QWORD [rbp-56]")?);
+                        }
+                    }
+                    code.push_str(&self.debug_separator(" ", ",", "r15"));
+                    code.push_str(&self.generate_format_text("rbx", "}"));
+                }
+            },
+            Type::RefCell(inner) => {
+                // Comme une structure dont l'unique champ `value` suit l'état d'emprunt
+                code.push_str(&self.generate_format_text("rbx", "RefCell {"));
+                code.push_str(&self.debug_separator(" ", "", "QWORD [rbp-56]"));
+                code.push_str(&self.generate_format_text("rbx", "value: "));
+                code.push_str("    lea rax, [r12+8]\n");
+                code.push_str(&self.generate_debug_value(inner, "rbp-72", "r13", "r14", "QWORD [rbp-56]")?);
+                code.push_str(&self.debug_separator(" ", ",", "r15"));
+                code.push_str(&self.generate_format_text("rbx", "}"));
+            },
+            Type::Tuple(elements) => {
+                let offsets = self.fields_layout(elements).0;
+                code.push_str(&self.generate_format_text("rbx", "("));
+                for (i, (element, offset)) in elements.iter().zip(offsets).enumerate() {
+                    code.push_str(&self.debug_separator(if i == 0 { "" } else { ", " }, if i == 0 { "" } else { "," }, "QWORD [rbp-56]"));
+                    code.push_str(&format!("    lea rax, [r12+{}]\n", offset));
+                    code.push_str(&self.generate_debug_value(element, "rbp-72", "r13", "r14", "QWORD [rbp-56]")?);
+                }
+                // Un tuple à un élément garde sa virgule : (1,)
+                code.push_str(&self.debug_separator(if elements.len() == 1 { "," } else { "" }, ",", "r15"));
+                code.push_str(&self.generate_format_text("rbx", ")"));
+            },
+            Type::Option(inner) => {
+                let offset = self.fields_layout(&[Type::Bool, (**inner).clone()]).0[1];
+                let (none_label, end_label) = (self.new_label("debug_none"), self.new_label("debug_option_end"));
+                code.push_str("    cmp BYTE [r12], 0\n");
+                code.push_str(&format!("    je {}\n", none_label));
+                code.push_str(&self.generate_debug_variant("Some", inner, offset)?);
+                code.push_str(&format!("    jmp {}\n", end_label));
+                code.push_str(&format!("{}:\n", none_label));
+                code.push_str(&self.generate_format_text("rbx", "None"));
+                code.push_str(&format!("{}:\n", end_label));
+            },
+            Type::Result(ok, err) => {
+                let offset = self.result_layout(ok, err).0;
+                let (err_label, end_label) = (self.new_label("debug_err"), self.new_label("debug_result_end"));
+                code.push_str("    cmp BYTE [r12], 1\n");
+                code.push_str(&format!("    jne {}\n", err_label));
+                code.push_str(&self.generate_debug_variant("Ok", ok, offset)?);
+                code.push_str(&format!("    jmp {}\n", end_label));
+                code.push_str(&format!("{}:\n", err_label));
+                code.push_str(&self.generate_debug_variant("Err", err, offset)?);
+                code.push_str(&format!("{}:\n", end_label));
+            },
+            // Séquences : un Vec et une tranche donnent leurs éléments par (pointeur, longueur), un tableau en place
+            Type::Vec(element) | Type::Ref(element, _) | Type::Array(element, _) => {
+                let (element, length, data) = match (typ, &**element) {
+                    (Type::Array(_, length), _) => (&**element, length.to_string(), "r12"),
+                    (Type::Vec(_), _) => (&**element, format!("QWORD [r12+{}]", VEC_LEN), "QWORD [r12]"),
+                    (_, Type::Slice(element)) => (&**element, "QWORD [r12+8]".to_string(), "QWORD [r12]"),
+                    _ => unreachable!(),
+                };
+                let size = self.type_size(element);
+                let (loop_label, first_label, element_label, end_label, close_label) = (self.new_label("debug_loop"),
+                    self.new_label("debug_first"), self.new_label("debug_element"), self.new_label("debug_loop_end"), self.new_label("debug_close"));
+                code.push_str(&self.generate_format_text("rbx", "["));
+                code.push_str("    mov QWORD [rbp-48], 0\n");
+                code.push_str(&format!("{}:\n", loop_label));
+                code.push_str("    mov rax, QWORD [rbp-48]\n");
+                code.push_str(&format!("    cmp rax, {}\n", length));
+                code.push_str(&format!("    jae {}\n", end_label));
+                code.push_str("    test rax, rax\n");
+                code.push_str(&format!("    jz {}\n", first_label));
+                code.push_str(&self.debug_separator(", ", ",", "QWORD [rbp-56]"));
+                code.push_str(&format!("    jmp {}\n", element_label));
+                code.push_str(&format!("{}:\n", first_label));
+                code.push_str(&self.debug_separator("", "", "QWORD [rbp-56]"));
+                code.push_str(&format!("{}:\n", element_label));
+                code.push_str(&format!("    imul rax, QWORD [rbp-48], {}\n", size));
+                code.push_str(&format!("    add rax, {}  ; Adresse de l'élément\n", data));
+                code.push_str(&self.generate_debug_value(element, "rbp-72", "r13", "r14", "QWORD [rbp-56]")?);
+                code.push_str("    inc QWORD [rbp-48]\n");
+                code.push_str(&format!("    jmp {}\n", loop_label));
+                code.push_str(&format!("{}:\n", end_label));
+                code.push_str("    cmp QWORD [rbp-48], 0\n");
+                code.push_str(&format!("    je {}\n", close_label));
+                code.push_str(&self.debug_separator("", ",", "r15"));
+                code.push_str(&format!("{}:\n", close_label));
+                code.push_str(&self.generate_format_text("rbx", "]"));
+            },
+            // Tables : {clé: valeur, ...} ou {élément, ...} dans l'ordre des entrées ; [rbp-80] compte les entrées affichées
+            Type::HashMap(_, _) | Type::HashSet(_) => {
+                let (key, value) = Self::table_parts(typ);
+                let (value_offset, slot_size) = self.map_layout(typ);
+                let (loop_label, first_label, entry_label, next_label, end_label, close_label) = (self.new_label("debug_loop"),
+                    self.new_label("debug_first"), self.new_label("debug_entry"), self.new_label("debug_next"),
+                    self.new_label("debug_loop_end"), self.new_label("debug_close"));
+                code.push_str(&self.generate_format_text("rbx", "{"));
+                code.push_str("    mov QWORD [rbp-48], 0
+");
+                code.push_str("    mov QWORD [rbp-80], 0
+");
+                code.push_str(&format!("{}:
+", loop_label));
+                code.push_str("    mov rax, QWORD [rbp-48]
+");
+                code.push_str(&format!("    cmp rax, QWORD [r12+{}]
+", VEC_CAP));
+                code.push_str(&format!("    jae {}
+", end_label));
+                code.push_str(&format!("    imul rax, rax, {}
+", slot_size));
+                code.push_str("    add rax, QWORD [r12]
+");
+                code.push_str(&format!("    cmp QWORD [rax], 0  ; Entrée libre
+    je {}
+", next_label));
+                code.push_str("    cmp QWORD [rbp-80], 0
+");
+                code.push_str(&format!("    je {}
+", first_label));
+                code.push_str(&self.debug_separator(", ", ",", "QWORD [rbp-56]"));
+                code.push_str(&format!("    jmp {}
+", entry_label));
+                code.push_str(&format!("{}:
+", first_label));
+                code.push_str(&self.debug_separator("", "", "QWORD [rbp-56]"));
+                code.push_str(&format!("{}:
+", entry_label));
+                code.push_str("    inc QWORD [rbp-80]
+");
+                code.push_str(&format!("    imul rax, QWORD [rbp-48], {}
+", slot_size));
+                code.push_str("    add rax, QWORD [r12]
+");
+                code.push_str(&format!("    add rax, {}  ; Adresse de la clé
+", MAP_KEY));
+                code.push_str(&self.generate_debug_value(&key, "rbp-72", "r13", "r14", "QWORD [rbp-56]")?);
+                if let Some(value) = value {
+                    code.push_str(&self.generate_format_text("rbx", ": "));
+                    code.push_str(&format!("    imul rax, QWORD [rbp-48], {}
+", slot_size));
+                    code.push_str("    add rax, QWORD [r12]
+");
+                    code.push_str(&format!("    add rax, {}  ; Adresse de la valeur
+", value_offset));
+                    code.push_str(&self.generate_debug_value(&value, "rbp-72", "r13", "r14", "QWORD [rbp-56]")?);
+                }
+                code.push_str(&format!("{}:
+", next_label));
+                code.push_str("    inc QWORD [rbp-48]
+");
+                code.push_str(&format!("    jmp {}
+", loop_label));
+                code.push_str(&format!("{}:
+", end_label));
+                code.push_str("    cmp QWORD [rbp-80], 0
+");
+                code.push_str(&format!("    je {}
+", close_label));
+                code.push_str(&self.debug_separator("", ",", "r15"));
+                code.push_str(&format!("{}:
+", close_label));
+                code.push_str(&self.generate_format_text("rbx", "}"));
+            },
+            _ => unreachable!("type sans fonction d'affichage {{:?}} : {}", typ),
+        }
+
+        code.push_str("    add rsp, 40\n");
+        for register in ["r15", "r14", "r13", "r12", "rbx"] {
+            code.push_str(&format!("    pop {}\n", register));
+        }
+        code.push_str("    pop rbp\n");
+        code.push_str("    ret\n");
+        Ok(code)
+    }
+
+    // Variante à un champ d'Option ou de Result : Some(x), ou sur plusieurs lignes avec {:#?}
+    fn generate_debug_variant(&mut self, name: &str, typ: &Type, offset: usize) -> Result<String, usize> {
+        let mut code = self.generate_format_text("rbx", &format!("{}(", name));
+        code.push_str(&self.debug_separator("", "", "QWORD [rbp-56]"));
+        code.push_str(&format!("    lea rax, [r12+{}]\n", offset));
+        code.push_str(&self.generate_debug_value(typ, "rbp-72", "r13", "r14", "QWORD [rbp-56]")?);
+        code.push_str(&self.debug_separator("", ",", "r15"));
+        code.push_str(&self.generate_format_text("rbx", ")"));
+        Ok(code)
+    }
+
+    // Séparateur d'une fonction d'affichage {:?} : le texte `compact`, ou avec {:#?} (bit `#` de r13)
+    // le texte `pretty` suivi d'un saut de ligne indenté à la profondeur `depth`
+    fn debug_separator(&mut self, compact: &str, pretty: &str, depth: &str) -> String {
+        let (compact_label, end_label) = (self.new_label("debug_compact"), self.new_label("debug_separator_end"));
+        let mut code = format!("    test r13, 0x400\n    jz {}\n", compact_label);
+        if !pretty.is_empty() {
+            code.push_str(&self.generate_format_text("rbx", pretty));
+        }
+        code.push_str(&format!("    mov rsi, {}\n", depth));
+        code.push_str("    mov rdi, rbx\n");
+        code.push_str("    call __fmt_newline\n");
+        code.push_str(&format!("    jmp {}\n", end_label));
+        code.push_str(&format!("{}:\n", compact_label));
+        if !compact.is_empty() {
+            code.push_str(&self.generate_format_text("rbx", compact));
+        }
+        code.push_str(&format!("{}:\n", end_label));
+        code
+    }
+
+    // Étiquette de la glue de destruction d'un type, émise une seule fois à la fin du programme
+    fn drop_function(&mut self, typ: &Type) -> String {
+        if let Some(label) = self.drop_functions.get(&typ.to_string()) {
+            return label.clone();
+        }
+        let label = format!("__drop_{}", self.drop_functions.len());
+        self.drop_functions.insert(typ.to_string(), label.clone());
+        self.pending_drops.push(typ.clone());
+        label
+    }
+
+    // Glue de destruction d'un type : adresse de la valeur dans rdi, gardée dans rbx ; r12 compte les
+    // éléments d'une séquence. Le destructeur Drop passe avant les champs, détruits dans l'ordre de leur
+    // déclaration comme les éléments. La valeur est ensuite remise à zéro : une valeur nulle n'ayant rien
+    // à libérer, la détruire une seconde fois (après un déplacement, par exemple) est sans effet.
+    fn generate_drop_function(&mut self, typ: &Type) -> String {
+        let label = self.drop_functions[&typ.to_string()].clone();
+        let mut code = format!("\n; Destruction de {}\n{}:\n", self.types.display_type(typ), label);
+        code.push_str("    push rbp\n");
+        code.push_str("    mov rbp, rsp\n");
+        code.push_str("    push rbx\n");
+        code.push_str("    push r12\n");
+        code.push_str("    mov rbx, rdi\n");
+
+        let done = self.new_label("L_drop_done");
+        match typ {
+            Type::String | Type::Vec(_) => {
+                if let Type::Vec(element) = typ {
+                    code.push_str(&self.drop_elements(element, &format!("QWORD [rbx+{}]", VEC_LEN), "QWORD [rbx]"));
+                }
+                code.push_str("    mov rdi, QWORD [rbx]\n");
+                code.push_str("    call __free\n");
+            },
+            Type::Box(inner) => {
+                code.push_str("    mov rdi, QWORD [rbx]\n");
+                code.push_str(&format!("    test rdi, rdi\n    jz {}\n", done));
+                if let Type::Dyn(_) = **inner {
+                    // drop_in_place du type concret, en tête de la vtable
+                    let free = self.new_label("L_drop_free");
+                    code.push_str("    mov rax, QWORD [rbx+8]\n");
+                    code.push_str("    mov rax, QWORD [rax]\n");
+                    code.push_str(&format!("    test rax, rax\n    jz {}\n", free));
+                    code.push_str("    call rax\n");
+                    code.push_str(&format!("{}:\n", free));
+                    code.push_str("    mov rdi, QWORD [rbx]\n");
+                } else if self.needs_drop(inner) {
+                    code.push_str(&format!("    call {}\n", self.drop_function(inner)));
+                    code.push_str("    mov rdi, QWORD [rbx]\n");
+                }
+                code.push_str("    call __free\n");
+            },
+            Type::Rc(inner) => {
+                // Le dernier propriétaire détruit la valeur partagée puis libère le bloc et son compteur
+                code.push_str("    mov rdi, QWORD [rbx]\n");
+                code.push_str(&format!("    test rdi, rdi\n    jz {}\n", done));
+                code.push_str("    dec QWORD [rdi-8]  ; Références fortes\n");
+                code.push_str(&format!("    jnz {}\n", done));
+                if self.needs_drop(inner) {
+                    code.push_str(&format!("    call {}\n", self.drop_function(inner)));
+                    code.push_str("    mov rdi, QWORD [rbx]\n");
+                }
+                code.push_str("    sub rdi, 8\n");
+                code.push_str("    call __free\n");
+            },
+            Type::Guard(_, mutable) => {
+                // Fin de l'emprunt : l'état de la cellule précède la valeur empruntée
+                code.push_str("    mov rax, QWORD [rbx]\n");
+                code.push_str(&format!("    test rax, rax\n    jz {}\n", done));
+                if *mutable {
+                    code.push_str("    mov QWORD [rax-8], 0\n");
+                } else {
+                    code.push_str("    dec QWORD [rax-8]\n");
+                }
+            },
+            Type::RefCell(inner) => {
+                code.push_str(&self.drop_part(inner, 8));
+            },
+            Type::HashMap(_, _) | Type::HashSet(_) => {
+                // Clé et valeur de chaque entrée occupée, puis le tableau des entrées
+                let (key, value) = Self::table_parts(typ);
+                let (value_offset, slot_size) = self.map_layout(typ);
+                if self.needs_drop(&key) || value.as_ref().is_some_and(|value| self.needs_drop(value)) {
+                    let (loop_label, next_label, end_label) =
+                        (self.new_label("L_drop_entry"), self.new_label("L_drop_entry_next"), self.new_label("L_drop_entries_end"));
+                    code.push_str("    xor r12d, r12d
+");
+                    code.push_str(&format!("{}:
+", loop_label));
+                    code.push_str(&format!("    cmp r12, QWORD [rbx+{}]
+    jae {}
+", VEC_CAP, end_label));
+                    code.push_str(&format!("    imul rdi, r12, {}
+", slot_size));
+                    code.push_str("    add rdi, QWORD [rbx]
+");
+                    code.push_str(&format!("    cmp QWORD [rdi], 0
+    je {}
+", next_label));
+                    if self.needs_drop(&key) {
+                        code.push_str(&format!("    add rdi, {}
+", MAP_KEY));
+                        code.push_str(&format!("    call {}
+", self.drop_function(&key)));
+                    }
+                    if let Some(value) = value.filter(|value| self.needs_drop(value)) {
+                        code.push_str(&format!("    imul rdi, r12, {}
+", slot_size));
+                        code.push_str("    add rdi, QWORD [rbx]
+");
+                        code.push_str(&format!("    add rdi, {}
+", value_offset));
+                        code.push_str(&format!("    call {}
+", self.drop_function(&value)));
+                    }
+                    code.push_str(&format!("{}:
+", next_label));
+                    code.push_str("    inc r12
+");
+                    code.push_str(&format!("    jmp {}
+", loop_label));
+                    code.push_str(&format!("{}:
+", end_label));
+                }
+                code.push_str("    mov rdi, QWORD [rbx]
+");
+                code.push_str("    call __free
+");
+            },
+            Type::Entry(key, _) => {
+                code.push_str(&self.drop_part(key, 8));
+            },
+            Type::Closure(_, _) => {
+                // Le dernier propriétaire de l'environnement détruit les captures puis le libère
+                let free = self.new_label("L_drop_free");
+                code.push_str("    mov rdi, QWORD [rbx+8]\n");
+                code.push_str(&format!("    test rdi, rdi\n    jz {}\n", done));
+                code.push_str("    dec QWORD [rdi]  ; Références à l'environnement\n");
+                code.push_str(&format!("    jnz {}\n", done));
+                code.push_str("    mov rax, QWORD [rdi+8]\n");
+                code.push_str(&format!("    test rax, rax\n    jz {}\n", free));
+                code.push_str(&format!("    add rdi, {}\n", CLOSURE_ENV_HEADER));
+                code.push_str("    call rax\n");
+                code.push_str(&format!("{}:\n", free));
+                code.push_str("    mov rdi, QWORD [rbx+8]\n");
+                code.push_str("    call __free\n");
+            },
+            Type::Option(inner) => {
+                let payload = self.fields_layout(&[Type::Bool, (**inner).clone()]).0[1];
+                code.push_str(&format!("    cmp BYTE [rbx], 0\n    je {}\n", done));
+                code.push_str(&self.drop_part(inner, payload));
+            },
+            Type::Result(ok, err) => {
+                let payload = self.result_layout(ok, err).0;
+                let err_label = self.new_label("L_drop_err");
+                code.push_str(&format!("    cmp BYTE [rbx], 1\n    jne {}\n", err_label));
+                code.push_str(&self.drop_part(ok, payload));
+                code.push_str(&format!("    jmp {}\n{}:\n", done, err_label));
+                code.push_str(&self.drop_part(err, payload));
+            },
+            Type::Tuple(elements) => {
+                let (offsets, _, _) = self.fields_layout(elements);
+                for (element, offset) in elements.iter().zip(offsets) {
+                    code.push_str(&self.drop_part(element, offset));
+                }
+            },
+            Type::Array(element, length) => {
+                code.push_str(&self.drop_elements(element, &length.to_string(), "rbx"));
+            },
+            Type::Named(name) if self.types.enums.contains_key(name) => {
+                // Seuls les champs de la variante présente sont détruits
+                let variants = self.types.enums[name].variants.clone();
+                for (index, ((_, fields), offsets)) in variants.iter().zip(self.enum_layout(name).0).enumerate() {
+                    if !fields.iter().any(|field| self.needs_drop(field)) {
+                        continue;
+                    }
+                    let next = self.new_label("L_drop_variant");
+                    code.push_str(&format!("    cmp BYTE [rbx], {}\n    jne {}\n", index + 1, next));
+                    for (field, offset) in fields.iter().zip(offsets) {
+                        code.push_str(&self.drop_part(field, offset));
+                    }
+                    code.push_str(&format!("    jmp {}\n{}:\n", done, next));
+                }
+            },
+            Type::Named(name) => {
+                let fields = self.struct_layout(name).0;
+                if self.has_drop_impl(name) {
+                    // L'octet d'initialisation est nul pour une valeur déplacée
+                    let skip = self.new_label("L_drop_fields");
+                    code.push_str(&format!("    cmp BYTE [rbx+{}], 0\n    je {}\n", self.drop_flag(name), skip));
+                    code.push_str("    mov rdi, rbx\n");
+                    code.push_str(&format!("    call {}\n", TypeChecker::method_label(name, Some("Drop"), "drop")));
+                    code.push_str(&format!("{}:\n", skip));
+                }
+                for (_, field_type, offset) in fields {
+                    code.push_str(&self.drop_part(&field_type, offset));
+                }
+            },
+            _ => unreachable!("type sans glue de destruction : {}", typ),
+        }
+
+        code.push_str(&format!("{}:\n", done));
+        code.push_str(&Self::zero_memory("rbx", self.type_size(typ)));
+        code.push_str("    pop r12\n");
+        code.push_str("    pop rbx\n");
+        code.push_str("    pop rbp\n");
+        code.push_str("    ret\n");
+        code
+    }
+
+    // Destruction d'une partie (champ, élément, valeur d'une variante) située à l'octet `offset` de [rbx]
+    fn drop_part(&mut self, typ: &Type, offset: usize) -> String {
+        if !self.needs_drop(typ) {
+            return String::new();
+        }
+        format!("    lea rdi, [rbx+{}]\n    call {}\n", offset, self.drop_function(typ))
+    }
+
+    // Destruction dans l'ordre des `length` éléments rangés à partir de `data`
+    fn drop_elements(&mut self, element: &Type, length: &str, data: &str) -> String {
+        if !self.needs_drop(element) {
+            return String::new();
+        }
+        let (loop_label, end_label) = (self.new_label("L_drop_element"), self.new_label("L_drop_elements_end"));
+        let mut code = String::from("    xor r12d, r12d\n");
+        code.push_str(&format!("{}:\n", loop_label));
+        code.push_str(&format!("    cmp r12, {}\n    jae {}\n", length, end_label));
+        code.push_str(&format!("    imul rdi, r12, {}\n", self.type_size(element)));
+        code.push_str(&format!("    add rdi, {}\n", data));
+        code.push_str(&format!("    call {}\n", self.drop_function(element)));
+        code.push_str("    inc r12\n");
+        code.push_str(&format!("    jmp {}\n", loop_label));
+        code.push_str(&format!("{}:\n", end_label));
+        code
+    }
+
+    // clone() : copie en profondeur de la valeur désignée par le receveur, à travers ses références.
+    // Une valeur sans ressources est copiée octet par octet ; les autres passent par leur glue de copie.
+    fn generate_clone(&mut self, obj_expr: &Expr) -> Result<String, usize> {
+        let mut typ = self.types.expr_type(obj_expr)?;
+        let mut code = String::from("    ; clone()\n");
+        if let Type::Ref(_, _) | Type::Guard(_, _) = typ {
+            code.push_str(&self.generate_expr_code(obj_expr)?);
+            while let Type::Ref(inner, _) | Type::Guard(inner, _) = typ {
+                typ = *inner;
+                if let Type::Ref(_, _) | Type::Guard(_, _) = typ {
+                    code.push_str("    mov rax, QWORD [rax]  ; Déréférencement\n");
+                }
+            }
+        } else {
+            code.push_str(&self.generate_address(obj_expr)?);
+        }
+
+        if !self.needs_drop(&typ) {
+            if !self.is_aggregate(&typ) {
+                code.push_str(&self.load_from(&typ, "rax"));
+                return Ok(code);
             }
+            let size = self.type_size(&typ);
+            let slot = self.alloc_slot(size);
+            code.push_str(&Self::copy_memory("rax", &format!("rbp-{}", slot), size));
+            code.push_str(&format!("    lea rax, [rbp-{}]\n", slot));
+            return Ok(code);
+        }
+        let slot = self.alloc_slot(self.type_size(&typ));
+        code.push_str("    mov rdi, rax\n");
+        code.push_str(&format!("    lea rsi, [rbp-{}]\n", slot));
+        code.push_str(&format!("    call {}\n", self.clone_function(&typ)));
+        code.push_str(&self.load_from(&typ, &format!("rbp-{}", slot)));
+        Ok(code)
+    }
+
+    // Étiquette de la glue de copie en profondeur d'un type, émise une seule fois à la fin du programme
+    fn clone_function(&mut self, typ: &Type) -> String {
+        if let Some(label) = self.clone_functions.get(&typ.to_string()) {
+            return label.clone();
+        }
+        let label = format!("__clone_{}", self.clone_functions.len());
+        self.clone_functions.insert(typ.to_string(), label.clone());
+        self.pending_clones.push(typ.clone());
+        label
+    }
+
+    // Glue de copie en profondeur d'un type : adresse de la source dans rdi, gardée dans rbx, adresse
+    // de la copie dans rsi, gardée dans r12 ; r13 compte les éléments d'une séquence. La valeur est
+    // d'abord copiée octet par octet, puis chaque ressource est remplacée par une copie qui lui est propre :
+    // tampon d'un vecteur ou d'une chaîne, valeur d'un Box ; un Rc gagne une référence.
+    fn generate_clone_function(&mut self, typ: &Type) -> String {
+        let label = self.clone_functions[&typ.to_string()].clone();
+        let mut code = format!("\n; Copie en profondeur de {}\n{}:\n", self.types.display_type(typ), label);
+        code.push_str("    push rbp\n");
+        code.push_str("    mov rbp, rsp\n");
+        code.push_str("    push rbx\n");
+        code.push_str("    push r12\n");
+        code.push_str("    push r13\n");
+        code.push_str("    sub rsp, 8  ; Alignement de la pile\n");
+        code.push_str("    mov rbx, rdi\n");
+        code.push_str("    mov r12, rsi\n");
+        code.push_str(&Self::copy_memory("rbx", "r12", self.type_size(typ)));
+
+        let done = self.new_label("L_clone_done");
+        match typ {
+            Type::String | Type::Vec(_) => {
+                // Nouveau tampon de la longueur de la source, où les éléments sont recopiés
+                self.uses_vec_runtime = true;
+                let element = match typ {
+                    Type::Vec(element) => (**element).clone(),
+                    _ => Type::U8,
+                };
+                code.push_str(&Self::zero_memory("r12", VEC_SIZE));
+                code.push_str("    mov rdi, r12\n");
+                code.push_str("    mov rsi, QWORD [rbx]\n");
+                code.push_str(&format!("    mov rdx, QWORD [rbx+{}]\n", VEC_LEN));
+                code.push_str(&format!("    mov ecx, {}\n", self.type_size(&element)));
+                code.push_str("    call __vec_extend\n");
+                if self.needs_drop(&element) {
+                    let size = self.type_size(&element);
+                    let (loop_label, end_label) = (self.new_label("L_clone_element"), self.new_label("L_clone_elements_end"));
+                    code.push_str("    xor r13d, r13d\n");
+                    code.push_str(&format!("{}:\n", loop_label));
+                    code.push_str(&format!("    cmp r13, QWORD [rbx+{}]\n    jae {}\n", VEC_LEN, end_label));
+                    code.push_str(&format!("    imul rdi, r13, {}\n", size));
+                    code.push_str("    mov rsi, rdi\n");
+                    code.push_str("    add rdi, QWORD [rbx]\n");
+                    code.push_str("    add rsi, QWORD [r12]\n");
+                    code.push_str(&format!("    call {}\n", self.clone_function(&element)));
+                    code.push_str("    inc r13\n");
+                    code.push_str(&format!("    jmp {}\n", loop_label));
+                    code.push_str(&format!("{}:\n", end_label));
+                }
+            },
+            Type::Box(inner) => {
+                code.push_str(&format!("    mov rdi, {}\n", self.type_size(inner).max(1)));
+                code.push_str("    call __alloc\n");
+                code.push_str("    mov QWORD [r12], rax\n");
+                if self.needs_drop(inner) {
+                    code.push_str("    mov rdi, QWORD [rbx]\n");
+                    code.push_str("    mov rsi, rax\n");
+                    code.push_str(&format!("    call {}\n", self.clone_function(inner)));
+                } else {
+                    code.push_str("    mov r13, QWORD [rbx]\n");
+                    code.push_str(&Self::copy_memory("r13", "rax", self.type_size(inner)));
+                }
+            },
+            Type::Rc(_) => {
+                code.push_str("    mov rax, QWORD [rbx]\n");
+                code.push_str("    inc QWORD [rax-8]  ; Références fortes\n");
+            },
+            Type::Option(inner) => {
+                let payload = self.fields_layout(&[Type::Bool, (**inner).clone()]).0[1];
+                code.push_str(&format!("    cmp BYTE [rbx], 0\n    je {}\n", done));
+                code.push_str(&self.clone_part(inner, payload));
+            },
+            Type::Result(ok, err) => {
+                let payload = self.result_layout(ok, err).0;
+                let err_label = self.new_label("L_clone_err");
+                code.push_str(&format!("    cmp BYTE [rbx], 1\n    jne {}\n", err_label));
+                code.push_str(&self.clone_part(ok, payload));
+                code.push_str(&format!("    jmp {}\n{}:\n", done, err_label));
+                code.push_str(&self.clone_part(err, payload));
+            },
+            Type::Tuple(elements) => {
+                let (offsets, _, _) = self.fields_layout(elements);
+                for (element, offset) in elements.iter().zip(offsets) {
+                    code.push_str(&self.clone_part(element, offset));
+                }
+            },
+            Type::Array(element, length) => {
+                let size = self.type_size(element);
+                for i in 0..*length {
+                    code.push_str(&self.clone_part(element, i * size));
+                }
+            },
+            Type::Named(name) if self.types.enums.contains_key(name) => {
+                // Seuls les champs de la variante présente sont copiés
+                let variants = self.types.enums[name].variants.clone();
+                for (index, ((_, fields), offsets)) in variants.iter().zip(self.enum_layout(name).0).enumerate() {
+                    if !fields.iter().any(|field| self.needs_drop(field)) {
+                        continue;
+                    }
+                    let next = self.new_label("L_clone_variant");
+                    code.push_str(&format!("    cmp BYTE [rbx], {}\n    jne {}\n", index + 1, next));
+                    for (field, offset) in fields.iter().zip(offsets) {
+                        code.push_str(&self.clone_part(field, offset));
+                    }
+                    code.push_str(&format!("    jmp {}\n{}:\n", done, next));
+                }
+            },
+            Type::Named(name) => {
+                for (_, field_type, offset) in self.struct_layout(name).0 {
+                    code.push_str(&self.clone_part(&field_type, offset));
+                }
+            },
+            _ => unreachable!("type sans glue de copie : {}", typ),
+        }
+
+        code.push_str(&format!("{}:\n", done));
+        code.push_str("    add rsp, 8\n");
+        code.push_str("    pop r13\n");
+        code.push_str("    pop r12\n");
+        code.push_str("    pop rbx\n");
+        code.push_str("    pop rbp\n");
+        code.push_str("    ret\n");
+        code
+    }
+
+    // Copie en profondeur d'une partie (champ, élément, valeur d'une variante) située à l'octet `offset`
+    // de [rbx] vers le même décalage de [r12]
+    fn clone_part(&mut self, typ: &Type, offset: usize) -> String {
+        if !self.needs_drop(typ) {
+            return String::new();
+        }
+        format!("    lea rdi, [rbx+{}]\n    lea rsi, [r12+{}]\n    call {}\n", offset, offset, self.clone_function(typ))
+    }
+
+    // a.cmp(&b) : les adresses des deux valeurs, à travers leurs références, sont passées à la fonction de
+    // comparaison de leur type, dont le résultat -1, 0 ou 1 devient l'étiquette de Less, Equal ou Greater
+    fn generate_cmp(&mut self, obj_expr: &Expr, arg: &Expr) -> Result<String, usize> {
+        let mut code = String::from("    ; cmp()\n");
+        let (receiver_code, receiver_type) = self.generate_receiver(obj_expr)?;
+        code.push_str(&receiver_code);
+        let left = self.alloc_slot(8);
+        code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", left));
+        code.push_str(&self.generate_receiver(arg)?.0);
+        code.push_str("    mov rsi, rax\n");
+        code.push_str(&format!("    mov rdi, QWORD [rbp-{}]\n", left));
+        code.push_str(&format!("    call {}\n", self.compare_function(&receiver_type)));
+        code.push_str("    add eax, 2\n");
+        code.push_str(&format!("    mov BYTE [rbp-{}], al\n", left));
+        code.push_str(&format!("    lea rax, [rbp-{}]\n", left));
+        Ok(code)
+    }
+
+    // Méthodes d'un Ordering, lu par son étiquette : reverse() échange Less et Greater, then() ne retient
+    // son argument que si la valeur est Equal
+    fn generate_ordering_method(&mut self, obj_expr: &Expr, method_name: &str, args: &[Expr]) -> Result<String, usize> {
+        let mut code = format!("    ; {}()\n", method_name);
+        code.push_str(&self.generate_receiver(obj_expr)?.0);
+        code.push_str("    movzx eax, BYTE [rax]\n");
+        let condition = match method_name {
+            "is_lt" => ("1", "e"),
+            "is_le" => ("2", "be"),
+            "is_gt" => ("3", "e"),
+            "is_ge" => ("2", "ae"),
+            "is_eq" => ("2", "e"),
+            "is_ne" => ("2", "ne"),
+            _ => {
+                let result = self.alloc_slot(8);
+                code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", result));
+                if method_name == "then" {
+                    code.push_str(&self.generate_expr_code(&args[0])?);
+                    code.push_str("    movzx ecx, BYTE [rax]\n");
+                    code.push_str(&format!("    mov rax, QWORD [rbp-{}]\n", result));
+                    code.push_str("    cmp eax, 2\n");
+                    code.push_str("    cmove eax, ecx\n");
+                } else {
+                    code.push_str("    mov ecx, 4\n");
+                    code.push_str("    sub ecx, eax\n");
+                    code.push_str("    mov eax, ecx\n");
+                }
+                code.push_str(&format!("    mov BYTE [rbp-{}], al\n", result));
+                code.push_str(&format!("    lea rax, [rbp-{}]\n", result));
+                return Ok(code);
+            },
+        };
+        code.push_str(&format!("    cmp eax, {}\n    set{} al\n    movzx eax, al\n", condition.0, condition.1));
+        Ok(code)
+    }
+
+    // Fonction de comparaison de deux valeurs d'un type triable, au format de qsort : adresses des
+    // valeurs dans rdi et rsi, -1, 0 ou 1 dans eax. Celle d'un entier, d'un booléen ou d'un caractère
+    // vient du runtime ; celle d'un autre type est émise une seule fois à la fin du programme.
+    fn compare_function(&mut self, typ: &Type) -> String {
+        match typ {
+            Type::U8 | Type::Bool => return "__cmp_u8".to_string(),
+            Type::Char => return "__cmp_i32".to_string(),
+            _ if TypeChecker::is_integer(typ) => return format!("__cmp_i{}", self.type_size(typ) * 8),
+            _ => {},
+        }
+        if let Some(label) = self.compare_functions.get(&typ.to_string()) {
+            return label.clone();
+        }
+        let label = format!("__compare_{}", self.compare_functions.len());
+        self.compare_functions.insert(typ.to_string(), label.clone());
+        self.pending_compares.push(typ.clone());
+        label
+    }
+
+    // Comparaison de deux valeurs : adresses dans rdi et rsi, gardées dans rbx et r12. Les chaînes sont
+    // comparées octet par octet, les références, Box et Rc par la valeur désignée, les tuples et les
+    // Option champ par champ jusqu'au premier qui diffère.
+    fn generate_compare_function(&mut self, typ: &Type) -> String {
+        let label = self.compare_functions[&typ.to_string()].clone();
+        let mut code = format!("\n; Comparaison de {}\n{}:\n", self.types.display_type(typ), label);
+        code.push_str("    push rbp\n");
+        code.push_str("    mov rbp, rsp\n");
+        code.push_str("    push rbx\n");
+        code.push_str("    push r12\n");
+        code.push_str("    mov rbx, rdi\n");
+        code.push_str("    mov r12, rsi\n");
+
+        let done = self.new_label("L_compare_done");
+        let string = matches!(typ, Type::String | Type::Str) || matches!(typ, Type::Ref(inner, _) if **inner == Type::Str);
+        match typ {
+            _ if string => {
+                // String et &str commencent par le pointeur vers les octets puis la longueur
+                self.uses_string_runtime = true;
+                code.push_str(&format!("    mov rdi, QWORD [rbx]\n    mov rsi, QWORD [rbx+{}]\n", VEC_LEN));
+                code.push_str(&format!("    mov rdx, QWORD [r12]\n    mov rcx, QWORD [r12+{}]\n", VEC_LEN));
+                code.push_str("    call __str_cmp\n");
+            },
+            Type::Ref(inner, _) | Type::Box(inner) | Type::Rc(inner) => {
+                code.push_str("    mov rdi, QWORD [rbx]\n");
+                code.push_str("    mov rsi, QWORD [r12]\n");
+                code.push_str(&format!("    call {}\n", self.compare_function(inner)));
+            },
+            Type::Option(inner) => {
+                // None (étiquette nulle) précède toute valeur Some
+                let payload = self.fields_layout(&[Type::Bool, (**inner).clone()]).0[1];
+                code.push_str("    movzx edi, BYTE [rbx]\n");
+                code.push_str("    movzx esi, BYTE [r12]\n");
+                code.push_str("    mov eax, edi\n");
+                code.push_str("    sub eax, esi\n");
+                code.push_str(&format!("    jnz {}\n", done));
+                code.push_str(&format!("    test edi, edi\n    jz {}\n", done));
+                code.push_str(&format!("    lea rdi, [rbx+{}]\n    lea rsi, [r12+{}]\n", payload, payload));
+                code.push_str(&format!("    call {}\n", self.compare_function(inner)));
+            },
+            Type::Tuple(elements) => {
+                let (offsets, _, _) = self.fields_layout(elements);
+                for (element, offset) in elements.iter().zip(offsets) {
+                    code.push_str(&format!("    lea rdi, [rbx+{}]\n    lea rsi, [r12+{}]\n", offset, offset));
+                    code.push_str(&format!("    call {}\n", self.compare_function(element)));
+                    code.push_str(&format!("    test eax, eax\n    jnz {}\n", done));
+                }
+            },
+            _ => unreachable!("type sans fonction de comparaison : {}", typ),
+        }
+
+        code.push_str(&format!("{}:\n", done));
+        code.push_str("    pop r12\n");
+        code.push_str("    pop rbx\n");
+        code.push_str("    pop rbp\n");
+        code.push_str("    ret\n");
+        code
+    }
+
+    // Adresse de l'en-tête (pointeur, longueur) d'une chaîne dans rax : une String et un &str
+    // commencent tous deux par le pointeur vers les octets puis la longueur
+    fn generate_string_header(&mut self, expr: &Expr) -> Result<String, usize> {
+        let typ = self.types.expr_type(expr)?;
+        let Some(derefs) = TypeChecker::string_derefs(&typ) else { unreachable!() };
+        let mut code = self.generate_expr_code(expr)?;
+        for _ in 0..derefs {
+            code.push_str("    mov rax, QWORD [rax]  ; Déréférencement\n");
         }
-        
         Ok(code)
     }
-    
-    fn generate_expr_code(&mut self, expr: &Expr) -> Result<String, usize> {
+
+    // Concaténation `String + &str` (la String de gauche est déplacée puis étendue) et comparaisons
+    // de chaînes par ordre lexicographique des octets
+    fn generate_string_operation(&mut self, expr: &Expr, left: &Expr, op: &BinaryOp, right: &Expr) -> Result<String, usize> {
+        self.types.expr_type(expr)?;
+        self.uses_vec_runtime = true;
+        self.uses_string_runtime = true;
+
         let mut code = String::new();
-        
-        match expr {
-            Expr::Literal(Literal::Int(value)) => {
-                code.push_str(&format!("    mov rax, {}\n", value));
+        if let BinaryOp::Add = op {
+            let result = self.alloc_slot(VEC_SIZE);
+            code.push_str("    ; Concaténation\n");
+            code.push_str(&self.generate_owned(left, &Type::String)?);
+            code.push_str(&Self::copy_memory("rax", &format!("rbp-{}", result), VEC_SIZE));
+            code.push_str(&self.generate_string_header(right)?);
+            code.push_str(&format!("    mov rsi, QWORD [rax]\n    mov rdx, QWORD [rax+{}]\n", VEC_LEN));
+            code.push_str(&format!("    lea rdi, [rbp-{}]\n", result));
+            code.push_str("    mov ecx, 1\n");
+            code.push_str("    call __vec_extend\n");
+            code.push_str(&format!("    lea rax, [rbp-{}]\n", result));
+            code.push_str(&self.hold(&Type::String));
+            return Ok(code);
+        }
+
+        let left_header = self.alloc_slot(8);
+        code.push_str("    ; Comparaison de chaînes\n");
+        code.push_str(&self.generate_string_header(left)?);
+        code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", left_header));
+        code.push_str(&self.generate_string_header(right)?);
+        code.push_str(&format!("    mov rdx, QWORD [rax]\n    mov rcx, QWORD [rax+{}]\n", VEC_LEN));
+        code.push_str(&format!("    mov rax, QWORD [rbp-{}]\n", left_header));
+        code.push_str(&format!("    mov rdi, QWORD [rax]\n    mov rsi, QWORD [rax+{}]\n", VEC_LEN));
+        code.push_str("    call __str_cmp\n");
+        let condition = match op {
+            BinaryOp::Equal => "e",
+            BinaryOp::NotEqual => "ne",
+            BinaryOp::Less => "l",
+            BinaryOp::LessEqual => "le",
+            BinaryOp::Greater => "g",
+            _ => "ge",
+        };
+        code.push_str(&format!("    cmp eax, 0\n    set{} al\n    movzx eax, al\n", condition));
+        Ok(code)
+    }
+
+    // Méthodes des chaînes ; `String::from(s)` est traduit comme `s.to_string()`
+    fn generate_string_method(&mut self, obj_expr: &Expr, method_name: &str, type_args: &[Type], args: &[Expr]) -> Result<String, usize> {
+        self.uses_vec_runtime = true;
+        self.uses_string_runtime = true;
+
+        let mut code = format!("    ; Méthode {}()\n", method_name);
+        let receiver = self.alloc_slot(8);
+        code.push_str(&self.generate_receiver(obj_expr)?.0);
+        code.push_str(&format!("    mov QWORD [rbp-{}], rax  ; Adresse de la chaîne\n", receiver));
+
+        match method_name {
+            "len" | "is_empty" => {
+                code.push_str(&format!("    mov rax, QWORD [rax+{}]  ; Longueur en octets\n", VEC_LEN));
+                if method_name == "is_empty" {
+                    code.push_str("    test rax, rax\n    sete al\n    movzx eax, al\n");
+                }
             },
-            Expr::Literal(Literal::String(_value)) => {
-                self.error_handler.report_error(0, "Les chaînes littérales ne sont pas encore prises en charge dans les expressions");
-                return Err(0);
+            "to_string" => {
+                // Copie des octets dans un nouveau tampon sur le tas
+                let result = self.alloc_slot(VEC_SIZE);
+                code.push_str(&format!("    mov rsi, QWORD [rax]\n    mov rdx, QWORD [rax+{}]\n", VEC_LEN));
+                code.push_str(&format!("    lea rdi, [rbp-{}]\n", result));
+                code.push_str("    call __string_from\n");
+                code.push_str(&format!("    lea rax, [rbp-{}]\n", result));
             },
-            Expr::Literal(Literal::VecLiteral(elements)) => {
-                // Créer un vecteur statique sur la pile de manière plus sûre
-                code.push_str("    ; Vec literal - creating static array\n");
-                
-                // Calculer un offset sûr dans l'espace de pile alloué
-                let base_offset = 64;
-                
-                // Stocker la taille du vecteur en premier
-                code.push_str(&format!("    mov DWORD [rbp-{}], {}  ; Store vec length\n", 
-                    base_offset, elements.len()));
-                
-                // Stocker les éléments APRÈS la taille avec des adresses croissantes
-                for (i, element) in elements.iter().enumerate() {
-                    code.push_str(&self.generate_expr_code(element)?);
-                    // Stocker élément i à (base_offset - 4) + (i * 4) pour avoir des adresses croissantes
-                    let element_offset = base_offset - 4 - (i * 4);
-                    code.push_str(&format!("    mov DWORD [rbp-{}], eax  ; Store vec element {}\n", 
-                        element_offset, i));
-                }
-                
-                // Retourner l'adresse du vecteur (pointant vers la taille)
-                code.push_str(&format!("    lea rax, [rbp-{}]  ; Return vec base address (points to length)\n", base_offset));
-            },
-            Expr::VecNew(_) => {
-                // Vec::new() - créer un vecteur vide
-                code.push_str("    ; Vec::new() - creating empty vector\n");
-                let base_offset = 128;
-                // Initialiser la taille à 0
-                code.push_str(&format!("    mov DWORD [rbp-{}], 0  ; Initialize empty vec length\n", base_offset - 4));
-                // Retourner l'adresse du vecteur (pointant vers la taille)
-                code.push_str(&format!("    lea rax, [rbp-{}]  ; Return empty vec address\n", base_offset - 4));
-            },
-            Expr::VecIndex(vec_expr, index_expr) => {
-                // vec[index] - accès par index
-                code.push_str("    ; Vector indexing\n");
-                
-                // Évaluer l'expression du vecteur pour obtenir l'adresse de base
-                code.push_str(&self.generate_expr_code(vec_expr)?);
-                code.push_str("    push rax  ; Save vec base address\n");
-                
-                // Évaluer l'index
-                code.push_str(&self.generate_expr_code(index_expr)?);
-                code.push_str("    mov rcx, rax  ; Move index to rcx\n");
-                code.push_str("    pop rax  ; Restore vec base address\n");
-                
-                // Fix vector memory layout issue:
-                // The vector layout in memory is:
-                // [length(4)][element0(4)][element1(4)]...
-                // Each element is 4 bytes after the previous one
-                
-                // First, skip the length field (+4 bytes)
-                // Then add (index * 4) to get to the correct element
-                code.push_str("    mov rdx, rax         ; Copy vec base address\n");
-                code.push_str("    add rdx, 4           ; Skip length field\n");
-                code.push_str("    imul rcx, 4          ; Multiply index by 4 (element size)\n");
-                code.push_str("    add rdx, rcx         ; Add index offset to get element address\n");
-                code.push_str("    mov eax, DWORD [rdx] ; Load element value\n");
-                code.push_str("    movsx rax, eax       ; Sign extend to 64-bit\n");
-            },
-            Expr::MethodCall(obj_expr, method_name, args) => {
-                match method_name.as_str() {
-                    "push" => {
-                        // vec.push(value) - ajouter un élément
-                        code.push_str("    ; Vector push method\n");
-                        
-                        if args.len() != 1 {
-                            self.error_handler.report_error(0, "push() method expects exactly one argument");
-                            return Err(0);
-                        }
-                        
-                        // Évaluer l'objet vecteur pour obtenir son adresse
-                        code.push_str(&self.generate_expr_code(obj_expr)?);
-                        code.push_str("    push rax  ; Save vec address\n");
-                        
-                        // Évaluer la valeur à ajouter
-                        code.push_str(&self.generate_expr_code(&args[0])?);
-                        code.push_str("    mov rcx, rax  ; Save value to push\n");
-                        code.push_str("    pop rax  ; Restore vec address\n");
-                        
-                        // Charger la taille actuelle
-                        code.push_str("    mov edx, DWORD [rax]  ; Load current length\n");
-                        
-                        // Calculer l'adresse où stocker le nouvel élément
-                        // Updated to use consistent layout: base + 4 + (length * 4)
-                        code.push_str("    mov r8d, edx  ; Copy length\n");
-                        code.push_str("    imul r8d, 4  ; length * 4\n");
-                        code.push_str("    lea r9, [rax + 4]  ; vec_base + 4 (skip length field)\n");
-                        code.push_str("    add r9, r8  ; Calculate storage address: base + 4 + (length * 4)\n");
-                        
-                        // Stocker la nouvelle valeur
-                        code.push_str("    mov DWORD [r9], ecx  ; Store new value\n");
-                        
-                        // Incrémenter la taille
-                        code.push_str("    inc edx  ; Increment length\n");
-                        code.push_str("    mov DWORD [rax], edx  ; Store new length\n");
-                        
-                        // Retourner l'adresse du vecteur (pour le chaînage)
-                        code.push_str("    ; push() returns void, rax already contains vec address\n");
-                    },
-                    "len" => {
-                        // vec.len() - obtenir la taille
-                        code.push_str("    ; Vector len method\n");
-                        
-                        if !args.is_empty() {
-                            self.error_handler.report_error(0, "len() method expects no arguments");
-                            return Err(0);
-                        }
-                        
-                        // Évaluer l'objet vecteur pour obtenir son adresse
-                        code.push_str(&self.generate_expr_code(obj_expr)?);
-                        
-                        // Charger la taille (stockée à l'adresse du vecteur)
-                        code.push_str("    mov eax, DWORD [rax]  ; Load vector length\n");
-                        code.push_str("    movsx rax, eax  ; Sign extend to 64-bit\n");
-                    },
-                    _ => {
-                        self.error_handler.report_error(0, &format!("Méthode non supportée: {}", method_name));
-                        return Err(0);
-                    }
-                }
+            "push" => {
+                code.push_str(&self.generate_expr_code(&args[0])?);
+                code.push_str("    mov esi, eax\n");
+                code.push_str(&format!("    mov rdi, QWORD [rbp-{}]\n", receiver));
+                code.push_str("    call __string_push\n");
             },
-            Expr::Variable(name) => {
-                // Récupérer les informations sur la variable
-                if let Some(var_info) = self.variable_info.get(name) {
-                    // Charger la variable selon son type, en utilisant des instructions plus sûres
-                    match var_info.var_type {
-                        Type::I32 => {
-                            code.push_str(&format!("    mov eax, DWORD [rbp-{}]  ; Load variable {}\n", var_info.offset, name));
-                            code.push_str("    movsx rax, eax  ; Sign extend to 64-bit\n");
-                        },
-                        Type::I8 => code.push_str(&format!("    movsx rax, BYTE [rbp-{}]\n", var_info.offset)),
-                        Type::I16 => code.push_str(&format!("    movsx rax, WORD [rbp-{}]\n", var_info.offset)),
-                        Type::I64 | Type::I128 => code.push_str(&format!("    mov rax, QWORD [rbp-{}]\n", var_info.offset)),
-                        Type::String => code.push_str(&format!("    mov rax, QWORD [rbp-{}]\n", var_info.offset)),
-                        Type::Vec(_) => {
-                            // Charger l'adresse du vecteur
-                            code.push_str(&format!("    mov rax, QWORD [rbp-{}]  ; Load vec address {}\n", var_info.offset, name));
-                        },
-                        _ => {
-                            code.push_str(&format!("    mov eax, DWORD [rbp-{}]  ; Load variable {}\n", var_info.offset, name));
-                            code.push_str("    movsx rax, eax  ; Sign extend to 64-bit\n");
-                        }
-                    }
-                } else {
-                    // Vérifier si c'est un paramètre de fonction
-                    if let Some(current_func) = &self.current_function {
-                        if let Some(param_names) = self.function_params.get(current_func) {
-                            if let Some(param_index) = param_names.iter().position(|p| p == name) {
-                                let offset = (param_index + 1) * 8;
-                                code.push_str(&format!("    mov rax, QWORD [rbp-{}]  ; Load parameter {}\n", offset, name));
-                                return Ok(code);
-                            }
-                        }
-                    }
-                    
-                    self.error_handler.report_error(0, &format!("Variable non trouvée: {}", name));
-                    code.push_str(&format!("    mov rax, 0  ; Placeholder for variable {}\n", name));
-                }
+            "push_str" => {
+                code.push_str(&self.generate_string_header(&args[0])?);
+                code.push_str(&format!("    mov rsi, QWORD [rax]\n    mov rdx, QWORD [rax+{}]\n", VEC_LEN));
+                code.push_str(&format!("    mov rdi, QWORD [rbp-{}]\n", receiver));
+                code.push_str("    mov ecx, 1\n");
+                code.push_str("    call __vec_extend\n");
             },
-            Expr::Binary(left, op, right) => {
-                // Évaluer d'abord l'opérande gauche
-                code.push_str(&self.generate_expr_code(left)?);
-                // Sauvegarder le résultat
-                code.push_str("    push rax\n");
-                // Évaluer ensuite l'opérande droite
-                code.push_str(&self.generate_expr_code(right)?);
-                // Opérande droite est dans rax, gauche dans la pile
-                code.push_str("    mov rcx, rax\n");
-                code.push_str("    pop rax\n");
-                
-                // Effectuer l'opération
-                match op {
-                    BinaryOp::Add => {
-                        code.push_str("    add rax, rcx\n");
-                    },
-                    BinaryOp::Subtract => {
-                        code.push_str("    sub rax, rcx\n");
-                    },
-                    BinaryOp::Multiply => {
-                        code.push_str("    imul rax, rcx\n");
-                    },
-                    BinaryOp::Divide => {
-                        code.push_str("    xor rdx, rdx\n");  // Nettoyer rdx pour la division
-                        code.push_str("    idiv rcx\n");
-                    },
-                    // Add comparison operators for if conditions
-                    BinaryOp::Equal => {
-                        code.push_str("    cmp rax, rcx\n");
-                        code.push_str("    sete al\n");      // Set AL to 1 if equal, 0 otherwise
-                        code.push_str("    movzx rax, al\n"); // Zero-extend AL to RAX
-                    },
-                    BinaryOp::NotEqual => {
-                        code.push_str("    cmp rax, rcx\n");
-                        code.push_str("    setne al\n");     // Set AL to 1 if not equal, 0 otherwise
-                        code.push_str("    movzx rax, al\n");
-                    },
-                    BinaryOp::Less => {
-                        code.push_str("    cmp rax, rcx\n");
-                        code.push_str("    setl al\n");      // Set AL to 1 if less, 0 otherwise
-                        code.push_str("    movzx rax, al\n");
-                    },
-                    BinaryOp::LessEqual => {
-                        code.push_str("    cmp rax, rcx\n");
-                        code.push_str("    setle al\n");     // Set AL to 1 if less or equal, 0 otherwise
-                        code.push_str("    movzx rax, al\n");
+            "chars" | "bytes" | "split_whitespace" | "split" => {
+                // Les morceaux sont rassemblés dans un nouveau vecteur
+                let pattern = match method_name {
+                    "split" => Some(self.generate_pattern(&args[0])?),
+                    _ => None,
+                };
+                let result = self.alloc_slot(VEC_SIZE);
+                if let Some((pattern_code, _, _)) = &pattern {
+                    code.push_str(pattern_code);
+                }
+                for field in [0, VEC_LEN, VEC_CAP] {
+                    code.push_str(&format!("    mov QWORD [rbp-{}], 0\n", result - field));
+                }
+                code.push_str(&Self::load_string_bytes(receiver));
+                match (method_name, pattern) {
+                    ("chars", _) => {
+                        code.push_str(&format!("    lea rdx, [rbp-{}]\n", result));
+                        code.push_str("    call __str_chars\n");
                     },
-                    BinaryOp::Greater => {
-                        code.push_str("    cmp rax, rcx\n");
-                        code.push_str("    setg al\n");      // Set AL to 1 if greater, 0 otherwise
-                        code.push_str("    movzx rax, al\n");
+                    ("bytes", _) => {
+                        code.push_str("    mov rdx, rsi\n    mov rsi, rdi\n");
+                        code.push_str(&format!("    lea rdi, [rbp-{}]\n", result));
+                        code.push_str("    call __string_from\n");
                     },
-                    BinaryOp::GreaterEqual => {
-                        code.push_str("    cmp rax, rcx\n");
-                        code.push_str("    setge al\n");     // Set AL to 1 if greater or equal, 0 otherwise
-                        code.push_str("    movzx rax, al\n");
+                    ("split", Some((_, data, length))) => {
+                        code.push_str(&format!("    mov rdx, QWORD [rbp-{}]\n    mov rcx, QWORD [rbp-{}]\n", data, length));
+                        code.push_str(&format!("    lea r8, [rbp-{}]\n", result));
+                        code.push_str("    call __str_split\n");
                     },
                     _ => {
-                        self.error_handler.report_error(0, &format!("Opérateur binaire non pris en charge: {:?}", op));
-                        return Err(0);
-                    }
+                        code.push_str(&format!("    lea rdx, [rbp-{}]\n", result));
+                        code.push_str("    call __str_split_whitespace\n");
+                    },
                 }
+                code.push_str(&format!("    lea rax, [rbp-{}]\n", result));
             },
-            Expr::FunctionCall(callee, args) => {
-                code.push_str(&format!("\n    ; Appel de fonction: {}()\n", callee));
-                
-                // Sauvegarder les registres volatiles avant l'appel
-                code.push_str("    ; Sauvegarde des registres volatiles\n");
-                code.push_str("    push rcx\n");
-                code.push_str("    push rdx\n");
-                code.push_str("    push rsi\n");
-                code.push_str("    push rdi\n");
-                
-                // Préparer les arguments dans les registres
-                let registers = ["rdi", "rsi", "rdx", "rcx", "r8", "r9"];
-                
-                // Alignement de la pile (important pour les appels de fonction)
-                let stack_adjustment = if args.len() > 6 {
-                    (args.len() - 6) * 8
-                } else {
-                    0
+            "trim" | "trim_start" | "trim_end" => {
+                let mode = match method_name {
+                    "trim_start" => 1,
+                    "trim_end" => 2,
+                    _ => 3,
                 };
-                
-                if stack_adjustment > 0 {
-                    code.push_str(&format!("    sub rsp, {}\n", stack_adjustment));
-                }
-                
-                // Mettre les arguments dans les registres ou sur la pile
-                for (i, arg) in args.iter().enumerate() {
-                    code.push_str(&self.generate_expr_code(arg)?);
-                    
-                    if i < 6 {
-                        code.push_str(&format!("    mov {}, rax\n", registers[i]));
-                    } else {
-                        // Empiler les arguments supplémentaires
-                        code.push_str(&format!("    mov QWORD [rsp+{}], rax\n", (i - 6) * 8));
-                    }
+                let result = self.alloc_slot(16);
+                code.push_str(&Self::load_string_bytes(receiver));
+                code.push_str(&format!("    mov edx, {}\n", mode));
+                code.push_str("    call __str_trim\n");
+                code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", result));
+                code.push_str(&format!("    mov QWORD [rbp-{}], rdx\n", result - 8));
+                code.push_str(&format!("    lea rax, [rbp-{}]\n", result));
+            },
+            "contains" | "find" | "starts_with" | "ends_with" => {
+                let (pattern_code, data, length) = self.generate_pattern(&args[0])?;
+                code.push_str(&pattern_code);
+                code.push_str(&Self::load_string_bytes(receiver));
+                code.push_str(&format!("    mov rdx, QWORD [rbp-{}]\n    mov rcx, QWORD [rbp-{}]\n", data, length));
+                match method_name {
+                    "starts_with" | "ends_with" => code.push_str(&format!("    call __str_{}\n", method_name)),
+                    "contains" => code.push_str("    call __str_find\n    cmp rax, -1\n    setne al\n    movzx eax, al\n"),
+                    _ => {
+                        // Option<i32> : Some(position) ou None
+                        let option_type = Type::Option(Box::new(Type::I32));
+                        let payload = self.fields_layout(&[Type::Bool, Type::I32]).0[1];
+                        let result = self.alloc_slot(self.type_size(&option_type));
+                        code.push_str("    call __str_find\n");
+                        code.push_str(&format!("    mov DWORD [rbp-{}], eax\n", result - payload));
+                        code.push_str("    cmp rax, -1\n");
+                        code.push_str(&format!("    setne BYTE [rbp-{}]\n", result));
+                        code.push_str(&format!("    lea rax, [rbp-{}]\n", result));
+                    },
                 }
-                
-                // Appel de la fonction
-                code.push_str(&format!("    call {}\n", callee));
-                
-                // Restaurer la pile si nécessaire
-                if stack_adjustment > 0 {
-                    code.push_str(&format!("    add rsp, {}\n", stack_adjustment));
-                }
-                
-                // Restaurer les registres volatiles
-                code.push_str("    ; Restauration des registres volatiles\n");
-                code.push_str("    pop rdi\n");
-                code.push_str("    pop rsi\n");
-                code.push_str("    pop rdx\n");
-                code.push_str("    pop rcx\n");
-                
-                // Le résultat est déjà dans rax
             },
-            _ => {
-                self.error_handler.report_error(0, &format!("Type d'expression non pris en charge: {:?}", expr));
-                return Err(0);
-            }
+            "replace" => {
+                let (from, to) = (self.alloc_slot(8), self.alloc_slot(8));
+                for (arg, slot) in args.iter().zip([from, to]) {
+                    code.push_str(&self.generate_string_header(arg)?);
+                    code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", slot));
+                }
+                let result = self.alloc_slot(VEC_SIZE);
+                code.push_str(&format!("    lea rdi, [rbp-{}]\n", result));
+                code.push_str(&format!("    mov rsi, QWORD [rbp-{}]\n", receiver));
+                code.push_str(&format!("    mov rdx, QWORD [rbp-{}]\n    mov rcx, QWORD [rbp-{}]\n", from, to));
+                code.push_str("    call __str_replace\n");
+                code.push_str(&format!("    lea rax, [rbp-{}]\n", result));
+            },
+            "to_uppercase" | "to_lowercase" => {
+                // Copie, puis conversion sur place des lettres ASCII
+                let result = self.alloc_slot(VEC_SIZE);
+                code.push_str(&Self::load_string_bytes(receiver));
+                code.push_str("    mov rdx, rsi\n    mov rsi, rdi\n");
+                code.push_str(&format!("    lea rdi, [rbp-{}]\n", result));
+                code.push_str("    call __string_from\n");
+                code.push_str(&format!("    mov rdi, QWORD [rbp-{}]\n    mov rsi, QWORD [rbp-{}]\n", result, result - VEC_LEN));
+                code.push_str(&format!("    mov edx, {}\n", (method_name == "to_uppercase") as u8));
+                code.push_str("    call __str_ascii_case\n");
+                code.push_str(&format!("    lea rax, [rbp-{}]\n", result));
+            },
+            "parse" => {
+                // Result<T, ParseIntError> : la valeur lue ou la cause de l'échec
+                let target = type_args[0].clone();
+                let (min, max) = match target {
+                    Type::I8 => (i8::MIN as i64, i8::MAX as i64),
+                    Type::I16 => (i16::MIN as i64, i16::MAX as i64),
+                    Type::I32 => (i32::MIN as i64, i32::MAX as i64),
+                    Type::U8 => (0, u8::MAX as i64),
+                    _ => (i64::MIN, i64::MAX),
+                };
+                let error_type = Type::Named(PARSE_INT_ERROR.to_string());
+                let (payload, size, _) = self.result_layout(&target, &error_type);
+                let result = self.alloc_slot(size);
+                let (error_label, done_label) = (self.new_label("L_parse_error"), self.new_label("L_parse_done"));
+                code.push_str(&Self::load_string_bytes(receiver));
+                code.push_str(&format!("    mov rdx, {}\n    mov rcx, {}\n", min, max));
+                code.push_str("    call __str_parse_int\n");
+                code.push_str("    test edx, edx\n");
+                code.push_str(&format!("    sete BYTE [rbp-{}]  ; Ok ?\n", result));
+                code.push_str(&format!("    jnz {}\n", error_label));
+                code.push_str(&self.store_to(&target, &format!("rbp-{}", result - payload)));
+                code.push_str(&format!("    jmp {}\n", done_label));
+                code.push_str(&format!("{}:\n", error_label));
+                code.push_str("    dec edx\n");
+                code.push_str(&format!("    mov BYTE [rbp-{}], dl  ; ParseIntError {{ kind }}\n", result - payload));
+                code.push_str(&format!("{}:\n", done_label));
+                code.push_str(&format!("    lea rax, [rbp-{}]\n", result));
+            },
+            _ => unreachable!(),
         }
-        
         Ok(code)
     }
-    
-    fn process_function_for_format_labels(&mut self, function: &Function) {
-        for (stmt_idx, stmt) in function.body.iter().enumerate() {
-            self.process_statement_for_format_labels(stmt, &function.name, stmt_idx);
+
+    // Octets et longueur de la chaîne dont l'en-tête est pointé par rbp-`header` dans rdi et rsi
+    fn load_string_bytes(header: usize) -> String {
+        format!("    mov rax, QWORD [rbp-{}]\n    mov rdi, QWORD [rax]\n    mov rsi, QWORD [rax+{}]\n", header, VEC_LEN)
+    }
+
+    // Motif de recherche rangé à rbp-`data` (octets) et rbp-`length` ; un caractère est d'abord encodé en UTF-8
+    fn generate_pattern(&mut self, pattern: &Expr) -> Result<(String, usize, usize), usize> {
+        let (data, length) = (self.alloc_slot(8), self.alloc_slot(8));
+        let mut code = String::new();
+        if self.types.expr_type(pattern)? == Type::Char {
+            let buffer = self.alloc_slot(8);
+            code.push_str(&self.generate_expr_code(pattern)?);
+            code.push_str("    mov edi, eax\n");
+            code.push_str(&format!("    lea rsi, [rbp-{}]\n", buffer));
+            code.push_str("    call __utf8_encode\n");
+            code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", length));
+            code.push_str(&format!("    lea rax, [rbp-{}]\n", buffer));
+            code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", data));
+        } else {
+            code.push_str(&self.generate_string_header(pattern)?);
+            code.push_str(&format!("    mov rcx, QWORD [rax+{}]\n    mov QWORD [rbp-{}], rcx\n", VEC_LEN, length));
+            code.push_str(&format!("    mov rcx, QWORD [rax]\n    mov QWORD [rbp-{}], rcx\n", data));
         }
+        Ok((code, data, length))
     }
 
-    fn process_statement_for_format_labels(&mut self, stmt: &Stmt, function_name: &str, index: usize) {
-        match stmt {
-            Stmt::Println(args) => {
-                if !args.is_empty() {
-                    if let Expr::Literal(Literal::String(_)) = &args[0] {
-                        let label = format!("fmt_{}_{}", function_name, index);
-                        self.format_labels.insert((function_name.to_string(), index), label);
-                    }
-                }
+    // Méthodes d'une plage : contains(&x) compare la valeur désignée aux bornes, is_empty() les bornes entre elles
+    fn generate_range_method(&mut self, obj_expr: &Expr, method_name: &str, args: &[Expr]) -> Result<String, usize> {
+        let mut code = format!("    ; Range::{}()\n", method_name);
+        let (receiver_code, receiver_type) = self.generate_receiver(obj_expr)?;
+        let Type::Range(element, inclusive) = receiver_type else { unreachable!() };
+        let (offsets, _, _) = self.fields_layout(&[(*element).clone(), (*element).clone()]);
+        let (range, start, end) = (self.alloc_slot(8), self.alloc_slot(8), self.alloc_slot(8));
+        code.push_str(&receiver_code);
+        code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", range));
+        code.push_str(&self.load_from(&element, "rax"));
+        code.push_str(&format!("    mov QWORD [rbp-{}], rax  ; Début\n", start));
+        code.push_str(&format!("    mov rax, QWORD [rbp-{}]\n", range));
+        code.push_str(&self.load_from(&element, &format!("rax+{}", offsets[1])));
+        code.push_str(&format!("    mov QWORD [rbp-{}], rax  ; Fin\n", end));
+
+        if method_name == "is_empty" {
+            code.push_str(&format!("    mov rax, QWORD [rbp-{}]\n", start));
+            code.push_str(&format!("    cmp rax, QWORD [rbp-{}]\n", end));
+            code.push_str(&format!("    {} al\n    movzx eax, al\n", if inclusive { "setg" } else { "setge" }));
+            return Ok(code);
+        }
+        let outside = self.new_label("L_range_outside");
+        code.push_str(&self.generate_expr_code(&args[0])?);
+        code.push_str(&self.load_from(&element, "rax"));
+        code.push_str("    mov rcx, rax\n    xor eax, eax\n");
+        code.push_str(&format!("    cmp rcx, QWORD [rbp-{}]\n    jl {}\n", start, outside));
+        code.push_str(&format!("    cmp rcx, QWORD [rbp-{}]\n    {} {}\n", end, if inclusive { "jg" } else { "jge" }, outside));
+        code.push_str("    mov eax, 1\n");
+        code.push_str(&format!("{}:\n", outside));
+        Ok(code)
+    }
+
+    // Méthodes des caractères (sémantique ASCII, lettres Latin-1 comprises pour is_alphabetic)
+    fn generate_char_method(&mut self, obj_expr: &Expr, method_name: &str, args: &[Expr]) -> Result<String, usize> {
+        self.uses_vec_runtime = true;
+        self.uses_string_runtime = true;
+
+        let mut code = format!("    ; char::{}()\n", method_name);
+        code.push_str(&self.generate_receiver(obj_expr)?.0);
+        code.push_str(&self.load_from(&Type::Char, "rax"));
+        match method_name {
+            "is_digit" => {
+                let character = self.alloc_slot(8);
+                code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", character));
+                code.push_str(&self.generate_expr_code(&args[0])?);
+                code.push_str("    mov esi, eax  ; Base\n");
+                code.push_str(&format!("    mov edi, DWORD [rbp-{}]\n", character));
+                code.push_str("    call __char_is_digit\n");
             },
-            Stmt::For(_, _, _, body) => {
-                if let Stmt::Block(stmts) = &**body {
-                    for (i, nested_stmt) in stmts.iter().enumerate() {
-                        let nested_index = index * 100 + i + 1;
-                        self.process_statement_for_format_labels(nested_stmt, function_name, nested_index);
-                    }
+            "is_numeric" => code.push_str("    mov edi, eax\n    mov esi, 10\n    call __char_is_digit\n"),
+            "is_alphabetic" | "is_alphanumeric" | "is_whitespace" => {
+                code.push_str(&format!("    mov edi, eax\n    call __char_{}\n", method_name));
+            },
+            "is_uppercase" => code.push_str("    sub eax, 0x41\n    cmp eax, 26\n    setb al\n    movzx eax, al\n"),
+            "is_lowercase" => code.push_str("    sub eax, 0x61\n    cmp eax, 26\n    setb al\n    movzx eax, al\n"),
+            // ecx vaut 32 pour une lettre à convertir, 0 sinon
+            "to_ascii_uppercase" => code.push_str("    lea ecx, [rax-0x61]\n    cmp ecx, 26\n    sbb ecx, ecx\n    and ecx, 32\n    sub eax, ecx\n"),
+            "to_ascii_lowercase" => code.push_str("    lea ecx, [rax-0x41]\n    cmp ecx, 26\n    sbb ecx, ecx\n    and ecx, 32\n    add eax, ecx\n"),
+            _ => unreachable!(),
+        }
+        Ok(code)
+    }
+
+    // Pointeur vers les éléments dans rdi et longueur dans rsi, rangés à rbp-`data` et rbp-`length`
+    fn load_sequence(data: usize, length: usize) -> String {
+        format!("    mov rdi, QWORD [rbp-{}]\n    mov rsi, QWORD [rbp-{}]\n", data, length)
+    }
+
+    // Méthodes des vecteurs, tableaux et tranches : le travail est confié aux routines de `runtime`.
+    // Celles qui changent la longueur reçoivent l'adresse du vecteur, les autres (pointeur, longueur).
+    fn generate_vec_method(&mut self, obj_expr: &Expr, method_name: &str, args: &[Expr]) -> Result<String, usize> {
+        let (Type::Array(element_type, _) | Type::Vec(element_type) | Type::Slice(element_type)) = self.types.indexed_type(obj_expr)?.0 else {
+            unreachable!()
+        };
+        let element_type = *element_type;
+        let element_size = self.type_size(&element_type);
+        let line = self.types.current_line;
+        self.uses_vec_runtime = true;
+        self.uses_bounds_check = true;
+
+        let mut code = String::new();
+        code.push_str(&format!("    ; Méthode {}()\n", method_name));
+        let (receiver, data, length) = (self.alloc_slot(8), self.alloc_slot(8), self.alloc_slot(8));
+        if matches!(method_name, "push" | "pop" | "insert" | "remove" | "extend" | "truncate" | "clear") {
+            code.push_str(&self.generate_receiver(obj_expr)?.0);
+            code.push_str(&format!("    mov QWORD [rbp-{}], rax  ; Adresse du vecteur\n", receiver));
+        } else {
+            code.push_str(&self.generate_sequence(obj_expr)?);
+            code.push_str(&format!("    mov QWORD [rbp-{}], rax  ; Adresse des éléments\n", data));
+            code.push_str(&format!("    mov QWORD [rbp-{}], rdx  ; Longueur\n", length));
+        }
+
+        match method_name {
+            "len" | "is_empty" => {
+                code.push_str("    mov rax, rdx\n");
+                if method_name == "is_empty" {
+                    code.push_str("    test rax, rax\n    sete al\n    movzx eax, al\n");
                 }
             },
-            Stmt::If(_, then_stmt, else_stmt) => {
-                // Process then branch - handle both Block and non-Block statements
-                if let Stmt::Block(stmts) = &**then_stmt {
-                    for (i, nested_stmt) in stmts.iter().enumerate() {
-                        let nested_index = index * 100 + i + 1;
-                        self.process_statement_for_format_labels(nested_stmt, function_name, nested_index);
-                    }
+            "get" => {
+                // Some(&élément) si l'index est valide, None sinon
+                let option = self.alloc_slot(16);
+                code.push_str(&self.generate_expr_code(&args[0])?);
+                code.push_str("    mov rcx, rax\n");
+                code.push_str(&Self::load_sequence(data, length));
+                let none_label = self.new_label("L_get_none");
+                code.push_str(&format!("    mov BYTE [rbp-{}], 0\n", option));
+                code.push_str("    cmp rcx, rsi\n");
+                code.push_str(&format!("    jae {}\n", none_label));
+                code.push_str(&format!("    mov BYTE [rbp-{}], 1\n", option));
+                code.push_str("    mov rax, rdi\n");
+                code.push_str(&Self::element_address(element_size));
+                code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", option - 8));
+                code.push_str(&format!("{}:\n", none_label));
+                code.push_str(&format!("    lea rax, [rbp-{}]\n", option));
+            },
+            "contains" => {
+                code.push_str(&self.generate_expr_code(&args[0])?);
+                code.push_str("    mov rdx, rax  ; Adresse de la valeur cherchée\n");
+                code.push_str(&Self::load_sequence(data, length));
+                code.push_str(&format!("    mov ecx, {}\n", element_size));
+                code.push_str(&format!("    lea r8, [rel {}]\n", self.compare_function(&element_type)));
+                code.push_str("    call __seq_contains\n");
+            },
+            "sort" => {
+                code.push_str(&Self::load_sequence(data, length));
+                code.push_str(&format!("    mov edx, {}\n", element_size));
+                code.push_str(&format!("    lea rcx, [rel {}]\n", self.compare_function(&element_type)));
+                code.push_str("    call __seq_sort\n");
+            },
+            "sort_by" => {
+                // Tri stable : la comparaison est appelée à travers rcx, l'environnement d'une fermeture dans r8
+                let (callee_code, callee, is_closure, _, _) = self.generate_callee(&args[0])?;
+                code.push_str(&callee_code);
+                if is_closure {
+                    code.push_str(&format!("    mov rax, QWORD [rbp-{}]\n    mov rcx, QWORD [rax]\n    mov r8, QWORD [rax+8]\n", callee));
                 } else {
-                    let then_index = index * 100 + 1;
-                    self.process_statement_for_format_labels(then_stmt, function_name, then_index);
-                }
-                
-                // Process else branch if it exists - handle both Block and non-Block statements
-                if let Some(else_stmt) = else_stmt {
-                    if let Stmt::Block(stmts) = &**else_stmt {
-                        for (i, nested_stmt) in stmts.iter().enumerate() {
-                            let nested_index = index * 100 + i + 2; // Start from +2 to avoid collision with then branch
-                            self.process_statement_for_format_labels(nested_stmt, function_name, nested_index);
-                        }
-                    } else {
-                        let else_index = index * 100 + 2;
-                        self.process_statement_for_format_labels(else_stmt, function_name, else_index);
-                    }
+                    code.push_str(&format!("    mov rcx, QWORD [rbp-{}]\n    xor r8d, r8d\n", callee));
                 }
+                code.push_str(&Self::load_sequence(data, length));
+                code.push_str(&format!("    mov edx, {}\n", element_size));
+                code.push_str("    call __seq_sort_by\n");
+            },
+            "swap" => {
+                let first = self.alloc_slot(8);
+                code.push_str(&self.generate_expr_code(&args[0])?);
+                code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", first));
+                code.push_str(&self.generate_expr_code(&args[1])?);
+                code.push_str("    mov rcx, rax\n");
+                code.push_str(&format!("    mov rdx, QWORD [rbp-{}]\n", first));
+                code.push_str(&Self::load_sequence(data, length));
+                code.push_str(&format!("    mov r8d, {}\n    mov r9d, {}\n", element_size, line));
+                code.push_str("    call __seq_swap\n");
+            },
+            "reverse" => {
+                code.push_str(&Self::load_sequence(data, length));
+                code.push_str(&format!("    mov edx, {}\n", element_size));
+                code.push_str("    call __seq_reverse\n");
+            },
+            "push" => {
+                // La valeur est évaluée avant une éventuelle réallocation
+                code.push_str(&self.generate_owned(&args[0], &element_type)?);
+                let value = self.alloc_slot(8);
+                code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", value));
+                code.push_str(&format!("    mov rdi, QWORD [rbp-{}]\n    mov esi, 1\n    mov edx, {}\n", receiver, element_size));
+                code.push_str("    call __vec_reserve\n");
+
+                // Écriture de l'élément à l'index `len`, puis incrémentation de la longueur
+                code.push_str(&format!("    mov rdx, QWORD [rbp-{}]\n", receiver));
+                code.push_str(&format!("    mov rcx, QWORD [rdx+{}]\n", VEC_LEN));
+                code.push_str("    mov rax, QWORD [rdx]\n");
+                code.push_str(&Self::element_address(element_size));
+                code.push_str("    mov rdx, rax\n");
+                code.push_str(&format!("    mov rax, QWORD [rbp-{}]\n", value));
+                code.push_str(&self.store_to(&element_type, "rdx"));
+                code.push_str(&format!("    mov rdx, QWORD [rbp-{}]\n", receiver));
+                code.push_str(&format!("    inc QWORD [rdx+{}]\n", VEC_LEN));
             },
-            Stmt::While(_, body) => {
-                if let Stmt::Block(stmts) = &**body {
-                    for (i, nested_stmt) in stmts.iter().enumerate() {
-                        let nested_index = index * 100 + i + 1;
-                        self.process_statement_for_format_labels(nested_stmt, function_name, nested_index);
+            "pop" => {
+                let option_type = Type::Option(Box::new(element_type.clone()));
+                let (offsets, size, _) = self.fields_layout(&[Type::Bool, element_type.clone()]);
+                let option = self.alloc_slot(size);
+                code.push_str(&format!("    mov rdi, QWORD [rbp-{}]\n    mov esi, {}\n", receiver, element_size));
+                code.push_str(&format!("    lea rdx, [rbp-{}]  ; Emplacement de la valeur\n", option - offsets[1]));
+                code.push_str("    call __vec_pop\n");
+                code.push_str(&format!("    mov BYTE [rbp-{}], al  ; Some si un élément a été retiré\n", option));
+                code.push_str(&self.load_from(&option_type, &format!("rbp-{}", option)));
+            },
+            "insert" => {
+                let index = self.alloc_slot(8);
+                code.push_str(&self.generate_expr_code(&args[0])?);
+                code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", index));
+                code.push_str(&self.generate_owned(&args[1], &element_type)?);
+                let value = self.alloc_slot(8);
+                code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", value));
+                code.push_str(&format!("    mov rdi, QWORD [rbp-{}]\n    mov rsi, QWORD [rbp-{}]\n", receiver, index));
+                code.push_str(&format!("    mov edx, {}\n    mov ecx, {}\n", element_size, line));
+                code.push_str("    call __vec_insert\n");
+                code.push_str("    mov rdx, rax  ; Place libérée\n");
+                code.push_str(&format!("    mov rax, QWORD [rbp-{}]\n", value));
+                code.push_str(&self.store_to(&element_type, "rdx"));
+            },
+            "remove" => {
+                let removed = self.alloc_slot(element_size.max(1));
+                code.push_str(&self.generate_expr_code(&args[0])?);
+                code.push_str("    mov rsi, rax\n");
+                code.push_str(&format!("    mov rdi, QWORD [rbp-{}]\n", receiver));
+                code.push_str(&format!("    mov edx, {}\n    mov ecx, {}\n", element_size, line));
+                code.push_str(&format!("    lea r8, [rbp-{}]\n", removed));
+                code.push_str("    call __vec_remove\n");
+                code.push_str(&self.load_from(&element_type, &format!("rbp-{}", removed)));
+            },
+            "extend" => {
+                let source_type = self.types.expr_type(&args[0])?;
+                if matches!(source_type, Type::Vec(_)) || (self.needs_drop(&source_type) && !matches!(source_type, Type::Ref(_, _))) {
+                    // Source déplacée : ses éléments passent au vecteur, puis le tampon d'un Vec est libéré
+                    let source = self.alloc_slot(8);
+                    code.push_str(&self.generate_owned(&args[0], &source_type)?);
+                    code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", source));
+                    match &source_type {
+                        Type::Vec(_) => code.push_str(&format!("    mov rdx, QWORD [rax+{}]\n    mov rax, QWORD [rax]\n", VEC_LEN)),
+                        Type::Array(_, length) => code.push_str(&format!("    mov edx, {}\n", length)),
+                        _ => unreachable!(),
+                    }
+                    code.push_str("    mov rsi, rax\n");
+                    code.push_str(&format!("    mov rdi, QWORD [rbp-{}]\n    mov ecx, {}\n", receiver, element_size));
+                    code.push_str("    call __vec_extend\n");
+                    if let Type::Vec(_) = source_type {
+                        code.push_str(&format!("    mov rax, QWORD [rbp-{}]\n", source));
+                        code.push_str("    mov rdi, QWORD [rax]\n");
+                        code.push_str("    call __free\n");
                     }
                 } else {
-                    let body_index = index * 100 + 1;
-                    self.process_statement_for_format_labels(body, function_name, body_index);
+                    code.push_str(&self.generate_sequence(&args[0])?);
+                    code.push_str("    mov rsi, rax\n");
+                    code.push_str(&format!("    mov rdi, QWORD [rbp-{}]\n    mov ecx, {}\n", receiver, element_size));
+                    code.push_str("    call __vec_extend\n");
                 }
             },
-            Stmt::Block(stmts) => {
-                for (i, nested_stmt) in stmts.iter().enumerate() {
-                    let nested_index = index * 100 + i + 1;
-                    self.process_statement_for_format_labels(nested_stmt, function_name, nested_index);
+            "truncate" | "clear" => {
+                match args.first() {
+                    Some(new_length) => {
+                        code.push_str(&self.generate_expr_code(new_length)?);
+                        code.push_str("    mov rsi, rax\n");
+                    },
+                    None => code.push_str("    xor esi, esi\n"),
                 }
+                if self.needs_drop(&element_type) {
+                    // Les éléments retirés sont détruits dans l'ordre
+                    let (new_length, index) = (self.alloc_slot(8), self.alloc_slot(8));
+                    let (loop_label, end_label) = (self.new_label("L_truncate_drop"), self.new_label("L_truncate_end"));
+                    code.push_str(&format!("    mov QWORD [rbp-{}], rsi\n", new_length));
+                    code.push_str(&format!("    mov QWORD [rbp-{}], rsi\n", index));
+                    code.push_str(&format!("{}:\n", loop_label));
+                    code.push_str(&format!("    mov rdx, QWORD [rbp-{}]\n", receiver));
+                    code.push_str(&format!("    mov rcx, QWORD [rbp-{}]\n", index));
+                    code.push_str(&format!("    cmp rcx, QWORD [rdx+{}]\n", VEC_LEN));
+                    code.push_str(&format!("    jae {}\n", end_label));
+                    code.push_str("    mov rax, QWORD [rdx]\n");
+                    code.push_str(&Self::element_address(element_size));
+                    code.push_str(&self.drop_at(&element_type, "rax"));
+                    code.push_str(&format!("    inc QWORD [rbp-{}]\n", index));
+                    code.push_str(&format!("    jmp {}\n", loop_label));
+                    code.push_str(&format!("{}:\n", end_label));
+                    code.push_str(&format!("    mov rsi, QWORD [rbp-{}]\n", new_length));
+                }
+                code.push_str(&format!("    mov rdi, QWORD [rbp-{}]\n", receiver));
+                code.push_str("    call __vec_truncate\n");
             },
-            _ => {}
+            _ => return self.error(&format!("Méthode non supportée: {}", method_name)),
         }
+
+        Ok(code)
     }
 
-    fn generate_format_strings_for_function(&mut self, function: &Function, code: &mut String) {
-        for (stmt_idx, stmt) in function.body.iter().enumerate() {
-            self.generate_format_strings_for_statement(stmt, &function.name, stmt_idx, code);
+    // Méthodes des tables de hachage (voir `runtime::MAP`) : la clé est passée dans rsi (entier étendu à
+    // 64 bits, ou octets d'une chaîne avec leur longueur dans rdx), la taille des entrées dans rcx et le
+    // genre de la clé dans r8
+    fn generate_map_method(&mut self, obj_expr: &Expr, method_name: &str, args: &[Expr]) -> Result<String, usize> {
+        if let Type::Entry(key, value) = TypeChecker::builtin_receiver(&self.types.expr_type(obj_expr)?, method_name) {
+            return self.generate_entry_method(obj_expr, &key.clone(), &value.clone(), method_name, args);
         }
-    }
+        let (receiver_code, table) = self.generate_receiver(obj_expr)?;
+        let (key, value) = Self::table_parts(&table);
+        let (value_offset, slot_size) = self.map_layout(&table);
+        self.uses_map_runtime = true;
 
-    fn generate_format_strings_for_statement(&mut self, stmt: &Stmt, function_name: &str, index: usize, code: &mut String) {
-        match stmt {
-            Stmt::Println(args) => {
-                if !args.is_empty() {
-                    if let Expr::Literal(Literal::String(format_str)) = &args[0] {
-                        let c_format = format_str.replace("{}", "%d");
-                        if let Some(label) = self.format_labels.get(&(function_name.to_string(), index)) {
-                            code.push_str(&format!("    {} db \"{}\", 10, 0\n", label, c_format));
-                        }
-                    }
+        let mut code = format!("    ; Méthode {}()\n", method_name);
+        code.push_str(&receiver_code);
+        let (receiver, key_slot) = (self.alloc_slot(8), self.alloc_slot(8));
+        code.push_str(&format!("    mov QWORD [rbp-{}], rax  ; Adresse de la table\n", receiver));
+        let call = |routine: &str| {
+            let mut call = format!("    mov rdi, QWORD [rbp-{}]\n", receiver);
+            call.push_str(&Self::map_key_args(&key, key_slot));
+            call.push_str(&format!("    mov ecx, {}\n    mov r8d, {}\n", slot_size, (key == Type::String) as u8));
+            call.push_str(&format!("    call {}\n", routine));
+            call
+        };
+
+        match method_name {
+            "len" => code.push_str(&format!("    mov rax, QWORD [rax+{}]\n", VEC_LEN)),
+            "is_empty" => code.push_str(&format!("    cmp QWORD [rax+{}], 0\n    sete al\n    movzx eax, al\n", VEC_LEN)),
+            "insert" => {
+                // La clé et la valeur sont déplacées dans la table ; une clé déjà présente est gardée,
+                // la nouvelle est détruite et l'ancienne valeur rendue
+                code.push_str(&self.generate_owned_key(&args[0], &key, key_slot)?);
+                let value_slot = match &value {
+                    Some(value) => {
+                        let value_slot = self.alloc_slot(self.type_size(value));
+                        code.push_str(&self.generate_owned(&args[1], value)?);
+                        code.push_str(&self.store_to(value, &format!("rbp-{}", value_slot)));
+                        value_slot
+                    },
+                    None => 0,
+                };
+                let (result_type, result_offset) = match &value {
+                    Some(value) => (Type::Option(Box::new(value.clone())), self.fields_layout(&[Type::Bool, value.clone()]).0[1]),
+                    None => (Type::Bool, 0),
+                };
+                let (entry, result) = (self.alloc_slot(8), self.alloc_slot(self.type_size(&result_type)));
+                let (new_label, store_label) = (self.new_label("L_map_new"), self.new_label("L_map_store"));
+                code.push_str(&call("__map_entry"));
+                code.push_str(&format!("    mov QWORD [rbp-{}], rax  ; Entrée\n", entry));
+                code.push_str("    test rdx, rdx\n");
+                code.push_str(&format!("    jz {}\n", new_label));
+                match &value {
+                    Some(value) => {
+                        code.push_str(&format!("    mov BYTE [rbp-{}], 1  ; Some(ancienne valeur)\n", result));
+                        code.push_str(&self.load_from(value, &format!("rax+{}", value_offset)));
+                        code.push_str(&self.store_to(value, &format!("rbp-{}", result - result_offset)));
+                    },
+                    None => code.push_str(&format!("    mov BYTE [rbp-{}], 0  ; Élément déjà présent\n", result)),
                 }
-            },
-            Stmt::For(_, _, _, body) => {
-                if let Stmt::Block(stmts) = &**body {
-                    for (i, nested_stmt) in stmts.iter().enumerate() {
-                        let nested_index = index * 100 + i + 1;
-                        self.generate_format_strings_for_statement(nested_stmt, function_name, nested_index, code);
-                    }
+                code.push_str(&self.drop_key(&key, key_slot));
+                code.push_str(&format!("    jmp {}\n", store_label));
+                code.push_str(&format!("{}:\n", new_label));
+                code.push_str(&format!("    mov BYTE [rbp-{}], {}\n", result, value.is_none() as u8));
+                code.push_str(&self.store_key(&key, key_slot, entry));
+                code.push_str(&format!("{}:\n", store_label));
+                if let Some(value) = &value {
+                    code.push_str(&self.load_from(value, &format!("rbp-{}", value_slot)));
+                    code.push_str(&format!("    mov rcx, QWORD [rbp-{}]\n", entry));
+                    code.push_str(&self.store_to(value, &format!("rcx+{}", value_offset)));
                 }
+                code.push_str(&self.load_from(&result_type, &format!("rbp-{}", result)));
             },
-            Stmt::If(_, then_stmt, else_stmt) => {
-                // Process then branch - handle both Block and non-Block statements
-                if let Stmt::Block(stmts) = &**then_stmt {
-                    for (i, nested_stmt) in stmts.iter().enumerate() {
-                        let nested_index = index * 100 + i + 1;
-                        self.generate_format_strings_for_statement(nested_stmt, function_name, nested_index, code);
-                    }
-                } else {
-                    let then_index = index * 100 + 1;
-                    self.generate_format_strings_for_statement(then_stmt, function_name, then_index, code);
+            "get" | "get_mut" => {
+                // Some(&valeur) si la clé est présente, None sinon
+                let option = self.alloc_slot(16);
+                let none_label = self.new_label("L_map_none");
+                code.push_str(&self.generate_lookup_key(&args[0], &key, key_slot)?);
+                code.push_str(&call("__map_find"));
+                code.push_str(&format!("    mov BYTE [rbp-{}], 0\n", option));
+                code.push_str("    test rax, rax\n");
+                code.push_str(&format!("    jz {}\n", none_label));
+                code.push_str(&format!("    mov BYTE [rbp-{}], 1\n", option));
+                code.push_str(&format!("    add rax, {}  ; Adresse de la valeur\n", value_offset));
+                code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", option - 8));
+                code.push_str(&format!("{}:\n", none_label));
+                code.push_str(&format!("    lea rax, [rbp-{}]\n", option));
+            },
+            "contains_key" | "contains" => {
+                code.push_str(&self.generate_lookup_key(&args[0], &key, key_slot)?);
+                code.push_str(&call("__map_find"));
+                code.push_str("    test rax, rax\n    setnz al\n    movzx eax, al\n");
+            },
+            "remove" => {
+                // L'entrée retirée est copiée dans le cadre : sa clé y est détruite, sa valeur rendue
+                let removed = self.alloc_slot(slot_size);
+                let (result_type, result_offset) = match &value {
+                    Some(value) => (Type::Option(Box::new(value.clone())), self.fields_layout(&[Type::Bool, value.clone()]).0[1]),
+                    None => (Type::Bool, 0),
+                };
+                let result = self.alloc_slot(self.type_size(&result_type));
+                let absent_label = self.new_label("L_map_absent");
+                code.push_str(&self.generate_lookup_key(&args[0], &key, key_slot)?);
+                code.push_str(&format!("    lea r9, [rbp-{}]  ; Entrée retirée\n", removed));
+                code.push_str(&call("__map_remove"));
+                code.push_str(&format!("    mov BYTE [rbp-{}], al\n", result));
+                code.push_str("    test rax, rax\n");
+                code.push_str(&format!("    jz {}\n", absent_label));
+                if self.needs_drop(&key) {
+                    code.push_str(&self.drop_at(&key, &format!("rbp-{}", removed - MAP_KEY)));
                 }
-                
-                // Process else branch if it exists - handle both Block and non-Block statements
-                if let Some(else_stmt) = else_stmt {
-                    if let Stmt::Block(stmts) = &**else_stmt {
-                        for (i, nested_stmt) in stmts.iter().enumerate() {
-                            let nested_index = index * 100 + i + 2; // Start from +2 to avoid collision with then branch
-                            self.generate_format_strings_for_statement(nested_stmt, function_name, nested_index, code);
-                        }
-                    } else {
-                        let else_index = index * 100 + 2;
-                        self.generate_format_strings_for_statement(else_stmt, function_name, else_index, code);
-                    }
+                if let Some(value) = &value {
+                    code.push_str(&self.load_from(value, &format!("rbp-{}", removed - value_offset)));
+                    code.push_str(&self.store_to(value, &format!("rbp-{}", result - result_offset)));
                 }
+                code.push_str(&format!("{}:\n", absent_label));
+                code.push_str(&self.load_from(&result_type, &format!("rbp-{}", result)));
             },
-            Stmt::While(_, body) => {
-                if let Stmt::Block(stmts) = &**body {
-                    for (i, nested_stmt) in stmts.iter().enumerate() {
-                        let nested_index = index * 100 + i + 1;
-                        self.generate_format_strings_for_statement(nested_stmt, function_name, nested_index, code);
-                    }
-                } else {
-                    let body_index = index * 100 + 1;
-                    self.generate_format_strings_for_statement(body, function_name, body_index, code);
-                }
+            "entry" => {
+                // Entry : adresse de la table, puis la clé déplacée, insérée ou détruite par or_insert()
+                let Some(value) = value else { unreachable!() };
+                let entry_type = Type::Entry(Box::new(key.clone()), Box::new(value));
+                let entry = self.alloc_slot(self.type_size(&entry_type));
+                code.push_str(&format!("    mov QWORD [rbp-{}], rax  ; Table\n", entry));
+                code.push_str(&self.generate_owned(&args[0], &key)?);
+                code.push_str(&self.store_to(&key, &format!("rbp-{}", entry - 8)));
+                code.push_str(&format!("    lea rax, [rbp-{}]\n", entry));
             },
-            Stmt::Block(stmts) => {
-                for (i, nested_stmt) in stmts.iter().enumerate() {
-                    let nested_index = index * 100 + i + 1;
-                    self.generate_format_strings_for_statement(nested_stmt, function_name, nested_index, code);
+            _ => return self.error(&format!("Méthode non supportée: {}", method_name)),
+        }
+
+        Ok(code)
+    }
+
+    // or_insert(valeur) et or_default() d'une Entry, déplacée dans le cadre : la valeur de la clé, insérée
+    // si besoin, est rendue par référence
+    fn generate_entry_method(&mut self, obj_expr: &Expr, key: &Type, value: &Type, method_name: &str, args: &[Expr]) -> Result<String, usize> {
+        let entry_type = Type::Entry(Box::new(key.clone()), Box::new(value.clone()));
+        let table = Type::HashMap(Box::new(key.clone()), Box::new(value.clone()));
+        let (value_offset, slot_size) = self.map_layout(&table);
+        self.uses_map_runtime = true;
+
+        let mut code = format!("    ; Entry::{}()\n", method_name);
+        let (entry, key_slot, slot) = (self.alloc_slot(self.type_size(&entry_type)), self.alloc_slot(8), self.alloc_slot(8));
+        code.push_str(&self.generate_owned(obj_expr, &entry_type)?);
+        code.push_str(&self.store_to(&entry_type, &format!("rbp-{}", entry)));
+        if *key == Type::String {
+            code.push_str(&format!("    lea rax, [rbp-{}]\n", entry - 8));
+        } else {
+            code.push_str(&self.load_from(key, &format!("rbp-{}", entry - 8)));
+        }
+        code.push_str(&format!("    mov QWORD [rbp-{}], rax  ; Clé\n", key_slot));
+        let value_slot = self.alloc_slot(self.type_size(value));
+        if let Some(arg) = args.first() {
+            code.push_str(&self.generate_owned(arg, value)?);
+            code.push_str(&self.store_to(value, &format!("rbp-{}", value_slot)));
+        }
+
+        let (new_label, done_label) = (self.new_label("L_entry_new"), self.new_label("L_entry_done"));
+        code.push_str(&format!("    mov rdi, QWORD [rbp-{}]\n", entry));
+        code.push_str(&Self::map_key_args(key, key_slot));
+        code.push_str(&format!("    mov ecx, {}\n    mov r8d, {}\n", slot_size, (*key == Type::String) as u8));
+        code.push_str("    call __map_entry\n");
+        code.push_str(&format!("    mov QWORD [rbp-{}], rax  ; Entrée\n", slot));
+        code.push_str("    test rdx, rdx\n");
+        code.push_str(&format!("    jz {}\n", new_label));
+        // Clé déjà présente : la clé de l'Entry et la valeur proposée sont détruites
+        code.push_str(&self.drop_key(key, key_slot));
+        if !args.is_empty() && self.needs_drop(value) {
+            code.push_str(&self.drop_at(value, &format!("rbp-{}", value_slot)));
+        }
+        code.push_str(&format!("    jmp {}\n", done_label));
+        code.push_str(&format!("{}:\n", new_label));
+        code.push_str(&self.store_key(key, key_slot, slot));
+        if args.is_empty() {
+            // or_default() : valeur par défaut faite d'octets nuls
+            code.push_str(&format!("    mov rcx, QWORD [rbp-{}]\n", slot));
+            code.push_str(&Self::zero_memory(&format!("rcx+{}", value_offset), self.type_size(value)));
+        } else {
+            code.push_str(&self.load_from(value, &format!("rbp-{}", value_slot)));
+            code.push_str(&format!("    mov rcx, QWORD [rbp-{}]\n", slot));
+            code.push_str(&self.store_to(value, &format!("rcx+{}", value_offset)));
+        }
+        code.push_str(&format!("{}:\n", done_label));
+        code.push_str(&format!("    mov rax, QWORD [rbp-{}]\n", slot));
+        code.push_str(&format!("    add rax, {}  ; Adresse de la valeur\n", value_offset));
+        Ok(code)
+    }
+
+    // Clé cherchée (`&K`, ou `&str` pour une clé String) : sa valeur entière, ou l'adresse de son en-tête
+    // (pointeur, longueur), rangée à rbp-`key_slot`
+    fn generate_lookup_key(&mut self, arg: &Expr, key: &Type, key_slot: usize) -> Result<String, usize> {
+        let mut code = if *key == Type::String {
+            self.generate_string_header(arg)?
+        } else {
+            let arg_type = self.types.expr_type(arg)?;
+            self.generate_expr_code(arg)? + &self.deref_scalar(&arg_type)
+        };
+        code.push_str(&format!("    mov QWORD [rbp-{}], rax  ; Clé\n", key_slot));
+        Ok(code)
+    }
+
+    // Clé déplacée vers la table : un entier est ramené à la largeur de son type puis étendu à 64 bits,
+    // une String est désignée par l'adresse de sa copie
+    fn generate_owned_key(&mut self, arg: &Expr, key: &Type, key_slot: usize) -> Result<String, usize> {
+        let mut code = self.generate_owned(arg, key)?;
+        if *key != Type::String {
+            code.push_str(&self.store_to(key, &format!("rbp-{}", key_slot)));
+            code.push_str(&self.load_from(key, &format!("rbp-{}", key_slot)));
+        }
+        code.push_str(&format!("    mov QWORD [rbp-{}], rax  ; Clé\n", key_slot));
+        Ok(code)
+    }
+
+    // Registres rsi (et rdx) désignant la clé rangée à rbp-`key_slot` pour les routines des tables
+    fn map_key_args(key: &Type, key_slot: usize) -> String {
+        if *key == Type::String {
+            format!("    mov rax, QWORD [rbp-{}]\n    mov rsi, QWORD [rax]\n    mov rdx, QWORD [rax+{}]\n", key_slot, VEC_LEN)
+        } else {
+            format!("    mov rsi, QWORD [rbp-{}]\n", key_slot)
+        }
+    }
+
+    // Range la clé possédée désignée par rbp-`key_slot` dans l'entrée dont l'adresse est à rbp-`entry`
+    fn store_key(&self, key: &Type, key_slot: usize, entry: usize) -> String {
+        let mut code = format!("    mov rcx, QWORD [rbp-{}]\n", entry);
+        code.push_str(&format!("    mov rax, QWORD [rbp-{}]\n", key_slot));
+        if *key == Type::String {
+            code.push_str(&Self::copy_memory("rax", &format!("rcx+{}", MAP_KEY), VEC_SIZE));
+        } else {
+            code.push_str(&format!("    mov QWORD [rcx+{}], rax\n", MAP_KEY));
+        }
+        code
+    }
+
+    // Destruction de la clé possédée non insérée (une String déjà présente dans la table)
+    fn drop_key(&mut self, key: &Type, key_slot: usize) -> String {
+        if !self.needs_drop(key) {
+            return String::new();
+        }
+        format!("    mov rax, QWORD [rbp-{}]\n", key_slot) + &self.drop_at(key, "rax")
+    }
+
+    // Méthodes d'Option : l'étiquette (1 pour Some) précède la valeur
+    fn generate_option_method(&mut self, obj_expr: &Expr, method_name: &str, args: &[Expr]) -> Result<String, usize> {
+        if method_name == "map" {
+            return self.generate_map(obj_expr, args);
+        }
+        let (receiver_code, receiver_type) = self.generate_receiver(obj_expr)?;
+        let Type::Option(inner) = receiver_type else { unreachable!() };
+        let payload = self.fields_layout(&[Type::Bool, (*inner).clone()]).0[1];
+
+        let mut code = String::new();
+        code.push_str(&format!("    ; Option::{}()\n", method_name));
+        code.push_str(&receiver_code);
+
+        match method_name {
+            "is_some" => code.push_str("    movzx eax, BYTE [rax]\n"),
+            "is_none" => code.push_str("    movzx eax, BYTE [rax]\n    xor eax, 1\n"),
+            "unwrap" => {
+                self.uses_bounds_check = true;
+                let some_label = self.new_label("L_unwrap_some");
+                code.push_str("    cmp BYTE [rax], 0\n");
+                code.push_str(&format!("    jne {}\n", some_label));
+                code.push_str(&format!("    mov edi, {}\n", self.types.current_line));
+                code.push_str("    lea rsi, [rel panic_unwrap_none]\n");
+                code.push_str("    call __panic\n");
+                code.push_str(&format!("{}:\n", some_label));
+                code.push_str(&self.load_place(&inner, &format!("rax+{}", payload)));
+            },
+            "expect" => {
+                let (option, message) = (self.alloc_slot(8), self.alloc_slot(8));
+                code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", option));
+                code.push_str(&self.generate_expr_code(&args[0])?);
+                code.push_str(&format!("    mov QWORD [rbp-{}], rax  ; Message\n", message));
+                let some_label = self.new_label("L_expect_some");
+                code.push_str(&format!("    mov rax, QWORD [rbp-{}]\n", option));
+                code.push_str("    cmp BYTE [rax], 0\n");
+                code.push_str(&format!("    jne {}\n", some_label));
+                code.push_str(&self.generate_panic_message(Some(message), "", None)?);
+                code.push_str(&format!("{}:\n", some_label));
+                code.push_str(&self.load_place(&inner, &format!("rax+{}", payload)));
+            },
+            "unwrap_or" if self.needs_drop(&inner) => {
+                code.push_str(&self.generate_unwrap_or(&args[0], &inner, payload)?);
+            },
+            "unwrap_or" => {
+                let option = self.alloc_slot(8);
+                code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", option));
+                code.push_str(&self.generate_coerced_expr(&args[0], &inner)?);
+                let done_label = self.new_label("L_unwrap_or");
+                code.push_str(&format!("    mov rcx, QWORD [rbp-{}]\n", option));
+                code.push_str("    cmp BYTE [rcx], 0\n");
+                code.push_str(&format!("    je {}\n", done_label));
+                code.push_str(&self.load_from(&inner, &format!("rcx+{}", payload)));
+                code.push_str(&format!("{}:\n", done_label));
+            },
+            _ => return self.error(&format!("Méthode non supportée: {}", method_name)),
+        }
+
+        Ok(code)
+    }
+
+    // Méthodes de Result : l'étiquette (1 pour Ok) précède la valeur ou l'erreur
+    fn generate_result_method(&mut self, obj_expr: &Expr, method_name: &str, args: &[Expr]) -> Result<String, usize> {
+        if method_name == "map" {
+            return self.generate_map(obj_expr, args);
+        }
+        let (receiver_code, receiver_type) = self.generate_receiver(obj_expr)?;
+        let Type::Result(ok, err) = receiver_type else { unreachable!() };
+        let payload = self.result_layout(&ok, &err).0;
+
+        let mut code = String::new();
+        code.push_str(&format!("    ; Result::{}()\n", method_name));
+        code.push_str(&receiver_code);
+
+        match method_name {
+            "is_ok" => code.push_str("    movzx eax, BYTE [rax]\n"),
+            "is_err" => code.push_str("    movzx eax, BYTE [rax]\n    xor eax, 1\n"),
+            "unwrap" | "expect" | "unwrap_err" => {
+                // Le message de panique reprend la forme {:?} de l'erreur (de la valeur pour unwrap_err)
+                let (result, message) = (self.alloc_slot(8), self.alloc_slot(8));
+                code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", result));
+                if method_name == "expect" {
+                    code.push_str(&self.generate_expr_code(&args[0])?);
+                    code.push_str(&format!("    mov QWORD [rbp-{}], rax  ; Message\n", message));
                 }
+                let (expected_tag, value_type, shown_type) = match method_name {
+                    "unwrap_err" => (0, &err, &ok),
+                    _ => (1, &ok, &err),
+                };
+                let success = self.new_label("L_unwrap");
+                code.push_str(&format!("    mov rax, QWORD [rbp-{}]\n", result));
+                code.push_str(&format!("    cmp BYTE [rax], {}\n", expected_tag));
+                code.push_str(&format!("    je {}\n", success));
+                code.push_str(&format!("    add rax, {}\n", payload));
+                code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", result));
+                code.push_str(&match method_name {
+                    "unwrap" => self.generate_panic_message(None, "called `Result::unwrap()` on an `Err` value: ", Some((shown_type, result)))?,
+                    "expect" => self.generate_panic_message(Some(message), ": ", Some((shown_type, result)))?,
+                    _ => self.generate_panic_message(None, "called `Result::unwrap_err()` on an `Ok` value: ", Some((shown_type, result)))?,
+                });
+                code.push_str(&format!("{}:\n", success));
+                code.push_str(&self.load_place(value_type, &format!("rax+{}", payload)));
+            },
+            "unwrap_or" if self.needs_drop(&ok) => {
+                code.push_str(&self.generate_unwrap_or(&args[0], &ok, payload)?);
             },
-            _ => {}
+            "unwrap_or" => {
+                let result = self.alloc_slot(8);
+                code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", result));
+                code.push_str(&self.generate_coerced_expr(&args[0], &ok)?);
+                let done_label = self.new_label("L_unwrap_or");
+                code.push_str(&format!("    mov rcx, QWORD [rbp-{}]\n", result));
+                code.push_str("    cmp BYTE [rcx], 0\n");
+                code.push_str(&format!("    je {}\n", done_label));
+                code.push_str(&self.load_from(&ok, &format!("rcx+{}", payload)));
+                code.push_str(&format!("{}:\n", done_label));
+            },
+            _ => return self.error(&format!("Méthode non supportée: {}", method_name)),
         }
+
+        Ok(code)
     }
-    
-    // New helper method to count all variables recursively
-    fn count_all_variables(&self, statements: &[Stmt], count: &mut usize) {
-        for stmt in statements {
-            match stmt {
-                Stmt::Let(_, _, _, _) => {
-                    *count += 1;
-                },
-                Stmt::For(_, _range_start, _range_end, body) => {
-                    *count += 1; // Count the loop variable
-                    if let Stmt::Block(stmts) = &**body {
-                        self.count_all_variables(stmts, count);
-                    }
-                },
-                Stmt::If(_, then_stmt, else_stmt) => {
-                    // Count variables in then branch
-                    if let Stmt::Block(stmts) = &**then_stmt {
-                        self.count_all_variables(stmts, count);
-                    }
-                    // Count variables in else branch if it exists
-                    if let Some(else_stmt) = else_stmt {
-                        if let Stmt::Block(stmts) = &**else_stmt {
-                            self.count_all_variables(stmts, count);
-                        }
-                    }
-                },
-                Stmt::While(_, body) => {
-                    if let Stmt::Block(stmts) = &**body {
-                        self.count_all_variables(stmts, count);
-                    }
-                },
-                Stmt::Block(stmts) => {
-                    self.count_all_variables(stmts, count);
-                },
-                _ => {}
-            }
+
+    // unwrap_or() d'une valeur à détruire (adresse de l'Option ou du Result dans rax) : la valeur par
+    // défaut est évaluée dans son emplacement, puis détruite et remplacée par la valeur de Some ou de Ok,
+    // qui quitte alors le receveur
+    fn generate_unwrap_or(&mut self, default: &Expr, inner: &Type, payload: usize) -> Result<String, usize> {
+        let size = self.type_size(inner);
+        let (receiver, value) = (self.alloc_slot(8), self.alloc_slot(size.max(8)));
+        let mut code = format!("    mov QWORD [rbp-{}], rax\n", receiver);
+        code.push_str(&self.generate_owned(default, inner)?);
+        code.push_str(&self.store_to(inner, &format!("rbp-{}", value)));
+        let done_label = self.new_label("L_unwrap_or");
+        code.push_str(&format!("    mov rax, QWORD [rbp-{}]\n", receiver));
+        code.push_str("    cmp BYTE [rax], 0\n");
+        code.push_str(&format!("    je {}\n", done_label));
+        code.push_str(&self.drop_at(inner, &format!("rbp-{}", value)));
+        code.push_str(&format!("    mov rax, QWORD [rbp-{}]\n", receiver));
+        code.push_str(&format!("    lea rcx, [rax+{}]\n", payload));
+        code.push_str(&Self::copy_memory("rcx", &format!("rbp-{}", value), size));
+        code.push_str(&self.vacate(inner, "rax", payload));
+        code.push_str(&format!("{}:\n", done_label));
+        if self.is_aggregate(inner) {
+            code.push_str(&format!("    lea rax, [rbp-{}]\n", value));
+        } else {
+            code.push_str(&format!("    mov rax, QWORD [rbp-{}]\n", value));
         }
+        Ok(code)
     }
 
-    // New helper method to assign offsets to all variables
-    fn assign_variable_offsets(&mut self, statements: &[Stmt], offset: &mut usize) {
-        for stmt in statements {
-            match stmt {
-                Stmt::Let(name, _, _, var_type) => {
-                    *offset += 8;
-                    self.variable_info.insert(name.clone(), VarInfo { 
-                        offset: *offset, 
-                        var_type: var_type.clone() 
-                    });
-                },
-                Stmt::For(var_name, _, _, body) => {
-                    // Assign offset for loop variable if not already assigned
-                    if !self.variable_info.contains_key(var_name) {
-                        *offset += 8;
-                        self.variable_info.insert(var_name.clone(), VarInfo { 
-                            offset: *offset, 
-                            var_type: Type::I32 
-                        });
-                    }
-                    if let Stmt::Block(stmts) = &**body {
-                        self.assign_variable_offsets(stmts, offset);
-                    }
-                },
-                Stmt::If(_, then_stmt, else_stmt) => {
-                    // Assign offsets for variables in then branch
-                    if let Stmt::Block(stmts) = &**then_stmt {
-                        self.assign_variable_offsets(stmts, offset);
-                    }
-                    // Assign offsets for variables in else branch if it exists
-                    if let Some(else_stmt) = else_stmt {
-                        if let Stmt::Block(stmts) = &**else_stmt {
-                            self.assign_variable_offsets(stmts, offset);
-                        }
-                    }
-                },
-                Stmt::While(_, body) => {
-                    if let Stmt::Block(stmts) = &**body {
-                        self.assign_variable_offsets(stmts, offset);
-                    }
-                },
-                Stmt::Block(stmts) => {
-                    self.assign_variable_offsets(stmts, offset);
-                },
-                _ => {}
+    // map() d'une Option ou d'un Result : la fonction reçoit la valeur de Some ou de Ok et son résultat
+    // forme la nouvelle variante ; None reste None, l'erreur est recopiée
+    fn generate_map(&mut self, obj_expr: &Expr, args: &[Expr]) -> Result<String, usize> {
+        let obj_type = self.types.expr_type(obj_expr)?;
+        let mapped = self.types.map_function(&obj_type, args)?;
+        let (callee_code, callee, is_closure, _, output) = self.generate_callee(&args[0])?;
+        let (receiver_code, receiver_type) = self.generate_receiver(obj_expr)?;
+        let (inner, payload, mapped_payload) = match (&receiver_type, &mapped) {
+            (Type::Option(inner), Type::Option(output)) => (
+                (**inner).clone(),
+                self.fields_layout(&[Type::Bool, (**inner).clone()]).0[1],
+                self.fields_layout(&[Type::Bool, (**output).clone()]).0[1],
+            ),
+            (Type::Result(ok, err), Type::Result(output, _)) => ((**ok).clone(), self.result_layout(ok, err).0, self.result_layout(output, err).0),
+            _ => unreachable!(),
+        };
+
+        let mut code = String::from("    ; map()\n");
+        code.push_str(&callee_code);
+        code.push_str(&receiver_code);
+        let (receiver, result) = (self.alloc_slot(8), self.alloc_slot(self.type_size(&mapped)));
+        code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", receiver));
+        code.push_str(&format!("    mov BYTE [rbp-{}], 0\n", result));
+        let (failure, done) = (self.new_label("L_map_none"), self.new_label("L_map_done"));
+        code.push_str("    cmp BYTE [rax], 0\n");
+        code.push_str(&format!("    je {}\n", failure));
+
+        // Appel de la fonction sur la valeur contenue (copie pour un agrégat)
+        if self.is_aggregate(&inner) {
+            let size = self.type_size(&inner);
+            let copy = self.alloc_slot(size);
+            code.push_str(&format!("    lea rcx, [rax+{}]\n", payload));
+            code.push_str(&Self::copy_memory("rcx", &format!("rbp-{}", copy), size));
+            if self.needs_drop(&inner) {
+                // La valeur est déplacée vers la fonction
+                code.push_str(&self.vacate(&inner, "rax", payload));
+            }
+            code.push_str(&format!("    lea rax, [rbp-{}]\n", copy));
+        } else {
+            code.push_str(&self.load_from(&inner, &format!("rax+{}", payload)));
+            if self.needs_drop(&inner) {
+                code.push_str(&format!("    mov rdx, QWORD [rbp-{}]\n", receiver));
+                code.push_str(&format!("    mov QWORD [rdx+{}], 0  ; Valeur déplacée\n", payload));
+            }
+        }
+        let argument = self.alloc_slot(8);
+        code.push_str(&format!("    mov QWORD [rbp-{}], rax\n", argument));
+        code.push_str(&Self::load_callee(callee, is_closure));
+        self.emit_call(&mut code, &[argument], "r11", &output);
+        code.push_str(&self.store_to(&output, &format!("rbp-{}", result - mapped_payload)));
+        code.push_str(&format!("    mov BYTE [rbp-{}], 1\n", result));
+        code.push_str(&format!("    jmp {}\n", done));
+
+        code.push_str(&format!("{}:\n", failure));
+        if let Type::Result(_, err) = &receiver_type {
+            code.push_str(&format!("    lea rcx, [rax+{}]\n", payload));
+            code.push_str(&Self::copy_memory("rcx", &format!("rbp-{}", result - mapped_payload), self.type_size(err)));
+            if self.needs_drop(err) {
+                code.push_str(&self.vacate(err, "rax", payload));
             }
         }
+        code.push_str(&format!("{}:\n", done));
+        code.push_str(&format!("    lea rax, [rbp-{}]\n", result));
+        Ok(code)
     }
-}
\ No newline at end of file
+}
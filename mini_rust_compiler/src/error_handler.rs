@@ -1,17 +1,27 @@
+use std::cell::RefCell;
+
 pub struct ErrorHandler {
     file_name: String,
+    // Messages déjà signalés, relus par les tests
+    messages: RefCell<Vec<String>>,
 }
 
 impl ErrorHandler {
     pub fn new(file_name: String) -> Self {
-        ErrorHandler { file_name }
+        ErrorHandler { file_name, messages: RefCell::new(Vec::new()) }
     }
     
     pub fn report_error(&self, line: usize, message: &str) {
-        if line > 0 {
-            eprintln!("{}:{} - Erreur à la ligne {}", self.file_name, message, line);
+        let message = if line > 0 {
+            format!("{}:{} - Erreur à la ligne {}", self.file_name, message, line)
         } else {
-            eprintln!("{}: {}", self.file_name, message);
-        }
+            format!("{}: {}", self.file_name, message)
+        };
+        eprintln!("{}", message);
+        self.messages.borrow_mut().push(message);
+    }
+
+    pub fn messages(&self) -> Vec<String> {
+        self.messages.borrow().clone()
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,416 @@
+// Chaînes de format des macros d'écriture (println!, format!, write!...) : analysées à la compilation en
+// morceaux de texte et en emplacements `{argument:spec}`, chacun relié à l'expression qu'il affiche.
+//
+// format   ::= '{' argument? (':' spec)? '}'
+// argument ::= INT | IDENT
+// spec     ::= (fill? align)? '+'? '#'? '0'? width? ('.' precision)? type
+// width    ::= count ; precision ::= count | '*' ; count ::= INT | (INT | IDENT) '$'
+// type     ::= '' | '?' | 'x' | 'X' | 'b' | 'o'
+
+use crate::parser::Expr;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Align {
+    Left,
+    Right,
+    Center,
+}
+
+// Largeur ou précision : écrite dans le format, ou lue dans un argument entier (`{:1$}`, `{:w$}`, `{:.*}`)
+#[derive(Debug, Clone, PartialEq)]
+pub enum Count {
+    Literal(usize),
+    Argument(usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Kind {
+    Display,
+    Debug,
+    LowerHex,
+    UpperHex,
+    Binary,
+    Octal,
+}
+
+impl Kind {
+    pub fn trait_name(&self) -> &'static str {
+        match self {
+            Kind::Display => "Display",
+            Kind::Debug => "Debug",
+            Kind::LowerHex => "LowerHex",
+            Kind::UpperHex => "UpperHex",
+            Kind::Binary => "Binary",
+            Kind::Octal => "Octal",
+        }
+    }
+
+    // Emplacement tel qu'on l'écrit : {}, {:?}, {:x}...
+    pub fn placeholder(&self) -> &'static str {
+        match self {
+            Kind::Display => "{}",
+            Kind::Debug => "{:?}",
+            Kind::LowerHex => "{:x}",
+            Kind::UpperHex => "{:X}",
+            Kind::Binary => "{:b}",
+            Kind::Octal => "{:o}",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spec {
+    pub fill: char,
+    pub align: Option<Align>, // Par défaut : à gauche pour le texte, à droite pour les nombres
+    pub plus: bool,
+    pub alternate: bool,      // `#` : préfixes 0x, 0b, 0o
+    pub zero: bool,           // `0` : zéros entre le signe et les chiffres
+    pub width: Option<Count>,
+    pub precision: Option<Count>,
+    pub kind: Kind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Piece {
+    Text(String),
+    Argument(usize, Spec), // Indice dans FormatArgs::args
+}
+
+// Format analysé et expressions qu'il affiche : arguments positionnels, puis nommés (`nom = expr`),
+// puis variables capturées (`{nom}` sans argument de ce nom)
+#[derive(Debug, Clone)]
+pub struct FormatArgs {
+    pub pieces: Vec<Piece>,
+    pub args: Vec<Expr>,
+}
+
+// Relie les emplacements aux arguments et vérifie que chaque argument fourni est utilisé
+struct Resolver {
+    positional: usize,
+    names: Vec<String>, // Arguments nommés puis capturés, à partir de l'indice `positional`
+    explicit: usize,    // Nombre d'arguments nommés explicites
+    next: usize,        // Prochain argument des emplacements implicites `{}`
+    implicit: usize,    // Nombre d'emplacements implicites, pour le message d'erreur
+    used: Vec<bool>,
+}
+
+impl Resolver {
+    fn next_argument(&mut self) -> Result<usize, String> {
+        let index = self.next;
+        self.next += 1;
+        self.implicit += 1;
+        if index >= self.positional {
+            return Err(String::new()); // Message construit une fois tous les emplacements comptés
+        }
+        self.used[index] = true;
+        Ok(index)
+    }
+
+    fn positional(&mut self, index: usize) -> Result<usize, String> {
+        if index >= self.positional {
+            return Err(match self.positional {
+                0 => format!("référence invalide à l'argument positionnel {} (aucun argument fourni)", index),
+                count => format!("référence invalide à l'argument positionnel {} ({} argument(s) fourni(s))", index, count),
+            });
+        }
+        self.used[index] = true;
+        Ok(index)
+    }
+
+    // Argument nommé, ou variable du même nom capturée à défaut
+    fn named(&mut self, name: &str) -> usize {
+        let index = match self.names.iter().position(|known| known == name) {
+            Some(index) => index,
+            None => {
+                self.names.push(name.to_string());
+                self.used.push(true);
+                self.names.len() - 1
+            },
+        };
+        self.used[self.positional + index] = true;
+        self.positional + index
+    }
+}
+
+pub fn parse(format: &str, positional: Vec<Expr>, named: Vec<(String, Expr)>) -> Result<FormatArgs, String> {
+    let mut resolver = Resolver {
+        positional: positional.len(),
+        names: named.iter().map(|(name, _)| name.clone()).collect(),
+        explicit: named.len(),
+        next: 0,
+        implicit: 0,
+        used: vec![false; positional.len() + named.len()],
+    };
+    for (i, (name, _)) in named.iter().enumerate() {
+        if named[..i].iter().any(|(other, _)| other == name) {
+            return Err(format!("argument nommé `{}` fourni plusieurs fois", name));
+        }
+    }
+
+    let chars: Vec<char> = format.chars().collect();
+    let mut pieces = Vec::new();
+    let mut text = String::new();
+    let mut missing = false;
+    let mut i = 0;
+
+    while i < chars.len() {
+        match chars[i] {
+            '{' if chars.get(i + 1) == Some(&'{') => {
+                text.push('{');
+                i += 2;
+            },
+            '}' if chars.get(i + 1) == Some(&'}') => {
+                text.push('}');
+                i += 2;
+            },
+            '}' => return Err("`}` isolé dans la chaîne de format : doublez-le (`}}`) pour l'afficher".to_string()),
+            '{' => {
+                if !text.is_empty() {
+                    pieces.push(Piece::Text(std::mem::take(&mut text)));
+                }
+                i += 1;
+                match placeholder(&chars, &mut i, &mut resolver) {
+                    Ok(piece) => pieces.push(piece),
+                    Err(message) if message.is_empty() => missing = true,
+                    Err(message) => return Err(message),
+                }
+            },
+            c => {
+                text.push(c);
+                i += 1;
+            },
+        }
+    }
+    if !text.is_empty() {
+        pieces.push(Piece::Text(text));
+    }
+
+    if missing {
+        return Err(format!("{} emplacement(s) dans la chaîne de format, mais {} argument(s) fourni(s)",
+            resolver.implicit, resolver.positional));
+    }
+    if let Some(index) = resolver.used[..resolver.positional].iter().position(|used| !used) {
+        return Err(format!("l'argument {} n'est jamais utilisé dans la chaîne de format", index));
+    }
+    if let Some(index) = resolver.used[resolver.positional..resolver.positional + resolver.explicit].iter().position(|used| !used) {
+        return Err(format!("l'argument nommé `{}` n'est jamais utilisé dans la chaîne de format", resolver.names[index]));
+    }
+
+    let mut args = positional;
+    args.extend(named.into_iter().map(|(_, value)| value));
+    args.extend(resolver.names[resolver.explicit..].iter().map(|name| Expr::Variable(name.clone())));
+    Ok(FormatArgs { pieces, args })
+}
+
+fn identifier(chars: &[char], i: &mut usize) -> Option<String> {
+    if !chars.get(*i).is_some_and(|c| c.is_alphabetic() || *c == '_') {
+        return None;
+    }
+    let start = *i;
+    while chars.get(*i).is_some_and(|c| c.is_alphanumeric() || *c == '_') {
+        *i += 1;
+    }
+    Some(chars[start..*i].iter().collect())
+}
+
+fn integer(chars: &[char], i: &mut usize) -> Option<usize> {
+    let start = *i;
+    while chars.get(*i).is_some_and(|c| c.is_ascii_digit()) {
+        *i += 1;
+    }
+    let digits: String = chars[start..*i].iter().collect();
+    digits.parse().ok()
+}
+
+// Largeur ou précision : entier, ou argument désigné par `N$` / `nom$`
+fn count(chars: &[char], i: &mut usize, resolver: &mut Resolver) -> Result<Option<Count>, String> {
+    let start = *i;
+    if let Some(value) = integer(chars, i) {
+        if chars.get(*i) == Some(&'$') {
+            *i += 1;
+            return Ok(Some(Count::Argument(resolver.positional(value)?)));
+        }
+        return Ok(Some(Count::Literal(value)));
+    }
+    if let Some(name) = identifier(chars, i) {
+        if chars.get(*i) == Some(&'$') {
+            *i += 1;
+            return Ok(Some(Count::Argument(resolver.named(&name))));
+        }
+    }
+    *i = start; // Pas un compte : c'est le type de l'emplacement
+    Ok(None)
+}
+
+// Emplacement dont l'accolade ouvrante vient d'être consommée
+fn placeholder(chars: &[char], i: &mut usize, resolver: &mut Resolver) -> Result<Piece, String> {
+    let argument = if let Some(index) = integer(chars, i) {
+        Some(resolver.positional(index)?)
+    } else {
+        identifier(chars, i).map(|name| resolver.named(&name))
+    };
+
+    let mut spec = Spec { fill: ' ', align: None, plus: false, alternate: false, zero: false, width: None, precision: None, kind: Kind::Display };
+    let mut precision_argument = false;
+    if chars.get(*i) == Some(&':') {
+        *i += 1;
+        let alignment = |c: Option<&char>| match c {
+            Some('<') => Some(Align::Left),
+            Some('>') => Some(Align::Right),
+            Some('^') => Some(Align::Center),
+            _ => None,
+        };
+        if let (Some(&fill), Some(align)) = (chars.get(*i), alignment(chars.get(*i + 1))) {
+            spec.fill = fill;
+            spec.align = Some(align);
+            *i += 2;
+        } else if let Some(align) = alignment(chars.get(*i)) {
+            spec.align = Some(align);
+            *i += 1;
+        }
+        match chars.get(*i) {
+            Some('+') => {
+                spec.plus = true;
+                *i += 1;
+            },
+            Some('-') => *i += 1, // Accepté par Rust, sans effet
+            _ => {},
+        }
+        if chars.get(*i) == Some(&'#') {
+            spec.alternate = true;
+            *i += 1;
+        }
+        if chars.get(*i) == Some(&'0') && chars.get(*i + 1) != Some(&'$') {
+            spec.zero = true;
+            *i += 1;
+        }
+        spec.width = count(chars, i, resolver)?;
+        if chars.get(*i) == Some(&'.') {
+            *i += 1;
+            if chars.get(*i) == Some(&'*') {
+                // `.*` : la précision est l'argument implicite suivant, avant la valeur
+                *i += 1;
+                precision_argument = true;
+            } else {
+                spec.precision = count(chars, i, resolver)?;
+                if spec.precision.is_none() {
+                    return Err("précision invalide dans la chaîne de format : attendu un entier, `N$`, `nom$` ou `*`".to_string());
+                }
+            }
+        }
+        let start = *i;
+        while chars.get(*i).is_some_and(|c| *c != '}') {
+            *i += 1;
+        }
+        let kind: String = chars[start..*i].iter().collect();
+        spec.kind = match kind.as_str() {
+            "" => Kind::Display,
+            "?" | "x?" | "X?" => Kind::Debug,
+            "x" => Kind::LowerHex,
+            "X" => Kind::UpperHex,
+            "b" => Kind::Binary,
+            "o" => Kind::Octal,
+            other => return Err(format!("format inconnu `{}` dans la chaîne de format", other)),
+        };
+    }
+
+    if chars.get(*i) != Some(&'}') {
+        return Err("`}` attendu pour fermer l'emplacement de la chaîne de format".to_string());
+    }
+    *i += 1;
+
+    if precision_argument {
+        spec.precision = Some(Count::Argument(resolver.next_argument()?));
+    }
+    let index = match argument {
+        Some(index) => index,
+        None => resolver.next_argument()?,
+    };
+    Ok(Piece::Argument(index, spec))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(names: &[&str]) -> Vec<Expr> {
+        names.iter().map(|name| Expr::Variable(name.to_string())).collect()
+    }
+
+    fn spec(kind: Kind) -> Spec {
+        Spec { fill: ' ', align: None, plus: false, alternate: false, zero: false, width: None, precision: None, kind }
+    }
+
+    #[test]
+    fn unescapes_braces_in_text() {
+        let format = parse("a{{b}}c", Vec::new(), Vec::new()).unwrap();
+        assert_eq!(format.pieces, vec![Piece::Text("a{b}c".to_string())]);
+        assert!(format.args.is_empty());
+    }
+
+    #[test]
+    fn resolves_implicit_positional_named_and_captured_arguments() {
+        let named = vec![("name".to_string(), Expr::Variable("z".to_string()))];
+        let format = parse("{} {1} {0} {name} {w}", vars(&["x", "y"]), named).unwrap();
+        let indices: Vec<usize> = format.pieces.iter().filter_map(|piece| match piece {
+            Piece::Argument(index, _) => Some(*index),
+            Piece::Text(_) => None,
+        }).collect();
+        assert_eq!(indices, vec![0, 1, 0, 2, 3]);
+        // La variable capturée `w` suit les arguments positionnels et nommés
+        assert_eq!(format.args.len(), 4);
+        assert!(matches!(&format.args[3], Expr::Variable(name) if name == "w"));
+    }
+
+    #[test]
+    fn parses_fill_alignment_width_and_precision() {
+        let format = parse("{:*^10.3}", vars(&["x"]), Vec::new()).unwrap();
+        let expected = Spec { fill: '*', align: Some(Align::Center), width: Some(Count::Literal(10)), precision: Some(Count::Literal(3)), ..spec(Kind::Display) };
+        assert_eq!(format.pieces, vec![Piece::Argument(0, expected)]);
+    }
+
+    #[test]
+    fn parses_sign_alternate_zero_and_kind() {
+        let format = parse("{:+#010x}{:?}{:b}", vars(&["x", "y", "z"]), Vec::new()).unwrap();
+        let hex = Spec { plus: true, alternate: true, zero: true, width: Some(Count::Literal(10)), ..spec(Kind::LowerHex) };
+        assert_eq!(format.pieces, vec![
+            Piece::Argument(0, hex),
+            Piece::Argument(1, spec(Kind::Debug)),
+            Piece::Argument(2, spec(Kind::Binary)),
+        ]);
+    }
+
+    #[test]
+    fn reads_width_and_precision_from_arguments() {
+        let format = parse("{:>1$} {:.*}", vars(&["x", "w", "y"]), Vec::new()).unwrap();
+        let width = Spec { align: Some(Align::Right), width: Some(Count::Argument(1)), ..spec(Kind::Display) };
+        // `.*` consomme d'abord la précision, puis la valeur
+        let precision = Spec { precision: Some(Count::Argument(1)), ..spec(Kind::Display) };
+        assert_eq!(format.pieces, vec![
+            Piece::Argument(0, width),
+            Piece::Text(" ".to_string()),
+            Piece::Argument(2, precision),
+        ]);
+    }
+
+    #[test]
+    fn rejects_missing_and_unused_arguments() {
+        assert_eq!(parse("{} {}", vars(&["x"]), Vec::new()).unwrap_err(),
+            "2 emplacement(s) dans la chaîne de format, mais 1 argument(s) fourni(s)");
+        assert_eq!(parse("{}", vars(&["x", "y"]), Vec::new()).unwrap_err(),
+            "l'argument 1 n'est jamais utilisé dans la chaîne de format");
+        assert_eq!(parse("{2}", vars(&["x"]), Vec::new()).unwrap_err(),
+            "référence invalide à l'argument positionnel 2 (1 argument(s) fourni(s))");
+        let named = vec![("a".to_string(), Expr::Variable("x".to_string()))];
+        assert_eq!(parse("", Vec::new(), named).unwrap_err(),
+            "l'argument nommé `a` n'est jamais utilisé dans la chaîne de format");
+    }
+
+    #[test]
+    fn rejects_malformed_placeholders() {
+        assert!(parse("a } b", Vec::new(), Vec::new()).unwrap_err().starts_with("`}` isolé"));
+        assert_eq!(parse("{:z}", vars(&["x"]), Vec::new()).unwrap_err(), "format inconnu `z` dans la chaîne de format");
+        assert!(parse("{:.}", vars(&["x"]), Vec::new()).unwrap_err().starts_with("précision invalide"));
+        assert!(parse("{", vars(&["x"]), Vec::new()).unwrap_err().starts_with("`}` attendu"));
+    }
+}
@@ -0,0 +1,90 @@
+// Prélude des itérateurs : le trait Iterator n'est pas écrit en mini-Rust mais fourni par le compilateur.
+// Une chaîne `source.adaptateur(..)...consommateur(..)` a le type `impl Iterator<Item = T>` jusqu'à son
+// consommateur ; elle n'est jamais matérialisée : la génération de code la fond en une seule boucle,
+// chaque adaptateur insérant ses instructions dans le calcul de l'élément suivant.
+
+// Méthode d'une collection ou d'une chaîne qui crée un itérateur
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Source {
+    Iter,     // v.iter() : &T
+    IterMut,  // v.iter_mut() : &mut T
+    IntoIter, // v.into_iter() : T (ou &T à travers une référence)
+    Chars,    // s.chars() : char
+    Bytes,    // s.bytes() : u8
+    Split,    // s.split(motif), s.split_whitespace() : &str
+    Keys,     // m.keys() : &K
+    Values,   // m.values(), m.values_mut() : &V ou &mut V
+}
+
+// Adaptateur : transforme un itérateur en un autre
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Adapter {
+    Map,       // map(|x| ..) : la valeur renvoyée
+    Filter,    // filter(|x: &T| ..) : les éléments acceptés
+    Enumerate, // enumerate() : (index, élément)
+    Zip,       // zip(autre) : (élément, élément de l'autre), jusqu'à la fin du plus court
+    Take,      // take(n) : les n premiers éléments
+    Skip,      // skip(n) : tous sauf les n premiers
+    StepBy,    // step_by(n) : le premier élément puis un sur n
+    Rev,       // rev() : en sens inverse, directement sur une plage ou une collection
+}
+
+// Consommateur : parcourt l'itérateur et produit une valeur
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Consumer {
+    Sum,
+    Product,
+    Count,
+    Min,
+    Max,
+    Collect, // collect::<Vec<_>>() ou collect::<String>() pour des char
+    Fold,    // fold(initial, |acc, x| ..)
+    Any,
+    All,
+    ForEach,
+}
+
+// Méthodes propres à une plage, qui n'en font pas un itérateur : `(0..10).contains(&x)`
+pub const RANGE_METHODS: [&str; 2] = ["contains", "is_empty"];
+
+// Rôle d'une méthode dans le protocole Iterator
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IteratorMethod {
+    Source(Source),
+    Adapter(Adapter),
+    Consumer(Consumer),
+}
+
+impl IteratorMethod {
+    pub fn from_name(name: &str) -> Option<IteratorMethod> {
+        Some(match name {
+            "iter" => IteratorMethod::Source(Source::Iter),
+            "iter_mut" => IteratorMethod::Source(Source::IterMut),
+            "into_iter" => IteratorMethod::Source(Source::IntoIter),
+            "chars" => IteratorMethod::Source(Source::Chars),
+            "bytes" => IteratorMethod::Source(Source::Bytes),
+            "split" | "split_whitespace" => IteratorMethod::Source(Source::Split),
+            "keys" => IteratorMethod::Source(Source::Keys),
+            "values" | "values_mut" => IteratorMethod::Source(Source::Values),
+            "map" => IteratorMethod::Adapter(Adapter::Map),
+            "filter" => IteratorMethod::Adapter(Adapter::Filter),
+            "enumerate" => IteratorMethod::Adapter(Adapter::Enumerate),
+            "zip" => IteratorMethod::Adapter(Adapter::Zip),
+            "take" => IteratorMethod::Adapter(Adapter::Take),
+            "skip" => IteratorMethod::Adapter(Adapter::Skip),
+            "step_by" => IteratorMethod::Adapter(Adapter::StepBy),
+            "rev" => IteratorMethod::Adapter(Adapter::Rev),
+            "sum" => IteratorMethod::Consumer(Consumer::Sum),
+            "product" => IteratorMethod::Consumer(Consumer::Product),
+            "count" => IteratorMethod::Consumer(Consumer::Count),
+            "min" => IteratorMethod::Consumer(Consumer::Min),
+            "max" => IteratorMethod::Consumer(Consumer::Max),
+            "collect" => IteratorMethod::Consumer(Consumer::Collect),
+            "fold" => IteratorMethod::Consumer(Consumer::Fold),
+            "any" => IteratorMethod::Consumer(Consumer::Any),
+            "all" => IteratorMethod::Consumer(Consumer::All),
+            "for_each" => IteratorMethod::Consumer(Consumer::ForEach),
+            _ => return None,
+        })
+    }
+}
@@ -1,6 +1,7 @@
 use crate::error_handler::ErrorHandler;
 
 #[derive(Debug, Clone, PartialEq)]
+#[allow(clippy::upper_case_acronyms)]
 pub enum TokenType {
     // Keywords
     Fn,
@@ -11,11 +12,19 @@ pub enum TokenType {
     Else,
     While,
     For,
+    Break,
+    Continue,
+    Match,
     In,
     Vec,  // Add Vec keyword
-    New,  // Add new keyword
-    Push, // Add push method
-    Len,  // Add len method
+    Struct,
+    Enum,
+    Impl,
+    Trait,
+    Dyn,
+    Use,
+    True,
+    False,
     
     // Types
     I32,
@@ -24,6 +33,8 @@ pub enum TokenType {
     Identifier(String),
     IntLiteral(i32),
     StringLiteral(String),
+    CharLiteral(char),
+    Lifetime(String),  // 'a, 'static, '_ : acceptées dans les types puis ignorées
     
     // Opérateurs
     Plus,
@@ -32,12 +43,20 @@ pub enum TokenType {
     Slash,
     Mod,
     Assign,
+    PlusAssign,   // += et les autres affectations composées
+    MinusAssign,
+    StarAssign,
+    SlashAssign,
+    ModAssign,
     Equal,
     NotEqual,
     Less,
     LessEqual,
     Greater,
     GreaterEqual,
+    Ampersand,  // & pour les références
+    Hash,       // # des attributs
+    Pipe,       // | des paramètres de fermeture (`||` : deux Pipe)
     
     // Ponctuation
     LeftParen,
@@ -51,11 +70,14 @@ pub enum TokenType {
     Colon,
     DoubleColon,  // Add :: for Vec::new syntax
     Arrow,
+    FatArrow, // => des bras de `match`
     DotDot,
+    DotDotEq, // ..= : plage inclusive
     Dot,  // Add . for method calls
+    Question,  // ? : propagation d'erreur
     
-    // Macro spécifiques
-    PrintlnMacro,
+    // Nom de macro suivi de `!` (println!, format!...)
+    Macro(String),
     
     // End of file
     EOF,
@@ -69,7 +91,6 @@ pub struct Token {
 
 #[derive(Clone)]
 pub struct Lexer<'a> {
-    source: &'a str,
     chars: Vec<char>,
     position: usize,
     line: usize,
@@ -79,7 +100,6 @@ pub struct Lexer<'a> {
 impl<'a> Lexer<'a> {
     pub fn new(source: &'a str, error_handler: &'a ErrorHandler) -> Self {
         Lexer {
-            source,
             chars: source.chars().collect(),
             position: 0,
             line: 1,
@@ -121,17 +141,50 @@ impl<'a> Lexer<'a> {
         
         match c {
             // Opérateurs simples
-            '+' => Ok(Token { token_type: TokenType::Plus, line: self.line }),
+            '+' => {
+                if self.match_char('=') {
+                    Ok(Token { token_type: TokenType::PlusAssign, line: self.line })
+                } else {
+                    Ok(Token { token_type: TokenType::Plus, line: self.line })
+                }
+            },
             '-' => {
                 if self.match_char('>') {
                     Ok(Token { token_type: TokenType::Arrow, line: self.line })
+                } else if self.match_char('=') {
+                    Ok(Token { token_type: TokenType::MinusAssign, line: self.line })
                 } else {
                     Ok(Token { token_type: TokenType::Minus, line: self.line })
                 }
             },
-            '*' => Ok(Token { token_type: TokenType::Star, line: self.line }),
-            '/' => Ok(Token { token_type: TokenType::Slash, line: self.line }),
-            '%' => Ok(Token { token_type: TokenType::Mod, line: self.line }),
+            '*' => {
+                if self.match_char('=') {
+                    Ok(Token { token_type: TokenType::StarAssign, line: self.line })
+                } else {
+                    Ok(Token { token_type: TokenType::Star, line: self.line })
+                }
+            },
+            '/' => {
+                if self.match_char('=') {
+                    Ok(Token { token_type: TokenType::SlashAssign, line: self.line })
+                } else {
+                    Ok(Token { token_type: TokenType::Slash, line: self.line })
+                }
+            },
+            '%' => {
+                if self.match_char('=') {
+                    Ok(Token { token_type: TokenType::ModAssign, line: self.line })
+                } else {
+                    Ok(Token { token_type: TokenType::Mod, line: self.line })
+                }
+            },
+            '&' => Ok(Token { token_type: TokenType::Ampersand, line: self.line }),
+            '|' => Ok(Token { token_type: TokenType::Pipe, line: self.line }),
+            '#' => {
+                // Attribut `#[..]`, ou `#![..]` pour un attribut interne
+                self.match_char('!');
+                Ok(Token { token_type: TokenType::Hash, line: self.line })
+            },
             
             // Ponctuation
             '(' => Ok(Token { token_type: TokenType::LeftParen, line: self.line }),
@@ -142,6 +195,7 @@ impl<'a> Lexer<'a> {
             ']' => Ok(Token { token_type: TokenType::RightBracket, line: self.line }),
             ',' => Ok(Token { token_type: TokenType::Comma, line: self.line }),
             ';' => Ok(Token { token_type: TokenType::Semicolon, line: self.line }),
+            '?' => Ok(Token { token_type: TokenType::Question, line: self.line }),
             ':' => {
                 if self.position < self.chars.len() && self.chars[self.position] == ':' {
                     self.position += 1; // Consume the second ':'
@@ -155,6 +209,8 @@ impl<'a> Lexer<'a> {
             '=' => {
                 if self.match_char('=') {
                     Ok(Token { token_type: TokenType::Equal, line: self.line })
+                } else if self.match_char('>') {
+                    Ok(Token { token_type: TokenType::FatArrow, line: self.line })
                 } else {
                     Ok(Token { token_type: TokenType::Assign, line: self.line })
                 }
@@ -183,6 +239,7 @@ impl<'a> Lexer<'a> {
             
             // Chaînes de caractères
             '"' => self.string(),
+            '\'' => self.char_literal(),
             
             // Nombres ou identifiants
             '0'..='9' => {
@@ -194,6 +251,10 @@ impl<'a> Lexer<'a> {
             '.' => {
                 if self.position < self.chars.len() && self.chars[self.position] == '.' {
                     self.position += 1;
+                    if self.position < self.chars.len() && self.chars[self.position] == '=' {
+                        self.position += 1;
+                        return Ok(Token { token_type: TokenType::DotDotEq, line: self.line });
+                    }
                     Ok(Token { token_type: TokenType::DotDot, line: self.line })
                 } else {
                     Ok(Token { token_type: TokenType::Dot, line: self.line })
@@ -207,7 +268,10 @@ impl<'a> Lexer<'a> {
             },
             
             // Autres caractères
-            _ => Err(self.line),
+            _ => {
+                self.error_handler.report_error(self.line, &format!("Caractère inattendu: '{}'", c));
+                Err(self.line)
+            },
         }
     }
     
@@ -222,13 +286,9 @@ impl<'a> Lexer<'a> {
                     self.line += 1;
                 },
                 // Commentaires
-                '/' => {
-                    if self.position + 1 < self.chars.len() && self.chars[self.position + 1] == '/' {
-                        while self.position < self.chars.len() && self.chars[self.position] != '\n' {
-                            self.position += 1;
-                        }
-                    } else {
-                        return;
+                '/' if self.position + 1 < self.chars.len() && self.chars[self.position + 1] == '/' => {
+                    while self.position < self.chars.len() && self.chars[self.position] != '\n' {
+                        self.position += 1;
                     }
                 },
                 _ => return,
@@ -246,13 +306,13 @@ impl<'a> Lexer<'a> {
     }
     
     fn string(&mut self) -> Result<Token, usize> {
-        let start = self.position;
+        let mut value = String::new();
         
         while self.position < self.chars.len() && self.chars[self.position] != '"' {
             if self.chars[self.position] == '\n' {
                 self.line += 1;
             }
-            self.position += 1;
+            value.push(self.escaped_char()?);
         }
         
         if self.position >= self.chars.len() {
@@ -262,18 +322,69 @@ impl<'a> Lexer<'a> {
         // Consommer le guillemet fermant
         self.position += 1;
         
-        let value = self.chars[start..self.position - 1].iter().collect();
-        
         Ok(Token {
             token_type: TokenType::StringLiteral(value),
             line: self.line,
         })
     }
     
+    // Caractère littéral : 'a', '\n', 'é'
+    fn char_literal(&mut self) -> Result<Token, usize> {
+        // Durée de vie : 'nom sans apostrophe fermante après le premier caractère
+        let starts_name = |c: &char| c.is_alphabetic() || *c == '_';
+        if self.chars.get(self.position).is_some_and(starts_name) && self.chars.get(self.position + 1) != Some(&'\'') {
+            let start = self.position;
+            while self.chars.get(self.position).is_some_and(|c| c.is_alphanumeric() || *c == '_') {
+                self.position += 1;
+            }
+            return Ok(Token {
+                token_type: TokenType::Lifetime(self.chars[start..self.position].iter().collect()),
+                line: self.line,
+            });
+        }
+        if self.position >= self.chars.len() || self.chars[self.position] == '\'' {
+            self.error_handler.report_error(self.line, "Caractère littéral vide");
+            return Err(self.line);
+        }
+        let value = self.escaped_char()?;
+        if !self.match_char('\'') {
+            self.error_handler.report_error(self.line, "Caractère littéral non terminé");
+            return Err(self.line);
+        }
+        
+        Ok(Token {
+            token_type: TokenType::CharLiteral(value),
+            line: self.line,
+        })
+    }
+    
+    // Lit un caractère d'une chaîne ou d'un caractère littéral en interprétant les échappements
+    fn escaped_char(&mut self) -> Result<char, usize> {
+        let c = self.chars[self.position];
+        self.position += 1;
+        if c != '\\' {
+            return Ok(c);
+        }
+        
+        let escaped = self.chars.get(self.position).copied();
+        self.position += 1;
+        match escaped {
+            Some('n') => Ok('\n'),
+            Some('t') => Ok('\t'),
+            Some('r') => Ok('\r'),
+            Some('0') => Ok('\0'),
+            Some(c @ ('\\' | '\'' | '"')) => Ok(c),
+            _ => {
+                self.error_handler.report_error(self.line, "Séquence d'échappement inconnue");
+                Err(self.line)
+            },
+        }
+    }
+    
     fn number(&mut self) -> Result<Token, usize> {
         let start = self.position;
         
-        while self.position < self.chars.len() && self.chars[self.position].is_digit(10) {
+        while self.position < self.chars.len() && self.chars[self.position].is_ascii_digit() {
             self.position += 1;
         }
         
@@ -305,19 +416,25 @@ impl<'a> Lexer<'a> {
             "if" => TokenType::If,
             "else" => TokenType::Else,
             "while" => TokenType::While,
+            "match" => TokenType::Match,
             "for" => TokenType::For,
+            "break" => TokenType::Break,
+            "continue" => TokenType::Continue,
             "in" => TokenType::In,
             "Vec" => TokenType::Vec,
-            "new" => TokenType::New,
+            "struct" => TokenType::Struct,
+            "enum" => TokenType::Enum,
+            "impl" => TokenType::Impl,
+            "trait" => TokenType::Trait,
+            "dyn" => TokenType::Dyn,
+            "true" => TokenType::True,
+            "false" => TokenType::False,
             "i32" => TokenType::I32,
-            "println" => {
-                // Gérer les macros comme println!
-                if self.position < self.chars.len() && self.chars[self.position] == '!' {
-                    self.position += 1;
-                    TokenType::PrintlnMacro
-                } else {
-                    TokenType::Identifier(text)
-                }
+            "use" => TokenType::Use,
+            // Appel de macro `nom!`, à distinguer de `x != y`
+            _ if self.chars.get(self.position) == Some(&'!') && self.chars.get(self.position + 1) != Some(&'=') => {
+                self.position += 1;
+                TokenType::Macro(text)
             },
             _ => TokenType::Identifier(text),
         };
@@ -0,0 +1,13 @@
+// Passes du compilateur. Le binaire les enchaîne jusqu'à l'exécutable ; les tests d'intégration
+// appellent directement l'analyse (lexer, parser, typage, emprunts), sans assembleur
+pub mod lexer;
+pub mod parser;
+pub mod type_checker;
+pub mod monomorphizer;
+pub mod captures;
+pub mod borrow_checker;
+pub mod iterators;
+pub mod code_generator;
+pub mod runtime;
+pub mod format;
+pub mod error_handler;
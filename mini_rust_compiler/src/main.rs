@@ -1,7 +1,4 @@
-mod lexer;
-mod parser;
-mod code_generator;
-mod error_handler;
+use mini_rust_compiler::{borrow_checker, code_generator, error_handler, lexer, monomorphizer, parser};
 
 use std::env;
 use std::fs;
@@ -15,16 +12,20 @@ fn main() {
     // Récupérer les arguments de ligne de commande
     let args: Vec<String> = env::args().collect();
     
+    // `--release` désactive le compte des allocations et le rapport de fuites de mémoire
+    let release = args.iter().skip(1).any(|arg| arg == "--release");
+    let sources: Vec<&String> = args.iter().skip(1).filter(|arg| *arg != "--release").collect();
+
     // Vérifier que le fichier source est fourni
-    if args.len() < 2 {
-        eprintln!("Usage: {} <fichier.rs>", args[0]);
+    if sources.len() != 1 {
+        eprintln!("Usage: {} [--release] <fichier.rs>", args[0]);
         process::exit(1);
     }
     
     let start_time = Instant::now();
     
     // Lire le fichier source
-    let source_path = &args[1];
+    let source_path = sources[0];
     let source_code = match fs::read_to_string(source_path) {
         Ok(content) => content,
         Err(e) => {
@@ -39,7 +40,7 @@ fn main() {
     let error_handler = error_handler::ErrorHandler::new(source_path.clone());
     
     // Lexer: transformer le code source en tokens
-    println!("Étape 1/3: Analyse lexicale...");
+    println!("Étape 1/5: Analyse lexicale...");
     let mut lexer = lexer::Lexer::new(&source_code, &error_handler);
     let tokens = match lexer.tokenize() {
         Ok(tokens) => tokens,
@@ -50,7 +51,7 @@ fn main() {
     };
     
     // Parser: créer l'arbre syntaxique abstrait
-    println!("Étape 2/3: Analyse syntaxique...");
+    println!("Étape 2/5: Analyse syntaxique...");
     let mut parser = parser::Parser::new(tokens, &error_handler);
     let ast = match parser.parse() {
         Ok(ast) => ast,
@@ -60,9 +61,28 @@ fn main() {
         }
     };
     
+    // Monomorphisation: une instance concrète par utilisation d'un élément générique
+    println!("Étape 3/5: Vérification des types génériques et monomorphisation...");
+    let monomorphizer = monomorphizer::Monomorphizer::new(&error_handler);
+    let ast = match monomorphizer.monomorphize(ast) {
+        Ok(ast) => ast,
+        Err(line) => {
+            error_handler.report_error(line, "Erreur de typage");
+            process::exit(1);
+        }
+    };
+    
+    // Vérification des emprunts : mutabilité, exclusivité de `&mut`, durée de vie des références, déplacements
+    println!("Étape 4/5: Vérification des emprunts et des déplacements...");
+    let borrow_checker = borrow_checker::BorrowChecker::new(&error_handler);
+    if let Err(line) = borrow_checker.check(&ast) {
+        error_handler.report_error(line, "Erreur d'emprunt");
+        process::exit(1);
+    }
+    
     // Générateur de code: produire du code machine à partir de l'AST
-    println!("Étape 3/3: Génération de code et compilation...");
-    let mut code_gen = code_generator::CodeGenerator::new(&error_handler);
+    println!("Étape 5/5: Génération de code et compilation...");
+    let mut code_gen = code_generator::CodeGenerator::new(&error_handler, release);
     let executable_path = match code_gen.generate(ast, source_path) {
         Ok(path) => path,
         Err(line) => {
@@ -0,0 +1,1747 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::error_handler::ErrorHandler;
+use crate::format::{FormatArgs, Piece, Count, Kind};
+use crate::captures;
+use crate::parser::{Program, Function, Stmt, Expr, Literal, Type, ImplBlock, UnaryOp, BinaryOp, Pattern, Closure, MatchArm};
+use crate::type_checker::{TypeChecker, MethodTarget, VARIANTS, PARSE_INT_ERROR};
+use crate::iterators::{IteratorMethod, Adapter, Consumer};
+
+// Profondeur d'instanciation au-delà de laquelle une récursion polymorphe (`f::<T>` appelant
+// `f::<(T,)>`) est refusée, comme la `recursion_limit` par défaut de rustc
+const RECURSION_LIMIT: usize = 128;
+
+// Nombre de nœuds au-delà duquel les arguments d'une instance sont refusés, comme la `type_length_limit`
+// de rustc : une récursion qui double le type à chaque niveau (`f::<(T, T)>`) l'atteint bien avant la
+// limite de profondeur. Plus basse que celle de rustc, chaque instance portant ses types dans son nom décoré
+const TYPE_LENGTH_LIMIT: usize = 1 << 16;
+
+// Corps restant à réécrire dans le programme produit, avec sa profondeur d'instanciation
+enum Pending {
+    Function(usize, usize),
+    Method(usize, usize, usize), // (bloc impl, méthode, profondeur)
+}
+
+// Remplace les fonctions, structures et blocs impl génériques par une instance concrète
+// pour chaque combinaison d'arguments utilisée, avant la génération de code.
+// Les corps génériques sont vérifiés une seule fois, leurs paramètres restant abstraits.
+pub struct Monomorphizer<'a> {
+    types: TypeChecker<'a>,
+    generic_functions: HashMap<String, Function>,
+    generic_impls: Vec<ImplBlock>,
+    generic_methods: HashMap<(String, String), (Function, usize)>, // (type, méthode) -> (méthode générique, bloc impl produit)
+    instantiated: HashSet<String>,
+    pending: Vec<Pending>,
+    output: Program,
+    return_type: Type,
+    depth: usize,                     // Profondeur d'instanciation du corps en cours de réécriture
+    vec_holes: usize,                 // Types à déduire (`let v = Vec::new();`, `let x = None;`) créés jusqu'ici
+    inferred: HashMap<String, Type>,  // Trou -> type d'élément déduit d'un push ou d'un type attendu
+    integer_holes: HashSet<String>,   // Trous des éléments de `vec![1, 2]` ou `vec![0; n]` : un type entier
+}
+
+impl<'a> Monomorphizer<'a> {
+    pub fn new(error_handler: &'a ErrorHandler) -> Self {
+        Monomorphizer {
+            types: TypeChecker::new(error_handler),
+            generic_functions: HashMap::new(),
+            generic_impls: Vec::new(),
+            generic_methods: HashMap::new(),
+            instantiated: HashSet::new(),
+            pending: Vec::new(),
+            output: Program {
+                functions: Vec::new(),
+                structs: Vec::new(),
+                enums: Vec::new(),
+                traits: Vec::new(),
+                impls: Vec::new(),
+            },
+            return_type: Type::Void,
+            depth: 0,
+            vec_holes: 0,
+            inferred: HashMap::new(),
+            integer_holes: HashSet::new(),
+        }
+    }
+
+    pub fn monomorphize(mut self, program: Program) -> Result<Program, usize> {
+        self.types.collect_declarations(&program)?;
+        self.check_generic_bodies(&program)?;
+
+        self.output.traits = program.traits.clone();
+
+        for function in program.functions {
+            if function.type_params.is_empty() {
+                self.output.functions.push(function);
+                self.pending.push(Pending::Function(self.output.functions.len() - 1, 0));
+            } else {
+                self.generic_functions.insert(function.name.clone(), function);
+            }
+        }
+
+        for impl_block in program.impls {
+            if impl_block.type_params.is_empty() && impl_block.type_args.is_empty() {
+                self.add_impl(impl_block)?;
+            } else {
+                self.generic_impls.push(impl_block);
+            }
+        }
+
+        // Structures non génériques : leurs champs peuvent utiliser des instances (Pair<i32>)
+        for struct_def in program.structs.into_iter().filter(|s| s.type_params.is_empty()) {
+            let mut struct_def = struct_def;
+            self.types.current_line = struct_def.line;
+            for (_, field_type) in &mut struct_def.fields {
+                *field_type = self.concretize(field_type)?;
+            }
+            self.types.structs.insert(struct_def.name.clone(), struct_def.clone());
+            self.output.structs.push(struct_def);
+        }
+        for mut enum_def in program.enums {
+            self.types.current_line = enum_def.line;
+            for field_type in enum_def.variants.iter_mut().flat_map(|(_, fields)| fields) {
+                *field_type = self.concretize(field_type)?;
+            }
+            self.types.enums.insert(enum_def.name.clone(), enum_def.clone());
+            self.output.enums.push(enum_def);
+        }
+
+        self.concretize_signatures()?;
+
+        while let Some(pending) = self.pending.pop() {
+            match pending {
+                Pending::Function(index, depth) => {
+                    self.depth = depth;
+                    let function = self.output.functions[index].clone();
+                    self.output.functions[index] = self.rewrite_function(function, None)?;
+                },
+                Pending::Method(impl_index, method_index, depth) => {
+                    self.depth = depth;
+                    let impl_block = &self.output.impls[impl_index];
+                    let function = impl_block.methods[method_index].clone();
+                    let self_type = self.types.type_from_name(&impl_block.type_name);
+                    self.output.impls[impl_index].methods[method_index] = self.rewrite_function(function, self_type)?;
+                },
+            }
+        }
+
+        Ok(self.output)
+    }
+
+    // Vérification unique des corps génériques, avec les seules opérations permises par les contraintes
+    fn check_generic_bodies(&mut self, program: &Program) -> Result<(), usize> {
+        for function in program.functions.iter().filter(|f| !f.type_params.is_empty()) {
+            self.types.check_generic_function(function, function.type_params.clone(), None)?;
+        }
+
+        for impl_block in &program.impls {
+            let is_generic = !impl_block.type_params.is_empty() || !impl_block.type_args.is_empty();
+            self.types.type_params = impl_block.type_params.clone();
+            let self_type = self.types.impl_type(impl_block);
+            self.types.type_params.clear();
+            let self_type = self_type?;
+
+            for method in &impl_block.methods {
+                if !is_generic && method.type_params.is_empty() {
+                    continue;
+                }
+                if impl_block.trait_name.is_some() && !method.type_params.is_empty() {
+                    self.types.current_line = method.line;
+                    return self.types.error(&format!("les méthodes génériques de trait ne sont pas prises en charge: {}", method.name));
+                }
+                let mut type_params = impl_block.type_params.clone();
+                type_params.extend(method.type_params.iter().cloned());
+                self.types.check_generic_function(method, type_params, Some(self_type.clone()))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // Ajoute un bloc impl concret au programme produit ; ses méthodes par défaut y sont recopiées
+    // et ses méthodes génériques attendent d'être instanciées
+    fn add_impl(&mut self, mut impl_block: ImplBlock) -> Result<usize, usize> {
+        if let Some(trait_name) = &impl_block.trait_name {
+            let defaults: Vec<Function> = self.types.traits[trait_name].methods.iter()
+                .filter(|m| m.has_default && !impl_block.methods.iter().any(|f| f.name == m.function.name))
+                .map(|m| m.function.clone())
+                .collect();
+            impl_block.methods.extend(defaults);
+        }
+
+        let index = self.output.impls.len();
+        let (generic, concrete): (Vec<Function>, Vec<Function>) = impl_block.methods.drain(..).partition(|m| !m.type_params.is_empty());
+        for method in generic {
+            self.generic_methods.insert((impl_block.type_name.clone(), method.name.clone()), (method, index));
+        }
+        impl_block.methods = concrete;
+
+        for method_index in 0..impl_block.methods.len() {
+            self.pending.push(Pending::Method(index, method_index, self.depth + 1));
+        }
+        self.output.impls.push(impl_block);
+        Ok(index)
+    }
+
+    // Les signatures déjà enregistrées désignent les instances par leur nom décoré
+    fn concretize_signatures(&mut self) -> Result<(), usize> {
+        let names: Vec<String> = self.types.functions.iter()
+            .filter(|(_, sig)| sig.type_params.is_empty())
+            .map(|(name, _)| name.clone())
+            .collect();
+        for name in names {
+            let mut sig = self.types.functions[&name].clone();
+            for param in &mut sig.params {
+                *param = self.concretize(param)?;
+            }
+            sig.return_type = self.concretize(&sig.return_type)?;
+            self.types.functions.insert(name, sig);
+        }
+
+        let keys: Vec<(String, String)> = self.types.methods.iter()
+            .filter(|(_, info)| info.sig.type_params.is_empty())
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in keys {
+            let mut info = self.types.methods[&key].clone();
+            for param in &mut info.sig.params {
+                *param = self.concretize(param)?;
+            }
+            info.sig.return_type = self.concretize(&info.sig.return_type)?;
+            self.types.methods.insert(key, info);
+        }
+
+        Ok(())
+    }
+
+    // Nom décoré d'une instance : le nombre d'arguments précède leurs noms (Pair<i32> -> Pair_1_i32)
+    fn mangle(name: &str, args: &[Type]) -> String {
+        let args: Vec<String> = args.iter().map(Self::mangle_type).collect();
+        format!("{}_{}_{}", name, args.len(), args.join("_"))
+    }
+
+    fn mangle_type(typ: &Type) -> String {
+        match typ {
+            Type::Generic(name, args) => Self::mangle(name, args),
+            Type::Ref(inner, false) => format!("ref_{}", Self::mangle_type(inner)),
+            Type::Ref(inner, true) => format!("refmut_{}", Self::mangle_type(inner)),
+            Type::Box(inner) => Self::mangle("Box", std::slice::from_ref(inner)),
+            Type::Rc(inner) => Self::mangle("Rc", std::slice::from_ref(inner)),
+            Type::RefCell(inner) => Self::mangle("RefCell", std::slice::from_ref(inner)),
+            Type::Guard(inner, false) => Self::mangle("Ref", std::slice::from_ref(inner)),
+            Type::Guard(inner, true) => Self::mangle("RefMut", std::slice::from_ref(inner)),
+            Type::Vec(inner) => Self::mangle("Vec", std::slice::from_ref(inner)),
+            Type::HashMap(key, value) => Self::mangle("HashMap", &[(**key).clone(), (**value).clone()]),
+            Type::HashSet(element) => Self::mangle("HashSet", std::slice::from_ref(element)),
+            Type::Entry(key, value) => Self::mangle("Entry", &[(**key).clone(), (**value).clone()]),
+            Type::Option(inner) => Self::mangle("Option", std::slice::from_ref(inner)),
+            Type::Result(ok, err) => Self::mangle("Result", &[(**ok).clone(), (**err).clone()]),
+            Type::Dyn(trait_name) => format!("dyn_{}", trait_name),
+            Type::Tuple(elements) => Self::mangle("tuple", elements),
+            Type::Array(element, length) => format!("array_{}_{}", length, Self::mangle_type(element)),
+            Type::Slice(element) => format!("slice_{}", Self::mangle_type(element)),
+            Type::FnPtr(params, ret) => format!("fn_{}", Self::mangle("sig", &[Type::Tuple(params.clone()), (**ret).clone()])),
+            Type::Closure(params, ret) => format!("closure_{}", Self::mangle("sig", &[Type::Tuple(params.clone()), (**ret).clone()])),
+            Type::Void => "unit".to_string(),
+            _ => typ.to_string(),
+        }
+    }
+
+    // Remplace chaque structure générique appliquée par son instance
+    fn concretize(&mut self, typ: &Type) -> Result<Type, usize> {
+        match typ {
+            Type::Generic(name, args) => {
+                let args = args.iter().map(|arg| self.concretize(arg)).collect::<Result<Vec<_>, _>>()?;
+                Ok(Type::Named(self.instantiate_struct(name, args)?))
+            },
+            Type::Named(name) if name == "_" => self.types.error("annotations de type nécessaires : impossible de déduire un paramètre générique"),
+            Type::Ref(inner, mutable) => Ok(Type::Ref(Box::new(self.concretize(inner)?), *mutable)),
+            Type::Box(inner) => Ok(Type::Box(Box::new(self.concretize(inner)?))),
+            Type::Rc(inner) => Ok(Type::Rc(Box::new(self.concretize(inner)?))),
+            Type::RefCell(inner) => Ok(Type::RefCell(Box::new(self.concretize(inner)?))),
+            Type::Guard(inner, mutable) => Ok(Type::Guard(Box::new(self.concretize(inner)?), *mutable)),
+            Type::Vec(inner) => Ok(Type::Vec(Box::new(self.concretize(inner)?))),
+            Type::HashMap(key, value) => Ok(Type::HashMap(Box::new(self.concretize(key)?), Box::new(self.concretize(value)?))),
+            Type::HashSet(element) => Ok(Type::HashSet(Box::new(self.concretize(element)?))),
+            Type::Entry(key, value) => Ok(Type::Entry(Box::new(self.concretize(key)?), Box::new(self.concretize(value)?))),
+            Type::Option(inner) => Ok(Type::Option(Box::new(self.concretize(inner)?))),
+            Type::Result(ok, err) => Ok(Type::Result(Box::new(self.concretize(ok)?), Box::new(self.concretize(err)?))),
+            Type::Tuple(elements) => Ok(Type::Tuple(elements.iter().map(|element| self.concretize(element)).collect::<Result<Vec<_>, _>>()?)),
+            Type::Array(element, length) => Ok(Type::Array(Box::new(self.concretize(element)?), *length)),
+            Type::Slice(element) => Ok(Type::Slice(Box::new(self.concretize(element)?))),
+            Type::FnPtr(params, ret) | Type::Closure(params, ret) => {
+                let params = params.iter().map(|param| self.concretize(param)).collect::<Result<Vec<_>, _>>()?;
+                let ret = Box::new(self.concretize(ret)?);
+                Ok(if matches!(typ, Type::FnPtr(_, _)) { Type::FnPtr(params, ret) } else { Type::Closure(params, ret) })
+            },
+            _ => Ok(typ.clone()),
+        }
+    }
+
+    fn instantiate_struct(&mut self, name: &str, args: Vec<Type>) -> Result<String, usize> {
+        let mangled = Self::mangle(name, &args);
+        if !self.instantiated.insert(mangled.clone()) {
+            return Ok(mangled);
+        }
+        self.check_depth(name, &args)?;
+
+        let def = self.types.structs[name].clone();
+        let subst: HashMap<String, Type> = def.type_params.iter().map(|param| param.name.clone()).zip(args.iter().cloned()).collect();
+        self.types.instances.insert(mangled.clone(), (name.to_string(), args.clone()));
+
+        let mut instance = def.clone();
+        instance.name = mangled.clone();
+        instance.type_params.clear();
+        instance.instance_of = Some((name.to_string(), args.clone()));
+        for (_, field_type) in &mut instance.fields {
+            *field_type = self.concretize(&TypeChecker::substitute(field_type, &subst))?;
+        }
+        // #[derive(Trait)] vaut `impl<T: Trait> Trait for S<T>` : l'instance n'a le trait que si ses arguments l'ont
+        instance.derives.retain(|trait_name| args.iter().all(|arg| self.types.satisfies(arg, trait_name)));
+        for trait_name in &instance.derives {
+            self.types.trait_impls.insert((mangled.clone(), trait_name.clone()));
+        }
+        self.types.structs.insert(mangled.clone(), instance.clone());
+        self.output.structs.push(instance);
+
+        // Blocs impl qui s'appliquent à cette instance (impl<T> Pair<T>, impl Pair<i32>, impl<T: Trait> ...)
+        for impl_block in self.generic_impls.clone() {
+            if impl_block.type_name != name || impl_block.type_args.len() != args.len() {
+                continue;
+            }
+
+            let mut impl_subst = HashMap::new();
+            let matches = impl_block.type_args.iter().zip(&args).all(|(pattern, arg)| {
+                self.types.unify(pattern, arg, &impl_block.type_params, &mut impl_subst).is_ok()
+                    && TypeChecker::substitute(pattern, &impl_subst) == *arg
+            });
+            if !matches {
+                continue;
+            }
+
+            if let Some(param) = impl_block.type_params.iter().find(|p| !impl_subst.contains_key(&p.name)) {
+                self.types.current_line = impl_block.line;
+                return self.types.error(&format!("le paramètre générique `{}` n'est pas contraint par le type du bloc impl", param.name));
+            }
+
+            // Un bloc impl conditionnel ne s'applique que si les contraintes sont satisfaites
+            let applies = impl_block.type_params.iter().all(|param| {
+                param.bounds.iter().all(|bound| self.types.satisfies(&impl_subst[&param.name], bound))
+            });
+            if !applies {
+                continue;
+            }
+
+            let mut instance_impl = impl_block.clone();
+            instance_impl.type_params.clear();
+            instance_impl.type_args.clear();
+            instance_impl.type_name = mangled.clone();
+            if let Some(trait_name) = &impl_block.trait_name {
+                for declared in &self.types.traits[trait_name].methods {
+                    if declared.has_default && !instance_impl.methods.iter().any(|m| m.name == declared.function.name) {
+                        instance_impl.methods.push(declared.function.clone());
+                    }
+                }
+            }
+            instance_impl.methods = instance_impl.methods.iter().map(|m| Self::substitute_function(m, &impl_subst)).collect();
+
+            self.types.register_impl(&instance_impl)?;
+            self.add_impl(instance_impl)?;
+        }
+
+        Ok(mangled)
+    }
+
+    fn instantiate_function(&mut self, name: &str, args: &[Type]) -> Result<String, usize> {
+        let label = Self::mangle(name, args);
+        if !self.instantiated.insert(label.clone()) {
+            return Ok(label);
+        }
+        self.check_depth(name, args)?;
+
+        let generic = self.generic_functions[name].clone();
+        let subst: HashMap<String, Type> = generic.type_params.iter().map(|param| param.name.clone()).zip(args.iter().cloned()).collect();
+        let mut instance = Self::substitute_function(&generic, &subst);
+        instance.name = label.clone();
+        instance.type_params.clear();
+
+        let sig = crate::type_checker::FnSig {
+            label: label.clone(),
+            type_params: Vec::new(),
+            params: instance.params.iter().map(|(_, typ)| self.concretize(typ)).collect::<Result<Vec<_>, _>>()?,
+            return_type: self.concretize(&instance.return_type.clone().unwrap_or(Type::Void))?,
+        };
+        self.types.functions.insert(label.clone(), sig);
+
+        self.output.functions.push(instance);
+        self.pending.push(Pending::Function(self.output.functions.len() - 1, self.depth + 1));
+        Ok(label)
+    }
+
+    // Une instance créée depuis un corps déjà trop profondément instancié, ou dont les arguments sont
+    // démesurés, signale une récursion sans fin
+    fn check_depth(&self, name: &str, args: &[Type]) -> Result<(), usize> {
+        let length: usize = args.iter().map(|arg| self.type_length(arg)).sum();
+        if length > TYPE_LENGTH_LIMIT {
+            return self.types.error(&format!(
+                "limite de taille des types ({} nœuds) dépassée lors de l'instanciation de `{}` : {} nœuds", TYPE_LENGTH_LIMIT, name, length));
+        }
+        if self.depth < RECURSION_LIMIT {
+            return Ok(());
+        }
+        let args: Vec<String> = args.iter().map(|arg| self.types.display_type(arg)).collect();
+        self.types.error(&format!(
+            "limite de récursion ({}) atteinte lors de l'instanciation de `{}::<{}>`", RECURSION_LIMIT, name, args.join(", ")))
+    }
+
+    // Nombre de nœuds d'un type, arguments des instances de structures génériques compris
+    fn type_length(&self, typ: &Type) -> usize {
+        let sum = |types: &[Type]| types.iter().map(|typ| self.type_length(typ)).sum::<usize>();
+        1 + match typ {
+            Type::Named(name) => self.types.instances.get(name).map_or(0, |(_, args)| sum(args)),
+            Type::Generic(_, args) | Type::Tuple(args) => sum(args),
+            Type::Ref(inner, _) | Type::Box(inner) | Type::Vec(inner) | Type::Option(inner) | Type::Array(inner, _) | Type::Slice(inner)
+            | Type::Iterator(inner) | Type::Range(inner, _) | Type::Rc(inner) | Type::RefCell(inner) | Type::Guard(inner, _)
+            | Type::HashSet(inner) => self.type_length(inner),
+            Type::Result(ok, err) | Type::HashMap(ok, err) | Type::Entry(ok, err) => self.type_length(ok) + self.type_length(err),
+            Type::FnPtr(params, ret) | Type::Closure(params, ret) => sum(params) + self.type_length(ret),
+            _ => 0,
+        }
+    }
+
+    // Instance d'une méthode ayant ses propres paramètres génériques (fn map<U>(&self, ...))
+    fn instantiate_method(&mut self, type_name: &str, method: &str, args: &[Type]) -> Result<String, usize> {
+        let mangled = Self::mangle(method, args);
+        if !self.instantiated.insert(format!("{}::{}", type_name, mangled)) {
+            return Ok(mangled);
+        }
+        self.check_depth(&format!("{}::{}", type_name, method), args)?;
+
+        let (generic, impl_index) = match self.generic_methods.get(&(type_name.to_string(), method.to_string())) {
+            Some(found) => found.clone(),
+            None => return self.types.error(&format!("Méthode générique inconnue: {}::{}", type_name, method)),
+        };
+        let subst: HashMap<String, Type> = generic.type_params.iter().map(|param| param.name.clone()).zip(args.iter().cloned()).collect();
+        let mut instance = Self::substitute_function(&generic, &subst);
+        instance.name = mangled.clone();
+        instance.type_params.clear();
+
+        self.types.register_impl(&ImplBlock {
+            type_params: Vec::new(),
+            trait_name: None,
+            type_name: type_name.to_string(),
+            type_args: Vec::new(),
+            methods: vec![instance.clone()],
+            line: instance.line,
+        })?;
+        let key = (type_name.to_string(), mangled.clone());
+        let mut info = self.types.methods[&key].clone();
+        for param in &mut info.sig.params {
+            *param = self.concretize(param)?;
+        }
+        info.sig.return_type = self.concretize(&info.sig.return_type)?;
+        self.types.methods.insert(key, info);
+
+        let methods = &mut self.output.impls[impl_index].methods;
+        methods.push(instance);
+        self.pending.push(Pending::Method(impl_index, methods.len() - 1, self.depth + 1));
+        Ok(mangled)
+    }
+
+    // Copie d'une fonction générique où chaque paramètre est remplacé par son argument concret
+    fn substitute_function(function: &Function, subst: &HashMap<String, Type>) -> Function {
+        let mut function = function.clone();
+        for (_, typ) in &mut function.params {
+            *typ = TypeChecker::substitute(typ, subst);
+        }
+        function.return_type = function.return_type.map(|typ| TypeChecker::substitute(&typ, subst));
+        function.body = function.body.iter().map(|stmt| Self::substitute_stmt(stmt, subst)).collect();
+        function
+    }
+
+    fn substitute_stmt(stmt: &Stmt, subst: &HashMap<String, Type>) -> Stmt {
+        let expr = |e: &Expr| Self::substitute_expr(e, subst);
+        let boxed = |s: &Stmt| Box::new(Self::substitute_stmt(s, subst));
+
+        match stmt {
+            Stmt::Let(pattern, init, var_type, line) => Stmt::Let(
+                pattern.clone(),
+                init.as_ref().map(expr),
+                var_type.as_ref().map(|typ| TypeChecker::substitute(typ, subst)),
+                *line,
+            ),
+            Stmt::Expression(e, line) => Stmt::Expression(expr(e), *line),
+            Stmt::Return(e, line) => Stmt::Return(e.as_ref().map(expr), *line),
+            Stmt::Break(_) | Stmt::Continue(_) => stmt.clone(),
+            Stmt::Print(format, stream, line) => Stmt::Print(Self::substitute_format(format, subst), *stream, *line),
+            Stmt::Block(stmts) => Stmt::Block(stmts.iter().map(|s| Self::substitute_stmt(s, subst)).collect()),
+            Stmt::If(condition, then_branch, else_branch, line) => Stmt::If(
+                expr(condition),
+                boxed(then_branch),
+                else_branch.as_ref().map(|s| boxed(s)),
+                *line,
+            ),
+            Stmt::While(condition, body, line) => Stmt::While(expr(condition), boxed(body), *line),
+            Stmt::Match(value, arms, line) => {
+                let arms = arms.iter().map(|arm| MatchArm {
+                    pattern: arm.pattern.clone(),
+                    guard: arm.guard.as_ref().map(expr),
+                    body: Self::substitute_stmt(&arm.body, subst),
+                }).collect();
+                Stmt::Match(expr(value), arms, *line)
+            },
+            Stmt::For(pattern, iterable, body, line) => Stmt::For(pattern.clone(), expr(iterable), boxed(body), *line),
+        }
+    }
+
+    fn substitute_expr(expr: &Expr, subst: &HashMap<String, Type>) -> Expr {
+        let sub = |e: &Expr| Self::substitute_expr(e, subst);
+        let boxed = |e: &Expr| Box::new(Self::substitute_expr(e, subst));
+
+        match expr {
+            Expr::FunctionCall(name, type_args, args) => {
+                // T::f() désigne la fonction associée du type concret
+                let name = match name.split_once("::") {
+                    Some((owner, member)) if subst.contains_key(owner) => format!("{}::{}", TypeChecker::type_key(&subst[owner]), member),
+                    _ => name.clone(),
+                };
+                Expr::FunctionCall(
+                    name,
+                    type_args.iter().map(|typ| TypeChecker::substitute(typ, subst)).collect(),
+                    args.iter().map(sub).collect(),
+                )
+            },
+            Expr::Binary(left, op, right) => Expr::Binary(boxed(left), op.clone(), boxed(right)),
+            Expr::Unary(op, operand) => Expr::Unary(op.clone(), boxed(operand)),
+            Expr::Literal(Literal::Array(elements)) => Expr::Literal(Literal::Array(elements.iter().map(sub).collect())),
+            Expr::Literal(Literal::ArrayRepeat(value, length)) => Expr::Literal(Literal::ArrayRepeat(boxed(value), *length)),
+            Expr::Range(start, end, inclusive) => Expr::Range(start.as_deref().map(boxed), end.as_deref().map(boxed), *inclusive),
+            Expr::VecNew(elements) => Expr::VecNew(elements.iter().map(sub).collect()),
+            Expr::VecRepeat(value, count) => Expr::VecRepeat(boxed(value), boxed(count)),
+            Expr::Index(base, index) => Expr::Index(boxed(base), boxed(index)),
+            Expr::MethodCall(obj_expr, method, type_args, args) => {
+                Expr::MethodCall(boxed(obj_expr), method.clone(), type_args.iter().map(|typ| TypeChecker::substitute(typ, subst)).collect(), args.iter().map(sub).collect())
+            },
+            Expr::FieldAccess(base, field) => Expr::FieldAccess(boxed(base), field.clone()),
+            Expr::TupleIndex(base, index) => Expr::TupleIndex(boxed(base), *index),
+            Expr::Tuple(elements) => Expr::Tuple(elements.iter().map(sub).collect()),
+            Expr::StructLiteral(name, fields) => Expr::StructLiteral(name.clone(), fields.iter().map(|(f, e)| (f.clone(), sub(e))).collect()),
+            Expr::Assign(place, value) => Expr::Assign(boxed(place), boxed(value)),
+            Expr::CompoundAssign(place, op, value) => Expr::CompoundAssign(boxed(place), op.clone(), boxed(value)),
+            Expr::Try(operand) => Expr::Try(boxed(operand)),
+            Expr::Format(format) => Expr::Format(Self::substitute_format(format, subst)),
+            Expr::Write(destination, format) => Expr::Write(boxed(destination), Self::substitute_format(format, subst)),
+            Expr::Closure(closure) => {
+                let mut closure = closure.clone();
+                for (_, typ) in closure.params.iter_mut() {
+                    *typ = typ.as_ref().map(|typ| TypeChecker::substitute(typ, subst));
+                }
+                closure.return_type = closure.return_type.map(|typ| TypeChecker::substitute(&typ, subst));
+                closure.body = closure.body.iter().map(|stmt| Self::substitute_stmt(stmt, subst)).collect();
+                Expr::Closure(closure)
+            },
+            Expr::Call(callee, args) => Expr::Call(boxed(callee), args.iter().map(sub).collect()),
+            // T::f utilisée comme valeur désigne la fonction associée du type concret
+            Expr::Variable(name) => match name.split_once("::") {
+                Some((owner, member)) if subst.contains_key(owner) => Expr::Variable(format!("{}::{}", TypeChecker::type_key(&subst[owner]), member)),
+                _ => expr.clone(),
+            },
+            Expr::Literal(_) => expr.clone(),
+        }
+    }
+
+    fn substitute_format(format: &FormatArgs, subst: &HashMap<String, Type>) -> FormatArgs {
+        FormatArgs { pieces: format.pieces.clone(), args: format.args.iter().map(|arg| Self::substitute_expr(arg, subst)).collect() }
+    }
+
+    // Réécrit un corps concret : chaque usage d'un élément générique désigne désormais son instance
+    fn rewrite_function(&mut self, mut function: Function, self_type: Option<Type>) -> Result<Function, usize> {
+        self.types.self_type = self_type;
+        self.types.variables.clear();
+        self.types.current_line = function.line;
+
+        for (name, typ) in &mut function.params {
+            *typ = self.concretize(typ)?;
+            let resolved = self.types.resolve_type(typ);
+            self.types.variables.insert(name.clone(), resolved);
+        }
+        if let Some(return_type) = &function.return_type {
+            function.return_type = Some(self.concretize(return_type)?);
+        }
+        self.return_type = self.types.resolve_type(&function.return_type.clone().unwrap_or(Type::Void));
+
+        let first_hole = self.vec_holes;
+        let body = std::mem::take(&mut function.body);
+        function.body = body.iter().map(|stmt| self.rewrite_statement(stmt)).collect::<Result<Vec<_>, _>>()?;
+
+        // Les annotations `Vec<_n>` ajoutées aux `let` reçoivent le type d'élément déduit
+        if self.vec_holes > first_hole {
+            let subst: HashMap<String, Type> = (first_hole..self.vec_holes)
+                .map(|n| (Self::hole_name(n), self.resolve_hole(&Self::hole_name(n))))
+                .collect();
+            function.body = function.body.iter().map(|stmt| Self::substitute_stmt(stmt, &subst)).collect();
+        }
+
+        self.types.self_type = None;
+        Ok(function)
+    }
+
+    fn rewrite_statement(&mut self, stmt: &Stmt) -> Result<Stmt, usize> {
+        Ok(match stmt {
+            Stmt::Let(pattern, initializer, var_type, line) => {
+                self.types.current_line = *line;
+                let wildcards = var_type.as_ref().is_some_and(|typ| TypeChecker::mentions(typ, "_"));
+                let var_type = match var_type {
+                    Some(var_type) => {
+                        self.types.check_type(var_type, *line)?;
+                        // `let v: Vec<_> = ...` : chaque `_` devient un trou, déduit de l'initialisation
+                        let var_type = self.fill_wildcards(var_type);
+                        Some(self.concretize(&var_type)?)
+                    },
+                    None => None,
+                };
+                // `let v = Vec::new();` : le type des éléments sera déduit de l'utilisation du vecteur,
+                // comme pour `vec![0; n]` ou `vec![1, 2]`, et de même pour les clés et les valeurs d'une table
+                // `let x;` : le type sera déduit de la première affectation
+                let var_type = match (var_type, initializer) {
+                    (None, Some(init)) => self.open_type(init),
+                    (None, None) if matches!(pattern, Pattern::Binding(_, _)) => Some(self.new_hole()),
+                    (var_type, _) => var_type,
+                };
+                let expected = var_type.as_ref().map(|typ| self.types.resolve_type(typ));
+                let initializer = match initializer {
+                    Some(init) => Some(self.rewrite_expr(init, expected.as_ref())?),
+                    None => None,
+                };
+
+                if let (true, Some(typ), Some(init)) = (wildcards, &expected, &initializer) {
+                    let init_type = self.types.expr_type(init)?;
+                    self.infer_holes(typ, &init_type);
+                }
+                if let (Some(typ), Some(init)) = (&expected, &initializer) {
+                    self.check_coercion(init, typ)?;
+                }
+                let declared = match (&expected, &initializer) {
+                    (Some(typ), _) => TypeChecker::substitute(typ, &self.inferred),
+                    (None, Some(init)) => {
+                        let init_type = self.types.expr_type(init)?;
+                        self.concretize(&init_type)?
+                    },
+                    (None, None) => Type::I32,
+                };
+                if let Type::Iterator(_) = declared {
+                    return self.types.error(
+                        "un itérateur ne peut pas être rangé dans une variable : consommez-le dans une boucle `for` ou par sum(), collect()...");
+                }
+                self.types.bind_pattern(pattern, &declared)?;
+                Stmt::Let(pattern.clone(), initializer, var_type, *line)
+            },
+            Stmt::Expression(expr, line) => {
+                self.types.current_line = *line;
+                Stmt::Expression(self.rewrite_expr(expr, None)?, *line)
+            },
+            Stmt::Return(expr, line) => {
+                self.types.current_line = *line;
+                let expected = self.return_type.clone();
+                let expr = match expr {
+                    Some(expr) => Some(self.rewrite_expr(expr, Some(&expected))?),
+                    None => None,
+                };
+                // Le type de retour d'une fermeture non annotée se déduit de ses valeurs renvoyées
+                if let Some(expr) = expr.as_ref().filter(|expr| !Self::is_integer_literal(expr)) {
+                    let actual = self.types.expr_type(expr)?;
+                    self.infer_holes(&expected, &actual);
+                }
+                // La dernière expression d'un corps devient un `return`, même suivie d'un `;` : dans une
+                // fonction sans valeur de retour, sa valeur est ignorée
+                if let Some(expr) = expr.as_ref().filter(|_| expected != Type::Void) {
+                    self.check_coercion(expr, &expected)?;
+                }
+                Stmt::Return(expr, *line)
+            },
+            Stmt::Break(_) | Stmt::Continue(_) => stmt.clone(),
+            Stmt::Print(format, stream, line) => {
+                self.types.current_line = *line;
+                Stmt::Print(self.rewrite_format(format)?, *stream, *line)
+            },
+            Stmt::Block(stmts) => {
+                let saved = self.types.variables.clone();
+                let stmts = stmts.iter().map(|stmt| self.rewrite_statement(stmt)).collect::<Result<Vec<_>, _>>();
+                self.restore_variables(saved);
+                Stmt::Block(stmts?)
+            },
+            Stmt::If(condition, then_branch, else_branch, line) => {
+                self.types.current_line = *line;
+                let condition = self.rewrite_expr(condition, None)?;
+                let then_branch = Box::new(self.rewrite_statement(then_branch)?);
+                let else_branch = match else_branch {
+                    Some(else_branch) => Some(Box::new(self.rewrite_statement(else_branch)?)),
+                    None => None,
+                };
+                Stmt::If(condition, then_branch, else_branch, *line)
+            },
+            Stmt::While(condition, body, line) => {
+                self.types.current_line = *line;
+                let condition = self.rewrite_expr(condition, None)?;
+                Stmt::While(condition, Box::new(self.rewrite_statement(body)?), *line)
+            },
+            Stmt::Match(value, arms, line) => {
+                self.types.current_line = *line;
+                let value = self.rewrite_expr(value, None)?;
+                let value_type = self.types.expr_type(&value)?;
+                let value_type = self.concretize(&value_type)?;
+                let arms = arms.iter().map(|arm| self.rewrite_arm(arm, &value_type)).collect::<Result<Vec<_>, _>>()?;
+                self.types.current_line = *line;
+                self.types.check_exhaustive(&arms, &value_type)?;
+                Stmt::Match(value, arms, *line)
+            },
+            Stmt::For(pattern, iterable, body, line) => {
+                self.types.current_line = *line;
+                let iterable = self.rewrite_expr(iterable, None)?;
+                let element_type = self.types.iteration_type(&iterable)?;
+                let saved = self.types.variables.clone();
+                let body = self.types.bind_pattern(pattern, &element_type).and_then(|_| self.rewrite_statement(body));
+                self.restore_variables(saved);
+                Stmt::For(pattern.clone(), iterable, Box::new(body?), *line)
+            },
+        })
+    }
+
+    // Bras d'un `match` : les variables du motif ne sont visibles que dans sa garde et son corps
+    fn rewrite_arm(&mut self, arm: &MatchArm, value_type: &Type) -> Result<MatchArm, usize> {
+        let saved = self.types.variables.clone();
+        let arm = self.types.bind_pattern(&arm.pattern, value_type).and_then(|_| {
+            let guard = match &arm.guard {
+                Some(guard) => Some(self.rewrite_expr(guard, None)?),
+                None => None,
+            };
+            Ok(MatchArm { pattern: arm.pattern.clone(), guard, body: self.rewrite_statement(&arm.body)? })
+        });
+        self.restore_variables(saved);
+        arm
+    }
+
+    // Type attendu pour un argument, s'il ne dépend plus d'aucun paramètre générique
+    fn expected_type(&self, declared: &Type, type_params: &[crate::parser::TypeParam], subst: &HashMap<String, Type>) -> Option<Type> {
+        let typ = self.types.resolve_type(&TypeChecker::substitute(declared, subst));
+        if type_params.iter().any(|param| TypeChecker::mentions(&typ, &param.name)) {
+            None
+        } else {
+            Some(typ)
+        }
+    }
+
+    fn rewrite_args(&mut self, args: &[Expr], params: &[Type]) -> Result<Vec<Expr>, usize> {
+        args.iter().enumerate().map(|(i, arg)| {
+            let expected = params.get(i).map(|typ| self.types.resolve_type(typ));
+            self.rewrite_expr(arg, expected.as_ref())
+        }).collect()
+    }
+
+    // Arguments réécrits d'un appel non générique, comparés aux paramètres de la signature
+    fn check_args(&mut self, callee: &str, args: &[Expr], params: &[Type]) -> Result<(), usize> {
+        if args.len() != params.len() {
+            return self.types.error(&format!("{}() attend {} argument(s), {} fourni(s)", callee, params.len(), args.len()));
+        }
+        for (arg, param) in args.iter().zip(params) {
+            let param = self.types.resolve_type(param);
+            self.check_coercion(arg, &param)?;
+        }
+        Ok(())
+    }
+
+    // Paramètres d'une signature générique une fois ses arguments génériques fixés
+    fn instantiate_params(&mut self, type_params: &[crate::parser::TypeParam], args: &[Type], params: &[Type]) -> Result<Vec<Type>, usize> {
+        let subst: HashMap<String, Type> = type_params.iter().map(|param| param.name.clone()).zip(args.iter().cloned()).collect();
+        params.iter().map(|param| self.concretize(&TypeChecker::substitute(param, &subst))).collect()
+    }
+
+    // Une valeur réécrite doit convenir au type attendu : annotation d'un `let`, paramètre, champ,
+    // type de retour ou place affectée
+    fn check_coercion(&mut self, expr: &Expr, expected: &Type) -> Result<(), usize> {
+        let expected = TypeChecker::substitute(expected, &self.inferred);
+        if self.coerces(expr, &expected)? {
+            return Ok(());
+        }
+        let actual = TypeChecker::substitute(&self.types.expr_type(expr)?, &self.inferred);
+        if let (Type::Ref(target, _) | Type::Box(target), Type::Ref(source, _) | Type::Box(source)) = (&expected, &actual) {
+            if let Type::Dyn(trait_name) = &**target {
+                return self.types.error(&format!("le trait `{}` n'est pas implémenté pour `{}`", trait_name, self.types.display_type(source)));
+            }
+        }
+        self.types.error(&format!("types incompatibles : attendu `{}`, trouvé `{}`",
+            self.types.display_type(&expected), self.types.display_type(&actual)))
+    }
+
+    // Les tuples, tableaux et vec![..] littéraux sont vérifiés élément par élément : chaque littéral
+    // entier y prend le type attendu
+    fn coerces(&self, expr: &Expr, expected: &Type) -> Result<bool, usize> {
+        let all = |this: &Self, elements: &[Expr], expected: &Type| -> Result<bool, usize> {
+            for element in elements {
+                if !this.coerces(element, expected)? {
+                    return Ok(false);
+                }
+            }
+            Ok(true)
+        };
+        match (expr, expected) {
+            (Expr::Tuple(elements), Type::Tuple(types)) if elements.len() == types.len() => {
+                for (element, typ) in elements.iter().zip(types) {
+                    if !self.coerces(element, typ)? {
+                        return Ok(false);
+                    }
+                }
+                return Ok(true);
+            },
+            (Expr::Literal(Literal::Array(elements)), Type::Array(element, _)) | (Expr::VecNew(elements), Type::Vec(element)) => {
+                return all(self, elements, element);
+            },
+            (Expr::Literal(Literal::ArrayRepeat(value, _)), Type::Array(element, _)) | (Expr::VecRepeat(value, _), Type::Vec(element)) => {
+                return self.coerces(value, element);
+            },
+            _ => {},
+        }
+        let actual = TypeChecker::substitute(&self.types.expr_type(expr)?, &self.inferred);
+        // Une variable initialisée par un littéral (`let mut total = 0;`) reste i32 faute d'inférence :
+        // un i32 convient donc à tout type entier attendu, hors des types composés dont il changerait la taille
+        if actual == Type::I32 && TypeChecker::is_integer(expected) {
+            return Ok(true);
+        }
+        Ok(self.types.coerces_to(&actual, expected))
+    }
+
+    // Un littéral tableau doit avoir la longueur du type tableau attendu (`let a: [i32; 3] = [1, 2];`)
+    fn check_array_length(&self, expected: &Type, expected_length: usize, length: usize) -> Result<(), usize> {
+        if length == expected_length {
+            return Ok(());
+        }
+        self.types.error(&format!("types incompatibles : attendu `{}`, trouvé un tableau de {} élément(s)",
+            self.types.display_type(expected), length))
+    }
+
+    // Un opérande gauche scalaire exige un opérande droit de même nature : deux entiers pour
+    // l'arithmétique, deux entiers, booléens ou caractères pour une comparaison (`1 + true` est refusé)
+    fn check_operands(&self, left: &Expr, op: &BinaryOp, right: &Expr) -> Result<(), usize> {
+        let (left_type, right_type) = (self.types.expr_type(left)?, self.types.expr_type(right)?);
+        let (left_scalar, _) = TypeChecker::scalar_pointee(&left_type);
+        let (right_scalar, _) = TypeChecker::scalar_pointee(&right_type);
+        let kind = |typ: &Type| match typ {
+            typ if TypeChecker::is_integer(typ) => Some("entier"),
+            Type::Bool => Some("bool"),
+            Type::Char => Some("char"),
+            _ => None,
+        };
+        let arithmetic = matches!(op, BinaryOp::Add | BinaryOp::Subtract | BinaryOp::Multiply | BinaryOp::Divide | BinaryOp::Modulo);
+        let accepted = match (kind(left_scalar), kind(right_scalar)) {
+            // Chaînes et autres opérandes : vérifiés par le typage de l'opération
+            (None, _) => true,
+            (Some(left_kind), right_kind) if arithmetic && left_kind == "entier" => right_kind == Some("entier"),
+            (Some(_), _) if arithmetic => false,
+            (left_kind, right_kind) => left_kind == right_kind,
+        };
+        if accepted {
+            return Ok(());
+        }
+        let (_, symbol) = TypeChecker::operator_trait(op);
+        self.types.error(&format!("l'opérateur `{}` ne s'applique pas aux types `{}` et `{}`", symbol,
+            self.types.display_type(&left_type), self.types.display_type(&right_type)))
+    }
+
+    // `lieu op= valeur` : un entier et une valeur du même type (ou une référence vers elle), ou
+    // `String += &str`
+    fn check_compound_assign(&mut self, place_type: &Type, op: &BinaryOp, value: &Expr) -> Result<(), usize> {
+        let value_type = self.types.expr_type(value)?;
+        self.infer_holes(place_type, &value_type);
+        if TypeChecker::is_integer(place_type) {
+            if TypeChecker::scalar_pointee(&value_type).0 == place_type {
+                return Ok(());
+            }
+            return self.check_coercion(value, place_type);
+        }
+        if *place_type == Type::String && matches!(op, BinaryOp::Add) {
+            if value_type != Type::String && TypeChecker::string_derefs(&value_type).is_some() {
+                return Ok(());
+            }
+            return self.types.error(&format!("types incompatibles : attendu `&str`, trouvé `{}`", self.types.display_type(&value_type)));
+        }
+        let (_, symbol) = TypeChecker::operator_trait(op);
+        self.types.error(&format!("l'opérateur `{}=` ne s'applique pas au type `{}`", symbol, self.types.display_type(place_type)))
+    }
+
+    // Éléments d'un tableau ou d'un vec![..] : sans type attendu, le premier donne celui des suivants
+    // (`[Some(1), None]`)
+    fn rewrite_elements(&mut self, elements: &[Expr], mut expected: Option<Type>) -> Result<Vec<Expr>, usize> {
+        let mut rewritten = Vec::new();
+        for element in elements {
+            let element = self.rewrite_expr(element, expected.as_ref())?;
+            if expected.is_none() {
+                expected = Some(self.types.expr_type(&element)?).filter(|typ| !TypeChecker::mentions(typ, "_"));
+            }
+            rewritten.push(element);
+        }
+        Ok(rewritten)
+    }
+
+    fn rewrite_format(&mut self, format: &FormatArgs) -> Result<FormatArgs, usize> {
+        let args = format.args.iter().map(|arg| self.rewrite_expr(arg, None)).collect::<Result<Vec<_>, _>>()?;
+        let format = FormatArgs { pieces: format.pieces.clone(), args };
+        self.check_format(&format)?;
+        Ok(format)
+    }
+
+    // Chaque emplacement exige le trait correspondant de son argument ; une référence à un scalaire
+    // est formatée comme la valeur désignée
+    fn check_format(&self, format: &FormatArgs) -> Result<(), usize> {
+        let mut types = Vec::new();
+        for arg in &format.args {
+            let typ = TypeChecker::substitute(&self.types.expr_type(arg)?, &self.inferred);
+            types.push(TypeChecker::scalar_pointee(&typ).0.clone());
+        }
+        for piece in &format.pieces {
+            let Piece::Argument(index, spec) = piece else { continue };
+            for count in [&spec.width, &spec.precision].into_iter().flatten() {
+                if let Count::Argument(count) = count {
+                    if !TypeChecker::is_integer(&types[*count]) {
+                        return self.types.error(&format!(
+                            "la largeur ou la précision doit être un entier, pas `{}`", self.types.display_type(&types[*count])));
+                    }
+                }
+            }
+            let typ = &types[*index];
+            match spec.kind {
+                Kind::Debug => self.types.check_debug(typ, &mut Vec::new())?,
+                Kind::Display if !self.types.satisfies(typ, "Display") => {
+                    let mut pointee = typ;
+                    while let Type::Ref(inner, _) | Type::Box(inner) | Type::Rc(inner) | Type::Guard(inner, _) = pointee {
+                        pointee = inner;
+                    }
+                    let hint = match pointee {
+                        Type::Named(_) => " : implémentez `fmt::Display` pour ce type",
+                        _ => "",
+                    };
+                    return self.types.error(&format!("le type `{}` ne peut pas être affiché avec {{}}{}", self.types.display_type(typ), hint));
+                },
+                kind if !self.types.satisfies(typ, kind.trait_name()) => {
+                    return self.types.error(&format!("le trait `{}` n'est pas implémenté pour `{}`", kind.trait_name(), self.types.display_type(typ)));
+                },
+                _ => {},
+            }
+        }
+        Ok(())
+    }
+
+    fn rewrite_expr(&mut self, expr: &Expr, expected: Option<&Type>) -> Result<Expr, usize> {
+        let boxed = |this: &mut Self, e: &Expr| this.rewrite_expr(e, None).map(Box::new);
+
+        let rewritten = match expr {
+            Expr::Binary(left, op, right) => {
+                let (left, right) = (boxed(self, left)?, boxed(self, right)?);
+                self.check_operands(&left, op, &right)?;
+                Expr::Binary(left, op.clone(), right)
+            },
+            Expr::Unary(op, operand) => Expr::Unary(op.clone(), boxed(self, operand)?),
+            Expr::Literal(Literal::Array(elements)) => {
+                let expected = match expected {
+                    Some(array @ Type::Array(element, length)) => {
+                        self.check_array_length(array, *length, elements.len())?;
+                        Some((**element).clone())
+                    },
+                    _ => None,
+                };
+                Expr::Literal(Literal::Array(self.rewrite_elements(elements, expected)?))
+            },
+            Expr::Literal(Literal::ArrayRepeat(value, length)) => {
+                let expected = match expected {
+                    Some(array @ Type::Array(element, expected_length)) => {
+                        self.check_array_length(array, *expected_length, *length)?;
+                        Some((**element).clone())
+                    },
+                    _ => None,
+                };
+                Expr::Literal(Literal::ArrayRepeat(Box::new(self.rewrite_expr(value, expected.as_ref())?), *length))
+            },
+            Expr::Range(start, end, inclusive) => {
+                // Les bornes d'une plage attendue (`let r: Range<i64> = 0..10;`) en prennent le type
+                let expected = match expected {
+                    Some(Type::Range(element, _)) => Some((**element).clone()),
+                    _ => None,
+                };
+                let mut bound = |bound: &Expr| self.rewrite_expr(bound, expected.as_ref()).map(Box::new);
+                let start = start.as_deref().map(&mut bound).transpose()?;
+                let end = end.as_deref().map(&mut bound).transpose()?;
+                Expr::Range(start, end, *inclusive)
+            },
+            Expr::VecNew(elements) => {
+                // Les éléments de vec![..] prennent le type attendu de ceux du vecteur
+                let expected = match expected {
+                    Some(Type::Vec(element)) => Some((**element).clone()),
+                    _ => None,
+                };
+                Expr::VecNew(self.rewrite_elements(elements, expected)?)
+            },
+            Expr::VecRepeat(value, count) => {
+                let expected = match expected {
+                    Some(Type::Vec(element)) => Some((**element).clone()),
+                    _ => None,
+                };
+                Expr::VecRepeat(Box::new(self.rewrite_expr(value, expected.as_ref())?), boxed(self, count)?)
+            },
+            Expr::Format(format) => Expr::Format(self.rewrite_format(format)?),
+            Expr::Write(destination, format) => Expr::Write(boxed(self, destination)?, self.rewrite_format(format)?),
+            Expr::Index(base, index) => Expr::Index(boxed(self, base)?, boxed(self, index)?),
+            Expr::FieldAccess(base, field) => Expr::FieldAccess(boxed(self, base)?, field.clone()),
+            Expr::TupleIndex(base, index) => Expr::TupleIndex(boxed(self, base)?, *index),
+            Expr::Tuple(elements) => {
+                // Chaque élément reçoit le type attendu correspondant : (Pair { .. }, 1) avec (Pair<i64>, i64)
+                let expected: Vec<Type> = match expected {
+                    Some(Type::Tuple(types)) => types.clone(),
+                    _ => Vec::new(),
+                };
+                Expr::Tuple(self.rewrite_args(elements, &expected)?)
+            },
+            Expr::Assign(place, value) => {
+                let place = self.rewrite_expr(place, None)?;
+                let place_type = self.types.expr_type(&place)?;
+                let value = self.rewrite_expr(value, Some(&place_type))?;
+                let value_type = self.types.expr_type(&value)?;
+                self.infer_holes(&place_type, &value_type);
+                self.check_coercion(&value, &place_type)?;
+                Expr::Assign(Box::new(place), Box::new(value))
+            },
+            Expr::CompoundAssign(place, op, value) => {
+                let place = self.rewrite_expr(place, None)?;
+                let place_type = self.types.expr_type(&place)?;
+                let expected = Some(&place_type).filter(|typ| TypeChecker::is_integer(typ));
+                let value = self.rewrite_expr(value, expected)?;
+                self.check_compound_assign(&place_type, op, &value)?;
+                Expr::CompoundAssign(Box::new(place), op.clone(), Box::new(value))
+            },
+            Expr::Try(operand) => {
+                let operand = self.rewrite_expr(operand, Self::parse_target(operand, expected).as_ref())?;
+                self.check_try(&operand)?;
+                Expr::Try(Box::new(operand))
+            },
+            Expr::StructLiteral(name, fields) => self.rewrite_struct_literal(name, fields, expected)?,
+            Expr::FunctionCall(name, type_args, args) => self.rewrite_call(name, type_args, args, expected)?,
+            Expr::MethodCall(obj_expr, method, type_args, args) => self.rewrite_method_call(obj_expr, method, type_args, args, expected)?,
+            Expr::Closure(closure) => self.rewrite_closure(closure, expected)?,
+            Expr::Call(callee, args) => {
+                let callee = self.rewrite_expr(callee, None)?;
+                self.rewrite_indirect_call(callee, args)?
+            },
+            // Variante sans champ (`List::Nil`) : construite comme les autres, par un appel
+            Expr::Variable(name) if !self.types.variables.contains_key(name)
+                && self.types.enum_variant(name).is_some_and(|(_, _, fields)| fields.is_empty()) => {
+                self.rewrite_call(name, &[], &[], expected)?
+            },
+            Expr::Literal(_) | Expr::Variable(_) => expr.clone(),
+        };
+
+        if let Some(expected) = expected {
+            let actual = self.types.expr_type(&rewritten)?;
+            self.infer_holes(&actual, expected);
+        }
+        Ok(rewritten)
+    }
+
+    // Fermeture : les types de ses paramètres et de son retour viennent des annotations, sinon du type
+    // attendu, sinon de trous déduits de l'usage ; ses captures sont les variables locales englobantes
+    // nommées dans son corps
+    fn rewrite_closure(&mut self, closure: &Closure, expected: Option<&Type>) -> Result<Expr, usize> {
+        let line = self.types.current_line;
+        let (expected_params, expected_return) = match expected {
+            Some(Type::Closure(params, ret) | Type::FnPtr(params, ret)) if params.len() == closure.params.len() => {
+                (params.iter().map(Some).collect(), Some(&**ret))
+            },
+            _ => (vec![None; closure.params.len()], None),
+        };
+        let known = |typ: &&Type| !TypeChecker::mentions(typ, "_");
+
+        let mut params = Vec::new();
+        for ((name, annotation), expected) in closure.params.iter().zip(expected_params) {
+            let typ = match (annotation, expected.filter(known)) {
+                (Some(annotation), _) => {
+                    self.types.check_type(annotation, line)?;
+                    let annotation = self.types.resolve_type(annotation);
+                    self.concretize(&annotation)?
+                },
+                (None, Some(expected)) => expected.clone(),
+                (None, None) => self.new_hole(),
+            };
+            params.push((name.clone(), typ));
+        }
+        let return_type = match (&closure.return_type, expected_return.filter(known)) {
+            (Some(annotation), _) => {
+                self.types.check_type(annotation, line)?;
+                let annotation = self.types.resolve_type(annotation);
+                self.concretize(&annotation)?
+            },
+            (None, Some(expected)) => expected.clone(),
+            (None, None) => self.new_hole(),
+        };
+
+        let captures: Vec<String> = captures::free_variables(closure).into_iter()
+            .filter(|name| self.types.variables.contains_key(name))
+            .collect();
+        if let (Some(Type::FnPtr(_, _)), Some(first)) = (expected, captures.first()) {
+            return self.types.error(&format!(
+                "une fermeture qui capture `{}` ne peut pas être convertie en pointeur de fonction `{}`",
+                first, self.types.display_type(expected.unwrap())));
+        }
+
+        // Le corps voit les variables englobantes (captures) et ses paramètres
+        let saved = self.types.variables.clone();
+        let saved_return = std::mem::replace(&mut self.return_type, return_type.clone());
+        for (name, typ) in &params {
+            self.types.variables.insert(name.clone(), typ.clone());
+        }
+        let body = closure.body.iter().map(|stmt| self.rewrite_statement(stmt)).collect::<Result<Vec<_>, _>>();
+        self.return_type = saved_return;
+        self.restore_variables(saved);
+        self.types.current_line = line;
+
+        // Les trous déjà déduits du corps sont remplacés dans la signature
+        Ok(Expr::Closure(Closure {
+            params: params.into_iter().map(|(name, typ)| (name, Some(TypeChecker::substitute(&typ, &self.inferred)))).collect(),
+            return_type: Some(TypeChecker::substitute(&return_type, &self.inferred)),
+            body: body?,
+            is_move: closure.is_move,
+            captures,
+            line: closure.line,
+        }))
+    }
+
+    // Appel d'une valeur appelable : les arguments reçoivent les types des paramètres, et un paramètre
+    // de fermeture encore inconnu est déduit de l'argument passé
+    fn rewrite_indirect_call(&mut self, callee: Expr, args: &[Expr]) -> Result<Expr, usize> {
+        let callee_type = self.types.expr_type(&callee)?;
+        let (params, _) = self.types.callable_signature(&callee_type)?;
+        let args = self.rewrite_args(args, &params)?;
+        for (param, arg) in params.iter().zip(&args) {
+            if !Self::is_integer_literal(arg) {
+                let arg_type = self.types.expr_type(arg)?;
+                self.infer_holes(param, &arg_type);
+            }
+            self.check_coercion(arg, param)?;
+        }
+        let call = Expr::Call(Box::new(callee), args);
+        self.types.expr_type(&call)?;
+        Ok(call)
+    }
+
+    // `?` renvoie l'échec tel quel : la fonction doit renvoyer une Option pour une Option, et un Result
+    // de même type d'erreur pour un Result (aucune conversion From)
+    fn check_try(&mut self, operand: &Expr) -> Result<(), usize> {
+        let operand_type = self.types.expr_type(operand)?;
+        let return_type = self.return_type.clone();
+        let shown = self.types.display_type(&return_type);
+        match (&operand_type, &return_type) {
+            (Type::Option(_), Type::Option(_)) => Ok(()),
+            (Type::Result(_, err), Type::Result(_, expected)) => {
+                self.infer_holes(err, expected);
+                let err = TypeChecker::substitute(err, &self.inferred);
+                if err != **expected {
+                    return self.types.error(&format!(
+                        "l'opérateur `?` ne convertit pas les erreurs : la fonction renvoie `{}`, mais l'erreur propagée est de type `{}`",
+                        shown, self.types.display_type(&err)));
+                }
+                Ok(())
+            },
+            (Type::Option(_), _) => self.types.error(&format!(
+                "l'opérateur `?` sur une `Option` n'est utilisable que dans une fonction qui renvoie une `Option` (la fonction renvoie `{}`)", shown)),
+            (Type::Result(_, _), _) => self.types.error(&format!(
+                "l'opérateur `?` sur un `Result` n'est utilisable que dans une fonction qui renvoie un `Result` (la fonction renvoie `{}`)", shown)),
+            _ => self.types.expr_type(&Expr::Try(Box::new(operand.clone()))).map(|_| ()),
+        }
+    }
+
+    // Some/None/Ok/Err : les arguments génériques du type construit deviennent explicites, pris du turbofish
+    // ou du type attendu, sinon de la valeur ; ce qui reste inconnu est un trou, déduit de la suite de la fonction
+    fn rewrite_variant(&mut self, name: &str, type_args: &[Type], args: &[Expr], expected: Option<&Type>) -> Result<Expr, usize> {
+        self.types.check_variant(name, type_args, args)?;
+        let mut slots: Vec<Option<Type>> = Vec::new();
+        for typ in type_args {
+            self.types.check_type(typ, self.types.current_line)?;
+            let typ = self.types.resolve_type(typ);
+            slots.push(Some(self.concretize(&typ)?));
+        }
+        if slots.is_empty() {
+            slots = match (name, expected) {
+                ("Some" | "None", Some(Type::Option(inner))) => vec![Some((**inner).clone())],
+                ("Ok" | "Err", Some(Type::Result(ok, err))) => vec![Some((**ok).clone()), Some((**err).clone())],
+                ("Some" | "None", _) => vec![None],
+                _ => vec![None, None],
+            };
+        }
+
+        let payload = if name == "Err" { 1 } else { 0 };
+        let mut rewritten = Vec::new();
+        if let Some(arg) = args.first() {
+            let arg = self.rewrite_expr(arg, slots[payload].as_ref())?;
+            let arg_type = self.types.expr_type(&arg)?;
+            match &slots[payload] {
+                None => slots[payload] = Some(self.concretize(&arg_type)?),
+                // Un littéral entier laisse le type ouvert, comme pour push
+                Some(_) if Self::is_integer_literal(&arg) => {},
+                Some(declared) => {
+                    let declared = declared.clone();
+                    self.infer_holes(&declared, &arg_type);
+                },
+            }
+            rewritten.push(arg);
+        }
+
+        let type_args = slots.into_iter()
+            .map(|slot| slot.unwrap_or_else(|| self.new_hole()))
+            .collect();
+        Ok(Expr::FunctionCall(name.to_string(), type_args, rewritten))
+    }
+
+    // Type à trous d'une collection vide ou de littéraux entiers, éventuellement dans un Box, un Rc ou
+    // un RefCell (`Rc::new(RefCell::new(Vec::new()))`) : ses paramètres seront déduits de l'utilisation
+    fn open_type(&mut self, expr: &Expr) -> Option<Type> {
+        match expr {
+            Expr::VecNew(elements) if elements.iter().all(Self::is_integer_literal) => {
+                let hole = self.new_hole();
+                if !elements.is_empty() {
+                    self.integer_holes.insert(hole.to_string());
+                }
+                Some(Type::Vec(Box::new(hole)))
+            },
+            Expr::VecRepeat(value, _) if Self::is_integer_literal(value) => {
+                let hole = self.new_hole();
+                self.integer_holes.insert(hole.to_string());
+                Some(Type::Vec(Box::new(hole)))
+            },
+            Expr::FunctionCall(name, type_args, _) if name == "HashMap::new" && type_args.is_empty() => {
+                Some(Type::HashMap(Box::new(self.new_hole()), Box::new(self.new_hole())))
+            },
+            Expr::FunctionCall(name, type_args, _) if name == "HashSet::new" && type_args.is_empty() => {
+                Some(Type::HashSet(Box::new(self.new_hole())))
+            },
+            Expr::FunctionCall(name, _, args) if args.len() == 1 => {
+                let inner = Box::new(self.open_type(&args[0])?);
+                match name.as_str() {
+                    "Box::new" => Some(Type::Box(inner)),
+                    "Rc::new" => Some(Type::Rc(inner)),
+                    "RefCell::new" => Some(Type::RefCell(inner)),
+                    _ => None,
+                }
+            },
+            _ => None,
+        }
+    }
+
+    // `Vec::new()`, `HashMap::new()`... éventuellement enveloppé : son type ne dit rien des éléments
+    fn is_empty_collection(expr: &Expr) -> bool {
+        match expr {
+            Expr::VecNew(elements) => elements.is_empty(),
+            Expr::FunctionCall(name, _, args) => match (name.as_str(), args.as_slice()) {
+                ("HashMap::new" | "HashSet::new", []) => true,
+                ("Box::new" | "Rc::new" | "RefCell::new", [arg]) => Self::is_empty_collection(arg),
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    fn is_integer_literal(expr: &Expr) -> bool {
+        match expr {
+            Expr::Literal(Literal::Int(_)) => true,
+            Expr::Unary(UnaryOp::Negate, operand) => matches!(**operand, Expr::Literal(Literal::Int(_))),
+            _ => false,
+        }
+    }
+
+    // Quitte une portée : les types déduits entre-temps s'appliquent aussi aux variables restaurées
+    fn restore_variables(&mut self, saved: HashMap<String, Type>) {
+        self.types.variables = saved;
+        for typ in self.types.variables.values_mut() {
+            *typ = TypeChecker::substitute(typ, &self.inferred);
+        }
+    }
+
+    // Remplace chaque `_` d'une annotation par un nouveau trou
+    fn fill_wildcards(&mut self, typ: &Type) -> Type {
+        let mut fill = |typ: &Type| Box::new(self.fill_wildcards(typ));
+        match typ {
+            Type::Named(name) if name == "_" => self.new_hole(),
+            Type::Ref(inner, mutable) => Type::Ref(fill(inner), *mutable),
+            Type::Box(inner) => Type::Box(fill(inner)),
+            Type::Rc(inner) => Type::Rc(fill(inner)),
+            Type::RefCell(inner) => Type::RefCell(fill(inner)),
+            Type::Vec(inner) => Type::Vec(fill(inner)),
+            Type::HashSet(inner) => Type::HashSet(fill(inner)),
+            Type::HashMap(key, value) => Type::HashMap(fill(key), fill(value)),
+            Type::Option(inner) => Type::Option(fill(inner)),
+            Type::Slice(inner) => Type::Slice(fill(inner)),
+            Type::Array(inner, length) => Type::Array(fill(inner), *length),
+            Type::Result(ok, err) => Type::Result(fill(ok), fill(err)),
+            Type::Tuple(elements) => Type::Tuple(elements.iter().map(|element| self.fill_wildcards(element)).collect()),
+            Type::Generic(name, args) => Type::Generic(name.clone(), args.iter().map(|arg| self.fill_wildcards(arg)).collect()),
+            _ => typ.clone(),
+        }
+    }
+
+    fn hole_name(n: usize) -> String {
+        format!("_{}", n)
+    }
+
+    fn new_hole(&mut self) -> Type {
+        self.vec_holes += 1;
+        Type::Named(Self::hole_name(self.vec_holes - 1))
+    }
+
+    // Type final d'un trou ; i32 par défaut si rien ne l'a déterminé, comme pour les littéraux entiers
+    fn resolve_hole(&self, name: &str) -> Type {
+        self.inferred.get(name).cloned().unwrap_or(Type::I32)
+    }
+
+    // Argument d'une instance générique : les trous encore ouverts y sont fixés à i32, comme à la fin de
+    // la fonction, puisque l'instance exige un type concret (`show(&v)` après `v.push(3)`)
+    fn settle_holes(&mut self, typ: &Type) -> Type {
+        let typ = TypeChecker::substitute(typ, &self.inferred);
+        let open: HashMap<String, Type> = (0..self.vec_holes)
+            .map(Self::hole_name)
+            .filter(|name| TypeChecker::mentions(&typ, name))
+            .map(|name| (name, Type::I32))
+            .collect();
+        if open.is_empty() {
+            return typ;
+        }
+        for var_type in self.types.variables.values_mut() {
+            *var_type = TypeChecker::substitute(var_type, &open);
+        }
+        self.inferred.extend(open.clone());
+        TypeChecker::substitute(&typ, &open)
+    }
+
+    // Complète les trous de `actual` à partir du type attendu, puis met à jour le type des variables
+    fn infer_holes(&mut self, actual: &Type, expected: &Type) {
+        let holes: Vec<crate::parser::TypeParam> = (0..self.vec_holes)
+            .map(Self::hole_name)
+            .filter(|name| !self.inferred.contains_key(name) && TypeChecker::mentions(actual, name))
+            .map(|name| crate::parser::TypeParam { name, bounds: Vec::new() })
+            .collect();
+        if holes.is_empty() {
+            return;
+        }
+
+        let mut subst = HashMap::new();
+        if self.types.unify(actual, expected, &holes, &mut subst).is_err() {
+            return;
+        }
+        // Seuls les types complets sont retenus : un trou ne renvoie jamais vers un autre
+        subst.retain(|_, typ| (0..self.vec_holes).all(|n| !TypeChecker::mentions(typ, &Self::hole_name(n))) && !TypeChecker::mentions(typ, "_"));
+        if subst.is_empty() {
+            return;
+        }
+        for typ in self.types.variables.values_mut() {
+            *typ = TypeChecker::substitute(typ, &subst);
+        }
+        self.inferred.extend(subst);
+    }
+
+    fn rewrite_struct_literal(&mut self, name: &str, fields: &[(String, Expr)], expected: Option<&Type>) -> Result<Expr, usize> {
+        let struct_type = self.types.resolve_type(&Type::Named(name.to_string()));
+        let def = match self.types.structs.get(&TypeChecker::type_key(&struct_type)) {
+            Some(def) => def.clone(),
+            None => return self.types.error(&format!("Structure inconnue: {}", name)),
+        };
+
+        if def.type_params.is_empty() {
+            let mut rewritten = Vec::new();
+            for (field, value) in fields {
+                let declared = def.fields.iter().find(|(f, _)| f == field).map(|(_, typ)| typ.clone());
+                let value = self.rewrite_expr(value, declared.as_ref())?;
+                if let Some(declared) = &declared {
+                    self.check_coercion(&value, declared)?;
+                }
+                rewritten.push((field.clone(), value));
+            }
+            return Ok(Expr::StructLiteral(name.to_string(), rewritten));
+        }
+
+        // Structure générique : arguments déduits du type attendu et des valeurs des champs
+        let mut subst = HashMap::new();
+        if let Some(expected) = expected {
+            let pattern = Type::Generic(def.name.clone(), def.type_params.iter().map(|p| Type::Named(p.name.clone())).collect());
+            let _ = self.types.unify(&pattern, expected, &def.type_params, &mut subst);
+        }
+
+        let mut rewritten = Vec::new();
+        for (field, value) in fields {
+            let declared = def.fields.iter().find(|(f, _)| f == field).map(|(_, typ)| typ.clone());
+            let field_expected = declared.as_ref().and_then(|typ| self.expected_type(typ, &def.type_params, &subst));
+            let value = self.rewrite_expr(value, field_expected.as_ref())?;
+            if let Some(declared) = &declared {
+                // `Vec::new()` n'a pas de type d'élément propre : il prend celui du champ substitué
+                // (`Stack { items: Vec::new() }` dans `fn new() -> Stack<T>`), sinon il ne fixe aucun paramètre
+                let value_type = match (&value, &field_expected) {
+                    (Expr::VecNew(elements), Some(field_expected)) if elements.is_empty() => field_expected.clone(),
+                    (Expr::VecNew(elements), None) if elements.is_empty() => {
+                        rewritten.push((field.clone(), value));
+                        continue;
+                    },
+                    _ => self.types.expr_type(&value)?,
+                };
+                if let Err(message) = self.types.unify(declared, &value_type, &def.type_params, &mut subst) {
+                    return self.types.error(&message);
+                }
+            }
+            rewritten.push((field.clone(), value));
+        }
+
+        let mut args = Vec::new();
+        for param in &def.type_params {
+            match subst.get(&param.name) {
+                Some(arg) => {
+                    let arg = self.settle_holes(arg);
+                    args.push(self.concretize(&arg)?)
+                },
+                None => return self.types.error(&format!(
+                    "annotations de type nécessaires : impossible de déduire le paramètre `{}` de `{}`", param.name, def.name)),
+            }
+        }
+        for param in &def.type_params {
+            let Some(arg) = subst.get(&param.name) else { continue };
+            for bound in &param.bounds {
+                if !self.types.satisfies(arg, bound) {
+                    return self.types.error(&format!(
+                        "le trait `{}` n'est pas implémenté pour `{}` (requis par la contrainte `{}: {}` de `{}`)",
+                        bound, self.types.display_type(arg), param.name, bound, def.name));
+                }
+            }
+        }
+
+        let instance = self.instantiate_struct(&def.name, args)?;
+        Ok(Expr::StructLiteral(instance, rewritten))
+    }
+
+    fn rewrite_call(&mut self, name: &str, type_args: &[Type], args: &[Expr], expected: Option<&Type>) -> Result<Expr, usize> {
+        // Variable locale appelable (fermeture ou pointeur de fonction)
+        if self.types.variables.contains_key(name) {
+            return self.rewrite_indirect_call(Expr::Variable(name.to_string()), args);
+        }
+        if VARIANTS.contains(&name) {
+            return self.rewrite_variant(name, type_args, args, expected);
+        }
+        if let Some((_, _, fields)) = self.types.enum_variant(name) {
+            // Les champs de la variante fixent le type attendu de chaque argument
+            if args.len() != fields.len() {
+                return self.types.error(&format!("la variante `{}` attend {} champ(s), {} fourni(s)", name, fields.len(), args.len()));
+            }
+            let args = self.rewrite_args(args, &fields)?;
+            self.check_args(name, &args, &fields)?;
+            return Ok(Expr::FunctionCall(name.to_string(), Vec::new(), args));
+        }
+        if matches!(name, "Box::new" | "Rc::new" | "RefCell::new" | "Rc::clone" | "Rc::strong_count") {
+            let inner = match (name, expected) {
+                ("Box::new", Some(Type::Box(inner))) | ("Rc::new", Some(Type::Rc(inner))) | ("RefCell::new", Some(Type::RefCell(inner))) => Some((**inner).clone()),
+                _ => None,
+            };
+            // Le type d'une collection vide enveloppée vient du type attendu et reste sur l'appel
+            let type_args = match &inner {
+                Some(inner) if args.first().is_some_and(Self::is_empty_collection) => vec![inner.clone()],
+                _ => Vec::new(),
+            };
+            let args = self.rewrite_args(args, &inner.into_iter().collect::<Vec<_>>())?;
+            return Ok(Expr::FunctionCall(name.to_string(), type_args, args));
+        }
+        if name == "HashMap::new" || name == "HashSet::new" {
+            // Les paramètres de la table viennent du type attendu : annotation, paramètre ou champ
+            let type_args = match (name, type_args, expected) {
+                (_, [_, ..], _) => type_args.iter().map(|arg| self.concretize(arg)).collect::<Result<Vec<_>, _>>()?,
+                ("HashMap::new", [], Some(Type::HashMap(key, value))) => vec![(**key).clone(), (**value).clone()],
+                ("HashSet::new", [], Some(Type::HashSet(element))) => vec![(**element).clone()],
+                _ => return self.types.error(&format!("annotations de type nécessaires : précisez le type de la table créée par {}()", name)),
+            };
+            let args = self.rewrite_args(args, &[])?;
+            return Ok(Expr::FunctionCall(name.to_string(), type_args, args));
+        }
+        if name == "String::new" || name == "String::from" || (name == "drop" && !self.types.functions.contains_key(name)) {
+            let args = self.rewrite_args(args, &[])?;
+            return Ok(Expr::FunctionCall(name.to_string(), Vec::new(), args));
+        }
+
+        let (sig, seed) = self.types.lookup_function(name)?;
+        if sig.type_params.is_empty() {
+            if !type_args.is_empty() {
+                return self.types.error(&format!("`{}` n'attend pas d'arguments génériques", name));
+            }
+            let args = self.rewrite_args(args, &sig.params)?;
+            self.check_args(name, &args, &sig.params)?;
+            return Ok(Expr::FunctionCall(name.to_string(), Vec::new(), args));
+        }
+
+        // Les fermetures sont réécrites en dernier : les autres arguments fixent les types de leurs paramètres
+        let mut partial = seed.clone();
+        let mut rewritten: Vec<Option<Expr>> = vec![None; args.len()];
+        for closures in [false, true] {
+            for (i, arg) in args.iter().enumerate() {
+                if matches!(arg, Expr::Closure(_)) != closures {
+                    continue;
+                }
+                let arg_expected = sig.params.get(i).and_then(|typ| self.expected_type(typ, &sig.type_params, &partial));
+                let arg = self.rewrite_expr(arg, arg_expected.as_ref())?;
+                if let Some(param) = sig.params.get(i) {
+                    let arg_type = self.types.expr_type(&arg)?;
+                    let _ = self.types.unify(param, &arg_type, &sig.type_params, &mut partial);
+                }
+                rewritten[i] = Some(arg);
+            }
+        }
+        let rewritten: Vec<Expr> = rewritten.into_iter().flatten().collect();
+        let arg_types = rewritten.iter().map(|arg| self.types.expr_type(arg)).collect::<Result<Vec<_>, _>>()?;
+        let explicit = type_args.iter()
+            .map(|typ| {
+                self.types.check_type(typ, self.types.current_line)?;
+                let typ = self.types.resolve_type(typ);
+                self.concretize(&typ)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let subst = self.types.infer_call(&sig, seed, &explicit, &arg_types, expected)?;
+        let mut concrete = Vec::new();
+        for param in &sig.type_params {
+            match subst.get(&param.name) {
+                Some(arg) => {
+                    let arg = self.settle_holes(arg);
+                    concrete.push(self.concretize(&arg)?)
+                },
+                None => return self.types.error(&format!(
+                    "annotations de type nécessaires : impossible de déduire le paramètre `{}` de `{}`", param.name, name)),
+            }
+        }
+        self.types.check_bounds(name, &sig.type_params, &subst)?;
+        let params = self.instantiate_params(&sig.type_params, &concrete, &sig.params)?;
+        self.check_args(name, &rewritten, &params)?;
+
+        let callee = match name.split_once("::") {
+            // Fonction associée d'un type générique : Pair::new -> Pair_1_i32::new
+            Some((owner, member)) => {
+                let owner_type = match owner {
+                    "Self" => self.types.self_type.clone().unwrap_or(Type::Void),
+                    _ => Type::Named(owner.to_string()),
+                };
+                let key = (TypeChecker::type_key(&owner_type), member.to_string());
+                let info = self.types.methods[&key].clone();
+                let owner_type = TypeChecker::substitute(&info.impl_type, &subst);
+                let owner_name = TypeChecker::type_key(&self.concretize(&owner_type)?);
+
+                let own_params = self.generic_methods.get(&key).map(|(f, _)| f.type_params.len()).unwrap_or(0);
+                let member = if own_params > 0 {
+                    self.instantiate_method(&owner_name, member, &concrete[concrete.len() - own_params..])?
+                } else {
+                    member.to_string()
+                };
+                format!("{}::{}", owner_name, member)
+            },
+            None => self.instantiate_function(name, &concrete)?,
+        };
+
+        Ok(Expr::FunctionCall(callee, Vec::new(), rewritten))
+    }
+
+    // Arguments d'un adaptateur ou d'un consommateur : une fermeture sans annotation reçoit les types
+    // de l'élément (et de l'accumulateur pour fold)
+    fn rewrite_iterator_args(&mut self, item: &Type, method: &str, args: &[Expr]) -> Result<Vec<Expr>, usize> {
+        let closure = |params: Vec<Type>, output: Type| Some(Type::Closure(params, Box::new(output)));
+        let expected = match IteratorMethod::from_name(method) {
+            Some(IteratorMethod::Adapter(Adapter::Map)) => closure(vec![item.clone()], self.new_hole()),
+            Some(IteratorMethod::Adapter(Adapter::Filter)) => closure(vec![Type::Ref(Box::new(item.clone()), false)], Type::Bool),
+            Some(IteratorMethod::Consumer(Consumer::Any | Consumer::All)) => closure(vec![item.clone()], Type::Bool),
+            Some(IteratorMethod::Consumer(Consumer::ForEach)) => closure(vec![item.clone()], Type::Void),
+            Some(IteratorMethod::Consumer(Consumer::Fold)) if args.len() == 2 => {
+                let initial = self.rewrite_expr(&args[0], None)?;
+                let accumulator = self.types.expr_type(&initial)?;
+                let accumulator = self.concretize(&accumulator)?;
+                let function = self.rewrite_expr(&args[1], closure(vec![accumulator.clone(), item.clone()], accumulator).as_ref())?;
+                return Ok(vec![initial, function]);
+            },
+            _ => None,
+        };
+        args.iter().map(|arg| self.rewrite_expr(arg, expected.as_ref())).collect()
+    }
+
+    // Type attendu d'un appel à parse() sans turbofish dont on extrait la valeur (`s.parse().unwrap()`,
+    // `s.parse()?`) : le Result qui porte le type cible
+    fn parse_target(operand: &Expr, expected: Option<&Type>) -> Option<Type> {
+        match (operand, expected) {
+            (Expr::MethodCall(_, method, type_args, _), Some(target)) if method == "parse" && type_args.is_empty() => {
+                Some(Type::Result(Box::new(target.clone()), Box::new(Type::Named(PARSE_INT_ERROR.to_string()))))
+            },
+            _ => None,
+        }
+    }
+
+    fn rewrite_method_call(&mut self, obj_expr: &Expr, method: &str, type_args: &[Type], args: &[Expr], expected: Option<&Type>) -> Result<Expr, usize> {
+        let receiver_expected = match method {
+            "unwrap" | "expect" | "unwrap_or" => Self::parse_target(obj_expr, expected),
+            _ => None,
+        };
+        let obj_expr = self.rewrite_expr(obj_expr, receiver_expected.as_ref())?;
+        let obj_type = self.types.expr_type(&obj_expr)?;
+        let target = self.types.resolve_method(&obj_type, method)?;
+        if !type_args.is_empty() && !matches!(target, MethodTarget::Builtin) {
+            return self.types.error(&format!("arguments génériques explicites non pris en charge pour la méthode `{}`", method));
+        }
+
+        let info = match target {
+            MethodTarget::Static(info) => info,
+            MethodTarget::Dynamic(trait_name, index) => {
+                let declared = self.types.traits[&trait_name].methods[index].function.clone();
+                let params: Vec<Type> = declared.params.iter().skip(1).map(|(_, typ)| typ.clone()).collect();
+                let args = self.rewrite_args(args, &params)?;
+                self.check_args(method, &args, &params)?;
+                return Ok(Expr::MethodCall(Box::new(obj_expr), method.to_string(), Vec::new(), args));
+            },
+            MethodTarget::Builtin if TypeChecker::iterator_item(&obj_type, method).is_some() => {
+                let item = TypeChecker::iterator_item(&obj_type, method).unwrap();
+                let args = self.rewrite_iterator_args(&item, method, args)?;
+                // sum::<i64>() d'éléments encore ouverts (`s.insert(3)`) en fixe le type
+                if let ("sum" | "product", [target]) = (method, type_args) {
+                    self.infer_holes(TypeChecker::strip_refs(&item), target);
+                }
+                // Le corps d'une fermeture peut avoir fixé le type des éléments (`Point { x: *x }`)
+                let item = TypeChecker::substitute(&item, &self.inferred);
+                // collect() sans turbofish produit la collection attendue par le contexte
+                let type_args = match (method, type_args, expected) {
+                    ("collect", [], Some(Type::Vec(_))) => vec![Type::Vec(Box::new(Type::Named("_".to_string())))],
+                    ("collect", [], Some(Type::String)) => vec![Type::String],
+                    _ => type_args.to_vec(),
+                };
+                self.types.iterator_method_type(&item, method, &type_args, &args)?;
+                return Ok(Expr::MethodCall(Box::new(obj_expr), method.to_string(), type_args, args));
+            },
+            MethodTarget::Builtin if method == "map" => {
+                // L'argument reçoit la valeur contenue : une fermeture sans annotation en prend le type
+                let mut receiver = &obj_type;
+                while let Type::Ref(inner, _) = receiver {
+                    receiver = inner;
+                }
+                let payload = match receiver {
+                    Type::Option(inner) | Type::Result(inner, _) => Some((**inner).clone()),
+                    _ => None,
+                };
+                let expected = payload.map(|payload| Type::Closure(vec![payload], Box::new(self.new_hole())));
+                let args = args.iter().map(|arg| self.rewrite_expr(arg, expected.as_ref())).collect::<Result<Vec<_>, _>>()?;
+                self.types.map_function(&obj_type, &args)?;
+                return Ok(Expr::MethodCall(Box::new(obj_expr), method.to_string(), Vec::new(), args));
+            },
+            MethodTarget::Builtin => {
+                // parse() sans turbofish vise le type attendu (`let r: Result<i8, _> = s.parse();`)
+                let type_args = match (method, type_args, expected) {
+                    ("parse", [], Some(Type::Result(ok, _))) if !matches!(&**ok, Type::Named(name) if TypeChecker::is_hole(name)) => {
+                        vec![(**ok).clone()]
+                    },
+                    _ => type_args.to_vec(),
+                };
+                let type_args = type_args.as_slice();
+                let (params, _) = self.types.builtin_signature(&obj_type, method, type_args)?;
+                let receiver = TypeChecker::builtin_receiver(&obj_type, method);
+
+                // Vecteur créé sans annotation : push, insert et extend fixent le type de ses éléments
+                if let Type::Vec(element) = receiver {
+                    if let Type::Named(name) = &**element {
+                        if TypeChecker::is_hole(name) && !self.inferred.contains_key(name) {
+                            // La fonction de comparaison de sort_by() reçoit des éléments encore ouverts
+                            let expected: &[Type] = if method == "sort_by" { &params } else { &[] };
+                            let (name, args) = (name.clone(), self.rewrite_args(args, expected)?);
+                            let value = match method {
+                                "push" | "extend" => args.first(),
+                                "insert" => args.get(1),
+                                _ => None,
+                            };
+                            // Un littéral entier laisse le type ouvert (v.push(1) puis un usage en Vec<i64>)
+                            match value {
+                                None => {},
+                                Some(arg) if Self::is_integer_literal(arg) => {},
+                                Some(arg) => {
+                                    let mut value_type = self.types.expr_type(arg)?;
+                                    if method == "extend" {
+                                        while let Type::Ref(inner, _) = value_type {
+                                            value_type = *inner;
+                                        }
+                                        if let Type::Vec(inner) | Type::Array(inner, _) = value_type {
+                                            value_type = *inner;
+                                        }
+                                    }
+                                    if self.integer_holes.contains(&name) && !TypeChecker::is_integer(&value_type) {
+                                        return self.types.error(&format!(
+                                            "types incompatibles : attendu un entier, trouvé `{}`", self.types.display_type(&value_type)));
+                                    }
+                                    self.infer_holes(&Type::Named(name), &value_type);
+                                },
+                            }
+                            return Ok(Expr::MethodCall(Box::new(obj_expr), method.to_string(), Vec::new(), args));
+                        }
+                    }
+                }
+                // Table créée sans annotation : les clés et les valeurs passées par valeur (insert, entry,
+                // or_insert) fixent ses paramètres, sauf un littéral entier qui les laisse ouverts
+                if let Type::HashMap(_, _) | Type::HashSet(_) | Type::Entry(_, _) = receiver {
+                    if (0..self.vec_holes).any(|n| TypeChecker::mentions(receiver, &Self::hole_name(n))) {
+                        let args = self.rewrite_args(args, &[])?;
+                        for (param, arg) in params.iter().zip(&args) {
+                            if !matches!(param, Type::Ref(_, _)) && !Self::is_integer_literal(arg) {
+                                let arg_type = self.types.expr_type(arg)?;
+                                self.infer_holes(param, &arg_type);
+                            }
+                        }
+                        return Ok(Expr::MethodCall(Box::new(obj_expr), method.to_string(), Vec::new(), args));
+                    }
+                }
+                let args = self.rewrite_args(args, &params)?;
+                // Une valeur rangée dans une collection a le type de ses éléments
+                let stored = match (receiver, method) {
+                    (Type::Vec(_), "push") | (Type::Entry(_, _), "or_insert") => Some(0),
+                    (Type::Vec(_) | Type::HashMap(_, _), "insert") => Some(1),
+                    _ => None,
+                };
+                if let Some((arg, param)) = stored.and_then(|position| args.get(position).zip(params.get(position))) {
+                    self.check_coercion(arg, param)?;
+                }
+                return Ok(Expr::MethodCall(Box::new(obj_expr), method.to_string(), type_args.to_vec(), args));
+            },
+        };
+
+        if info.sig.type_params.is_empty() {
+            let args = self.rewrite_args(args, &info.sig.params[1..])?;
+            self.check_args(method, &args, &info.sig.params[1..])?;
+            return Ok(Expr::MethodCall(Box::new(obj_expr), method.to_string(), Vec::new(), args));
+        }
+
+        // Méthode générique : ses paramètres propres se déduisent des arguments
+        let mut rewritten = Vec::new();
+        for arg in args {
+            rewritten.push(self.rewrite_expr(arg, None)?);
+        }
+        let arg_types = rewritten.iter().map(|arg| self.types.expr_type(arg)).collect::<Result<Vec<_>, _>>()?;
+        let params = crate::type_checker::FnSig { params: info.sig.params[1..].to_vec(), ..info.sig.clone() };
+        let subst = self.types.infer_call(&params, HashMap::new(), &[], &arg_types, None)?;
+
+        let mut concrete = Vec::new();
+        for param in &info.sig.type_params {
+            match subst.get(&param.name) {
+                Some(arg) => {
+                    let arg = self.settle_holes(arg);
+                    concrete.push(self.concretize(&arg)?)
+                },
+                None => return self.types.error(&format!(
+                    "annotations de type nécessaires : impossible de déduire le paramètre `{}` de `{}`", param.name, method)),
+            }
+        }
+        self.types.check_bounds(method, &info.sig.type_params, &subst)?;
+        let params = self.instantiate_params(&info.sig.type_params, &concrete, &info.sig.params[1..])?;
+        self.check_args(method, &rewritten, &params)?;
+
+        let mut receiver = &obj_type;
+        while let Type::Ref(inner, _) | Type::Box(inner) | Type::Rc(inner) | Type::Guard(inner, _) = receiver {
+            receiver = inner;
+        }
+        let type_name = TypeChecker::type_key(receiver);
+        let instance = self.instantiate_method(&type_name, method, &concrete)?;
+        Ok(Expr::MethodCall(Box::new(obj_expr), instance, Vec::new(), rewritten))
+    }
+}
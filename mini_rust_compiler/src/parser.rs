@@ -1,21 +1,49 @@
+use std::collections::HashMap;
+
 use crate::error_handler::ErrorHandler;
+use crate::format::{self, FormatArgs};
 use crate::lexer::{Token, TokenType};
+use crate::type_checker::{TypeChecker, FMT_ERROR, FORMATTER};
 
 // Définition des structures pour l'AST (Abstract Syntax Tree)
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum Expr {
     Binary(Box<Expr>, BinaryOp, Box<Expr>),
     Unary(UnaryOp, Box<Expr>),
     Literal(Literal),
     Variable(String),
-    Call(String, Vec<Expr>),
-    FunctionCall(String, Vec<Expr>),
+    FunctionCall(String, Vec<Type>, Vec<Expr>), // f(args), Type::f(args) ou f::<T>(args) ; aussi Some/None/Ok/Err
     VecNew(Vec<Expr>),        // Vec::new() or vec![...]
-    VecIndex(Box<Expr>, Box<Expr>), // vec[index]
-    MethodCall(Box<Expr>, String, Vec<Expr>), // obj.method(args)
+    VecRepeat(Box<Expr>, Box<Expr>), // vec![valeur; n], n calculé à l'exécution
+    Index(Box<Expr>, Box<Expr>), // tableau[index] ou vec[index]
+    MethodCall(Box<Expr>, String, Vec<Type>, Vec<Expr>), // obj.method(args) ou obj.method::<T>(args)
+    FieldAccess(Box<Expr>, String), // obj.champ
+    StructLiteral(String, Vec<(String, Expr)>), // Point { x: 1, y: 2 }
+    Tuple(Vec<Expr>),          // (a, b) ; le tuple vide () est la valeur unité
+    TupleIndex(Box<Expr>, usize), // tuple.0
+    Range(Option<Box<Expr>>, Option<Box<Expr>>, bool),  // a..b, a..=b (inclusive), ou ..b, a.., .. entre crochets
+    Format(FormatArgs),        // format!("..", args)
+    Write(Box<Expr>, FormatArgs), // write!(dest, "..", args) ; writeln! ajoute le saut de ligne au format
+    Assign(Box<Expr>, Box<Expr>), // lieu = valeur
+    CompoundAssign(Box<Expr>, BinaryOp, Box<Expr>), // lieu += valeur : le lieu n'est évalué qu'une fois
+    Try(Box<Expr>),            // expr? : valeur de Ok/Some, sinon retour anticipé de Err/None
+    Closure(Closure),          // |a, b| a + b, move |x| { ... }
+    Call(Box<Expr>, Vec<Expr>), // Appel indirect d'une valeur appelable : (self.f)(x), make_adder(1)(2)
 }
 
-#[derive(Debug)]
+// Fermeture : ses paramètres sans type annoté sont inférés du contexte ; les variables capturées
+// sont déterminées par la monomorphisation (par référence, ou par valeur avec `move`)
+#[derive(Debug, Clone)]
+pub struct Closure {
+    pub params: Vec<(String, Option<Type>)>,
+    pub return_type: Option<Type>,
+    pub body: Vec<Stmt>,
+    pub is_move: bool,
+    pub captures: Vec<String>,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone)]
 pub enum BinaryOp {
     Add,
     Subtract,
@@ -30,62 +58,309 @@ pub enum BinaryOp {
     GreaterEqual,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum UnaryOp {
     Negate,
+    Deref,   // *expr
+    Ref,     // &expr
+    RefMut,  // &mut expr
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[allow(clippy::enum_variant_names)]
 pub enum Literal {
     Int(i32),
-    String(String),
-    VecLiteral(Vec<Expr>),    // [1, 2, 3]
+    Bool(bool),
+    String(String),                // "texte", de type &str
+    Char(char),                    // 'c'
+    Array(Vec<Expr>),              // [1, 2, 3]
+    ArrayRepeat(Box<Expr>, usize), // [0; 100]
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Type {
     I8,
     I16,
     I32,
     I64,
     I128,
+    U8,
     F32,
     F64,
+    Bool,
+    Char,
     String,
+    Str,                   // str (uniquement derrière une référence : &str)
     Vec(Box<Type>),  // Vec<T>
+    Option(Box<Type>), // Option<T>
+    Result(Box<Type>, Box<Type>), // Result<T, E>
     Void,
+    Named(String),         // Structure utilisateur, paramètre générique, ou `Self` dans un trait/impl
+    Generic(String, Vec<Type>), // Structure générique appliquée : Pair<i32>
+    Ref(Box<Type>, bool),  // &T / &mut T
+    Box(Box<Type>),        // Box<T>
+    Rc(Box<Type>),         // Rc<T> : pointeur vers la valeur, précédée sur le tas de son compteur de références
+    RefCell(Box<Type>),    // RefCell<T> : état des emprunts puis valeur
+    Guard(Box<Type>, bool), // Ref<T> / RefMut<T> (vrai) rendus par borrow() et borrow_mut() : pointeur vers la valeur
+    Dyn(String),           // dyn Trait (uniquement derrière & ou Box)
+    Tuple(Vec<Type>),      // (i32, bool) ; le tuple vide est Void
+    Array(Box<Type>, usize), // [T; N]
+    Slice(Box<Type>),      // [T] (uniquement derrière une référence : &[T], &mut [T])
+    FnPtr(Vec<Type>, Box<Type>),   // fn(A) -> R : adresse d'une fonction
+    Closure(Vec<Type>, Box<Type>), // impl Fn(A) -> R, dyn Fn(A) -> R ou F: Fn(A) -> R : code et environnement
+    Iterator(Box<Type>),   // impl Iterator<Item = T> : chaîne d'adaptateurs, jamais matérialisée
+    Range(Box<Type>, bool), // Range<T> ou RangeInclusive<T> (vrai) : bornes de début et de fin
+    HashMap(Box<Type>, Box<Type>), // HashMap<K, V> : table à adressage ouvert (entrées, nombre, capacité)
+    HashSet(Box<Type>),    // HashSet<T> : table dont les entrées n'ont pas de valeur
+    Entry(Box<Type>, Box<Type>), // Entry<K, V> rendue par entry() : table visée et clé
 }
 
-#[derive(Debug)]
+// Liste des paramètres et type de retour d'une signature `(A, B) -> R`
+fn signature_to_string(params: &[Type], return_type: &Type) -> String {
+    let params: Vec<String> = params.iter().map(|param| param.to_string()).collect();
+    match return_type {
+        Type::Void => format!("({})", params.join(", ")),
+        _ => format!("({}) -> {}", params.join(", "), return_type),
+    }
+}
+
+impl std::fmt::Display for Type {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Type::I8 => write!(f, "i8"),
+            Type::I16 => write!(f, "i16"),
+            Type::I32 => write!(f, "i32"),
+            Type::I64 => write!(f, "i64"),
+            Type::I128 => write!(f, "i128"),
+            Type::U8 => write!(f, "u8"),
+            Type::F32 => write!(f, "f32"),
+            Type::F64 => write!(f, "f64"),
+            Type::Bool => write!(f, "bool"),
+            Type::Char => write!(f, "char"),
+            Type::String => write!(f, "String"),
+            Type::Str => write!(f, "str"),
+            Type::Vec(inner) => write!(f, "Vec<{}>", inner),
+            Type::Option(inner) => write!(f, "Option<{}>", inner),
+            Type::Result(ok, err) => write!(f, "Result<{}, {}>", ok, err),
+            Type::Void => write!(f, "()"),
+            Type::Named(name) => write!(f, "{}", name),
+            Type::Generic(name, args) => {
+                let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+                write!(f, "{}<{}>", name, args.join(", "))
+            },
+            Type::Ref(inner, true) => write!(f, "&mut {}", inner),
+            Type::Ref(inner, false) => write!(f, "&{}", inner),
+            Type::Box(inner) => write!(f, "Box<{}>", inner),
+            Type::Rc(inner) => write!(f, "Rc<{}>", inner),
+            Type::RefCell(inner) => write!(f, "RefCell<{}>", inner),
+            Type::Guard(inner, false) => write!(f, "Ref<{}>", inner),
+            Type::Guard(inner, true) => write!(f, "RefMut<{}>", inner),
+            Type::Dyn(trait_name) => write!(f, "dyn {}", trait_name),
+            Type::Array(element, size) => write!(f, "[{}; {}]", element, size),
+            Type::Slice(element) => write!(f, "[{}]", element),
+            Type::FnPtr(params, return_type) => write!(f, "fn{}", signature_to_string(params, return_type)),
+            Type::Closure(params, return_type) => write!(f, "impl Fn{}", signature_to_string(params, return_type)),
+            Type::Iterator(item) => write!(f, "impl Iterator<Item = {}>", item),
+            Type::HashMap(key, value) => write!(f, "HashMap<{}, {}>", key, value),
+            Type::HashSet(element) => write!(f, "HashSet<{}>", element),
+            Type::Entry(key, value) => write!(f, "Entry<{}, {}>", key, value),
+            Type::Range(element, false) => write!(f, "Range<{}>", element),
+            Type::Range(element, true) => write!(f, "RangeInclusive<{}>", element),
+            Type::Tuple(elements) if elements.len() == 1 => write!(f, "({},)", elements[0]),
+            Type::Tuple(elements) => {
+                let elements: Vec<String> = elements.iter().map(|element| element.to_string()).collect();
+                write!(f, "({})", elements.join(", "))
+            },
+        }
+    }
+}
+
+// Chaque instruction (sauf les blocs) conserve sa ligne pour les diagnostics
+#[derive(Debug, Clone)]
 pub enum Stmt {
-    Expression(Expr),
-    Let(String, Option<Expr>, bool, Type),
-    Assign(String, Expr),
+    Expression(Expr, usize),
+    Let(Pattern, Option<Expr>, Option<Type>, usize),
     Block(Vec<Stmt>),
-    If(Expr, Box<Stmt>, Option<Box<Stmt>>),
-    While(Expr, Box<Stmt>),
-    For(String, Expr, Expr, Box<Stmt>),
-    Return(Option<Expr>),
-    Println(Vec<Expr>),
+    If(Expr, Box<Stmt>, Option<Box<Stmt>>, usize),
+    While(Expr, Box<Stmt>, usize),
+    Match(Expr, Vec<MatchArm>, usize), // match valeur { motif => ..., } ; aussi if let et while let
+    For(Pattern, Expr, Box<Stmt>, usize), // for motif in itérable (plage ou tableau)
+    Break(usize),    // break ; sortie de la boucle la plus interne
+    Continue(usize), // continue ; tour suivant de la boucle la plus interne
+    Return(Option<Expr>, usize),
+    Print(FormatArgs, Stream, usize), // print!, println!, eprint!, eprintln!
 }
 
-#[derive(Debug)]
+// Bras d'un `match` : seul le premier dont le motif correspond et dont la garde est vraie est exécuté
+#[derive(Debug, Clone)]
+pub struct MatchArm {
+    pub pattern: Pattern,
+    pub guard: Option<Expr>, // motif if condition => ...
+    pub body: Stmt,
+}
+
+// Flux d'écriture des macros d'affichage
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Stream {
+    Stdout,
+    Stderr,
+}
+
+// Motif de liaison d'un `let` ou d'une boucle `for` ; les motifs réfutables (variantes, littéraux)
+// ne sont admis que par `match`, `if let` et `while let`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Pattern {
+    Binding(String, bool), // (nom, mutable)
+    Wildcard,              // _
+    Tuple(Vec<Pattern>),   // (a, (b, _))
+    Variant(String, Option<Box<Pattern>>), // Some(p), None, Ok(p), Err(p), Enum::V(p, q) (champs en tuple)
+    Int(i64),              // 3, -1
+    Bool(bool),            // true
+    Char(char),            // 'c'
+    Ref(Box<Pattern>),     // &p : filtre la valeur désignée par une référence
+    Or(Vec<Pattern>),      // 1 | 2 : alternatives, qui ne lient aucune variable
+}
+
+impl Pattern {
+    // Un motif réfutable peut ne pas correspondre à une valeur de son type
+    pub fn is_refutable(&self) -> bool {
+        match self {
+            Pattern::Binding(_, _) | Pattern::Wildcard => false,
+            Pattern::Tuple(patterns) => patterns.iter().any(Pattern::is_refutable),
+            Pattern::Ref(pattern) => pattern.is_refutable(),
+            Pattern::Variant(_, _) | Pattern::Int(_) | Pattern::Bool(_) | Pattern::Char(_) => true,
+            Pattern::Or(alternatives) => alternatives.iter().all(Pattern::is_refutable),
+        }
+    }
+    
+    // Noms liés par le motif
+    pub fn names(&self) -> Vec<String> {
+        match self {
+            Pattern::Binding(name, _) => vec![name.clone()],
+            Pattern::Tuple(patterns) | Pattern::Or(patterns) => patterns.iter().flat_map(Pattern::names).collect(),
+            Pattern::Variant(_, Some(payload)) | Pattern::Ref(payload) => payload.names(),
+            _ => Vec::new(),
+        }
+    }
+
+    // Noms liés par le motif, chacun avec sa mutabilité (`mut x`)
+    pub fn bindings(&self) -> Vec<(String, bool)> {
+        match self {
+            Pattern::Binding(name, mutable) => vec![(name.clone(), *mutable)],
+            Pattern::Tuple(patterns) => patterns.iter().flat_map(Pattern::bindings).collect(),
+            Pattern::Variant(_, Some(payload)) | Pattern::Ref(payload) => payload.bindings(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl std::fmt::Display for Pattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Pattern::Binding(name, true) => write!(f, "mut {}", name),
+            Pattern::Binding(name, false) => write!(f, "{}", name),
+            Pattern::Wildcard => write!(f, "_"),
+            Pattern::Tuple(patterns) if patterns.len() == 1 => write!(f, "({},)", patterns[0]),
+            Pattern::Tuple(patterns) => {
+                let patterns: Vec<String> = patterns.iter().map(|pattern| pattern.to_string()).collect();
+                write!(f, "({})", patterns.join(", "))
+            },
+            // Les champs d'une variante d'énumération suivent son nom : `List::Cons(x, rest)`
+            Pattern::Variant(name, Some(payload)) if name.contains("::") && matches!(**payload, Pattern::Tuple(ref fields) if fields.len() > 1) => {
+                write!(f, "{}{}", name, payload)
+            },
+            Pattern::Variant(name, Some(payload)) => write!(f, "{}({})", name, payload),
+            Pattern::Variant(name, None) => write!(f, "{}", name),
+            Pattern::Int(value) => write!(f, "{}", value),
+            Pattern::Bool(value) => write!(f, "{}", value),
+            Pattern::Char(value) => write!(f, "{:?}", value),
+            Pattern::Ref(pattern) => write!(f, "&{}", pattern),
+            Pattern::Or(alternatives) => {
+                let alternatives: Vec<String> = alternatives.iter().map(|pattern| pattern.to_string()).collect();
+                write!(f, "{}", alternatives.join(" | "))
+            },
+        }
+    }
+}
+
+// Paramètre générique et ses contraintes de traits : T: PartialOrd + Display
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeParam {
+    pub name: String,
+    pub bounds: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
 pub struct Function {
     pub name: String,
-    pub params: Vec<(String, String)>, // (nom, type)
-    pub return_type: Option<String>,
+    pub type_params: Vec<TypeParam>,
+    pub params: Vec<(String, Type)>, // (nom, type) ; `self` est le premier paramètre d'une méthode
+    pub mutable_params: Vec<String>, // Paramètres déclarés `mut` (`mut x: i32`, `mut self`)
+    pub return_type: Option<Type>,
     pub body: Vec<Stmt>,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct StructDef {
+    pub name: String,
+    pub type_params: Vec<TypeParam>,
+    pub fields: Vec<(String, Type)>,
+    pub derives: Vec<String>, // Traits de #[derive(..)]
+    pub instance_of: Option<(String, Vec<Type>)>, // Structure générique et arguments d'une instance monomorphisée
+    pub line: usize,
+}
+
+// enum Nom { Variante(T, U), Unite } : variantes dans l'ordre de déclaration, qui fixe leur étiquette
+#[derive(Debug, Clone)]
+pub struct EnumDef {
+    pub name: String,
+    pub variants: Vec<(String, Vec<Type>)>, // (nom, types des champs) ; aucun champ pour une variante unité
+    pub derives: Vec<String>,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct TraitMethod {
+    pub function: Function,
+    pub has_default: bool, // Le trait fournit une implémentation par défaut
+}
+
+#[derive(Debug, Clone)]
+pub struct TraitDef {
+    pub name: String,
+    pub methods: Vec<TraitMethod>,
+    pub line: usize,
+}
+
+// impl Type { ... } ou impl Trait for Type { ... }, éventuellement générique : impl<T> Pair<T> { ... }
+#[derive(Debug, Clone)]
+pub struct ImplBlock {
+    pub type_params: Vec<TypeParam>,
+    pub trait_name: Option<String>,
+    pub type_name: String,
+    pub type_args: Vec<Type>, // Arguments du type implémenté (vide s'il n'est pas générique)
+    pub methods: Vec<Function>,
+    pub line: usize,
 }
 
 #[derive(Debug)]
 pub struct Program {
     pub functions: Vec<Function>,
+    pub structs: Vec<StructDef>,
+    pub enums: Vec<EnumDef>,
+    pub traits: Vec<TraitDef>,
+    pub impls: Vec<ImplBlock>,
 }
 
 pub struct Parser<'a> {
     tokens: Vec<Token>,
     current: usize,
     error_handler: &'a ErrorHandler,
+    no_struct_literal: bool, // Vrai dans les conditions de if/while/for (`if x { ... }`)
+    fn_bound: Option<Type>,  // Contrainte Fn(A) -> R lue par trait_bounds
+    closure_params: Vec<(String, Type)>, // Paramètres génériques contraints par Fn, remplacés par leur type de fermeture
+    loop_depth: usize,       // Boucles englobant l'instruction analysée, hors fermetures (break, continue)
 }
 
 impl<'a> Parser<'a> {
@@ -94,24 +369,116 @@ impl<'a> Parser<'a> {
             tokens,
             current: 0,
             error_handler,
+            no_struct_literal: false,
+            loop_depth: 0,
+            fn_bound: None,
+            closure_params: Vec::new(),
         }
     }
     
     pub fn parse(&mut self) -> Result<Program, usize> {
-        let mut program = Program { functions: Vec::new() };
+        let mut program = Program {
+            functions: Vec::new(),
+            structs: Vec::new(),
+            enums: Vec::new(),
+            traits: Vec::new(),
+            impls: Vec::new(),
+        };
         
         while !self.is_at_end() {
-            match self.function() {
-                Ok(function) => program.functions.push(function),
-                Err(line) => return Err(line),
+            let derives = self.attributes()?;
+            if !derives.is_empty() && !self.check(TokenType::Struct) && !self.check(TokenType::Enum) {
+                self.error_handler.report_error(self.peek().line, "#[derive(..)] ne s'applique qu'aux structures et aux énumérations");
+                return Err(self.peek().line);
+            }
+            
+            if self.check(TokenType::Fn) {
+                program.functions.push(self.function()?);
+            } else if self.match_token(TokenType::Struct) {
+                program.structs.push(self.struct_definition(derives)?);
+            } else if self.match_token(TokenType::Enum) {
+                program.enums.push(self.enum_definition(derives)?);
+            } else if self.match_token(TokenType::Trait) {
+                program.traits.push(self.trait_definition()?);
+            } else if self.match_token(TokenType::Impl) {
+                program.impls.push(self.impl_block()?);
+            } else if self.match_token(TokenType::Use) {
+                self.use_declaration()?;
+            } else {
+                self.error_handler.report_error(self.peek().line, &format!("Attendu 'fn', 'struct', 'enum', 'trait', 'impl' ou 'use' (found {:?})", self.peek().token_type));
+                return Err(self.peek().line);
             }
         }
         
         Ok(program)
     }
     
+    // Attributs `#[..]` précédant un élément : seuls les traits de `#[derive(..)]` sont conservés,
+    // les autres attributs (`#[allow(..)]`...) sont ignorés
+    fn attributes(&mut self) -> Result<Vec<String>, usize> {
+        let mut derives = Vec::new();
+        while self.match_token(TokenType::Hash) {
+            self.consume(TokenType::LeftBracket, "Attendu '[' après '#'")?;
+            if matches!(&self.peek().token_type, TokenType::Identifier(name) if name == "derive") {
+                self.advance();
+                self.consume(TokenType::LeftParen, "Attendu '(' après 'derive'")?;
+                while !self.check(TokenType::RightParen) && !self.is_at_end() {
+                    derives.push(self.identifier("Attendu le nom d'un trait à dériver")?);
+                    if !self.match_token(TokenType::Comma) {
+                        break;
+                    }
+                }
+                self.consume(TokenType::RightParen, "Attendu ')' après les traits dérivés")?;
+            } else {
+                let mut depth = 0;
+                while !self.is_at_end() {
+                    if depth == 0 && self.check(TokenType::RightBracket) {
+                        break;
+                    }
+                    match self.advance().token_type {
+                        TokenType::LeftBracket => depth += 1,
+                        TokenType::RightBracket => depth -= 1,
+                        _ => {},
+                    }
+                }
+            }
+            self.consume(TokenType::RightBracket, "Attendu ']' après l'attribut")?;
+        }
+        Ok(derives)
+    }
+    
+    // `use std::fmt::Write;` : les éléments de la bibliothèque standard pris en charge sont déjà
+    // connus du compilateur, la déclaration est donc acceptée puis ignorée
+    fn use_declaration(&mut self) -> Result<(), usize> {
+        while !self.check(TokenType::Semicolon) && !self.is_at_end() {
+            match self.peek().token_type {
+                TokenType::Identifier(_) | TokenType::DoubleColon | TokenType::LeftBrace | TokenType::RightBrace
+                | TokenType::Comma | TokenType::Star => {
+                    self.advance();
+                },
+                _ => {
+                    self.error_handler.report_error(self.peek().line, "Chemin invalide dans la déclaration `use`");
+                    return Err(self.peek().line);
+                }
+            }
+        }
+        self.consume(TokenType::Semicolon, "Attendu ';' après la déclaration `use`")?;
+        Ok(())
+    }
+    
     fn function(&mut self) -> Result<Function, usize> {
-        self.consume(TokenType::Fn, "Attendu 'fn'")?;
+        let mut function = self.function_signature()?;
+        
+        // Corps de la fonction
+        self.consume(TokenType::LeftBrace, "Attendu '{' avant le corps de la fonction")?;
+        function.body = self.function_body()?;
+        
+        Ok(function)
+    }
+    
+    // fn nom(params) -> Type, sans le corps (partagé avec les déclarations de trait)
+    fn function_signature(&mut self) -> Result<Function, usize> {
+        let line = self.consume(TokenType::Fn, "Attendu 'fn'")?.line;
         
         let name = match &self.peek().token_type {
             TokenType::Identifier(name) => name.clone(),
@@ -119,26 +486,42 @@ impl<'a> Parser<'a> {
         };
         self.advance();
         
+        let mut type_params = self.type_parameters()?;
+        
         self.consume(TokenType::LeftParen, "Attendu '(' après le nom de la fonction")?;
         
         // Paramètres
         let mut params = Vec::new();
+        let mut mutable_params = Vec::new();
         if !self.check(TokenType::RightParen) {
             loop {
+                // Receveur d'une méthode : self, mut self, &self, &mut self
+                if params.is_empty() {
+                    if let Some((self_type, mutable)) = self.self_parameter() {
+                        if mutable {
+                            mutable_params.push("self".to_string());
+                        }
+                        params.push(("self".to_string(), self_type));
+                        if !self.match_token(TokenType::Comma) {
+                            break;
+                        }
+                        continue;
+                    }
+                }
+                
+                let mutable = self.match_token(TokenType::Mut);
                 let param_name = match &self.peek().token_type {
                     TokenType::Identifier(name) => name.clone(),
                     _ => return Err(self.peek().line),
                 };
                 self.advance();
+                if mutable {
+                    mutable_params.push(param_name.clone());
+                }
                 
                 self.consume(TokenType::Colon, "Attendu ':' après le nom du paramètre")?;
                 
-                let param_type = match &self.peek().token_type {
-                    TokenType::I32 => "i32".to_string(),
-                    TokenType::Identifier(type_name) => type_name.clone(),
-                    _ => return Err(self.peek().line),
-                };
-                self.advance();
+                let param_type = self.type_annotation()?;
                 
                 params.push((param_name, param_type));
                 
@@ -151,46 +534,385 @@ impl<'a> Parser<'a> {
         self.consume(TokenType::RightParen, "Attendu ')' après les paramètres")?;
         
         // Type de retour
-        let return_type = if self.match_token(TokenType::Arrow) {
-            if let TokenType::I32 = self.peek().token_type {
-                self.advance();
-                Some("i32".to_string())
-            } else if let TokenType::Identifier(ref type_name) = self.peek().token_type {
-                let type_name = type_name.clone();
-                self.advance();
-                Some(type_name)
-            } else {
-                return Err(self.peek().line);
-            }
+        let mut return_type = if self.match_token(TokenType::Arrow) {
+            Some(self.type_annotation()?)
         } else {
             None
         };
         
-        // Corps de la fonction
-        self.consume(TokenType::LeftBrace, "Attendu '{' avant le corps de la fonction")?;
+        self.where_clause(&mut type_params)?;
         
-        let mut body = Vec::new();
-        while !self.check(TokenType::RightBrace) && !self.is_at_end() {
-            match self.statement() {
-                Ok(stmt) => body.push(stmt),
-                Err(line) => return Err(line),
+        // F: Fn(A) -> R : le paramètre générique devient le type de fermeture correspondant
+        if !self.closure_params.is_empty() {
+            let subst: HashMap<String, Type> = self.closure_params.drain(..).collect();
+            type_params.retain(|param| !subst.contains_key(&param.name));
+            for (_, param_type) in params.iter_mut() {
+                *param_type = TypeChecker::substitute(param_type, &subst);
             }
+            return_type = return_type.map(|typ| TypeChecker::substitute(&typ, &subst));
         }
         
-        self.consume(TokenType::RightBrace, "Attendu '}' après le corps de la fonction")?;
-        
         Ok(Function {
             name,
+            type_params,
             params,
+            mutable_params,
             return_type,
-            body,
+            body: Vec::new(),
+            line,
         })
     }
     
+    // Paramètres génériques optionnels : <T, U: Trait + Autre>
+    fn type_parameters(&mut self) -> Result<Vec<TypeParam>, usize> {
+        let mut type_params = Vec::new();
+        if !self.match_token(TokenType::Less) {
+            return Ok(type_params);
+        }
+        
+        while !self.check(TokenType::Greater) && !self.is_at_end() {
+            // Paramètre de durée de vie (<'a>, <'a: 'b>) : ignoré
+            if self.skip_lifetime() {
+                if self.match_token(TokenType::Colon) {
+                    while self.skip_lifetime() && self.match_token(TokenType::Plus) {}
+                }
+                if !self.match_token(TokenType::Comma) {
+                    break;
+                }
+                continue;
+            }
+            let name = self.identifier("Attendu le nom d'un paramètre générique")?;
+            let bounds = if self.match_token(TokenType::Colon) {
+                self.trait_bounds()?
+            } else {
+                Vec::new()
+            };
+            if let Some(closure) = self.fn_bound.take() {
+                self.closure_params.push((name.clone(), closure));
+            }
+            type_params.push(TypeParam { name, bounds });
+            
+            if !self.match_token(TokenType::Comma) {
+                break;
+            }
+        }
+        
+        self.consume(TokenType::Greater, "Attendu '>' après les paramètres génériques")?;
+        Ok(type_params)
+    }
+    
+    // Trait + Trait ; les chemins (std::fmt::Display) et les arguments (Add<Output = T>) sont ramenés au nom du trait.
+    // Une contrainte Fn(A) -> R (ou FnMut, FnOnce) est conservée dans `fn_bound`
+    fn trait_bounds(&mut self) -> Result<Vec<String>, usize> {
+        let mut bounds = Vec::new();
+        loop {
+            let mut bound = self.identifier("Attendu un nom de trait")?;
+            while self.match_token(TokenType::DoubleColon) {
+                bound = self.identifier("Attendu un nom de trait après '::'")?;
+            }
+            if Self::is_fn_trait(&bound) && self.check(TokenType::LeftParen) {
+                let (params, return_type) = self.signature_type()?;
+                self.fn_bound = Some(Type::Closure(params, Box::new(return_type)));
+            } else if self.match_token(TokenType::Less) {
+                let mut depth = 1;
+                while depth > 0 && !self.is_at_end() {
+                    match self.advance().token_type {
+                        TokenType::Less => depth += 1,
+                        TokenType::Greater => depth -= 1,
+                        _ => {},
+                    }
+                }
+            }
+            bounds.push(bound);
+            
+            if !self.match_token(TokenType::Plus) {
+                break;
+            }
+        }
+        Ok(bounds)
+    }
+    
+    // Clause `where T: Trait, U: Autre` ajoutée aux contraintes des paramètres génériques
+    fn where_clause(&mut self, type_params: &mut [TypeParam]) -> Result<(), usize> {
+        if !matches!(&self.peek().token_type, TokenType::Identifier(name) if name == "where") {
+            return Ok(());
+        }
+        self.advance();
+        
+        while matches!(self.peek().token_type, TokenType::Identifier(_)) {
+            let line = self.peek().line;
+            let name = self.identifier("Attendu un paramètre générique dans la clause where")?;
+            self.consume(TokenType::Colon, "Attendu ':' dans la clause where")?;
+            let bounds = self.trait_bounds()?;
+            if let Some(closure) = self.fn_bound.take() {
+                self.closure_params.push((name.clone(), closure));
+            }
+            match type_params.iter_mut().find(|param| param.name == name) {
+                Some(param) => param.bounds.extend(bounds),
+                None => {
+                    self.error_handler.report_error(line, &format!("Paramètre générique inconnu dans la clause where: {}", name));
+                    return Err(line);
+                }
+            }
+            
+            if !self.match_token(TokenType::Comma) {
+                break;
+            }
+        }
+        Ok(())
+    }
+    
+    // Les contraintes Fn ne sont admises que sur les paramètres génériques d'une fonction
+    fn reject_closure_params(&mut self, line: usize) -> Result<(), usize> {
+        if let Some((name, _)) = self.closure_params.pop() {
+            self.closure_params.clear();
+            self.error_handler.report_error(line, &format!("contrainte Fn sur `{}` : seuls les paramètres génériques d'une fonction peuvent être des fermetures", name));
+            return Err(line);
+        }
+        Ok(())
+    }
+    
+    fn is_fn_trait(name: &str) -> bool {
+        matches!(name, "Fn" | "FnMut" | "FnOnce")
+    }
+    
+    // Signature d'un type fonction : (A, B) -> R, le retour valant () s'il est omis
+    fn signature_type(&mut self) -> Result<(Vec<Type>, Type), usize> {
+        self.consume(TokenType::LeftParen, "Attendu '(' dans le type fonction")?;
+        let mut params = Vec::new();
+        while !self.check(TokenType::RightParen) && !self.is_at_end() {
+            params.push(self.type_annotation()?);
+            if !self.match_token(TokenType::Comma) {
+                break;
+            }
+        }
+        self.consume(TokenType::RightParen, "Attendu ')' après les paramètres du type fonction")?;
+        let return_type = if self.match_token(TokenType::Arrow) {
+            self.type_annotation()?
+        } else {
+            Type::Void
+        };
+        Ok((params, return_type))
+    }
+    
+    // Reconnaît un receveur `self` et renvoie son type (en fonction de `Self`), et si la liaison est `mut self`
+    fn self_parameter(&mut self) -> Option<(Type, bool)> {
+        let is_self = |token: &Token| matches!(&token.token_type, TokenType::Identifier(name) if name == "self");
+        let self_type = Type::Named("Self".to_string());
+        let start = self.current;
+        
+        if self.match_token(TokenType::Ampersand) {
+            self.skip_lifetime();
+            let mutable = self.match_token(TokenType::Mut);
+            if is_self(self.peek()) {
+                self.advance();
+                return Some((Type::Ref(Box::new(self_type), mutable), false));
+            }
+        } else {
+            let mutable = self.match_token(TokenType::Mut);
+            if is_self(self.peek()) {
+                self.advance();
+                return Some((self_type, mutable));
+            }
+        }
+        
+        self.current = start;
+        None
+    }
+    
+    // Corps d'une fonction : la dernière expression sans ';' devient la valeur de retour
+    fn function_body(&mut self) -> Result<Vec<Stmt>, usize> {
+        let mut body = self.statements()?;
+        
+        self.consume(TokenType::RightBrace, "Attendu '}' après le corps de la fonction")?;
+        
+        if let Some(last) = body.pop() {
+            body.push(Self::tail_to_return(last));
+        }
+        
+        Ok(body)
+    }
+    
+    // Transforme l'expression finale d'un corps (éventuellement dans un if/else ou un match) en return
+    fn tail_to_return(stmt: Stmt) -> Stmt {
+        Self::map_tail(stmt, &|expr, line| Stmt::Return(Some(expr), line))
+    }
+    
+    // Remplace par `wrap(expr)` l'expression finale `expr` d'une instruction, dans chacune de ses branches
+    fn map_tail(stmt: Stmt, wrap: &dyn Fn(Expr, usize) -> Stmt) -> Stmt {
+        match stmt {
+            Stmt::Expression(expr, line) if !matches!(expr, Expr::Assign(..) | Expr::CompoundAssign(..)) => wrap(expr, line),
+            Stmt::If(condition, then_branch, Some(else_branch), line) => Stmt::If(
+                condition,
+                Box::new(Self::map_tail(*then_branch, wrap)),
+                Some(Box::new(Self::map_tail(*else_branch, wrap))),
+                line,
+            ),
+            Stmt::Match(value, arms, line) => {
+                let arms = arms.into_iter()
+                    .map(|arm| MatchArm { body: Self::map_tail(arm.body, wrap), ..arm })
+                    .collect();
+                Stmt::Match(value, arms, line)
+            },
+            Stmt::Block(mut stmts) => {
+                if let Some(last) = stmts.pop() {
+                    stmts.push(Self::map_tail(last, wrap));
+                }
+                Stmt::Block(stmts)
+            },
+            other => other,
+        }
+    }
+    
+    fn struct_definition(&mut self, derives: Vec<String>) -> Result<StructDef, usize> {
+        let line = self.previous().line;
+        let name = self.identifier("Attendu le nom de la structure")?;
+        let mut type_params = self.type_parameters()?;
+        self.where_clause(&mut type_params)?;
+        self.reject_closure_params(line)?;
+        
+        let mut fields = Vec::new();
+        if !self.match_token(TokenType::Semicolon) {
+            self.consume(TokenType::LeftBrace, "Attendu '{' après le nom de la structure")?;
+            while !self.check(TokenType::RightBrace) && !self.is_at_end() {
+                let field_name = self.identifier("Attendu le nom d'un champ")?;
+                self.consume(TokenType::Colon, "Attendu ':' après le nom du champ")?;
+                let field_type = self.type_annotation()?;
+                fields.push((field_name, field_type));
+                
+                if !self.match_token(TokenType::Comma) {
+                    break;
+                }
+            }
+            self.consume(TokenType::RightBrace, "Attendu '}' après les champs de la structure")?;
+        }
+        
+        Ok(StructDef { name, type_params, fields, derives, instance_of: None, line })
+    }
+    
+    // Variantes unités (`Nil`) ou à champs positionnels (`Cons(i32, Box<List>)`)
+    fn enum_definition(&mut self, derives: Vec<String>) -> Result<EnumDef, usize> {
+        let line = self.previous().line;
+        let name = self.identifier("Attendu le nom de l'énumération")?;
+        if self.check(TokenType::Less) {
+            self.error_handler.report_error(line, &format!("les énumérations génériques ne sont pas prises en charge: {}", name));
+            return Err(line);
+        }
+        
+        let mut variants: Vec<(String, Vec<Type>)> = Vec::new();
+        self.consume(TokenType::LeftBrace, "Attendu '{' après le nom de l'énumération")?;
+        while !self.check(TokenType::RightBrace) && !self.is_at_end() {
+            let variant_line = self.peek().line;
+            let variant = self.identifier("Attendu le nom d'une variante")?;
+            let mut fields = Vec::new();
+            if self.match_token(TokenType::LeftParen) {
+                while !self.check(TokenType::RightParen) && !self.is_at_end() {
+                    fields.push(self.type_annotation()?);
+                    if !self.match_token(TokenType::Comma) {
+                        break;
+                    }
+                }
+                self.consume(TokenType::RightParen, "Attendu ')' après les champs de la variante")?;
+            } else if self.check(TokenType::LeftBrace) {
+                self.error_handler.report_error(variant_line, &format!(
+                    "les variantes à champs nommés ne sont pas prises en charge : écrivez `{}(..)` avec des champs positionnels", variant));
+                return Err(variant_line);
+            }
+            if variants.iter().any(|(existing, _)| *existing == variant) {
+                self.error_handler.report_error(variant_line, &format!("Variante définie plusieurs fois: {}::{}", name, variant));
+                return Err(variant_line);
+            }
+            variants.push((variant, fields));
+            
+            if !self.match_token(TokenType::Comma) {
+                break;
+            }
+        }
+        self.consume(TokenType::RightBrace, "Attendu '}' après les variantes de l'énumération")?;
+        
+        Ok(EnumDef { name, variants, derives, line })
+    }
+    
+    fn trait_definition(&mut self) -> Result<TraitDef, usize> {
+        let line = self.previous().line;
+        let name = self.identifier("Attendu le nom du trait")?;
+        
+        self.consume(TokenType::LeftBrace, "Attendu '{' après le nom du trait")?;
+        
+        let mut methods = Vec::new();
+        while !self.check(TokenType::RightBrace) && !self.is_at_end() {
+            let mut function = self.function_signature()?;
+            let has_default = if self.match_token(TokenType::LeftBrace) {
+                function.body = self.function_body()?;
+                true
+            } else {
+                self.consume(TokenType::Semicolon, "Attendu ';' ou un corps après la signature de la méthode")?;
+                false
+            };
+            methods.push(TraitMethod { function, has_default });
+        }
+        
+        self.consume(TokenType::RightBrace, "Attendu '}' après le corps du trait")?;
+        
+        Ok(TraitDef { name, methods, line })
+    }
+    
+    fn impl_block(&mut self) -> Result<ImplBlock, usize> {
+        let line = self.previous().line;
+        let mut type_params = self.type_parameters()?;
+        let first_type = self.type_annotation()?;
+        
+        // impl Trait for Type
+        let (trait_name, implemented) = if self.match_token(TokenType::For) {
+            let trait_name = match first_type {
+                Type::Named(name) => name,
+                other => {
+                    self.error_handler.report_error(line, &format!("Attendu un nom de trait après 'impl' (found {})", other));
+                    return Err(line);
+                }
+            };
+            (Some(trait_name), self.type_annotation()?)
+        } else {
+            (None, first_type)
+        };
+        let (type_name, type_args) = match implemented {
+            Type::Named(name) => (name, Vec::new()),
+            Type::Generic(name, args) => (name, args),
+            other => (other.to_string(), Vec::new()),
+        };
+        self.where_clause(&mut type_params)?;
+        self.reject_closure_params(line)?;
+        
+        self.consume(TokenType::LeftBrace, "Attendu '{' après l'en-tête du bloc impl")?;
+        
+        let mut methods = Vec::new();
+        while !self.check(TokenType::RightBrace) && !self.is_at_end() {
+            methods.push(self.function()?);
+        }
+        
+        self.consume(TokenType::RightBrace, "Attendu '}' après le bloc impl")?;
+        
+        Ok(ImplBlock { type_params, trait_name, type_name, type_args, methods, line })
+    }
+    
+    // Instructions jusqu'à la '}' d'un bloc ou d'un corps. `let x = match v { .. };` en donne deux : la
+    // déclaration de x, initialisée ensuite par chacun des bras du match
+    fn statements(&mut self) -> Result<Vec<Stmt>, usize> {
+        let mut statements = Vec::new();
+        while !self.check(TokenType::RightBrace) && !self.is_at_end() {
+            if self.match_token(TokenType::Let) {
+                let (declaration, initialization) = self.let_statement()?;
+                statements.push(declaration);
+                statements.extend(initialization);
+            } else {
+                statements.push(self.statement()?);
+            }
+        }
+        Ok(statements)
+    }
+    
     fn statement(&mut self) -> Result<Stmt, usize> {
-        if self.match_token(TokenType::Let) {
-            self.let_statement()
-        } else if self.match_token(TokenType::Return) {
+        if self.match_token(TokenType::Return) {
             self.return_statement()
         } else if self.match_token(TokenType::LeftBrace) {
             self.block_statement()
@@ -198,31 +920,59 @@ impl<'a> Parser<'a> {
             self.if_statement()
         } else if self.match_token(TokenType::While) {
             self.while_statement()
+        } else if self.match_token(TokenType::Match) {
+            let stmt = self.match_statement()?;
+            self.match_token(TokenType::Semicolon);
+            Ok(stmt)
         } else if self.match_token(TokenType::For) {
             self.for_statement()  // Add for statement handling
-        } else if self.check(TokenType::PrintlnMacro) {
-            self.println_statement()
+        } else if self.match_any(&[TokenType::Break, TokenType::Continue]) {
+            self.jump_statement()
+        } else if let TokenType::Macro(name) = &self.peek().token_type {
+            match name.as_str() {
+                "print" | "println" | "eprint" | "eprintln" => {
+                    let name = name.clone();
+                    self.print_statement(&name)
+                },
+                _ => self.expression_statement(),
+            }
         } else {
             self.expression_statement()
         }
     }
     
-    fn let_statement(&mut self) -> Result<Stmt, usize> {
-        let mutable = self.match_token(TokenType::Mut);
-        
-        let name = match &self.peek().token_type {
-            TokenType::Identifier(name) => name.clone(),
-            _ => return Err(self.peek().line),
-        };
-        self.advance();
+    // Déclaration, suivie du match qui l'initialise pour `let x = match v { .. };`
+    fn let_statement(&mut self) -> Result<(Stmt, Option<Stmt>), usize> {
+        let line = self.previous().line;
+        let pattern = self.pattern()?;
+        if pattern.is_refutable() {
+            self.error_handler.report_error(line, &format!("motif réfutable `{}` dans un `let` : utilisez `match`, `if let` ou `while let`", pattern));
+            return Err(line);
+        }
         
-        // Parse le type
+        // Parse le type (inféré depuis l'initialisation s'il est absent)
         let var_type = if self.match_token(TokenType::Colon) {
-            self.type_annotation()?
+            Some(self.type_annotation()?)
         } else {
-            Type::I32  // Type par défaut
+            None
         };
         
+        // Le match devient une initialisation différée : chacun de ses bras affecte sa valeur à la variable
+        if self.check(TokenType::Assign) && matches!(self.tokens.get(self.current + 1).map(|t| &t.token_type), Some(TokenType::Match)) {
+            self.advance();
+            self.advance();
+            let Pattern::Binding(name, _) = &pattern else {
+                self.error_handler.report_error(line, &format!("un `match` ne peut initialiser qu'une variable, pas le motif `{}`", pattern));
+                return Err(line);
+            };
+            let target = Expr::Variable(name.clone());
+            let initialization = Self::map_tail(self.match_statement()?, &|expr, line| {
+                Stmt::Expression(Expr::Assign(Box::new(target.clone()), Box::new(expr)), line)
+            });
+            self.consume(TokenType::Semicolon, "Attendu ';' après la déclaration")?;
+            return Ok((Stmt::Let(pattern, None, var_type, line), Some(initialization)));
+        }
+        
         // Initialisation
         let initializer = if self.match_token(TokenType::Assign) {
             Some(self.expression()?)
@@ -232,10 +982,94 @@ impl<'a> Parser<'a> {
         
         self.consume(TokenType::Semicolon, "Attendu ';' après la déclaration")?;
         
-        Ok(Stmt::Let(name, initializer, mutable, var_type))  // Include type
+        Ok((Stmt::Let(pattern, initializer, var_type, line), None))
+    }
+    
+    // Motif : `mut`? nom, `_`, tuple de motifs `(a, (b, _))`, variante `Some(p)`/`None`/`Ok(p)`/`Err(p)`
+    // ou littéral entier, booléen ou caractère
+    fn pattern(&mut self) -> Result<Pattern, usize> {
+        if self.match_token(TokenType::Ampersand) {
+            return Ok(Pattern::Ref(Box::new(self.pattern()?)));
+        }
+        if self.match_token(TokenType::LeftParen) {
+            let mut elements = Vec::new();
+            let mut trailing_comma = false;
+            while !self.check(TokenType::RightParen) && !self.is_at_end() {
+                elements.push(self.pattern()?);
+                trailing_comma = self.match_token(TokenType::Comma);
+                if !trailing_comma {
+                    break;
+                }
+            }
+            self.consume(TokenType::RightParen, "Attendu ')' après le motif")?;
+            
+            // (p) est un simple motif parenthésé, (p,) un tuple à un élément
+            if elements.len() == 1 && !trailing_comma {
+                return Ok(elements.remove(0));
+            }
+            return Ok(Pattern::Tuple(elements));
+        }
+        
+        let negative = self.match_token(TokenType::Minus);
+        match self.peek().token_type.clone() {
+            TokenType::IntLiteral(value) => {
+                self.advance();
+                return Ok(Pattern::Int(if negative { -(value as i64) } else { value as i64 }));
+            },
+            _ if negative => {
+                self.error_handler.report_error(self.peek().line, "Attendu un entier après '-' dans le motif");
+                return Err(self.peek().line);
+            },
+            TokenType::True | TokenType::False => {
+                let value = self.advance().token_type == TokenType::True;
+                return Ok(Pattern::Bool(value));
+            },
+            TokenType::CharLiteral(value) => {
+                self.advance();
+                return Ok(Pattern::Char(value));
+            },
+            _ => {},
+        }
+        
+        let mutable = self.match_token(TokenType::Mut);
+        let mut name = self.identifier("Attendu un nom de variable ou un motif")?;
+        if name == "_" && !mutable {
+            return Ok(Pattern::Wildcard);
+        }
+        if !mutable && matches!(name.as_str(), "Option" | "Result") && self.match_token(TokenType::DoubleColon) {
+            name = self.identifier("Attendu une variante après '::'")?;
+        } else if !mutable && self.match_token(TokenType::DoubleColon) {
+            // Variante d'une énumération : Enum::Unite, Enum::Variante(p) ou Enum::Variante(p, q),
+            // dont les champs forment alors un motif tuple
+            let variant = format!("{}::{}", name, self.identifier("Attendu une variante après '::'")?);
+            if !self.match_token(TokenType::LeftParen) {
+                return Ok(Pattern::Variant(variant, None));
+            }
+            let mut fields = Vec::new();
+            while !self.check(TokenType::RightParen) && !self.is_at_end() {
+                fields.push(self.pattern()?);
+                if !self.match_token(TokenType::Comma) {
+                    break;
+                }
+            }
+            self.consume(TokenType::RightParen, "Attendu ')' après les champs de la variante")?;
+            let payload = if fields.len() == 1 { fields.remove(0) } else { Pattern::Tuple(fields) };
+            return Ok(Pattern::Variant(variant, Some(Box::new(payload))));
+        }
+        match name.as_str() {
+            "None" if !mutable => Ok(Pattern::Variant(name, None)),
+            "Some" | "Ok" | "Err" if !mutable => {
+                self.consume(TokenType::LeftParen, &format!("Attendu '(' après `{}` dans le motif", name))?;
+                let payload = self.pattern()?;
+                self.consume(TokenType::RightParen, "Attendu ')' après le motif")?;
+                Ok(Pattern::Variant(name, Some(Box::new(payload))))
+            },
+            _ => Ok(Pattern::Binding(name, mutable)),
+        }
     }
     
     fn return_statement(&mut self) -> Result<Stmt, usize> {
+        let line = self.previous().line;
         let value = if !self.check(TokenType::Semicolon) {
             Some(self.expression()?)
         } else {
@@ -244,15 +1078,41 @@ impl<'a> Parser<'a> {
         
         self.consume(TokenType::Semicolon, "Attendu ';' après l'instruction return")?;
         
-        Ok(Stmt::Return(value))
+        Ok(Stmt::Return(value, line))
     }
     
-    fn block_statement(&mut self) -> Result<Stmt, usize> {
-        let mut statements = Vec::new();
-        
-        while !self.check(TokenType::RightBrace) && !self.is_at_end() {
-            statements.push(self.statement()?);
+    // break / continue : le ';' est facultatif en fin de bloc (`if fini { break }`)
+    fn jump_statement(&mut self) -> Result<Stmt, usize> {
+        let stmt = self.jump()?;
+        if !self.check(TokenType::RightBrace) {
+            let keyword = if let Stmt::Break(_) = stmt { "break" } else { "continue" };
+            self.consume(TokenType::Semicolon, &format!("Attendu ';' après {}", keyword))?;
         }
+        Ok(stmt)
+    }
+
+    // break / continue, sans le ';' (aussi corps d'un bras de `match`)
+    fn jump(&mut self) -> Result<Stmt, usize> {
+        let token = self.previous().clone();
+        let keyword = if token.token_type == TokenType::Break { "break" } else { "continue" };
+        if self.loop_depth == 0 {
+            self.error_handler.report_error(token.line, &format!("`{}` en dehors d'une boucle", keyword));
+            return Err(token.line);
+        }
+
+        Ok(if token.token_type == TokenType::Break { Stmt::Break(token.line) } else { Stmt::Continue(token.line) })
+    }
+
+    // Corps d'une boucle while ou for, où break et continue sont admis
+    fn loop_body(&mut self) -> Result<Stmt, usize> {
+        self.loop_depth += 1;
+        let body = self.block_statement();
+        self.loop_depth -= 1;
+        body
+    }
+
+    fn block_statement(&mut self) -> Result<Stmt, usize> {
+        let statements = self.statements()?;
         
         self.consume(TokenType::RightBrace, "Attendu '}' après le bloc")?;
         
@@ -260,8 +1120,17 @@ impl<'a> Parser<'a> {
     }
     
     fn if_statement(&mut self) -> Result<Stmt, usize> {
+        let line = self.previous().line;
+        // if let motif = valeur : le motif ne lie ses variables que dans la première branche
+        let pattern = if self.match_token(TokenType::Let) {
+            let pattern = self.alternatives()?;
+            self.consume(TokenType::Assign, "Attendu '=' après le motif de `if let`")?;
+            Some(pattern)
+        } else {
+            None
+        };
         // Parse condition without requiring parentheses
-        let condition = self.expression()?;
+        let condition = self.condition()?;
         
         self.consume(TokenType::LeftBrace, "Attendu '{' après la condition if")?;
         let then_branch = Box::new(self.block_statement()?);
@@ -275,88 +1144,294 @@ impl<'a> Parser<'a> {
                 self.consume(TokenType::LeftBrace, "Attendu '{' après 'else'")?;
                 Some(Box::new(self.block_statement()?))
             }
-        } else {
-            None
-        };
+        } else {
+            None
+        };
+        
+        // if let devient un match dont le second bras, `_`, exécute la branche else
+        Ok(match pattern {
+            Some(pattern) => {
+                let else_branch = else_branch.map_or(Stmt::Block(Vec::new()), |else_branch| *else_branch);
+                Stmt::Match(condition, vec![
+                    MatchArm { pattern, guard: None, body: *then_branch },
+                    MatchArm { pattern: Pattern::Wildcard, guard: None, body: else_branch },
+                ], line)
+            },
+            None => Stmt::If(condition, then_branch, else_branch, line),
+        })
+    }
+    
+    // Expression d'en-tête (if/while/for) : `x { ... }` n'est pas un littéral de structure
+    fn condition(&mut self) -> Result<Expr, usize> {
+        let previous = self.no_struct_literal;
+        self.no_struct_literal = true;
+        let result = self.expression();
+        self.no_struct_literal = previous;
+        result
+    }
+    
+    fn while_statement(&mut self) -> Result<Stmt, usize> {
+        let line = self.previous().line;
+        // while let motif = valeur : la boucle s'arrête dès que la valeur ne correspond plus au motif
+        let pattern = if self.match_token(TokenType::Let) {
+            let pattern = self.alternatives()?;
+            self.consume(TokenType::Assign, "Attendu '=' après le motif de `while let`")?;
+            Some(pattern)
+        } else {
+            None
+        };
+        // Also update while to not require parentheses
+        let condition = self.condition()?;
+        
+        self.consume(TokenType::LeftBrace, "Attendu '{' après la condition while")?;
+        let body = Box::new(self.loop_body()?);
+        
+        // while let devient une boucle sans condition sur un match dont le second bras, `_`, en sort
+        Ok(match pattern {
+            Some(pattern) => {
+                let arms = vec![
+                    MatchArm { pattern, guard: None, body: *body },
+                    MatchArm { pattern: Pattern::Wildcard, guard: None, body: Stmt::Break(line) },
+                ];
+                Stmt::While(Expr::Literal(Literal::Bool(true)), Box::new(Stmt::Match(condition, arms, line)), line)
+            },
+            None => Stmt::While(condition, body, line),
+        })
+    }
+    
+    // match valeur { motif if garde => corps, ... } : le premier bras dont le motif correspond et dont
+    // la garde est vraie est exécuté
+    fn match_statement(&mut self) -> Result<Stmt, usize> {
+        let line = self.previous().line;
+        let value = self.condition()?;
+        self.consume(TokenType::LeftBrace, "Attendu '{' après la valeur de `match`")?;
+        
+        let mut arms = Vec::new();
+        while !self.check(TokenType::RightBrace) && !self.is_at_end() {
+            let pattern = self.alternatives()?;
+            let guard = if self.match_token(TokenType::If) {
+                Some(self.expression()?)
+            } else {
+                None
+            };
+            self.consume(TokenType::FatArrow, "Attendu '=>' après le motif du bras")?;
+            
+            // La ',' est facultative après un bloc et après le dernier bras
+            let block = self.check(TokenType::LeftBrace);
+            let body = self.arm_body()?;
+            if !self.match_token(TokenType::Comma) && !block && !self.check(TokenType::RightBrace) {
+                self.consume(TokenType::Comma, "Attendu ',' après le bras du `match`")?;
+            }
+            arms.push(MatchArm { pattern, guard, body });
+        }
         
-        Ok(Stmt::If(condition, then_branch, else_branch))
+        self.consume(TokenType::RightBrace, "Attendu '}' après les bras du `match`")?;
+        Ok(Stmt::Match(value, arms, line))
     }
     
-    fn while_statement(&mut self) -> Result<Stmt, usize> {
-        // Also update while to not require parentheses
-        let condition = self.expression()?;
-        
-        self.consume(TokenType::LeftBrace, "Attendu '{' après la condition while")?;
-        let body = Box::new(self.block_statement()?);
+    // Corps d'un bras : bloc, match imbriqué, return, break, continue, macro d'affichage ou expression
+    fn arm_body(&mut self) -> Result<Stmt, usize> {
+        let line = self.peek().line;
+        if self.match_token(TokenType::LeftBrace) {
+            return self.block_statement();
+        }
+        if self.match_token(TokenType::Match) {
+            return self.match_statement();
+        }
+        if self.match_token(TokenType::Return) {
+            let value = if self.check(TokenType::Comma) || self.check(TokenType::RightBrace) {
+                None
+            } else {
+                Some(self.expression()?)
+            };
+            return Ok(Stmt::Return(value, line));
+        }
+        if self.match_any(&[TokenType::Break, TokenType::Continue]) {
+            return self.jump();
+        }
+        if let TokenType::Macro(name) = &self.peek().token_type {
+            if matches!(name.as_str(), "print" | "println" | "eprint" | "eprintln") {
+                let name = name.clone();
+                return self.print_macro(&name);
+            }
+        }
+        Ok(Stmt::Expression(self.expression()?, line))
+    }
+    
+    // Motif de `match`, `if let` ou `while let`, éventuellement fait d'alternatives `p | q`
+    fn alternatives(&mut self) -> Result<Pattern, usize> {
+        let line = self.peek().line;
+        self.match_token(TokenType::Pipe);
+        let mut alternatives = vec![self.pattern()?];
+        while self.match_token(TokenType::Pipe) {
+            alternatives.push(self.pattern()?);
+        }
+        if alternatives.len() == 1 {
+            return Ok(alternatives.remove(0));
+        }
         
-        Ok(Stmt::While(condition, body))
+        let pattern = Pattern::Or(alternatives);
+        if !pattern.names().is_empty() {
+            self.error_handler.report_error(line, &format!("les alternatives du motif `{}` ne peuvent pas lier de variables", pattern));
+            return Err(line);
+        }
+        Ok(pattern)
     }
     
     // New method for parsing for loops
     fn for_statement(&mut self) -> Result<Stmt, usize> {
-        // Get the loop variable name
-        let var_name = match &self.peek().token_type {
-            TokenType::Identifier(name) => name.clone(),
-            _ => {
-                self.error_handler.report_error(self.peek().line, "Expected identifier after 'for'");
-                return Err(self.peek().line);
-            }
-        };
-        self.advance();
+        let line = self.previous().line;
+        // Loop variable pattern: `i` or `(i, x)`
+        let pattern = self.pattern()?;
+        if pattern.is_refutable() {
+            self.error_handler.report_error(line, &format!("motif réfutable `{}` dans une boucle `for`", pattern));
+            return Err(line);
+        }
         
         // Expect 'in' keyword
-        self.consume(TokenType::In, "Expected 'in' after identifier in for loop")?;
-        
-        // Parse range start expression
-        let range_start = self.expression()?;
+        self.consume(TokenType::In, "Expected 'in' after pattern in for loop")?;
         
-        // Expect '..' token
-        self.consume(TokenType::DotDot, "Expected '..' in range expression")?;
-        
-        // Parse range end expression
-        let range_end = self.expression()?;
+        // Iterable: range `start..end`, collection or iterator chain
+        let iterable = self.condition()?;
         
         // Parse loop body
         self.consume(TokenType::LeftBrace, "Expected '{' before for loop body")?;
-        let body = self.block_statement()?;
+        let body = self.loop_body()?;
         
         // Wrap the body in a Box
         if let Stmt::Block(statements) = body {
-            Ok(Stmt::For(var_name, range_start, range_end, Box::new(Stmt::Block(statements))))
+            Ok(Stmt::For(pattern, iterable, Box::new(Stmt::Block(statements)), line))
         } else {
             // This shouldn't happen since block_statement always returns a Stmt::Block
             Err(self.peek().line)
         }
     }
     
-    fn println_statement(&mut self) -> Result<Stmt, usize> {
-        self.advance(); // Consommer println!
+    // print!, println!, eprint!, eprintln! : écriture formatée sur la sortie standard ou d'erreur
+    fn print_statement(&mut self, name: &str) -> Result<Stmt, usize> {
+        let stmt = self.print_macro(name)?;
+        self.consume(TokenType::Semicolon, &format!("Attendu ';' après l'appel à {}!", name))?;
+        Ok(stmt)
+    }
+    
+    // Appel d'une macro d'affichage sans le ';' final (aussi corps d'une fermeture : `|| println!(..)`)
+    fn print_macro(&mut self, name: &str) -> Result<Stmt, usize> {
+        let line = self.advance().line; // Consommer le nom de la macro
+        let stream = if name.starts_with('e') { Stream::Stderr } else { Stream::Stdout };
         
-        self.consume(TokenType::LeftParen, "Attendu '(' après 'println!'")?;
+        self.consume(TokenType::LeftParen, &format!("Attendu '(' après '{}!'", name))?;
+        let format = self.format_arguments(name, name.ends_with("ln"))?;
+        self.consume(TokenType::RightParen, "Attendu ')' après les arguments")?;
         
-        let mut args = Vec::new();
-        if !self.check(TokenType::RightParen) {
-            // Parse the first argument (can be string literal or any expression)
-            args.push(self.expression()?);
-            
-            // Parse additional arguments separated by commas
+        Ok(Stmt::Print(format, stream, line))
+    }
+    
+    // format!(..) produit une String, write!(dest, ..) et writeln!(dest, ..) écrivent dans dest,
+    // vec![..] construit un vecteur
+    fn macro_expression(&mut self, name: &str) -> Result<Expr, usize> {
+        let line = self.advance().line; // Consommer le nom de la macro
+        if name == "vec" {
+            return self.vec_macro();
+        }
+        if !matches!(name, "format" | "write" | "writeln") {
+            self.error_handler.report_error(line, &format!("macro inconnue `{}!`", name));
+            return Err(line);
+        }
+        
+        self.consume(TokenType::LeftParen, &format!("Attendu '(' après '{}!'", name))?;
+        let expr = if name == "format" {
+            Expr::Format(self.format_arguments(name, false)?)
+        } else {
+            let destination = self.expression()?;
+            let newline = name == "writeln";
+            if !(newline && self.check(TokenType::RightParen)) {
+                self.consume(TokenType::Comma, &format!("Attendu ',' après la destination de {}!", name))?;
+            }
+            Expr::Write(Box::new(destination), self.format_arguments(name, newline)?)
+        };
+        self.consume(TokenType::RightParen, "Attendu ')' après les arguments")?;
+        
+        Ok(expr)
+    }
+    
+    // vec![], vec![a, b, c] ou vec![valeur; n]
+    fn vec_macro(&mut self) -> Result<Expr, usize> {
+        self.consume(TokenType::LeftBracket, "Attendu '[' après 'vec!'")?;
+        let mut elements = Vec::new();
+        if !self.check(TokenType::RightBracket) {
+            elements.push(self.expression()?);
+            if self.match_token(TokenType::Semicolon) {
+                let count = self.expression()?;
+                self.consume(TokenType::RightBracket, "Attendu ']' après la longueur de vec!")?;
+                return Ok(Expr::VecRepeat(Box::new(elements.remove(0)), Box::new(count)));
+            }
             while self.match_token(TokenType::Comma) {
-                args.push(self.expression()?);
+                if self.check(TokenType::RightBracket) {
+                    break;
+                }
+                elements.push(self.expression()?);
+            }
+        }
+        self.consume(TokenType::RightBracket, "Attendu ']' après les éléments de vec!")?;
+        Ok(Expr::VecNew(elements))
+    }
+
+    // Chaîne de format littérale et arguments d'une macro, jusqu'à la parenthèse fermante ;
+    // les variantes `ln` ajoutent un saut de ligne au format et acceptent de ne rien recevoir
+    fn format_arguments(&mut self, name: &str, newline: bool) -> Result<FormatArgs, usize> {
+        let line = self.peek().line;
+        let mut template = match &self.peek().token_type {
+            TokenType::StringLiteral(value) => value.clone(),
+            TokenType::RightParen if newline => String::new(),
+            _ => {
+                self.error_handler.report_error(line, &format!("{}! attend une chaîne de format littérale comme premier argument", name));
+                return Err(line);
             }
+        };
+        if newline {
+            template.push('\n');
         }
         
-        self.consume(TokenType::RightParen, "Attendu ')' après les arguments")?;
-        self.consume(TokenType::Semicolon, "Attendu ';' après l'appel à println!")?;
+        // Arguments positionnels, puis nommés (`nom = expr`)
+        let mut positional = Vec::new();
+        let mut named: Vec<(String, Expr)> = Vec::new();
+        if !self.check(TokenType::RightParen) {
+            self.advance(); // Chaîne de format
+            while self.match_token(TokenType::Comma) {
+                if self.check(TokenType::RightParen) {
+                    break; // Virgule finale
+                }
+                let is_named = matches!(self.tokens.get(self.current + 1).map(|t| &t.token_type), Some(TokenType::Assign));
+                if let (true, TokenType::Identifier(name)) = (is_named, &self.peek().token_type) {
+                    let name = name.clone();
+                    self.advance();
+                    self.advance(); // '='
+                    named.push((name, self.expression()?));
+                } else if named.is_empty() {
+                    positional.push(self.expression()?);
+                } else {
+                    self.error_handler.report_error(self.peek().line, "les arguments positionnels doivent précéder les arguments nommés");
+                    return Err(self.peek().line);
+                }
+            }
+        }
         
-        Ok(Stmt::Println(args))
+        format::parse(&template, positional, named).map_err(|message| {
+            self.error_handler.report_error(line, &message);
+            line
+        })
     }
     
     fn expression_statement(&mut self) -> Result<Stmt, usize> {
+        let line = self.peek().line;
         let expr = self.expression()?;
         
-        self.consume(TokenType::Semicolon, "Attendu ';' après l'expression")?;
+        // La dernière expression d'un bloc peut omettre le ';'
+        if !self.check(TokenType::RightBrace) {
+            self.consume(TokenType::Semicolon, "Attendu ';' après l'expression")?;
+        }
         
-        Ok(Stmt::Expression(expr))
+        Ok(Stmt::Expression(expr, line))
     }
     
     fn expression(&mut self) -> Result<Expr, usize> {
@@ -364,25 +1439,53 @@ impl<'a> Parser<'a> {
     }
     
     fn assignment(&mut self) -> Result<Expr, usize> {
-        let expr = self.equality()?;
+        let expr = self.range()?;
         
-        if self.match_token(TokenType::Assign) {
+        if self.match_any(&[TokenType::Assign, TokenType::PlusAssign, TokenType::MinusAssign, TokenType::StarAssign, TokenType::SlashAssign, TokenType::ModAssign]) {
+            let operator = match &self.previous().token_type {
+                TokenType::PlusAssign => Some(BinaryOp::Add),
+                TokenType::MinusAssign => Some(BinaryOp::Subtract),
+                TokenType::StarAssign => Some(BinaryOp::Multiply),
+                TokenType::SlashAssign => Some(BinaryOp::Divide),
+                TokenType::ModAssign => Some(BinaryOp::Modulo),
+                _ => None,
+            };
             let value = self.assignment()?;
             
-            if let Expr::Variable(name) = expr {
-                return Ok(Expr::Binary(
-                    Box::new(Expr::Variable(name)),
-                    BinaryOp::Equal,
-                    Box::new(value),
-                ));
-            }
-            
-            return Err(self.previous().line);
+            // Seuls les lieux (variable, champ, élément, déréférencement) sont assignables
+            return match expr {
+                Expr::Variable(_) | Expr::FieldAccess(_, _) | Expr::TupleIndex(_, _) | Expr::Index(_, _) | Expr::Unary(UnaryOp::Deref, _) => {
+                    Ok(match operator {
+                        Some(operator) => Expr::CompoundAssign(Box::new(expr), operator, Box::new(value)),
+                        None => Expr::Assign(Box::new(expr), Box::new(value)),
+                    })
+                },
+                _ => {
+                    self.error_handler.report_error(self.previous().line, "Cible d'affectation invalide");
+                    Err(self.previous().line)
+                }
+            };
         }
         
         Ok(expr)
     }
     
+    // Plage `a..b` ou `a..=b`, moins prioritaire que les opérateurs binaires ; la borne de fin d'une
+    // plage non inclusive est facultative (`&v[a..]`)
+    fn range(&mut self) -> Result<Expr, usize> {
+        let start = self.equality()?;
+        if !self.match_any(&[TokenType::DotDot, TokenType::DotDotEq]) {
+            return Ok(start);
+        }
+        let inclusive = self.previous().token_type == TokenType::DotDotEq;
+        let end = match self.peek().token_type {
+            TokenType::RightBracket | TokenType::RightParen | TokenType::Semicolon | TokenType::Comma | TokenType::LeftBrace
+                if !inclusive => None,
+            _ => Some(Box::new(self.equality()?)),
+        };
+        Ok(Expr::Range(Some(Box::new(start)), end, inclusive))
+    }
+
     fn equality(&mut self) -> Result<Expr, usize> {
         let mut expr = self.comparison()?;
         
@@ -458,6 +1561,15 @@ impl<'a> Parser<'a> {
             let right = self.unary()?;
             return Ok(Expr::Unary(UnaryOp::Negate, Box::new(right)));
         }
+        if self.match_token(TokenType::Star) {
+            let right = self.unary()?;
+            return Ok(Expr::Unary(UnaryOp::Deref, Box::new(right)));
+        }
+        if self.match_token(TokenType::Ampersand) {
+            let op = if self.match_token(TokenType::Mut) { UnaryOp::RefMut } else { UnaryOp::Ref };
+            let right = self.unary()?;
+            return Ok(Expr::Unary(op, Box::new(right)));
+        }
         
         self.call()
     }
@@ -467,32 +1579,26 @@ impl<'a> Parser<'a> {
         
         loop {
             if self.check(TokenType::LeftParen) {
-                if let Expr::Variable(callee) = expr {
-                    self.advance(); // Consume (
-                    
-                    let mut arguments = Vec::new();
-                    if !self.check(TokenType::RightParen) {
-                        loop {
-                            arguments.push(self.expression()?);
-                            
-                            if !self.match_token(TokenType::Comma) {
-                                break;
-                            }
-                        }
-                    }
-                    
-                    self.consume(TokenType::RightParen, "Attendu ')' après les arguments")?;
-                    
-                    expr = Expr::FunctionCall(callee, arguments);
-                } else {
-                    return Err(self.peek().line);
-                }
+                let arguments = self.arguments()?;
+                expr = match expr {
+                    Expr::Variable(callee) => Expr::FunctionCall(callee, Vec::new(), arguments),
+                    callee => Expr::Call(Box::new(callee), arguments),
+                };
+            } else if self.match_token(TokenType::Question) {
+                expr = Expr::Try(Box::new(expr));
             } else if self.match_token(TokenType::LeftBracket) {
-                // Array/Vec indexing: expr[index]
-                let index = self.expression()?;
+                // Array/Vec indexing: expr[index], or sub-slicing: expr[a..b], expr[..b], expr[a..], expr[..]
+                let index = self.index()?;
                 self.consume(TokenType::RightBracket, "Expected ']' after index")?;
-                expr = Expr::VecIndex(Box::new(expr), Box::new(index));
+                expr = Expr::Index(Box::new(expr), Box::new(index));
             } else if self.match_token(TokenType::Dot) {
+                // Tuple field: expr.0
+                if let TokenType::IntLiteral(index) = self.peek().token_type {
+                    self.advance();
+                    expr = Expr::TupleIndex(Box::new(expr), index as usize);
+                    continue;
+                }
+                
                 // Method call: expr.method(args)
                 let method_name = match &self.peek().token_type {
                     TokenType::Identifier(name) => name.clone(),
@@ -500,6 +1606,19 @@ impl<'a> Parser<'a> {
                 };
                 self.advance();
                 
+                // Arguments génériques explicites : expr.method::<T>(args)
+                let type_args = if self.match_token(TokenType::DoubleColon) {
+                    self.type_arguments()?
+                } else {
+                    Vec::new()
+                };
+                
+                // Field access: expr.field
+                if type_args.is_empty() && !self.check(TokenType::LeftParen) {
+                    expr = Expr::FieldAccess(Box::new(expr), method_name);
+                    continue;
+                }
+                
                 self.consume(TokenType::LeftParen, "Expected '(' after method name")?;
                 
                 let mut arguments = Vec::new();
@@ -515,7 +1634,7 @@ impl<'a> Parser<'a> {
                 
                 self.consume(TokenType::RightParen, "Expected ')' after method arguments")?;
                 
-                expr = Expr::MethodCall(Box::new(expr), method_name, arguments);
+                expr = Expr::MethodCall(Box::new(expr), method_name, type_args, arguments);
             } else {
                 break;
             }
@@ -525,30 +1644,52 @@ impl<'a> Parser<'a> {
     }
     
     fn primary(&mut self) -> Result<Expr, usize> {
+        let is_move = matches!(&self.peek().token_type, TokenType::Identifier(name) if name == "move")
+            && matches!(self.tokens.get(self.current + 1).map(|t| &t.token_type), Some(TokenType::Pipe));
+        if is_move || self.check(TokenType::Pipe) {
+            return self.closure(is_move);
+        }
         if let TokenType::IntLiteral(value) = &self.peek().token_type {
             let value = *value;
             self.advance();
             return Ok(Expr::Literal(Literal::Int(value)));
+        } else if self.match_token(TokenType::True) {
+            return Ok(Expr::Literal(Literal::Bool(true)));
+        } else if self.match_token(TokenType::False) {
+            return Ok(Expr::Literal(Literal::Bool(false)));
         } else if let TokenType::StringLiteral(value) = &self.peek().token_type.clone() {
             let value = value.clone();
             self.advance();
             return Ok(Expr::Literal(Literal::String(value)));
+        } else if let TokenType::CharLiteral(value) = self.peek().token_type {
+            self.advance();
+            return Ok(Expr::Literal(Literal::Char(value)));
+        } else if let TokenType::Macro(name) = &self.peek().token_type {
+            let name = name.clone();
+            return self.macro_expression(&name);
         } else if self.match_token(TokenType::LeftBracket) {
-            // Vec literal: [1, 2, 3]
+            // Array literal: [1, 2, 3] or repeat form [value; N]
             let mut elements = Vec::new();
             
             if !self.check(TokenType::RightBracket) {
-                loop {
-                    elements.push(self.expression()?);
-                    
-                    if !self.match_token(TokenType::Comma) {
+                elements.push(self.expression()?);
+                
+                if self.match_token(TokenType::Semicolon) {
+                    let count = self.array_length()?;
+                    self.consume(TokenType::RightBracket, "Expected ']' after array length")?;
+                    return Ok(Expr::Literal(Literal::ArrayRepeat(Box::new(elements.remove(0)), count)));
+                }
+                
+                while self.match_token(TokenType::Comma) {
+                    if self.check(TokenType::RightBracket) {
                         break;
                     }
+                    elements.push(self.expression()?);
                 }
             }
             
-            self.consume(TokenType::RightBracket, "Expected ']' after vec elements")?;
-            return Ok(Expr::Literal(Literal::VecLiteral(elements)));
+            self.consume(TokenType::RightBracket, "Expected ']' after array elements")?;
+            return Ok(Expr::Literal(Literal::Array(elements)));
         } else if self.match_token(TokenType::Vec) {
             // Vec::new() parsing
             if !self.check(TokenType::DoubleColon) {
@@ -557,25 +1698,8 @@ impl<'a> Parser<'a> {
             }
             self.advance(); // consume '::'
             
-            // Check if the next token is TokenType::New or Identifier("new")
+            // Check if the next token is Identifier("new")
             match &self.peek().token_type {
-                TokenType::New => {
-                    self.advance(); // consume 'new'
-                    
-                    if !self.check(TokenType::LeftParen) {
-                        self.error_handler.report_error(self.peek().line, &format!("Expected '(' after 'Vec::new', found {:?}", self.peek().token_type));
-                        return Err(self.peek().line);
-                    }
-                    self.advance(); // consume '('
-                    
-                    if !self.check(TokenType::RightParen) {
-                        self.error_handler.report_error(self.peek().line, &format!("Expected ')' after 'Vec::new(', found {:?}", self.peek().token_type));
-                        return Err(self.peek().line);
-                    }
-                    self.advance(); // consume ')'
-                    
-                    return Ok(Expr::VecNew(Vec::new()));
-                },
                 TokenType::Identifier(method_name) => {
                     if method_name == "new" {
                         self.advance(); // consume 'new'
@@ -604,19 +1728,241 @@ impl<'a> Parser<'a> {
                 }
             }
         } else if let TokenType::Identifier(name) = &self.peek().token_type.clone() {
-            let name = name.clone();
+            let mut name = name.clone();
             self.advance();
+            
+            // Chemin Type::fonction (fonction associée), avec un éventuel turbofish f::<T>(...)
+            if self.check(TokenType::DoubleColon) {
+                self.advance();
+                if self.check(TokenType::Less) {
+                    return self.turbofish_call(name);
+                }
+                let member = self.identifier("Attendu un nom après '::'")?;
+                name = match (name.as_str(), member.as_str()) {
+                    // Option::Some, Result::Err... : variantes des types prédéfinis
+                    ("Option", "Some" | "None") | ("Result", "Ok" | "Err") => member,
+                    _ => format!("{}::{}", name, member),
+                };
+                if self.match_token(TokenType::DoubleColon) {
+                    return self.turbofish_call(name);
+                }
+            } else if self.check(TokenType::LeftBrace) && !self.no_struct_literal && self.is_struct_literal_start() {
+                return self.struct_literal(name);
+            }
+            
+            // None est une valeur, pas une variable : son type se déduit du contexte
+            if name == "None" {
+                return Ok(Expr::FunctionCall(name, Vec::new(), Vec::new()));
+            }
             return Ok(Expr::Variable(name));
         } else if self.match_token(TokenType::LeftParen) {
-            let expr = self.expression()?;
+            // Expression parenthésée, valeur unité () ou tuple (a, b)
+            let previous = self.no_struct_literal;
+            self.no_struct_literal = false;
+            let mut elements = Vec::new();
+            let mut trailing_comma = false;
+            while !self.check(TokenType::RightParen) && !self.is_at_end() {
+                elements.push(self.expression()?);
+                trailing_comma = self.match_token(TokenType::Comma);
+                if !trailing_comma {
+                    break;
+                }
+            }
+            self.no_struct_literal = previous;
             self.consume(TokenType::RightParen, "Attendu ')' après l'expression")?;
-            return Ok(expr);
+            
+            if elements.len() == 1 && !trailing_comma {
+                return Ok(elements.remove(0));
+            }
+            return Ok(Expr::Tuple(elements));
         }
         
         self.error_handler.report_error(self.peek().line, &format!("Unexpected token: {:?}", self.peek().token_type));
         Err(self.peek().line)
     }
     
+    // Fermeture `move`? |params| corps ; un motif en paramètre est lié par un `let` en tête du corps
+    fn closure(&mut self, is_move: bool) -> Result<Expr, usize> {
+        if is_move {
+            self.advance();
+        }
+        let line = self.consume(TokenType::Pipe, "Attendu '|'")?.line;
+        
+        let mut params = Vec::new();
+        let mut bindings = Vec::new();
+        while !self.check(TokenType::Pipe) && !self.is_at_end() {
+            let pattern = self.pattern()?;
+            if pattern.is_refutable() {
+                self.error_handler.report_error(line, &format!("motif réfutable `{}` en paramètre de fermeture", pattern));
+                return Err(line);
+            }
+            let param_type = if self.match_token(TokenType::Colon) {
+                Some(self.type_annotation()?)
+            } else {
+                None
+            };
+            let name = match &pattern {
+                Pattern::Binding(name, false) => name.clone(),
+                _ => {
+                    let name = format!("__param{}", params.len());
+                    bindings.push(Stmt::Let(pattern, Some(Expr::Variable(name.clone())), None, line));
+                    name
+                }
+            };
+            params.push((name, param_type));
+            if !self.match_token(TokenType::Comma) {
+                break;
+            }
+        }
+        self.consume(TokenType::Pipe, "Attendu '|' après les paramètres de la fermeture")?;
+        
+        // Corps : un bloc (obligatoire après `-> R`) ou une expression
+        let return_type = if self.match_token(TokenType::Arrow) {
+            Some(self.type_annotation()?)
+        } else {
+            None
+        };
+        let body = if return_type.is_some() || self.check(TokenType::LeftBrace) {
+            self.consume(TokenType::LeftBrace, "Attendu '{' après le type de retour de la fermeture")?;
+            // Le corps est une fonction à part : break et continue n'y visent pas les boucles englobantes
+            let previous = (self.no_struct_literal, self.loop_depth);
+            (self.no_struct_literal, self.loop_depth) = (false, 0);
+            let body = self.function_body();
+            (self.no_struct_literal, self.loop_depth) = previous;
+            body?
+        } else if let TokenType::Macro(name) = self.peek().token_type.clone() {
+            match name.as_str() {
+                "print" | "println" | "eprint" | "eprintln" => vec![self.print_macro(&name)?],
+                _ => vec![Self::tail_to_return(Stmt::Expression(self.expression()?, line))],
+            }
+        } else {
+            let expr = self.expression()?;
+            vec![Self::tail_to_return(Stmt::Expression(expr, line))]
+        };
+        
+        bindings.extend(body);
+        Ok(Expr::Closure(Closure { params, return_type, body: bindings, is_move, captures: Vec::new(), line }))
+    }
+    
+    // Index entre crochets : une expression ou une plage dont les bornes sont facultatives
+    fn index(&mut self) -> Result<Expr, usize> {
+        if !self.match_any(&[TokenType::DotDot, TokenType::DotDotEq]) {
+            return self.expression();
+        }
+        let inclusive = self.previous().token_type == TokenType::DotDotEq;
+        let end = if self.check(TokenType::RightBracket) && !inclusive { None } else { Some(Box::new(self.expression()?)) };
+        Ok(Expr::Range(None, end, inclusive))
+    }
+
+    // Longueur d'un tableau : un entier littéral positif
+    fn array_length(&mut self) -> Result<usize, usize> {
+        match self.peek().token_type {
+            TokenType::IntLiteral(length) if length >= 0 => {
+                self.advance();
+                Ok(length as usize)
+            },
+            _ => {
+                self.error_handler.report_error(self.peek().line, &format!("Attendu une longueur de tableau entière (found {:?})", self.peek().token_type));
+                Err(self.peek().line)
+            }
+        }
+    }
+    
+    // <T, U> d'un turbofish, le '::' étant déjà consommé
+    fn type_arguments(&mut self) -> Result<Vec<Type>, usize> {
+        self.consume(TokenType::Less, "Attendu '<' après '::'")?;
+        let mut type_args = Vec::new();
+        while !self.check(TokenType::Greater) && !self.is_at_end() {
+            type_args.push(self.type_annotation()?);
+            if !self.match_token(TokenType::Comma) {
+                break;
+            }
+        }
+        self.consume(TokenType::Greater, "Attendu '>' après les arguments génériques")?;
+        Ok(type_args)
+    }
+    
+    // f::<T, U>(args), le '::' étant déjà consommé
+    fn turbofish_call(&mut self, name: String) -> Result<Expr, usize> {
+        let type_args = self.type_arguments()?;
+        if name == "None" {
+            return Ok(Expr::FunctionCall(name, type_args, Vec::new()));
+        }
+        
+        if !self.check(TokenType::LeftParen) {
+            self.error_handler.report_error(self.peek().line, &format!("Attendu '(' après {}::<...> (found {:?})", name, self.peek().token_type));
+            return Err(self.peek().line);
+        }
+        let arguments = self.arguments()?;
+        Ok(Expr::FunctionCall(name, type_args, arguments))
+    }
+    
+    // Liste d'arguments entre parenthèses
+    fn arguments(&mut self) -> Result<Vec<Expr>, usize> {
+        self.consume(TokenType::LeftParen, "Attendu '('")?;
+        
+        let mut arguments = Vec::new();
+        if !self.check(TokenType::RightParen) {
+            loop {
+                arguments.push(self.expression()?);
+                
+                if !self.match_token(TokenType::Comma) {
+                    break;
+                }
+            }
+        }
+        
+        self.consume(TokenType::RightParen, "Attendu ')' après les arguments")?;
+        Ok(arguments)
+    }
+    
+    // `Nom {` suivi de `}` ou de `champ:` / `champ,` / `champ }`
+    fn is_struct_literal_start(&self) -> bool {
+        match self.tokens.get(self.current + 1).map(|t| &t.token_type) {
+            Some(TokenType::RightBrace) => true,
+            Some(TokenType::Identifier(_)) => matches!(
+                self.tokens.get(self.current + 2).map(|t| &t.token_type),
+                Some(TokenType::Colon) | Some(TokenType::Comma) | Some(TokenType::RightBrace)
+            ),
+            _ => false,
+        }
+    }
+    
+    fn struct_literal(&mut self, name: String) -> Result<Expr, usize> {
+        self.consume(TokenType::LeftBrace, "Attendu '{' après le nom de la structure")?;
+        
+        let mut fields = Vec::new();
+        while !self.check(TokenType::RightBrace) && !self.is_at_end() {
+            let field_name = self.identifier("Attendu le nom d'un champ")?;
+            // Forme abrégée `Point { x, y }`
+            let value = if self.match_token(TokenType::Colon) {
+                self.expression()?
+            } else {
+                Expr::Variable(field_name.clone())
+            };
+            fields.push((field_name, value));
+            
+            if !self.match_token(TokenType::Comma) {
+                break;
+            }
+        }
+        
+        self.consume(TokenType::RightBrace, "Attendu '}' après les champs de la structure")?;
+        
+        Ok(Expr::StructLiteral(name, fields))
+    }
+    
+    fn identifier(&mut self, message: &str) -> Result<String, usize> {
+        if let TokenType::Identifier(name) = &self.peek().token_type {
+            let name = name.clone();
+            self.advance();
+            Ok(name)
+        } else {
+            self.error_handler.report_error(self.peek().line, &format!("{} (found {:?})", message, self.peek().token_type));
+            Err(self.peek().line)
+        }
+    }
+    
     // Méthodes utilitaires
     fn match_token(&mut self, token_type: TokenType) -> bool {
         if self.check(token_type) {
@@ -647,7 +1993,6 @@ impl<'a> Parser<'a> {
             (TokenType::StringLiteral(_), TokenType::StringLiteral(_)) => true,
             (TokenType::Identifier(_), TokenType::Identifier(_)) => true,
             (TokenType::DoubleColon, TokenType::DoubleColon) => true,
-            (TokenType::New, TokenType::New) => true,
             (a, b) => std::mem::discriminant(a) == std::mem::discriminant(b),
         }
     }
@@ -674,6 +2019,20 @@ impl<'a> Parser<'a> {
         &self.tokens[self.current - 1]
     }
     
+    // Consomme une durée de vie ('a, '_, 'static), sans effet sur le type
+    fn skip_lifetime(&mut self) -> bool {
+        if matches!(self.peek().token_type, TokenType::Lifetime(_)) {
+            self.advance();
+            return true;
+        }
+        false
+    }
+
+    // fmt::Result, alias de Result<(), fmt::Error>
+    fn fmt_result() -> Type {
+        Type::Result(Box::new(Type::Void), Box::new(Type::Named(FMT_ERROR.to_string())))
+    }
+
     fn is_at_end(&self) -> bool {
         matches!(self.peek().token_type, TokenType::EOF)
     }
@@ -696,22 +2055,185 @@ impl<'a> Parser<'a> {
                 self.consume(TokenType::Greater, "Expected '>' after Vec type parameter")?;
                 Ok(Type::Vec(Box::new(inner_type)))
             },
+            TokenType::Ampersand => {
+                self.advance();
+                self.skip_lifetime();
+                let mutable = self.match_token(TokenType::Mut);
+                let inner_type = self.type_annotation()?;
+                Ok(Type::Ref(Box::new(inner_type), mutable))
+            },
+            TokenType::LeftBracket => {
+                // Tableau de taille fixe : [T; N], ou tranche [T] (derrière une référence)
+                self.advance();
+                let element_type = self.type_annotation()?;
+                if self.match_token(TokenType::RightBracket) {
+                    return Ok(Type::Slice(Box::new(element_type)));
+                }
+                self.consume(TokenType::Semicolon, "Attendu ';' dans le type tableau [T; N]")?;
+                let length = self.array_length()?;
+                self.consume(TokenType::RightBracket, "Attendu ']' après la longueur du tableau")?;
+                Ok(Type::Array(Box::new(element_type), length))
+            },
+            TokenType::Dyn => {
+                self.advance();
+                let trait_name = self.identifier("Attendu un nom de trait après 'dyn'")?;
+                if Self::is_fn_trait(&trait_name) && self.check(TokenType::LeftParen) {
+                    let (params, return_type) = self.signature_type()?;
+                    return Ok(Type::Closure(params, Box::new(return_type)));
+                }
+                Ok(Type::Dyn(trait_name))
+            },
+            TokenType::Fn => {
+                self.advance();
+                let (params, return_type) = self.signature_type()?;
+                Ok(Type::FnPtr(params, Box::new(return_type)))
+            },
+            TokenType::Impl => {
+                // impl Fn(A) -> R : seuls les traits de fermeture sont admis après `impl`
+                self.advance();
+                let line = self.peek().line;
+                let trait_name = self.identifier("Attendu un nom de trait après 'impl'")?;
+                if !Self::is_fn_trait(&trait_name) {
+                    self.error_handler.report_error(line, &format!("`impl {}` : seuls Fn, FnMut et FnOnce sont admis dans un type `impl Trait`", trait_name));
+                    return Err(line);
+                }
+                let (params, return_type) = self.signature_type()?;
+                Ok(Type::Closure(params, Box::new(return_type)))
+            },
+            TokenType::LeftParen => {
+                // Type unité (), type parenthésé (T) ou tuple (T, U)
+                self.advance();
+                let mut elements = Vec::new();
+                let mut trailing_comma = false;
+                while !self.check(TokenType::RightParen) && !self.is_at_end() {
+                    elements.push(self.type_annotation()?);
+                    trailing_comma = self.match_token(TokenType::Comma);
+                    if !trailing_comma {
+                        break;
+                    }
+                }
+                self.consume(TokenType::RightParen, "Attendu ')' après le type")?;
+                
+                match elements.len() {
+                    0 => Ok(Type::Void),
+                    1 if !trailing_comma => Ok(elements.remove(0)),
+                    _ => Ok(Type::Tuple(elements)),
+                }
+            },
             TokenType::Identifier(type_name) => {
-                let type_name = type_name.clone(); // Clone the string to avoid borrowing issues
+                let mut type_name = type_name.clone(); // Clone the string to avoid borrowing issues
                 self.advance();
+
+                // Chemin (std::fmt::Result, fmt::Formatter) : seuls les éléments de std::fmt gardent leur module,
+                // les autres sont désignés par leur dernier segment
+                if self.check(TokenType::DoubleColon) {
+                    let mut segments = vec![type_name];
+                    while self.match_token(TokenType::DoubleColon) {
+                        segments.push(self.identifier("Attendu un nom de type après '::'")?);
+                    }
+                    let segments: Vec<&str> = segments.iter().map(String::as_str).skip_while(|s| matches!(*s, "std" | "core")).collect();
+                    match segments.as_slice() {
+                        ["fmt", "Result"] => return Ok(Self::fmt_result()),
+                        ["fmt", "Error"] => return Ok(Type::Named(FMT_ERROR.to_string())),
+                        ["fmt", "Formatter"] => type_name = FORMATTER.to_string(),
+                        [.., last] => type_name = last.to_string(),
+                        [] => return Err(self.previous().line),
+                    }
+                }
+
                 match type_name.as_str() {
                     "i8" => Ok(Type::I8),
                     "i16" => Ok(Type::I16),
                     "i32" => Ok(Type::I32),
                     "i64" => Ok(Type::I64),
                     "i128" => Ok(Type::I128),
+                    "u8" => Ok(Type::U8),
                     "f32" => Ok(Type::F32),
                     "f64" => Ok(Type::F64),
+                    "bool" => Ok(Type::Bool),
+                    "char" => Ok(Type::Char),
                     "String" => Ok(Type::String),
-                    _ => {
-                        self.error_handler.report_error(self.peek().line, &format!("Type inconnu: {}", type_name));
-                        Err(self.peek().line)
-                    }
+                    "str" => Ok(Type::Str),
+                    "Box" => {
+                        self.consume(TokenType::Less, "Attendu '<' après 'Box'")?;
+                        let inner_type = self.type_annotation()?;
+                        self.consume(TokenType::Greater, "Attendu '>' après le paramètre de Box")?;
+                        Ok(Type::Box(Box::new(inner_type)))
+                    },
+                    "Rc" | "RefCell" => {
+                        self.consume(TokenType::Less, &format!("Attendu '<' après '{}'", type_name))?;
+                        let inner_type = Box::new(self.type_annotation()?);
+                        self.consume(TokenType::Greater, &format!("Attendu '>' après le paramètre de {}", type_name))?;
+                        Ok(if type_name == "Rc" { Type::Rc(inner_type) } else { Type::RefCell(inner_type) })
+                    },
+                    "HashMap" => {
+                        self.consume(TokenType::Less, "Attendu '<' après 'HashMap'")?;
+                        let key_type = self.type_annotation()?;
+                        self.consume(TokenType::Comma, "Attendu ',' entre les paramètres de HashMap")?;
+                        let value_type = self.type_annotation()?;
+                        self.consume(TokenType::Greater, "Attendu '>' après les paramètres de HashMap")?;
+                        Ok(Type::HashMap(Box::new(key_type), Box::new(value_type)))
+                    },
+                    "HashSet" => {
+                        self.consume(TokenType::Less, "Attendu '<' après 'HashSet'")?;
+                        let element_type = self.type_annotation()?;
+                        self.consume(TokenType::Greater, "Attendu '>' après le paramètre de HashSet")?;
+                        Ok(Type::HashSet(Box::new(element_type)))
+                    },
+                    "Range" | "RangeInclusive" => {
+                        self.consume(TokenType::Less, &format!("Attendu '<' après '{}'", type_name))?;
+                        let inner_type = self.type_annotation()?;
+                        self.consume(TokenType::Greater, &format!("Attendu '>' après le paramètre de {}", type_name))?;
+                        Ok(Type::Range(Box::new(inner_type), type_name == "RangeInclusive"))
+                    },
+                    "Option" => {
+                        self.consume(TokenType::Less, "Attendu '<' après 'Option'")?;
+                        let inner_type = self.type_annotation()?;
+                        self.consume(TokenType::Greater, "Attendu '>' après le paramètre d'Option")?;
+                        Ok(Type::Option(Box::new(inner_type)))
+                    },
+                    // `Result` seul : fmt::Result importé par `use std::fmt::Result;`
+                    "Result" if !self.check(TokenType::Less) => Ok(Self::fmt_result()),
+                    // Formatter<'_> des implémentations de Display et Debug
+                    "Formatter" | FORMATTER => {
+                        if self.match_token(TokenType::Less) {
+                            self.skip_lifetime();
+                            self.consume(TokenType::Greater, "Attendu '>' après la durée de vie de Formatter")?;
+                        }
+                        Ok(Type::Named(FORMATTER.to_string()))
+                    },
+                    "Result" => {
+                        self.consume(TokenType::Less, "Attendu '<' après 'Result'")?;
+                        let ok_type = self.type_annotation()?;
+                        self.consume(TokenType::Comma, "Attendu ',' entre les paramètres de Result")?;
+                        let err_type = self.type_annotation()?;
+                        self.consume(TokenType::Greater, "Attendu '>' après les paramètres de Result")?;
+                        Ok(Type::Result(Box::new(ok_type), Box::new(err_type)))
+                    },
+                    // Structure générique appliquée : Nom<T, U>
+                    _ if self.check(TokenType::Less) => {
+                        self.advance();
+                        let mut args = Vec::new();
+                        while !self.check(TokenType::Greater) && !self.is_at_end() {
+                            if self.skip_lifetime() {
+                                if !self.match_token(TokenType::Comma) {
+                                    break;
+                                }
+                                continue;
+                            }
+                            args.push(self.type_annotation()?);
+                            if !self.match_token(TokenType::Comma) {
+                                break;
+                            }
+                        }
+                        self.consume(TokenType::Greater, "Attendu '>' après les arguments génériques")?;
+                        if args.is_empty() {
+                            return Ok(Type::Named(type_name)); // Seulement des durées de vie : Nom<'a>
+                        }
+                        Ok(Type::Generic(type_name, args))
+                    },
+                    // Les noms de structures sont vérifiés lors de la génération de code
+                    _ => Ok(Type::Named(type_name)),
                 }
             },
             _ => {
@@ -0,0 +1,2097 @@
+// Routines d'exécution écrites une seule fois en assembleur et ajoutées au programme généré
+// lorsqu'il en a besoin. Elles suivent la convention d'appel System V AMD64.
+//
+// Un vecteur est un triplet (pointeur, longueur, capacité) de 24 octets ; les routines `__seq_*`
+// ne reçoivent que le pointeur et la longueur et servent donc aussi aux tableaux.
+
+// Chaînes utilisées par les paniques ; `panic_source` est émise par le générateur de code
+pub const PANIC_DATA: &str = r#"    panic_header db "thread 'main' panicked at %s:%ld:", 10, 0
+    panic_bounds_format db "thread 'main' panicked at %s:%ld:", 10, "index out of bounds: the len is %ld but the index is %ld", 10, 0
+    panic_insert_format db "insertion index (is %ld) should be <= len (is %ld)", 10, 0
+    panic_remove_format db "removal index (is %ld) should be < len (is %ld)", 10, 0
+    panic_unwrap_none db "called `Option::unwrap()` on a `None` value", 10, 0
+    panic_already_borrowed db "RefCell already borrowed", 10, 0
+    panic_already_mutably_borrowed db "RefCell already mutably borrowed", 10, 0
+    panic_message db "%.*s", 10, 0
+    panic_slice_order db "slice index starts at %ld but ends at %ld", 10, 0
+    panic_slice_end db "range end index %ld out of range for slice of length %ld", 10, 0
+    panic_slice_start db "range start index %ld out of range for slice of length %ld", 10, 0
+    panic_str_bounds db "byte index %ld is out of bounds of string of length %ld", 10, 0
+    panic_str_order db "begin <= end (%ld <= %ld) when slicing string", 10, 0
+    panic_str_boundary db "byte index %ld is not a char boundary", 10, 0
+"#;
+
+// Compteur des blocs alloués sur le tas et non libérés, vérifié à la fin de main (compilation de débogage)
+pub const MEMORY_DATA: &str = r#"    live_allocations dq 0
+    leak_format db "fuite de mémoire : %ld allocation(s) non libérée(s) à la fin du programme", 10, 0
+"#;
+
+// Allocation sur le tas : toutes les allocations du programme passent par `__alloc`, `__realloc` et `__free`.
+// En compilation de débogage, elles tiennent le compte des blocs vivants, que `__leak_report` signale
+// sur la sortie d'erreur s'il n'est pas nul à la fin de main.
+pub const MEMORY: &str = r#"
+; Alloue rdi octets
+__alloc:
+    push rbp
+    mov rbp, rsp
+    call malloc
+    inc QWORD [rel live_allocations]
+    pop rbp
+    ret
+
+; Réalloue le bloc rdi (nul pour un nouveau bloc) à rsi octets
+__realloc:
+    push rbp
+    mov rbp, rsp
+    test rdi, rdi
+    jnz __realloc_existing
+    inc QWORD [rel live_allocations]
+__realloc_existing:
+    call realloc
+    pop rbp
+    ret
+
+; Libère le bloc rdi (sans effet pour un pointeur nul)
+__free:
+    test rdi, rdi
+    jz __free_null
+    dec QWORD [rel live_allocations]
+    jmp free
+__free_null:
+    ret
+
+; Signale les blocs qui n'ont pas été libérés
+__leak_report:
+    mov rdx, QWORD [rel live_allocations]
+    test rdx, rdx
+    jz __leak_report_done
+    push rbp
+    mov rbp, rsp
+    lea rsi, [rel leak_format]
+    mov edi, 2  ; stderr
+    xor eax, eax
+    call dprintf
+    pop rbp
+__leak_report_done:
+    ret
+"#;
+
+// Compilation optimisée : les allocations vont directement à la libc, sans compte ni rapport
+pub const MEMORY_RELEASE: &str = "%define __alloc malloc\n%define __realloc realloc\n%define __free free\n";
+
+pub const PANIC: &str = r#"
+; Panique : ligne dans rdi, format du message dans rsi, arguments du message dans rdx et rcx
+__panic:
+    push rbp
+    mov rbp, rsp
+    push rbx
+    push r12
+    push r13
+    push r14
+    mov rbx, rsi
+    mov r12, rdx
+    mov r13, rcx
+    mov rcx, rdi
+    lea rdx, [rel panic_source]
+    lea rsi, [rel panic_header]
+    mov edi, 2  ; stderr
+    xor eax, eax
+    call dprintf
+    mov edi, 2
+    mov rsi, rbx
+    mov rdx, r12
+    mov rcx, r13
+    xor eax, eax
+    call dprintf
+    mov edi, 101
+    call exit
+
+; Index hors limites : ligne dans rdi, longueur dans rsi, index dans rdx
+__panic_bounds_check:
+    push rbp
+    mov rbp, rsp
+    mov r9, rdx  ; Index
+    mov r8, rsi  ; Longueur
+    mov rcx, rdi  ; Ligne
+    lea rdx, [rel panic_source]
+    lea rsi, [rel panic_bounds_format]
+    mov edi, 2  ; stderr
+    xor eax, eax
+    call dprintf
+    mov edi, 101
+    call exit
+"#;
+
+pub const VEC: &str = r#"
+; Garantit la place pour rsi éléments de plus : vecteur dans rdi, taille d'un élément dans rdx.
+; La capacité double (au moins 4, au moins la longueur requise) et le tampon est réalloué.
+__vec_reserve:
+    push rbp
+    mov rbp, rsp
+    push rbx
+    push r12
+    mov rbx, rdi
+    mov r12, rdx
+    mov rax, QWORD [rbx+8]
+    add rax, rsi  ; Longueur requise
+    cmp rax, QWORD [rbx+16]
+    jbe __vec_reserve_done
+    mov rcx, QWORD [rbx+16]
+    add rcx, rcx
+    cmp rcx, rax
+    cmovb rcx, rax
+    mov eax, 4
+    cmp rcx, rax
+    cmovb rcx, rax
+    mov QWORD [rbx+16], rcx
+    mov rsi, rcx
+    imul rsi, r12
+    mov rdi, QWORD [rbx]
+    call __realloc
+    mov QWORD [rbx], rax
+__vec_reserve_done:
+    pop r12
+    pop rbx
+    pop rbp
+    ret
+
+; Retire le dernier élément : vecteur dans rdi, taille dans rsi, destination dans rdx.
+; Renvoie 1 dans rax si un élément a été copié, 0 si le vecteur était vide.
+__vec_pop:
+    mov rax, QWORD [rdi+8]
+    test rax, rax
+    jz __vec_pop_empty
+    push rbp
+    mov rbp, rsp
+    dec rax
+    mov QWORD [rdi+8], rax
+    imul rax, rsi
+    add rax, QWORD [rdi]
+    mov rdi, rdx
+    mov rdx, rsi
+    mov rsi, rax
+    call memcpy
+    mov eax, 1
+    pop rbp
+    ret
+__vec_pop_empty:
+    xor eax, eax
+    ret
+
+; Ouvre une place à l'index rsi : vecteur dans rdi, taille dans rdx, ligne dans rcx.
+; Renvoie dans rax l'adresse de la place, où l'appelant range la valeur.
+__vec_insert:
+    push rbp
+    mov rbp, rsp
+    push rbx
+    push r12
+    push r13
+    push r14
+    mov rbx, rdi
+    mov r12, rsi
+    mov r13, rdx
+    cmp r12, QWORD [rbx+8]
+    jbe __vec_insert_ok
+    mov rdi, rcx
+    lea rsi, [rel panic_insert_format]
+    mov rdx, r12
+    mov rcx, QWORD [rbx+8]
+    call __panic
+__vec_insert_ok:
+    mov rdi, rbx
+    mov esi, 1
+    mov rdx, r13
+    call __vec_reserve
+    mov r14, r12
+    imul r14, r13
+    add r14, QWORD [rbx]  ; Adresse de la place
+    lea rdi, [r14+r13]
+    mov rsi, r14
+    mov rdx, QWORD [rbx+8]
+    sub rdx, r12
+    imul rdx, r13
+    call memmove
+    inc QWORD [rbx+8]
+    mov rax, r14
+    pop r14
+    pop r13
+    pop r12
+    pop rbx
+    pop rbp
+    ret
+
+; Retire l'élément d'index rsi : vecteur dans rdi, taille dans rdx, ligne dans rcx, destination dans r8
+__vec_remove:
+    push rbp
+    mov rbp, rsp
+    push rbx
+    push r12
+    push r13
+    push r14
+    mov rbx, rdi
+    mov r12, rsi
+    mov r13, rdx
+    mov r14, r8
+    cmp r12, QWORD [rbx+8]
+    jb __vec_remove_ok
+    mov rdi, rcx
+    lea rsi, [rel panic_remove_format]
+    mov rdx, r12
+    mov rcx, QWORD [rbx+8]
+    call __panic
+__vec_remove_ok:
+    mov rsi, r12
+    imul rsi, r13
+    add rsi, QWORD [rbx]
+    mov rdi, r14
+    mov rdx, r13
+    call memcpy
+    mov rdi, r12
+    imul rdi, r13
+    add rdi, QWORD [rbx]
+    lea rsi, [rdi+r13]
+    mov rdx, QWORD [rbx+8]
+    sub rdx, r12
+    dec rdx
+    imul rdx, r13
+    call memmove
+    dec QWORD [rbx+8]
+    pop r14
+    pop r13
+    pop r12
+    pop rbx
+    pop rbp
+    ret
+
+; Raccourcit le vecteur rdi à rsi éléments (sans effet si rsi >= longueur)
+__vec_truncate:
+    cmp rsi, QWORD [rdi+8]
+    jae __vec_truncate_done
+    mov QWORD [rdi+8], rsi
+__vec_truncate_done:
+    ret
+
+; Ajoute rdx éléments copiés depuis rsi : vecteur dans rdi, taille dans rcx
+__vec_extend:
+    push rbp
+    mov rbp, rsp
+    push rbx
+    push r12
+    push r13
+    push r14
+    mov rbx, rdi
+    mov r12, rsi
+    mov r13, rdx
+    mov r14, rcx
+    mov rsi, r13
+    mov rdx, r14
+    call __vec_reserve
+    mov rdi, QWORD [rbx+8]
+    imul rdi, r14
+    add rdi, QWORD [rbx]
+    mov rsi, r12
+    mov rdx, r13
+    imul rdx, r14
+    call memmove
+    add QWORD [rbx+8], r13
+    pop r14
+    pop r13
+    pop r12
+    pop rbx
+    pop rbp
+    ret
+
+; Recherche la valeur pointée par rdx parmi rsi éléments de taille rcx à partir de rdi, avec la
+; fonction de comparaison r8 (au format de qsort) ; renvoie 1 ou 0 dans rax
+__seq_contains:
+    push rbp
+    mov rbp, rsp
+    push rbx
+    push r12
+    push r13
+    push r14
+    push r15
+    sub rsp, 8
+    mov rbx, rdi
+    mov r12, rsi
+    mov r13, rdx
+    mov r14, rcx
+    mov r15, r8
+__seq_contains_loop:
+    test r12, r12
+    jz __seq_contains_no
+    mov rdi, rbx
+    mov rsi, r13
+    call r15
+    test eax, eax
+    jz __seq_contains_yes
+    add rbx, r14
+    dec r12
+    jmp __seq_contains_loop
+__seq_contains_yes:
+    mov eax, 1
+    jmp __seq_contains_done
+__seq_contains_no:
+    xor eax, eax
+__seq_contains_done:
+    add rsp, 8
+    pop r15
+    pop r14
+    pop r13
+    pop r12
+    pop rbx
+    pop rbp
+    ret
+
+; Échange les éléments d'index rdx et rcx : données dans rdi, longueur dans rsi,
+; taille dans r8, ligne dans r9 ; panique si l'un des index est hors limites
+__seq_swap:
+    push rbp
+    mov rbp, rsp
+    cmp rdx, rsi
+    jb __seq_swap_first_ok
+    mov rdi, r9
+    call __panic_bounds_check
+__seq_swap_first_ok:
+    cmp rcx, rsi
+    jb __seq_swap_second_ok
+    mov rdx, rcx
+    mov rdi, r9
+    call __panic_bounds_check
+__seq_swap_second_ok:
+    imul rdx, r8
+    add rdx, rdi
+    imul rcx, r8
+    add rcx, rdi
+__seq_swap_loop:
+    test r8, r8
+    jz __seq_swap_done
+    mov al, BYTE [rdx]
+    mov r10b, BYTE [rcx]
+    mov BYTE [rdx], r10b
+    mov BYTE [rcx], al
+    inc rdx
+    inc rcx
+    dec r8
+    jmp __seq_swap_loop
+__seq_swap_done:
+    pop rbp
+    ret
+
+; Inverse l'ordre de rsi éléments de taille rdx à partir de rdi
+__seq_reverse:
+    test rsi, rsi
+    jz __seq_reverse_done
+    mov r8, rdi  ; Premier élément
+    lea r9, [rsi-1]
+    imul r9, rdx
+    add r9, rdi  ; Dernier élément
+__seq_reverse_outer:
+    cmp r8, r9
+    jae __seq_reverse_done
+    xor r10, r10
+__seq_reverse_inner:
+    cmp r10, rdx
+    jae __seq_reverse_next
+    mov al, BYTE [r8+r10]
+    mov cl, BYTE [r9+r10]
+    mov BYTE [r8+r10], cl
+    mov BYTE [r9+r10], al
+    inc r10
+    jmp __seq_reverse_inner
+__seq_reverse_next:
+    add r8, rdx
+    sub r9, rdx
+    jmp __seq_reverse_outer
+__seq_reverse_done:
+    ret
+
+; Vérifie les bornes d'une tranche : début dans rdi, fin dans rsi, longueur dans rdx, ligne dans rcx ;
+; r8 vaut 0 si la fin était omise (v[a..]), ce qui change le message de panique
+__slice_check:
+    push rbp
+    mov rbp, rsp
+    cmp rdi, rsi
+    ja __slice_check_order
+    cmp rsi, rdx
+    ja __slice_check_end
+    pop rbp
+    ret
+__slice_check_order:
+    test r8, r8
+    jz __slice_check_start
+    mov rdx, rsi
+    mov rsi, rdi
+    mov rdi, rcx
+    mov rcx, rdx
+    mov rdx, rsi
+    lea rsi, [rel panic_slice_order]
+    call __panic
+__slice_check_start:
+    mov rsi, rdi
+    mov rdi, rcx
+    mov rcx, rdx
+    mov rdx, rsi
+    lea rsi, [rel panic_slice_start]
+    call __panic
+__slice_check_end:
+    mov rdi, rcx
+    mov rcx, rdx
+    mov rdx, rsi
+    lea rsi, [rel panic_slice_end]
+    call __panic
+
+; Trie rsi éléments de taille rdx à partir de rdi avec la fonction de comparaison rcx
+__seq_sort:
+    push rbp
+    mov rbp, rsp
+    call qsort
+    pop rbp
+    ret
+
+; Trie de façon stable rsi éléments de taille rdx à partir de rdi (tri fusion ascendant, les séquences
+; fusionnées passant par un tampon). La comparaison rcx reçoit les adresses de deux éléments dans rdi et
+; rsi et l'environnement r8 dans r10, et renvoie dans al l'étiquette d'un Ordering (1 Less, 2 Equal,
+; 3 Greater). [rbp-48] tampon, [rbp-56] largeur des séquences, [rbp-64] début, [rbp-72] milieu et
+; [rbp-80] fin de la paire fusionnée, [rbp-88] et [rbp-96] éléments courants des deux séquences,
+; [rbp-104] destination dans le tampon.
+__seq_sort_by:
+    push rbp
+    mov rbp, rsp
+    push rbx
+    push r12
+    push r13
+    push r14
+    push r15
+    sub rsp, 72
+    mov rbx, rdi
+    mov r12, rsi
+    mov r13, rdx
+    mov r14, rcx
+    mov r15, r8
+    cmp r12, 1
+    jbe __seq_sort_by_done
+    mov rdi, r12
+    imul rdi, r13
+    call __alloc
+    mov QWORD [rbp-48], rax
+    mov QWORD [rbp-56], 1
+__seq_sort_by_pass:
+    mov rax, QWORD [rbp-56]
+    cmp rax, r12
+    jae __seq_sort_by_end
+    mov QWORD [rbp-64], 0
+__seq_sort_by_run:
+    mov rax, QWORD [rbp-64]
+    cmp rax, r12
+    jae __seq_sort_by_copy_back
+    add rax, QWORD [rbp-56]
+    cmp rax, r12
+    cmova rax, r12
+    mov QWORD [rbp-72], rax
+    add rax, QWORD [rbp-56]
+    cmp rax, r12
+    cmova rax, r12
+    mov QWORD [rbp-80], rax
+    mov rax, QWORD [rbp-64]
+    mov QWORD [rbp-88], rax
+    mov QWORD [rbp-104], rax
+    mov rax, QWORD [rbp-72]
+    mov QWORD [rbp-96], rax
+__seq_sort_by_merge:
+    mov rax, QWORD [rbp-88]
+    cmp rax, QWORD [rbp-72]
+    jae __seq_sort_by_left_done
+    mov rax, QWORD [rbp-96]
+    cmp rax, QWORD [rbp-80]
+    jae __seq_sort_by_take_left
+    mov rdi, QWORD [rbp-88]
+    imul rdi, r13
+    add rdi, rbx
+    mov rsi, QWORD [rbp-96]
+    imul rsi, r13
+    add rsi, rbx
+    mov r10, r15
+    call r14
+    cmp al, 3  ; Seul un élément de droite strictement plus petit passe devant : le tri reste stable
+    je __seq_sort_by_take_right
+__seq_sort_by_take_left:
+    mov rsi, QWORD [rbp-88]
+    inc QWORD [rbp-88]
+    jmp __seq_sort_by_store
+__seq_sort_by_left_done:
+    mov rax, QWORD [rbp-96]
+    cmp rax, QWORD [rbp-80]
+    jae __seq_sort_by_next_run
+__seq_sort_by_take_right:
+    mov rsi, QWORD [rbp-96]
+    inc QWORD [rbp-96]
+__seq_sort_by_store:
+    imul rsi, r13
+    add rsi, rbx
+    mov rdi, QWORD [rbp-104]
+    imul rdi, r13
+    add rdi, QWORD [rbp-48]
+    mov rdx, r13
+    call memcpy
+    inc QWORD [rbp-104]
+    jmp __seq_sort_by_merge
+__seq_sort_by_next_run:
+    mov rax, QWORD [rbp-56]
+    add rax, rax
+    add QWORD [rbp-64], rax
+    jmp __seq_sort_by_run
+__seq_sort_by_copy_back:
+    mov rdi, rbx
+    mov rsi, QWORD [rbp-48]
+    mov rdx, r12
+    imul rdx, r13
+    call memcpy
+    shl QWORD [rbp-56], 1
+    jmp __seq_sort_by_pass
+__seq_sort_by_end:
+    mov rdi, QWORD [rbp-48]
+    call __free
+__seq_sort_by_done:
+    add rsp, 72
+    pop r15
+    pop r14
+    pop r13
+    pop r12
+    pop rbx
+    pop rbp
+    ret
+
+; Comparaisons d'entiers pour qsort : renvoient -1, 0 ou 1
+__cmp_u8:
+    movzx eax, BYTE [rdi]
+    movzx ecx, BYTE [rsi]
+    jmp __cmp_result
+__cmp_i8:
+    movsx rax, BYTE [rdi]
+    movsx rcx, BYTE [rsi]
+    jmp __cmp_result
+__cmp_i16:
+    movsx rax, WORD [rdi]
+    movsx rcx, WORD [rsi]
+    jmp __cmp_result
+__cmp_i32:
+    movsxd rax, DWORD [rdi]
+    movsxd rcx, DWORD [rsi]
+    jmp __cmp_result
+__cmp_i128:
+    mov rax, QWORD [rdi+8]
+    mov rcx, QWORD [rsi+8]
+    cmp rax, rcx
+    jne __cmp_result
+    mov rax, QWORD [rdi]
+    mov rcx, QWORD [rsi]
+    cmp rax, rcx
+    seta al
+    setb cl
+    movzx eax, al
+    movzx ecx, cl
+    sub eax, ecx
+    ret
+__cmp_i64:
+    mov rax, QWORD [rdi]
+    mov rcx, QWORD [rsi]
+__cmp_result:
+    cmp rax, rcx
+    setg al
+    setl cl
+    movzx eax, al
+    movzx ecx, cl
+    sub eax, ecx
+    ret
+"#;
+
+// Messages (Display) et causes (Debug) d'un ParseIntError, indexés par son champ `kind`
+pub const STRING_DATA: &str = r#"    parse_error_empty db "cannot parse integer from empty string", 0
+    parse_error_invalid db "invalid digit found in string", 0
+    parse_error_pos db "number too large to fit in target type", 0
+    parse_error_neg db "number too small to fit in target type", 0
+    parse_error_kind_empty db "Empty", 0
+    parse_error_kind_invalid db "InvalidDigit", 0
+    parse_error_kind_pos db "PosOverflow", 0
+    parse_error_kind_neg db "NegOverflow", 0
+    align 8
+    parse_error_messages dq parse_error_empty, parse_error_invalid, parse_error_pos, parse_error_neg
+    parse_error_kinds dq parse_error_kind_empty, parse_error_kind_invalid, parse_error_kind_pos, parse_error_kind_neg
+"#;
+
+// Une String a la disposition d'un Vec<u8> et réutilise `__vec_reserve` et `__vec_extend`
+pub const STRING: &str = r#"
+; Crée dans rdi une chaîne contenant une copie des rdx octets pointés par rsi
+__string_from:
+    push rbp
+    mov rbp, rsp
+    push rbx
+    push r12
+    push r13
+    push r14
+    mov rbx, rdi
+    mov r12, rsi
+    mov r13, rdx
+    mov QWORD [rbx+8], r13
+    mov QWORD [rbx+16], r13
+    mov rdi, r13
+    call __alloc
+    mov QWORD [rbx], rax
+    mov rdi, rax
+    mov rsi, r12
+    mov rdx, r13
+    call memcpy
+    pop r14
+    pop r13
+    pop r12
+    pop rbx
+    pop rbp
+    ret
+
+; Encode le caractère edi en UTF-8 à l'adresse rsi ; renvoie le nombre d'octets dans rax
+__utf8_encode:
+    cmp edi, 0x80
+    jae __utf8_encode_2
+    mov BYTE [rsi], dil
+    mov eax, 1
+    ret
+__utf8_encode_2:
+    cmp edi, 0x800
+    jae __utf8_encode_3
+    mov eax, edi
+    shr eax, 6
+    or al, 0xC0
+    mov BYTE [rsi], al
+    mov eax, 2
+    jmp __utf8_encode_tail1
+__utf8_encode_3:
+    cmp edi, 0x10000
+    jae __utf8_encode_4
+    mov eax, edi
+    shr eax, 12
+    or al, 0xE0
+    mov BYTE [rsi], al
+    mov eax, 3
+    jmp __utf8_encode_tail2
+__utf8_encode_4:
+    mov eax, edi
+    shr eax, 18
+    or al, 0xF0
+    mov BYTE [rsi], al
+    mov ecx, edi
+    shr ecx, 12
+    and cl, 0x3F
+    or cl, 0x80
+    mov BYTE [rsi+1], cl
+    mov eax, 4
+__utf8_encode_tail2:
+    mov ecx, edi
+    shr ecx, 6
+    and cl, 0x3F
+    or cl, 0x80
+    mov BYTE [rsi+rax-2], cl
+__utf8_encode_tail1:
+    mov ecx, edi
+    and cl, 0x3F
+    or cl, 0x80
+    mov BYTE [rsi+rax-1], cl
+    ret
+
+; Ajoute le caractère esi, encodé en UTF-8, à la chaîne rdi
+__string_push:
+    push rbp
+    mov rbp, rsp
+    push rbx
+    sub rsp, 8
+    mov rbx, rdi
+    mov edi, esi
+    lea rsi, [rbp-16]
+    call __utf8_encode
+    mov rdi, rbx
+    lea rsi, [rbp-16]
+    mov rdx, rax
+    mov ecx, 1
+    call __vec_extend
+    add rsp, 8
+    pop rbx
+    pop rbp
+    ret
+
+; Vérifie les bornes d'une sous-chaîne : début dans rdi, fin dans rsi, longueur dans rdx, ligne dans rcx,
+; octets dans r9. Chaque borne doit aussi tomber au début d'un caractère UTF-8 (pas sur un octet 10xxxxxx).
+__str_slice_check:
+    push rbp
+    mov rbp, rsp
+    mov r8, rdi
+    cmp rdi, rdx
+    ja __str_slice_check_bounds
+    mov r8, rsi
+    cmp rsi, rdx
+    ja __str_slice_check_bounds
+    cmp rdi, rsi
+    ja __str_slice_check_order
+    mov r8, rdi
+    cmp r8, rdx
+    je __str_slice_check_end
+    mov al, BYTE [r9+r8]
+    and al, 0xC0
+    cmp al, 0x80
+    je __str_slice_check_boundary
+__str_slice_check_end:
+    mov r8, rsi
+    cmp r8, rdx
+    je __str_slice_check_ok
+    mov al, BYTE [r9+r8]
+    and al, 0xC0
+    cmp al, 0x80
+    je __str_slice_check_boundary
+__str_slice_check_ok:
+    pop rbp
+    ret
+__str_slice_check_bounds:
+    mov rdi, rcx
+    mov rcx, rdx
+    mov rdx, r8
+    lea rsi, [rel panic_str_bounds]
+    call __panic
+__str_slice_check_order:
+    mov r8, rsi
+    mov rdx, rdi
+    mov rdi, rcx
+    mov rcx, r8
+    lea rsi, [rel panic_str_order]
+    call __panic
+__str_slice_check_boundary:
+    mov rdi, rcx
+    mov rdx, r8
+    lea rsi, [rel panic_str_boundary]
+    call __panic
+
+; Compare les octets (rdi, rsi) et (rdx, rcx) dans l'ordre lexicographique : renvoie -1, 0 ou 1
+__str_cmp:
+    push rbp
+    mov rbp, rsp
+    push rbx
+    push r12
+    mov rbx, rsi
+    mov r12, rcx
+    mov rsi, rdx
+    mov rdx, rbx
+    cmp rdx, r12
+    cmova rdx, r12  ; Longueur commune
+    call memcmp
+    test eax, eax
+    jnz __str_cmp_sign
+    cmp rbx, r12  ; Préfixes égaux : la plus courte est la plus petite
+    seta al
+    setb cl
+    movzx eax, al
+    movzx ecx, cl
+    sub eax, ecx
+    jmp __str_cmp_done
+__str_cmp_sign:
+    setg al
+    setl cl
+    movzx eax, al
+    movzx ecx, cl
+    sub eax, ecx
+__str_cmp_done:
+    pop r12
+    pop rbx
+    pop rbp
+    ret
+
+; Ajoute la tranche (rsi, rdx) au Vec<&str> rdi
+__str_push_slice:
+    push rbp
+    mov rbp, rsp
+    push rbx
+    push r12
+    push r13
+    sub rsp, 8
+    mov rbx, rdi
+    mov r12, rsi
+    mov r13, rdx
+    mov esi, 1
+    mov edx, 16
+    call __vec_reserve
+    mov rax, QWORD [rbx+8]
+    shl rax, 4
+    add rax, QWORD [rbx]
+    mov QWORD [rax], r12
+    mov QWORD [rax+8], r13
+    inc QWORD [rbx+8]
+    add rsp, 8
+    pop r13
+    pop r12
+    pop rbx
+    pop rbp
+    ret
+
+; Décode les rsi octets UTF-8 pointés par rdi et ajoute les caractères au Vec<char> rdx
+__str_chars:
+    push rbp
+    mov rbp, rsp
+    push rbx
+    push r12
+    push r13
+    push r14
+    mov rbx, rdi
+    lea r12, [rdi+rsi]  ; Fin des octets
+    mov r13, rdx
+    mov rdi, rdx
+    mov edx, 4
+    call __vec_reserve  ; Au plus un caractère par octet
+    mov r14, QWORD [r13+8]
+    shl r14, 2
+    add r14, QWORD [r13]  ; Prochain emplacement libre
+__str_chars_loop:
+    cmp rbx, r12
+    jae __str_chars_done
+    movzx eax, BYTE [rbx]
+    inc rbx
+    xor ecx, ecx  ; Octets de continuation restants
+    cmp eax, 0x80
+    jb __str_chars_store
+    mov ecx, 1
+    and eax, 0x1F
+    cmp BYTE [rbx-1], 0xE0
+    jb __str_chars_continuation
+    mov ecx, 2
+    and eax, 0x0F
+    cmp BYTE [rbx-1], 0xF0
+    jb __str_chars_continuation
+    mov ecx, 3
+    and eax, 0x07
+__str_chars_continuation:
+    shl eax, 6
+    movzx edx, BYTE [rbx]
+    and edx, 0x3F
+    or eax, edx
+    inc rbx
+    dec ecx
+    jnz __str_chars_continuation
+__str_chars_store:
+    mov DWORD [r14], eax
+    add r14, 4
+    inc QWORD [r13+8]
+    jmp __str_chars_loop
+__str_chars_done:
+    pop r14
+    pop r13
+    pop r12
+    pop rbx
+    pop rbp
+    ret
+
+; Découpe les rsi octets pointés par rdi autour des blancs ; les morceaux non vides sont ajoutés au Vec<&str> rdx
+__str_split_whitespace:
+    push rbp
+    mov rbp, rsp
+    push rbx
+    push r12
+    push r13
+    push r14
+    mov rbx, rdi
+    lea r12, [rdi+rsi]
+    mov r13, rdx
+__str_split_whitespace_skip:
+    cmp rbx, r12
+    jae __str_split_whitespace_done
+    movzx edi, BYTE [rbx]
+    call __char_is_whitespace
+    test eax, eax
+    jz __str_split_whitespace_word
+    inc rbx
+    jmp __str_split_whitespace_skip
+__str_split_whitespace_word:
+    mov r14, rbx  ; Début du mot
+__str_split_whitespace_scan:
+    inc rbx
+    cmp rbx, r12
+    jae __str_split_whitespace_push
+    movzx edi, BYTE [rbx]
+    call __char_is_whitespace
+    test eax, eax
+    jz __str_split_whitespace_scan
+__str_split_whitespace_push:
+    mov rdi, r13
+    mov rsi, r14
+    mov rdx, rbx
+    sub rdx, r14
+    call __str_push_slice
+    jmp __str_split_whitespace_skip
+__str_split_whitespace_done:
+    pop r14
+    pop r13
+    pop r12
+    pop rbx
+    pop rbp
+    ret
+
+; Découpe les rsi octets pointés par rdi autour du motif (rdx, rcx) ; les morceaux sont ajoutés au Vec<&str> r8.
+; Un motif vide laisse la chaîne entière.
+__str_split:
+    push rbp
+    mov rbp, rsp
+    push rbx
+    push r12
+    push r13
+    push r14
+    push r15
+    sub rsp, 8
+    mov rbx, rdi  ; Début du morceau courant
+    lea r12, [rdi+rsi]
+    mov r13, rdx
+    mov r14, rcx
+    mov r15, r8
+    test r14, r14
+    jz __str_split_last
+__str_split_loop:
+    mov rdi, rbx
+    mov rsi, r12
+    sub rsi, rbx
+    mov rdx, r13
+    mov rcx, r14
+    call __str_find
+    cmp rax, -1
+    je __str_split_last
+    mov QWORD [rbp-48], rax
+    mov rdi, r15
+    mov rsi, rbx
+    mov rdx, rax
+    call __str_push_slice
+    add rbx, QWORD [rbp-48]
+    add rbx, r14
+    jmp __str_split_loop
+__str_split_last:
+    mov rdi, r15
+    mov rsi, rbx
+    mov rdx, r12
+    sub rdx, rbx
+    call __str_push_slice
+    add rsp, 8
+    pop r15
+    pop r14
+    pop r13
+    pop r12
+    pop rbx
+    pop rbp
+    ret
+
+; Retire les blancs des rsi octets pointés par rdi : au début si edx & 1, à la fin si edx & 2.
+; Renvoie la sous-chaîne dans rax (octets) et rdx (longueur).
+__str_trim:
+    push rbp
+    mov rbp, rsp
+    push rbx
+    push r12
+    push r13
+    sub rsp, 8
+    mov rbx, rdi
+    lea r12, [rdi+rsi]
+    mov r13d, edx
+    test r13d, 1
+    jz __str_trim_end
+__str_trim_start_loop:
+    cmp rbx, r12
+    jae __str_trim_end
+    movzx edi, BYTE [rbx]
+    call __char_is_whitespace
+    test eax, eax
+    jz __str_trim_end
+    inc rbx
+    jmp __str_trim_start_loop
+__str_trim_end:
+    test r13d, 2
+    jz __str_trim_done
+__str_trim_end_loop:
+    cmp r12, rbx
+    jbe __str_trim_done
+    movzx edi, BYTE [r12-1]
+    call __char_is_whitespace
+    test eax, eax
+    jz __str_trim_done
+    dec r12
+    jmp __str_trim_end_loop
+__str_trim_done:
+    mov rax, rbx
+    mov rdx, r12
+    sub rdx, rbx
+    add rsp, 8
+    pop r13
+    pop r12
+    pop rbx
+    pop rbp
+    ret
+
+; Cherche le motif (rdx, rcx) dans les rsi octets pointés par rdi : renvoie sa position en octets, ou -1
+__str_find:
+    push rbp
+    mov rbp, rsp
+    push rbx
+    push r12
+    push r13
+    push r14
+    push r15
+    sub rsp, 8
+    mov rbx, rdi
+    mov r12, rsi
+    mov r13, rdx
+    mov r14, rcx
+    xor r15d, r15d  ; Position essayée
+__str_find_loop:
+    mov rax, r12
+    sub rax, r15
+    cmp rax, r14
+    jb __str_find_none  ; Il reste moins d'octets que la longueur du motif
+    lea rdi, [rbx+r15]
+    mov rsi, r13
+    mov rdx, r14
+    call memcmp
+    test eax, eax
+    jz __str_find_found
+    inc r15
+    jmp __str_find_loop
+__str_find_found:
+    mov rax, r15
+    jmp __str_find_done
+__str_find_none:
+    mov rax, -1
+__str_find_done:
+    add rsp, 8
+    pop r15
+    pop r14
+    pop r13
+    pop r12
+    pop rbx
+    pop rbp
+    ret
+
+; Les rsi octets pointés par rdi commencent-ils par le motif (rdx, rcx) ? Renvoie 0 ou 1 dans eax
+__str_starts_with:
+    cmp rsi, rcx
+    jb __str_affix_missing
+    push rbp
+    mov rbp, rsp
+    mov rsi, rdx
+    mov rdx, rcx
+    call memcmp
+    pop rbp
+    test eax, eax
+    sete al
+    movzx eax, al
+    ret
+__str_affix_missing:
+    xor eax, eax
+    ret
+
+; Les rsi octets pointés par rdi se terminent-ils par le motif (rdx, rcx) ? Renvoie 0 ou 1 dans eax
+__str_ends_with:
+    cmp rsi, rcx
+    jb __str_affix_missing
+    add rdi, rsi
+    sub rdi, rcx
+    push rbp
+    mov rbp, rsp
+    mov rsi, rdx
+    mov rdx, rcx
+    call memcmp
+    pop rbp
+    test eax, eax
+    sete al
+    movzx eax, al
+    ret
+
+; Crée dans rdi une copie de la chaîne d'en-tête rsi où chaque occurrence de la chaîne d'en-tête rdx
+; est remplacée par celle d'en-tête rcx
+__str_replace:
+    push rbp
+    mov rbp, rsp
+    push rbx
+    push r12
+    push r13
+    push r14
+    push r15
+    sub rsp, 8
+    mov rbx, rdi
+    mov r12, QWORD [rsi]  ; Position courante
+    mov r13, QWORD [rsi+8]
+    add r13, r12  ; Fin des octets
+    mov r14, rdx
+    mov r15, rcx
+    mov QWORD [rbx], 0
+    mov QWORD [rbx+8], 0
+    mov QWORD [rbx+16], 0
+__str_replace_loop:
+    cmp r12, r13
+    jae __str_replace_done
+    mov rcx, QWORD [r14+8]
+    test rcx, rcx
+    jz __str_replace_copy  ; Motif vide : simple copie
+    mov rdi, r12
+    mov rsi, r13
+    sub rsi, r12
+    mov rdx, QWORD [r14]
+    call __str_starts_with
+    test eax, eax
+    jz __str_replace_copy
+    mov rdi, rbx
+    mov rsi, QWORD [r15]
+    mov rdx, QWORD [r15+8]
+    mov ecx, 1
+    call __vec_extend
+    add r12, QWORD [r14+8]
+    jmp __str_replace_loop
+__str_replace_copy:
+    mov rdi, rbx
+    mov rsi, r12
+    mov edx, 1
+    mov ecx, 1
+    call __vec_extend
+    inc r12
+    jmp __str_replace_loop
+__str_replace_done:
+    add rsp, 8
+    pop r15
+    pop r14
+    pop r13
+    pop r12
+    pop rbx
+    pop rbp
+    ret
+
+; Met en majuscules (edx = 1) ou en minuscules (edx = 0) les lettres ASCII des rsi octets pointés par rdi
+__str_ascii_case:
+    test rsi, rsi
+    jz __str_ascii_case_done
+    movzx eax, BYTE [rdi]
+    test edx, edx
+    jz __str_ascii_case_lower
+    lea ecx, [rax-0x61]
+    cmp ecx, 26
+    jae __str_ascii_case_next
+    sub eax, 32
+    jmp __str_ascii_case_store
+__str_ascii_case_lower:
+    lea ecx, [rax-0x41]
+    cmp ecx, 26
+    jae __str_ascii_case_next
+    add eax, 32
+__str_ascii_case_store:
+    mov BYTE [rdi], al
+__str_ascii_case_next:
+    inc rdi
+    dec rsi
+    jmp __str_ascii_case
+__str_ascii_case_done:
+    ret
+
+; Lit un entier décimal dans les rsi octets pointés par rdi, borné par [rdx, rcx] (rdx = 0 pour un type
+; non signé, qui n'accepte pas de signe `-`). Renvoie la valeur dans rax et dans edx 0 en cas de succès,
+; sinon 1 + l'indice de la cause dans parse_error_kinds.
+__str_parse_int:
+    mov r8, rdi
+    lea r9, [rdi+rsi]  ; Fin des octets
+    mov r10, rdx  ; Minimum
+    mov r11, rcx  ; Maximum
+    xor eax, eax
+    mov edx, 1  ; Empty
+    test rsi, rsi
+    jz __str_parse_int_error
+    xor esi, esi  ; Nombre négatif ?
+    movzx ecx, BYTE [r8]
+    cmp ecx, 0x2B  ; '+'
+    je __str_parse_int_sign
+    cmp ecx, 0x2D  ; '-'
+    jne __str_parse_int_digits
+    test r10, r10
+    jz __str_parse_int_digits  ; Type non signé : `-` est un chiffre invalide
+    mov esi, 1
+__str_parse_int_sign:
+    inc r8
+    mov edx, 2  ; InvalidDigit : signe seul
+    cmp r8, r9
+    je __str_parse_int_error
+__str_parse_int_digits:
+    cmp r8, r9
+    jae __str_parse_int_ok
+    movzx ecx, BYTE [r8]
+    sub ecx, 0x30
+    mov edx, 2  ; InvalidDigit
+    cmp ecx, 9
+    ja __str_parse_int_error
+    inc r8
+    imul rax, rax, 10
+    jo __str_parse_int_overflow
+    test esi, esi
+    jnz __str_parse_int_negative
+    add rax, rcx
+    jo __str_parse_int_overflow
+    cmp rax, r11
+    jg __str_parse_int_overflow
+    jmp __str_parse_int_digits
+__str_parse_int_negative:
+    sub rax, rcx  ; Accumulé en négatif pour atteindre le minimum
+    jo __str_parse_int_overflow
+    cmp rax, r10
+    jl __str_parse_int_overflow
+    jmp __str_parse_int_digits
+__str_parse_int_overflow:
+    mov edx, 3  ; PosOverflow, ou NegOverflow pour un nombre négatif
+    add edx, esi
+__str_parse_int_error:
+    xor eax, eax
+    ret
+__str_parse_int_ok:
+    xor edx, edx
+    ret
+
+; Le caractère edi est-il un blanc ASCII (espace, \t, \n, \v, \f, \r) ? Renvoie 0 ou 1 dans eax
+__char_is_whitespace:
+    xor eax, eax
+    cmp edi, 0x20
+    je __char_is_whitespace_yes
+    lea ecx, [rdi-9]
+    cmp ecx, 4
+    ja __char_is_whitespace_no
+__char_is_whitespace_yes:
+    mov eax, 1
+__char_is_whitespace_no:
+    ret
+
+; Le caractère edi est-il un chiffre dans la base esi (jusqu'à 36) ? Renvoie 0 ou 1 dans eax
+__char_is_digit:
+    lea eax, [rdi-0x30]
+    cmp eax, 10
+    jb __char_is_digit_value
+    mov eax, edi
+    or eax, 0x20  ; Minuscule
+    sub eax, 0x61
+    cmp eax, 26
+    jae __char_is_digit_no
+    add eax, 10
+__char_is_digit_value:
+    cmp eax, esi
+    setb al
+    movzx eax, al
+    ret
+__char_is_digit_no:
+    xor eax, eax
+    ret
+
+; Le caractère edi est-il une lettre (ASCII ou Latin-1) ? Renvoie 0 ou 1 dans eax
+__char_is_alphabetic:
+    mov eax, edi
+    or eax, 0x20
+    sub eax, 0x61
+    cmp eax, 26
+    jb __char_is_alphabetic_yes
+    cmp edi, 0xC0
+    jb __char_is_alphabetic_no
+    cmp edi, 0xFF
+    ja __char_is_alphabetic_no
+    cmp edi, 0xD7  ; ×
+    je __char_is_alphabetic_no
+    cmp edi, 0xF7  ; ÷
+    je __char_is_alphabetic_no
+__char_is_alphabetic_yes:
+    mov eax, 1
+    ret
+__char_is_alphabetic_no:
+    xor eax, eax
+    ret
+
+; Le caractère edi est-il une lettre ou un chiffre décimal ? Renvoie 0 ou 1 dans eax
+__char_is_alphanumeric:
+    call __char_is_alphabetic
+    test eax, eax
+    jnz __char_is_alphanumeric_done
+    mov esi, 10
+    call __char_is_digit
+__char_is_alphanumeric_done:
+    ret
+"#;
+
+// Écriture formatée (println!, format!, write!...) : le texte est construit dans un tampon ayant la disposition d'une
+// String, puis écrit d'un bloc. Une spécification de format est condensée dans un registre :
+// bits 0-7 base, bit 8 chiffres en majuscules, bit 9 `+`, bit 10 `#`, bit 11 `0`,
+// bits 16-23 alignement (0 par défaut, 1 gauche, 2 droite, 3 centré), bits 32-63 caractère de remplissage
+pub const FORMAT: &str = r#"
+; Écrit rcx copies des rdx octets pointés par rsi à l'adresse rdi
+__fmt_fill:
+    test rcx, rcx
+    jz __fmt_fill_done
+__fmt_fill_copy:
+    xor eax, eax
+__fmt_fill_byte:
+    mov r8b, BYTE [rsi+rax]
+    mov BYTE [rdi+rax], r8b
+    inc rax
+    cmp rax, rdx
+    jb __fmt_fill_byte
+    add rdi, rdx
+    dec rcx
+    jnz __fmt_fill_copy
+__fmt_fill_done:
+    ret
+
+; Complète jusqu'à rcx caractères le texte écrit dans le tampon rdi depuis l'octet rsi,
+; selon la spécification rdx ; r8 donne l'alignement par défaut (1 gauche, 2 droite)
+__fmt_pad:
+    push rbp
+    mov rbp, rsp
+    push rbx
+    push r12
+    push r13
+    push r14
+    push r15
+    sub rsp, 24
+    mov rbx, rdi
+    mov r12, rsi
+    mov r13, rdx
+    mov rdi, QWORD [rbx]
+    mov rsi, r12
+    xor eax, eax
+__fmt_pad_count:
+    cmp rsi, QWORD [rbx+8]
+    jae __fmt_pad_counted
+    movzx edx, BYTE [rdi+rsi]
+    and edx, 0xC0
+    cmp edx, 0x80
+    je __fmt_pad_next  ; Octet de continuation UTF-8
+    inc rax
+__fmt_pad_next:
+    inc rsi
+    jmp __fmt_pad_count
+__fmt_pad_counted:
+    sub rcx, rax  ; Caractères de remplissage
+    jle __fmt_pad_done
+    mov rax, r13
+    shr rax, 16
+    movzx eax, al
+    test eax, eax
+    cmovz eax, r8d
+    xor r14d, r14d  ; Remplissage avant le texte
+    mov r15, rcx    ; Remplissage après le texte
+    cmp eax, 1
+    je __fmt_pad_split
+    mov r14, rcx
+    xor r15d, r15d
+    cmp eax, 2
+    je __fmt_pad_split
+    mov r14, rcx
+    shr r14, 1
+    mov r15, rcx
+    sub r15, r14
+__fmt_pad_split:
+    mov rdi, r13
+    shr rdi, 32
+    lea rsi, [rbp-48]
+    call __utf8_encode
+    mov QWORD [rbp-56], rax  ; Longueur du remplissage encodé
+    mov rsi, r14
+    add rsi, r15
+    imul rsi, rax
+    mov rdi, rbx
+    mov edx, 1
+    call __vec_reserve
+    mov rdi, QWORD [rbx]
+    add rdi, r12
+    mov rax, r14
+    imul rax, QWORD [rbp-56]
+    add rdi, rax
+    mov rsi, QWORD [rbx]
+    add rsi, r12
+    mov rdx, QWORD [rbx+8]
+    sub rdx, r12
+    call memmove  ; Décale le texte après le remplissage de tête
+    mov rdi, QWORD [rbx]
+    add rdi, r12
+    lea rsi, [rbp-48]
+    mov rdx, QWORD [rbp-56]
+    mov rcx, r14
+    call __fmt_fill
+    mov rdi, QWORD [rbx]
+    add rdi, QWORD [rbx+8]
+    mov rax, r14
+    imul rax, QWORD [rbp-56]
+    add rdi, rax
+    lea rsi, [rbp-48]
+    mov rdx, QWORD [rbp-56]
+    mov rcx, r15
+    call __fmt_fill
+    mov rax, r14
+    add rax, r15
+    imul rax, QWORD [rbp-56]
+    add QWORD [rbx+8], rax
+__fmt_pad_done:
+    add rsp, 24
+    pop r15
+    pop r14
+    pop r13
+    pop r12
+    pop rbx
+    pop rbp
+    ret
+
+; Ajoute au tampon rdi le texte de rdx octets pointé par rsi : spécification dans rcx, largeur
+; dans r8, précision (nombre maximal de caractères, -1 sans limite) dans r9
+__fmt_str:
+    push rbp
+    mov rbp, rsp
+    push rbx
+    push r12
+    push r13
+    push r14
+    push r15
+    sub rsp, 8
+    mov rbx, rdi
+    mov r12, rsi
+    mov r13, rdx
+    mov r14, rcx
+    mov r15, r8
+    test r9, r9
+    js __fmt_str_append
+    xor eax, eax
+__fmt_str_truncate:
+    cmp rax, r13
+    jae __fmt_str_append
+    movzx ecx, BYTE [r12+rax]
+    and ecx, 0xC0
+    cmp ecx, 0x80
+    je __fmt_str_continue  ; Octet de continuation UTF-8
+    test r9, r9
+    jz __fmt_str_cut
+    dec r9
+__fmt_str_continue:
+    inc rax
+    jmp __fmt_str_truncate
+__fmt_str_cut:
+    mov r13, rax
+__fmt_str_append:
+    mov rax, QWORD [rbx+8]
+    mov QWORD [rbp-48], rax  ; Début du texte dans le tampon
+    mov rdi, rbx
+    mov rsi, r12
+    mov rdx, r13
+    mov ecx, 1
+    call __vec_extend
+    mov rdi, rbx
+    mov rsi, QWORD [rbp-48]
+    mov rdx, r14
+    mov rcx, r15
+    mov r8d, 1  ; À gauche par défaut
+    call __fmt_pad
+    add rsp, 8
+    pop r15
+    pop r14
+    pop r13
+    pop r12
+    pop rbx
+    pop rbp
+    ret
+
+; Ajoute au tampon rdi l'entier rsi (signé en base 10, non signé sinon) : spécification dans rdx,
+; largeur dans rcx. Le signe et le préfixe 0x, 0b ou 0o précèdent les zéros de remplissage.
+__fmt_int:
+    push rbp
+    mov rbp, rsp
+    push rbx
+    push r12
+    push r13
+    push r14
+    push r15
+    sub rsp, 104
+    mov rbx, rdi
+    mov r12, rsi
+    mov r13, rdx
+    mov r14, rcx
+    xor r15d, r15d  ; Longueur du préfixe, écrit en [rbp-128]
+    movzx ecx, r13b  ; Base
+    cmp ecx, 10
+    jne __fmt_int_plus
+    test r12, r12
+    jns __fmt_int_plus
+    neg r12
+    mov BYTE [rbp-128], 45  ; '-'
+    inc r15
+    jmp __fmt_int_alternate
+__fmt_int_plus:
+    test r13, 0x200
+    jz __fmt_int_alternate
+    mov BYTE [rbp-128], 43  ; '+'
+    inc r15
+__fmt_int_alternate:
+    test r13, 0x400
+    jz __fmt_int_digits
+    cmp ecx, 10
+    je __fmt_int_digits
+    mov BYTE [rbp+r15-128], 48  ; '0'
+    mov eax, 120  ; 'x'
+    mov edx, 98   ; 'b'
+    cmp ecx, 2
+    cmove eax, edx
+    mov edx, 111  ; 'o'
+    cmp ecx, 8
+    cmove eax, edx
+    mov BYTE [rbp+r15-127], al
+    add r15, 2
+__fmt_int_digits:
+    lea rdi, [rbp-48]  ; Les chiffres sont écrits à rebours sous [rbp-48]
+    mov rax, r12
+__fmt_int_digit:
+    xor edx, edx
+    div rcx
+    add edx, 48
+    cmp edx, 57
+    jbe __fmt_int_store
+    add edx, 39  ; Chiffre au-delà de 9 : 'a'...
+    test r13, 0x100
+    jz __fmt_int_store
+    sub edx, 32  ; ... ou 'A'
+__fmt_int_store:
+    dec rdi
+    mov BYTE [rdi], dl
+    test rax, rax
+    jnz __fmt_int_digit
+    mov QWORD [rbp-136], rdi
+    mov rax, QWORD [rbx+8]
+    mov QWORD [rbp-144], rax  ; Début du nombre dans le tampon
+    mov rdi, rbx
+    lea rsi, [rbp-128]
+    mov rdx, r15
+    mov ecx, 1
+    call __vec_extend
+    test r13, 0x800
+    jz __fmt_int_body
+    lea rax, [rbp-48]
+    sub rax, QWORD [rbp-136]
+    add rax, r15
+    mov r15, r14
+    sub r15, rax  ; Zéros entre le préfixe et les chiffres
+    jle __fmt_int_body
+    mov rdi, rbx
+    mov rsi, r15
+    mov edx, 1
+    call __vec_reserve
+    mov rdi, QWORD [rbx]
+    add rdi, QWORD [rbx+8]
+    mov esi, 48
+    mov rdx, r15
+    call memset
+    add QWORD [rbx+8], r15
+__fmt_int_body:
+    mov rdi, rbx
+    mov rsi, QWORD [rbp-136]
+    lea rdx, [rbp-48]
+    sub rdx, rsi
+    mov ecx, 1
+    call __vec_extend
+    test r13, 0x800
+    jnz __fmt_int_done  ; Le remplissage par des zéros remplace l'alignement
+    mov rdi, rbx
+    mov rsi, QWORD [rbp-144]
+    mov rdx, r13
+    mov rcx, r14
+    mov r8d, 2  ; À droite par défaut
+    call __fmt_pad
+__fmt_int_done:
+    add rsp, 104
+    pop r15
+    pop r14
+    pop r13
+    pop r12
+    pop rbx
+    pop rbp
+    ret
+
+; Forme Debug d'un texte : ajoute au tampon rdi les rdx octets pointés par rsi entre les guillemets ecx,
+; en échappant ce guillemet, la barre oblique inverse et les caractères de contrôle (\n, \t, \u{1b}...)
+__fmt_debug_escape:
+    push rbp
+    mov rbp, rsp
+    push rbx
+    push r12
+    push r13
+    push r14
+    mov rbx, rdi
+    mov r12, rsi
+    mov r13, rdx
+    mov r14d, ecx
+    lea rsi, [rdx+rdx*2]
+    add rsi, rsi
+    add rsi, 2  ; Au plus 6 octets par octet source, plus les guillemets
+    mov edx, 1
+    call __vec_reserve
+    mov rdi, QWORD [rbx]
+    add rdi, QWORD [rbx+8]
+    mov BYTE [rdi], r14b
+    inc rdi
+    xor ecx, ecx
+__fmt_debug_escape_loop:
+    cmp rcx, r13
+    jae __fmt_debug_escape_end
+    movzx eax, BYTE [r12+rcx]
+    inc rcx
+    cmp eax, r14d
+    je __fmt_debug_escape_backslash
+    cmp eax, 92  ; Barre oblique inverse
+    je __fmt_debug_escape_backslash
+    mov edx, 110  ; n
+    cmp eax, 10
+    je __fmt_debug_escape_letter
+    mov edx, 114  ; r
+    cmp eax, 13
+    je __fmt_debug_escape_letter
+    mov edx, 116  ; t
+    cmp eax, 9
+    je __fmt_debug_escape_letter
+    mov edx, 48  ; 0
+    test eax, eax
+    je __fmt_debug_escape_letter
+    cmp eax, 32
+    jb __fmt_debug_escape_unicode
+    cmp eax, 127
+    je __fmt_debug_escape_unicode
+    mov BYTE [rdi], al
+    inc rdi
+    jmp __fmt_debug_escape_loop
+__fmt_debug_escape_backslash:
+    mov BYTE [rdi], 92
+    mov BYTE [rdi+1], al
+    add rdi, 2
+    jmp __fmt_debug_escape_loop
+__fmt_debug_escape_letter:
+    mov BYTE [rdi], 92
+    mov BYTE [rdi+1], dl
+    add rdi, 2
+    jmp __fmt_debug_escape_loop
+__fmt_debug_escape_unicode:
+    mov BYTE [rdi], 92
+    mov BYTE [rdi+1], 117  ; u
+    mov BYTE [rdi+2], 123  ; Accolade ouvrante
+    add rdi, 3
+    mov edx, eax
+    shr edx, 4
+    jz __fmt_debug_escape_low
+    add edx, 48
+    mov BYTE [rdi], dl
+    inc rdi
+__fmt_debug_escape_low:
+    and eax, 15
+    add eax, 48
+    cmp eax, 57
+    jbe __fmt_debug_escape_digit
+    add eax, 39  ; Chiffres hexadécimaux en minuscules
+__fmt_debug_escape_digit:
+    mov BYTE [rdi], al
+    mov BYTE [rdi+1], 125  ; Accolade fermante
+    add rdi, 2
+    jmp __fmt_debug_escape_loop
+__fmt_debug_escape_end:
+    mov BYTE [rdi], r14b
+    inc rdi
+    sub rdi, QWORD [rbx]
+    mov QWORD [rbx+8], rdi
+    pop r14
+    pop r13
+    pop r12
+    pop rbx
+    pop rbp
+    ret
+
+; Forme Debug du caractère esi, entre apostrophes, ajoutée au tampon rdi
+__fmt_debug_char:
+    push rbp
+    mov rbp, rsp
+    push rbx
+    sub rsp, 8
+    mov rbx, rdi
+    mov edi, esi
+    lea rsi, [rbp-16]
+    call __utf8_encode
+    mov rdi, rbx
+    lea rsi, [rbp-16]
+    mov rdx, rax
+    mov ecx, 39  ; Apostrophe
+    call __fmt_debug_escape
+    add rsp, 8
+    pop rbx
+    pop rbp
+    ret
+
+; Saut de ligne suivi de 4 espaces par niveau d'indentation rsi (forme {:#?}), ajouté au tampon rdi
+__fmt_newline:
+    push rbp
+    mov rbp, rsp
+    push rbx
+    push r12
+    mov rbx, rdi
+    lea r12, [rsi*4+1]
+    mov rsi, r12
+    mov edx, 1
+    call __vec_reserve
+    mov rdi, QWORD [rbx]
+    add rdi, QWORD [rbx+8]
+    mov BYTE [rdi], 10
+    inc rdi
+    mov esi, 32
+    lea rdx, [r12-1]
+    call memset
+    add QWORD [rbx+8], r12
+    pop r12
+    pop rbx
+    pop rbp
+    ret
+
+; Écrit le tampon rdi dans le flux rsi puis libère ses octets. Comme la sortie standard de Rust,
+; le flux est vidé dès qu'une ligne est complète : les sorties standard et d'erreur restent dans l'ordre.
+__fmt_write:
+    push rbp
+    mov rbp, rsp
+    push rbx
+    push r12
+    mov rbx, rdi
+    mov r12, rsi
+    mov rdi, QWORD [rbx]
+    mov esi, 1
+    mov rdx, QWORD [rbx+8]
+    mov rcx, r12
+    call fwrite
+    mov rdi, QWORD [rbx]
+    mov esi, 10
+    mov rdx, QWORD [rbx+8]
+    call memchr
+    test rax, rax
+    jz __fmt_write_free
+    mov rdi, r12
+    call fflush
+__fmt_write_free:
+    mov rdi, QWORD [rbx]
+    call __free
+    pop r12
+    pop rbx
+    pop rbp
+    ret
+"#;
+
+// Tables de hachage (HashMap, HashSet) à adressage ouvert et sondage linéaire. L'en-tête a la disposition
+// d'un Vec : (entrées, nombre de clés, capacité), la capacité étant nulle ou une puissance de 2. Chaque
+// entrée commence par son état (0 libre, 1 occupée) et l'empreinte de sa clé, suivis de la clé puis de
+// la valeur. Une clé est désignée par rsi et rdx selon son genre (r8) : 0 pour un entier (valeur dans
+// rsi, rangée sur 8 octets), 1 pour une chaîne (octets dans rsi, longueur dans rdx, rangée comme une String).
+// La table est agrandie au-delà des trois quarts de sa capacité, et une suppression décale les entrées
+// suivantes au lieu de laisser une marque : une entrée libre termine toujours la recherche.
+pub const MAP: &str = r#"
+; Empreinte de la clé (rsi, rdx) de genre r8 dans rax : mélange d'un entier, FNV-1a des octets d'une chaîne
+__map_hash:
+    test r8, r8
+    jnz __map_hash_bytes
+    mov rax, rsi
+    mov rcx, rax
+    shr rcx, 33
+    xor rax, rcx
+    mov rcx, 0xff51afd7ed558ccd
+    imul rax, rcx
+    mov rcx, rax
+    shr rcx, 33
+    xor rax, rcx
+    ret
+__map_hash_bytes:
+    mov rax, 0xcbf29ce484222325
+    mov r9, 0x100000001b3
+    xor ecx, ecx
+__map_hash_loop:
+    cmp rcx, rdx
+    jae __map_hash_done
+    movzx r10d, BYTE [rsi+rcx]
+    xor rax, r10
+    imul rax, r9
+    inc rcx
+    jmp __map_hash_loop
+__map_hash_done:
+    ret
+
+; Cherche la clé (rsi, rdx, genre r8) dans la table rdi de capacité non nulle, dont les entrées font rcx
+; octets. Renvoie dans rax l'entrée de la clé (rdx = 1) ou l'entrée libre où l'insérer (rdx = 0), et
+; l'empreinte de la clé dans rcx.
+__map_probe:
+    push rbp
+    mov rbp, rsp
+    push rbx
+    push r12
+    push r13
+    push r14
+    push r15
+    sub rsp, 24
+    mov rbx, rdi
+    mov r12, rsi
+    mov r13, rdx
+    mov r14, rcx
+    mov r15, r8
+    call __map_hash
+    mov QWORD [rbp-48], rax  ; Empreinte
+    mov rcx, QWORD [rbx+16]
+    dec rcx
+    and rax, rcx
+    mov QWORD [rbp-56], rax  ; Index de l'entrée examinée
+__map_probe_loop:
+    mov rax, QWORD [rbp-56]
+    imul rax, r14
+    add rax, QWORD [rbx]
+    mov QWORD [rbp-64], rax
+    cmp QWORD [rax], 0
+    je __map_probe_free
+    mov rcx, QWORD [rbp-48]
+    cmp QWORD [rax+8], rcx
+    jne __map_probe_next
+    test r15, r15
+    jnz __map_probe_string
+    cmp QWORD [rax+16], r12
+    je __map_probe_found
+    jmp __map_probe_next
+__map_probe_string:
+    cmp QWORD [rax+24], r13
+    jne __map_probe_next
+    test r13, r13
+    jz __map_probe_found
+    mov rdi, QWORD [rax+16]
+    mov rsi, r12
+    mov rdx, r13
+    call memcmp
+    test eax, eax
+    jz __map_probe_found
+__map_probe_next:
+    mov rax, QWORD [rbp-56]
+    inc rax
+    mov rcx, QWORD [rbx+16]
+    dec rcx
+    and rax, rcx
+    mov QWORD [rbp-56], rax
+    jmp __map_probe_loop
+__map_probe_found:
+    mov edx, 1
+    jmp __map_probe_done
+__map_probe_free:
+    xor edx, edx
+__map_probe_done:
+    mov rax, QWORD [rbp-64]
+    mov rcx, QWORD [rbp-48]
+    add rsp, 24
+    pop r15
+    pop r14
+    pop r13
+    pop r12
+    pop rbx
+    pop rbp
+    ret
+
+; Entrée de la clé (rsi, rdx, genre r8) dans la table rdi aux entrées de rcx octets, ou 0 dans rax
+__map_find:
+    cmp QWORD [rdi+16], 0
+    je __map_find_none
+    push rbp
+    mov rbp, rsp
+    call __map_probe
+    pop rbp
+    test rdx, rdx
+    jz __map_find_none
+    ret
+__map_find_none:
+    xor eax, eax
+    ret
+
+; Entrée de la clé (rsi, rdx, genre r8) dans la table rdi aux entrées de rcx octets, créée si besoin.
+; Renvoie son adresse dans rax, et dans rdx 1 si la clé était présente ou 0 pour une nouvelle entrée,
+; où l'appelant range la clé et la valeur.
+__map_entry:
+    push rbp
+    mov rbp, rsp
+    push rbx
+    push r12
+    push r13
+    push r14
+    push r15
+    sub rsp, 8
+    mov rbx, rdi
+    mov r12, rsi
+    mov r13, rdx
+    mov r14, rcx
+    mov r15, r8
+    mov rax, QWORD [rbx+8]
+    inc rax
+    shl rax, 2
+    mov rdx, QWORD [rbx+16]
+    lea rdx, [rdx+rdx*2]
+    cmp rax, rdx
+    jbe __map_entry_probe
+    mov rdi, rbx
+    mov rsi, r14
+    call __map_grow
+__map_entry_probe:
+    mov rdi, rbx
+    mov rsi, r12
+    mov rdx, r13
+    mov rcx, r14
+    mov r8, r15
+    call __map_probe
+    test rdx, rdx
+    jnz __map_entry_done
+    mov QWORD [rax], 1  ; Entrée occupée
+    mov QWORD [rax+8], rcx  ; Empreinte
+    inc QWORD [rbx+8]
+__map_entry_done:
+    add rsp, 8
+    pop r15
+    pop r14
+    pop r13
+    pop r12
+    pop rbx
+    pop rbp
+    ret
+
+; Double la capacité (au moins 8) de la table rdi aux entrées de rsi octets et y replace les entrées
+__map_grow:
+    push rbp
+    mov rbp, rsp
+    push rbx
+    push r12
+    push r13
+    push r14
+    push r15
+    sub rsp, 8
+    mov rbx, rdi
+    mov r12, rsi
+    mov r15, QWORD [rbx+16]
+    add r15, r15
+    mov eax, 8
+    cmp r15, rax
+    cmovb r15, rax
+    mov rdi, r15
+    imul rdi, r12
+    mov QWORD [rbp-48], rdi
+    call __alloc
+    mov r13, rax
+    mov rdi, rax
+    xor esi, esi
+    mov rdx, QWORD [rbp-48]
+    call memset
+    dec r15  ; Masque des index
+    xor r14d, r14d
+__map_grow_loop:
+    cmp r14, QWORD [rbx+16]
+    jae __map_grow_done
+    mov rsi, r14
+    imul rsi, r12
+    add rsi, QWORD [rbx]
+    cmp QWORD [rsi], 0
+    je __map_grow_next
+    mov rax, QWORD [rsi+8]
+__map_grow_probe:
+    and rax, r15
+    mov rdi, rax
+    imul rdi, r12
+    add rdi, r13
+    inc rax
+    cmp QWORD [rdi], 0
+    jne __map_grow_probe
+    mov rdx, r12
+    call memcpy
+__map_grow_next:
+    inc r14
+    jmp __map_grow_loop
+__map_grow_done:
+    mov rdi, QWORD [rbx]
+    call __free
+    mov QWORD [rbx], r13
+    inc r15
+    mov QWORD [rbx+16], r15
+    add rsp, 8
+    pop r15
+    pop r14
+    pop r13
+    pop r12
+    pop rbx
+    pop rbp
+    ret
+
+; Retire la clé (rsi, rdx, genre r8) de la table rdi aux entrées de rcx octets : l'entrée est copiée
+; dans r9 et 1 est renvoyé dans rax, ou 0 si la clé est absente. Les entrées suivantes qui peuvent
+; se rapprocher de leur place d'origine comblent le trou.
+__map_remove:
+    cmp QWORD [rdi+16], 0
+    je __map_remove_none
+    push rbp
+    mov rbp, rsp
+    push rbx
+    push r12
+    push r13
+    push r14
+    push r15
+    sub rsp, 8
+    mov rbx, rdi
+    mov r12, rcx
+    mov r13, r9
+    call __map_probe
+    test rdx, rdx
+    jz __map_remove_absent
+    mov r14, rax  ; Trou
+    mov rdi, r13
+    mov rsi, rax
+    mov rdx, r12
+    call memcpy
+    dec QWORD [rbx+8]
+    mov r15, QWORD [rbx+16]
+    dec r15  ; Masque des index
+    mov rax, r14
+    sub rax, QWORD [rbx]
+    xor edx, edx
+    div r12
+    mov QWORD [rbp-48], rax  ; Index du trou
+    mov r13, rax
+__map_remove_next:
+    inc r13
+    and r13, r15
+    mov rsi, r13
+    imul rsi, r12
+    add rsi, QWORD [rbx]
+    cmp QWORD [rsi], 0
+    je __map_remove_end
+    ; L'entrée peut combler le trou si elle en est au moins aussi loin de sa place d'origine
+    mov rax, r13
+    sub rax, QWORD [rsi+8]
+    and rax, r15
+    mov rcx, r13
+    sub rcx, QWORD [rbp-48]
+    and rcx, r15
+    cmp rax, rcx
+    jb __map_remove_next
+    mov rdi, r14
+    mov rdx, r12
+    call memcpy
+    mov QWORD [rbp-48], r13
+    mov r14, r13
+    imul r14, r12
+    add r14, QWORD [rbx]
+    jmp __map_remove_next
+__map_remove_end:
+    mov QWORD [r14], 0  ; Entrée libre
+    mov eax, 1
+    jmp __map_remove_done
+__map_remove_absent:
+    xor eax, eax
+__map_remove_done:
+    add rsp, 8
+    pop r15
+    pop r14
+    pop r13
+    pop r12
+    pop rbx
+    pop rbp
+    ret
+__map_remove_none:
+    xor eax, eax
+    ret
+"#;
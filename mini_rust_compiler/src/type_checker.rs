@@ -0,0 +1,2551 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::error_handler::ErrorHandler;
+use crate::format::{FormatArgs, Piece, Count};
+use crate::iterators::{Adapter, Consumer, IteratorMethod, RANGE_METHODS};
+use crate::parser::{Program, Function, Stmt, Expr, BinaryOp, UnaryOp, Literal, Type, StructDef, TraitDef, TraitMethod, ImplBlock, TypeParam, Pattern, EnumDef, MatchArm};
+
+// Signature d'une fonction ou d'une méthode (le receveur `self` compris)
+#[derive(Clone)]
+pub struct FnSig {
+    pub label: String,
+    pub type_params: Vec<TypeParam>, // Paramètres génériques : ceux du bloc impl, puis ceux de la fonction
+    pub params: Vec<Type>,
+    pub return_type: Type,
+}
+
+// Méthode d'un type, issue d'un bloc impl inhérent ou d'une implémentation de trait
+#[derive(Clone)]
+pub struct MethodInfo {
+    pub sig: FnSig,
+    pub has_self: bool,
+    pub inherent: bool, // Bloc impl inhérent (prioritaire sur les traits)
+    pub impl_type: Type, // Type implémenté tel qu'écrit dans l'en-tête du bloc impl (Pair<T>)
+}
+
+// Cible d'un appel de méthode après résolution du type du receveur
+pub enum MethodTarget {
+    Static(MethodInfo),
+    Dynamic(String, usize), // (trait, index de la méthode dans la vtable)
+    Builtin,                // Méthodes intégrées (vecteurs, tableaux, chaînes, caractères, Option, Result)
+}
+
+// Traits fournis par le compilateur et implémentés par les types entiers
+const BUILTIN_TRAITS: [&str; 20] = [
+    "PartialEq", "Eq", "PartialOrd", "Ord", "Copy", "Clone", "Display", "Debug",
+    "Default", "Add", "Sub", "Mul", "Div", "Rem", "Neg", "Sized",
+    "LowerHex", "UpperHex", "Binary", "Octal",
+];
+
+// Méthodes intégrées qui modifient leur receveur (`&mut self`)
+pub const MUTATING_METHODS: [&str; 16] = [
+    "push", "push_str", "pop", "insert", "remove", "extend", "truncate", "clear", "sort", "sort_by", "swap", "reverse", "iter_mut",
+    "entry", "get_mut", "values_mut",
+];
+
+// Méthodes d'un Ordering, qui le prennent par valeur
+pub const ORDERING_METHODS: [&str; 8] = ["reverse", "then", "is_lt", "is_le", "is_gt", "is_ge", "is_eq", "is_ne"];
+
+// Erreur renvoyée par str::parse ; son unique champ `kind` code la cause :
+// 0 Empty, 1 InvalidDigit, 2 PosOverflow, 3 NegOverflow
+pub const PARSE_INT_ERROR: &str = "ParseIntError";
+
+// Erreur de write! et writeln! (`Result<(), fmt::Error>`), sans champ ; l'écriture dans une String n'échoue jamais
+pub const FMT_ERROR: &str = "fmt::Error";
+
+// Résultat de cmp() et des fonctions de comparaison de sort_by() (`std::cmp::Ordering`) : une énumération
+// prédéfinie, dont les étiquettes 1, 2 et 3 désignent Less, Equal et Greater
+pub const ORDERING: &str = "Ordering";
+
+// Destination de `fmt` dans les implémentations de Display et Debug : son unique champ est l'adresse
+// de la String en cours d'écriture, que `write!(f, ..)` prolonge
+pub const FORMATTER: &str = "fmt::Formatter";
+
+// Variantes d'Option et de Result, construites comme des appels de fonction (`None` sans argument) ;
+// leurs arguments génériques sont ceux du type construit : Some::<T>, Ok::<T, E>
+pub const VARIANTS: [&str; 4] = ["Some", "None", "Ok", "Err"];
+
+// Déclarations du programme et typage des expressions, partagés par la monomorphisation et la génération de code
+pub struct TypeChecker<'a> {
+    error_handler: &'a ErrorHandler,
+    pub structs: HashMap<String, StructDef>,
+    pub enums: HashMap<String, EnumDef>,
+    pub traits: HashMap<String, TraitDef>,
+    pub functions: HashMap<String, FnSig>,
+    pub methods: HashMap<(String, String), MethodInfo>,   // (type, méthode)
+    pub trait_impls: HashSet<(String, String)>,           // (type, trait)
+    pub instances: HashMap<String, (String, Vec<Type>)>,  // Instance monomorphisée -> (structure générique, arguments)
+    pub variables: HashMap<String, Type>,                 // Variables visibles et leur type
+    pub self_type: Option<Type>,                          // Type désigné par `Self` dans un bloc impl
+    pub type_params: Vec<TypeParam>,                      // Paramètres génériques visibles dans le corps vérifié
+    pub current_line: usize,
+}
+
+impl<'a> TypeChecker<'a> {
+    pub fn new(error_handler: &'a ErrorHandler) -> Self {
+        let parse_error = StructDef {
+            name: PARSE_INT_ERROR.to_string(),
+            type_params: Vec::new(),
+            fields: vec![("kind".to_string(), Type::U8)],
+            derives: Vec::new(),
+            instance_of: None,
+            line: 0,
+        };
+        let parse_error_traits = ["Clone", "Debug", "Display", "PartialEq", "Eq"]
+            .map(|trait_name| (PARSE_INT_ERROR.to_string(), trait_name.to_string()));
+        let fmt_error = StructDef {
+            name: FMT_ERROR.to_string(),
+            type_params: Vec::new(),
+            fields: Vec::new(),
+            derives: Vec::new(),
+            instance_of: None,
+            line: 0,
+        };
+        let fmt_error_traits = ["Clone", "Copy", "Debug", "Display", "Default", "PartialEq", "Eq"]
+            .map(|trait_name| (FMT_ERROR.to_string(), trait_name.to_string()));
+        let ordering = EnumDef {
+            name: ORDERING.to_string(),
+            variants: ["Less", "Equal", "Greater"].map(|name| (name.to_string(), Vec::new())).to_vec(),
+            derives: ["Debug", "Clone", "Copy"].map(String::from).to_vec(),
+            line: 0,
+        };
+        let ordering_traits = ["Clone", "Copy", "Debug"]
+            .map(|trait_name| (ORDERING.to_string(), trait_name.to_string()));
+        let formatter = StructDef {
+            name: FORMATTER.to_string(),
+            type_params: Vec::new(),
+            fields: vec![("buffer".to_string(), Type::Ref(Box::new(Type::String), true))],
+            derives: Vec::new(),
+            instance_of: None,
+            line: 0,
+        };
+
+        // Display et Debug peuvent être implémentés par l'utilisateur :
+        // fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result
+        let fmt_trait = |name: &str| TraitDef {
+            name: name.to_string(),
+            methods: vec![TraitMethod {
+                function: Function {
+                    name: "fmt".to_string(),
+                    type_params: Vec::new(),
+                    params: vec![
+                        ("self".to_string(), Type::Ref(Box::new(Type::Named("Self".to_string())), false)),
+                        ("f".to_string(), Type::Ref(Box::new(Type::Named(FORMATTER.to_string())), true)),
+                    ],
+                    mutable_params: Vec::new(),
+                    return_type: Some(Type::Result(Box::new(Type::Void), Box::new(Type::Named(FMT_ERROR.to_string())))),
+                    body: Vec::new(),
+                    line: 0,
+                },
+                has_default: false,
+            }],
+            line: 0,
+        };
+
+        // Drop peut être implémenté par l'utilisateur : fn drop(&mut self), appelée à la destruction
+        let drop_trait = TraitDef {
+            name: "Drop".to_string(),
+            methods: vec![TraitMethod {
+                function: Function {
+                    name: "drop".to_string(),
+                    type_params: Vec::new(),
+                    params: vec![("self".to_string(), Type::Ref(Box::new(Type::Named("Self".to_string())), true))],
+                    mutable_params: Vec::new(),
+                    return_type: None,
+                    body: Vec::new(),
+                    line: 0,
+                },
+                has_default: false,
+            }],
+            line: 0,
+        };
+
+        TypeChecker {
+            error_handler,
+            structs: HashMap::from([
+                (PARSE_INT_ERROR.to_string(), parse_error),
+                (FMT_ERROR.to_string(), fmt_error),
+                (FORMATTER.to_string(), formatter),
+            ]),
+            enums: HashMap::from([(ORDERING.to_string(), ordering)]),
+            traits: HashMap::from([
+                ("Display".to_string(), fmt_trait("Display")),
+                ("Debug".to_string(), fmt_trait("Debug")),
+                ("Drop".to_string(), drop_trait),
+            ]),
+            functions: HashMap::new(),
+            methods: HashMap::new(),
+            trait_impls: parse_error_traits.into_iter().chain(fmt_error_traits).chain(ordering_traits).collect(),
+            instances: HashMap::new(),
+            variables: HashMap::new(),
+            self_type: None,
+            type_params: Vec::new(),
+            current_line: 0,
+        }
+    }
+
+    // Signale une erreur à la ligne courante et renvoie la valeur d'erreur
+    pub fn error<T>(&self, message: &str) -> Result<T, usize> {
+        self.error_handler.report_error(self.current_line, message);
+        Err(self.current_line)
+    }
+
+    fn error_at<T>(&self, line: usize, message: &str) -> Result<T, usize> {
+        self.error_handler.report_error(line, message);
+        Err(line)
+    }
+
+    // Remplace `Self` par le type concret du bloc impl courant
+    pub fn resolve_type(&self, typ: &Type) -> Type {
+        match &self.self_type {
+            Some(self_type) => Self::substitute_self(typ, self_type),
+            None => typ.clone(),
+        }
+    }
+
+    pub fn substitute_self(typ: &Type, concrete: &Type) -> Type {
+        Self::substitute(typ, &HashMap::from([("Self".to_string(), concrete.clone())]))
+    }
+
+    // Remplace les paramètres génériques (et `Self`) d'un type selon `subst`
+    pub fn substitute(typ: &Type, subst: &HashMap<String, Type>) -> Type {
+        match typ {
+            Type::Named(name) => subst.get(name).cloned().unwrap_or_else(|| typ.clone()),
+            Type::Generic(name, args) => Type::Generic(name.clone(), args.iter().map(|arg| Self::substitute(arg, subst)).collect()),
+            Type::Ref(inner, mutable) => Type::Ref(Box::new(Self::substitute(inner, subst)), *mutable),
+            Type::Box(inner) => Type::Box(Box::new(Self::substitute(inner, subst))),
+            Type::Rc(inner) => Type::Rc(Box::new(Self::substitute(inner, subst))),
+            Type::HashMap(key, value) => Type::HashMap(Box::new(Self::substitute(key, subst)), Box::new(Self::substitute(value, subst))),
+            Type::HashSet(element) => Type::HashSet(Box::new(Self::substitute(element, subst))),
+            Type::Entry(key, value) => Type::Entry(Box::new(Self::substitute(key, subst)), Box::new(Self::substitute(value, subst))),
+            Type::RefCell(inner) => Type::RefCell(Box::new(Self::substitute(inner, subst))),
+            Type::Guard(inner, mutable) => Type::Guard(Box::new(Self::substitute(inner, subst)), *mutable),
+            Type::Vec(inner) => Type::Vec(Box::new(Self::substitute(inner, subst))),
+            Type::Option(inner) => Type::Option(Box::new(Self::substitute(inner, subst))),
+            Type::Result(ok, err) => Type::Result(Box::new(Self::substitute(ok, subst)), Box::new(Self::substitute(err, subst))),
+            Type::Tuple(elements) => Type::Tuple(elements.iter().map(|element| Self::substitute(element, subst)).collect()),
+            Type::Array(element, length) => Type::Array(Box::new(Self::substitute(element, subst)), *length),
+            Type::Slice(element) => Type::Slice(Box::new(Self::substitute(element, subst))),
+            Type::Iterator(item) => Type::Iterator(Box::new(Self::substitute(item, subst))),
+            Type::Range(element, inclusive) => Type::Range(Box::new(Self::substitute(element, subst)), *inclusive),
+            Type::FnPtr(params, ret) => Type::FnPtr(params.iter().map(|param| Self::substitute(param, subst)).collect(), Box::new(Self::substitute(ret, subst))),
+            Type::Closure(params, ret) => Type::Closure(params.iter().map(|param| Self::substitute(param, subst)).collect(), Box::new(Self::substitute(ret, subst))),
+            _ => typ.clone(),
+        }
+    }
+
+    pub fn mentions(typ: &Type, name: &str) -> bool {
+        match typ {
+            Type::Named(named) => named == name,
+            Type::Generic(_, args) | Type::Tuple(args) => args.iter().any(|arg| Self::mentions(arg, name)),
+            Type::Ref(inner, _) | Type::Box(inner) | Type::Vec(inner) | Type::Option(inner) | Type::Array(inner, _) | Type::Slice(inner)
+            | Type::Iterator(inner) | Type::Range(inner, _) | Type::Rc(inner) | Type::RefCell(inner) | Type::Guard(inner, _)
+            | Type::HashSet(inner) => Self::mentions(inner, name),
+            Type::Result(ok, err) | Type::HashMap(ok, err) | Type::Entry(ok, err) => Self::mentions(ok, name) || Self::mentions(err, name),
+            Type::FnPtr(params, ret) | Type::Closure(params, ret) => {
+                params.iter().any(|param| Self::mentions(param, name)) || Self::mentions(ret, name)
+            },
+            _ => false,
+        }
+    }
+
+    // Nom sous lequel les méthodes d'un type sont enregistrées (Pair pour Pair<T>)
+    pub fn type_key(typ: &Type) -> String {
+        match typ {
+            Type::Named(name) | Type::Generic(name, _) => name.clone(),
+            _ => typ.to_string(),
+        }
+    }
+
+    // Type nommé dans un en-tête `impl` : structure ou énumération de l'utilisateur, ou type primitif
+    pub fn type_from_name(&self, name: &str) -> Option<Type> {
+        match name {
+            "i8" => Some(Type::I8),
+            "i16" => Some(Type::I16),
+            "i32" => Some(Type::I32),
+            "i64" => Some(Type::I64),
+            "i128" => Some(Type::I128),
+            "u8" => Some(Type::U8),
+            "bool" => Some(Type::Bool),
+            _ if self.structs.contains_key(name) || self.enums.contains_key(name) => Some(Type::Named(name.to_string())),
+            _ => None,
+        }
+    }
+
+    // Étiquette assembleur d'une méthode : Type__methode ou Type__Trait__methode
+    pub fn method_label(type_name: &str, trait_name: Option<&str>, method: &str) -> String {
+        match trait_name {
+            Some(trait_name) => format!("{}__{}__{}", type_name, trait_name, method),
+            None => format!("{}__{}", type_name, method),
+        }
+    }
+
+    pub fn is_type_param(&self, name: &str) -> bool {
+        self.type_params.iter().any(|param| param.name == name)
+    }
+
+    fn param_bounds(&self, name: &str) -> Vec<String> {
+        self.type_params.iter()
+            .filter(|param| param.name == name)
+            .flat_map(|param| param.bounds.clone())
+            .collect()
+    }
+
+    // Une contrainte en entraîne d'autres (Ord: PartialOrd + Eq, Copy: Clone, ...)
+    fn bound_implies(bound: &str, required: &str) -> bool {
+        bound == required || match bound {
+            "Ord" => matches!(required, "PartialOrd" | "Eq" | "PartialEq"),
+            "PartialOrd" | "Eq" => required == "PartialEq",
+            "Copy" => required == "Clone",
+            _ => false,
+        }
+    }
+
+    // Le type implémente-t-il le trait ? Un paramètre générique n'a que les traits de ses contraintes.
+    pub fn satisfies(&self, typ: &Type, trait_name: &str) -> bool {
+        match typ {
+            Type::Named(name) if self.is_type_param(name) => {
+                trait_name == "Sized" || self.param_bounds(name).iter().any(|bound| Self::bound_implies(bound, trait_name))
+            },
+            Type::Named(name) if name == "_" => true,
+            Type::Named(name) if Self::is_hole(name) => self.satisfies(&Type::I32, trait_name),
+            Type::Bool | Type::Char if matches!(trait_name, "LowerHex" | "UpperHex" | "Binary" | "Octal") => false,
+            Type::I8 | Type::I16 | Type::I32 | Type::I64 | Type::I128 | Type::U8 | Type::Bool | Type::Char => {
+                BUILTIN_TRAITS.contains(&trait_name) || self.trait_impls.contains(&(typ.to_string(), trait_name.to_string()))
+            },
+            Type::Ref(_, false) | Type::FnPtr(_, _) if matches!(trait_name, "Copy" | "Clone") => true,
+            Type::String | Type::Str if matches!(trait_name, "Clone" | "Display" | "Debug" | "PartialEq" | "Eq" | "PartialOrd" | "Ord") => {
+                trait_name != "Clone" || *typ == Type::String
+            },
+            Type::Tuple(elements) if matches!(trait_name, "Copy" | "Clone" | "Debug") => elements.iter().all(|element| self.satisfies(element, trait_name)),
+            Type::Array(element, _) | Type::Option(element) if matches!(trait_name, "Copy" | "Clone" | "Debug") => self.satisfies(element, trait_name),
+            Type::Result(ok, err) if matches!(trait_name, "Copy" | "Clone" | "Debug") => self.satisfies(ok, trait_name) && self.satisfies(err, trait_name),
+            Type::Vec(element) | Type::Slice(element) | Type::HashSet(element) if trait_name == "Debug" => self.satisfies(element, trait_name),
+            Type::Vec(element) | Type::Box(element) if trait_name == "Clone" => !matches!(**element, Type::Dyn(_)) && self.satisfies(element, trait_name),
+            Type::HashMap(key, value) if trait_name == "Debug" => self.satisfies(key, trait_name) && self.satisfies(value, trait_name),
+            Type::Void if trait_name == "Debug" => true,
+            Type::Ref(inner, _) | Type::Box(inner) | Type::Rc(inner) | Type::Guard(inner, _)
+                if matches!(trait_name, "Display" | "Debug" | "PartialEq" | "Eq" | "PartialOrd" | "Ord") => {
+                self.satisfies(inner, trait_name)
+            },
+            Type::Rc(_) if trait_name == "Clone" => true,
+            Type::RefCell(inner) if trait_name == "Debug" => self.satisfies(inner, trait_name),
+            _ => trait_name == "Sized" || self.trait_impls.contains(&(Self::type_key(typ), trait_name.to_string())),
+        }
+    }
+
+    // Vérifie les contraintes des paramètres génériques d'un appel une fois ceux-ci déterminés
+    pub fn check_bounds(&self, callee: &str, type_params: &[TypeParam], subst: &HashMap<String, Type>) -> Result<(), usize> {
+        for param in type_params {
+            let Some(concrete) = subst.get(&param.name) else { continue };
+            for bound in &param.bounds {
+                if !self.satisfies(concrete, bound) {
+                    return self.error(&format!(
+                        "le trait `{}` n'est pas implémenté pour `{}` (requis par la contrainte `{}: {}` de `{}`)",
+                        bound, self.display_type(concrete), param.name, bound, callee));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Type tel qu'écrit par l'utilisateur : les instances monomorphisées reprennent leur forme générique
+    pub fn display_type(&self, typ: &Type) -> String {
+        match typ {
+            Type::Named(name) if Self::is_hole(name) => "_".to_string(),
+            Type::Named(name) => match self.instances.get(name) {
+                Some((generic, args)) => self.display_type(&Type::Generic(generic.clone(), args.clone())),
+                None => name.clone(),
+            },
+            Type::Generic(name, args) => {
+                let args: Vec<String> = args.iter().map(|arg| self.display_type(arg)).collect();
+                format!("{}<{}>", name, args.join(", "))
+            },
+            Type::Ref(inner, true) => format!("&mut {}", self.display_type(inner)),
+            Type::Ref(inner, false) => format!("&{}", self.display_type(inner)),
+            Type::Box(inner) => format!("Box<{}>", self.display_type(inner)),
+            Type::Rc(inner) => format!("Rc<{}>", self.display_type(inner)),
+            Type::HashMap(key, value) => format!("HashMap<{}, {}>", self.display_type(key), self.display_type(value)),
+            Type::HashSet(element) => format!("HashSet<{}>", self.display_type(element)),
+            Type::Entry(key, value) => format!("Entry<{}, {}>", self.display_type(key), self.display_type(value)),
+            Type::RefCell(inner) => format!("RefCell<{}>", self.display_type(inner)),
+            Type::Guard(inner, false) => format!("Ref<{}>", self.display_type(inner)),
+            Type::Guard(inner, true) => format!("RefMut<{}>", self.display_type(inner)),
+            Type::Vec(inner) => format!("Vec<{}>", self.display_type(inner)),
+            Type::Option(inner) => format!("Option<{}>", self.display_type(inner)),
+            Type::Result(ok, err) => format!("Result<{}, {}>", self.display_type(ok), self.display_type(err)),
+            Type::Array(element, length) => format!("[{}; {}]", self.display_type(element), length),
+            Type::Slice(element) => format!("[{}]", self.display_type(element)),
+            Type::Iterator(item) => format!("impl Iterator<Item = {}>", self.display_type(item)),
+            Type::Range(element, false) => format!("Range<{}>", self.display_type(element)),
+            Type::Range(element, true) => format!("RangeInclusive<{}>", self.display_type(element)),
+            Type::Tuple(elements) if elements.len() == 1 => format!("({},)", self.display_type(&elements[0])),
+            Type::Tuple(elements) => {
+                let elements: Vec<String> = elements.iter().map(|element| self.display_type(element)).collect();
+                format!("({})", elements.join(", "))
+            },
+            Type::FnPtr(params, ret) | Type::Closure(params, ret) => {
+                let prefix = if matches!(typ, Type::FnPtr(_, _)) { "fn" } else { "impl Fn" };
+                let params: Vec<String> = params.iter().map(|param| self.display_type(param)).collect();
+                match &**ret {
+                    Type::Void => format!("{}({})", prefix, params.join(", ")),
+                    _ => format!("{}({}) -> {}", prefix, params.join(", "), self.display_type(ret)),
+                }
+            },
+            _ => typ.to_string(),
+        }
+    }
+
+    pub fn is_formatter(typ: &Type) -> bool {
+        matches!(typ, Type::Named(name) if name == FORMATTER)
+    }
+
+    pub fn is_integer(typ: &Type) -> bool {
+        match typ {
+            Type::I8 | Type::I16 | Type::I32 | Type::I64 | Type::I128 | Type::U8 => true,
+            Type::Named(name) => Self::is_hole(name),
+            _ => false,
+        }
+    }
+
+    // Type encore à déduire par la monomorphisation (`let mut v = Vec::new(); v.push(1);`) : seul un
+    // littéral entier le laisse ouvert, et il vaut i32 si rien d'autre ne le fixe
+    pub fn is_hole(name: &str) -> bool {
+        name.len() > 1 && name.starts_with('_') && name[1..].bytes().all(|b| b.is_ascii_digit())
+    }
+
+    // Unifie un type de la signature (contenant des paramètres génériques) avec un type effectif
+    pub fn unify(&self, pattern: &Type, actual: &Type, type_params: &[TypeParam], subst: &mut HashMap<String, Type>) -> Result<(), String> {
+        // Une instance monomorphisée se compare à sa forme générique
+        if let Type::Named(name) = actual {
+            if let Some((generic, args)) = self.instances.get(name) {
+                if matches!(pattern, Type::Generic(_, _)) {
+                    return self.unify(pattern, &Type::Generic(generic.clone(), args.clone()), type_params, subst);
+                }
+            }
+        }
+
+        match (pattern, actual) {
+            (_, Type::Named(name)) if name == "_" => Ok(()),
+            (Type::Named(name), _) if type_params.iter().any(|param| param.name == *name) => {
+                match subst.get(name) {
+                    None => {
+                        subst.insert(name.clone(), actual.clone());
+                        Ok(())
+                    },
+                    Some(bound) if bound == actual => Ok(()),
+                    // Un littéral entier (i32 par défaut) s'adapte à l'autre type entier
+                    Some(bound) if Self::is_integer(bound) && Self::is_integer(actual) => {
+                        if *bound == Type::I32 {
+                            subst.insert(name.clone(), actual.clone());
+                        }
+                        Ok(())
+                    },
+                    Some(bound) => Err(format!(
+                        "types incompatibles pour le paramètre générique `{}` : `{}` et `{}`",
+                        name, self.display_type(bound), self.display_type(actual))),
+                }
+            },
+            (Type::Ref(pattern, _), Type::Ref(actual, _))
+            | (Type::Box(pattern), Type::Box(actual))
+            | (Type::Rc(pattern), Type::Rc(actual))
+            | (Type::RefCell(pattern), Type::RefCell(actual))
+            | (Type::Guard(pattern, _), Type::Guard(actual, _))
+            | (Type::HashSet(pattern), Type::HashSet(actual))
+            | (Type::Vec(pattern), Type::Vec(actual))
+            | (Type::Option(pattern), Type::Option(actual))
+            | (Type::Iterator(pattern), Type::Iterator(actual))
+            | (Type::Range(pattern, _), Type::Range(actual, _)) => self.unify(pattern, actual, type_params, subst),
+            (Type::Result(pattern_ok, pattern_err), Type::Result(actual_ok, actual_err))
+            | (Type::HashMap(pattern_ok, pattern_err), Type::HashMap(actual_ok, actual_err))
+            | (Type::Entry(pattern_ok, pattern_err), Type::Entry(actual_ok, actual_err)) => {
+                self.unify(pattern_ok, actual_ok, type_params, subst)?;
+                self.unify(pattern_err, actual_err, type_params, subst)
+            },
+            (Type::Array(pattern, _), Type::Array(actual, _)) => self.unify(pattern, actual, type_params, subst),
+            // &Vec<T> et &[T; N] se convertissent en &[T]
+            (Type::Slice(pattern), Type::Slice(actual) | Type::Vec(actual) | Type::Array(actual, _)) => self.unify(pattern, actual, type_params, subst),
+            // &String se convertit en &str
+            (Type::Str, Type::Str | Type::String) => Ok(()),
+            (Type::Tuple(pattern_args), Type::Tuple(actual_args)) if pattern_args.len() == actual_args.len() => {
+                for (pattern, actual) in pattern_args.iter().zip(actual_args) {
+                    self.unify(pattern, actual, type_params, subst)?;
+                }
+                Ok(())
+            },
+            (Type::Generic(pattern_name, pattern_args), Type::Generic(actual_name, actual_args)) if pattern_name == actual_name => {
+                for (pattern, actual) in pattern_args.iter().zip(actual_args) {
+                    self.unify(pattern, actual, type_params, subst)?;
+                }
+                Ok(())
+            },
+            // Une fonction nommée convient là où une fermeture est attendue
+            (Type::FnPtr(pattern_params, pattern_ret) | Type::Closure(pattern_params, pattern_ret), Type::FnPtr(actual_params, actual_ret) | Type::Closure(actual_params, actual_ret))
+                if pattern_params.len() == actual_params.len() => {
+                for (pattern, actual) in pattern_params.iter().zip(actual_params) {
+                    self.unify(pattern, actual, type_params, subst)?;
+                }
+                self.unify(pattern_ret, actual_ret, type_params, subst)
+            },
+            _ => Ok(()),
+        }
+    }
+
+    // Type d'une signature générique après substitution ; les paramètres non déterminés deviennent `_`
+    pub fn instantiate(typ: &Type, type_params: &[TypeParam], subst: &HashMap<String, Type>) -> Type {
+        let mut complete = subst.clone();
+        for param in type_params {
+            complete.entry(param.name.clone()).or_insert_with(|| Type::Named("_".to_string()));
+        }
+        Self::substitute(typ, &complete)
+    }
+
+    // Détermine les paramètres génériques d'un appel à partir du turbofish, des arguments et du type attendu
+    pub fn infer_call(&self, sig: &FnSig, mut subst: HashMap<String, Type>, explicit: &[Type], arg_types: &[Type], expected: Option<&Type>) -> Result<HashMap<String, Type>, usize> {
+        if !explicit.is_empty() {
+            if explicit.len() > sig.type_params.len() {
+                return self.error(&format!("{} argument(s) générique(s) attendu(s), {} fourni(s)", sig.type_params.len(), explicit.len()));
+            }
+            // Le turbofish désigne les paramètres propres à la fonction, qui suivent ceux du bloc impl
+            let own = &sig.type_params[sig.type_params.len() - explicit.len()..];
+            for (param, arg) in own.iter().zip(explicit) {
+                subst.insert(param.name.clone(), self.resolve_type(arg));
+            }
+        }
+
+        for (param, arg) in sig.params.iter().zip(arg_types) {
+            if let Err(message) = self.unify(param, arg, &sig.type_params, &mut subst) {
+                return self.error(&message);
+            }
+        }
+
+        if let Some(expected) = expected {
+            // Le type attendu ne sert qu'à compléter l'inférence
+            let _ = self.unify(&sig.return_type, expected, &sig.type_params, &mut subst);
+        }
+
+        Ok(subst)
+    }
+
+    // Vérifie qu'un type écrit dans le programme existe et, pour `dyn Trait`, que le trait est utilisable comme objet
+    pub fn check_type(&self, typ: &Type, line: usize) -> Result<(), usize> {
+        match typ {
+            Type::Named(name) => {
+                if name == "Self" || name == "_" || self.is_type_param(name) {
+                    return Ok(());
+                }
+                if self.enums.contains_key(name) {
+                    return Ok(());
+                }
+                match self.structs.get(name) {
+                    Some(def) if def.type_params.is_empty() => Ok(()),
+                    Some(def) => self.error_at(line, &format!("la structure `{}` attend {} argument(s) générique(s)", name, def.type_params.len())),
+                    None => self.error_at(line, &format!("Type inconnu: {}", name)),
+                }
+            },
+            Type::Generic(name, args) => {
+                match self.structs.get(name) {
+                    Some(def) if def.type_params.len() == args.len() => {
+                        for arg in args {
+                            self.check_type(arg, line)?;
+                        }
+                        Ok(())
+                    },
+                    Some(def) => self.error_at(line, &format!(
+                        "la structure `{}` attend {} argument(s) générique(s), {} fourni(s)", name, def.type_params.len(), args.len())),
+                    None => self.error_at(line, &format!("Type inconnu: {}", name)),
+                }
+            },
+            Type::Dyn(trait_name) => self.check_object_safety(trait_name, line),
+            Type::Ref(inner, _) | Type::Box(inner) | Type::Vec(inner) | Type::Option(inner) => self.check_type(inner, line),
+            Type::Rc(inner) | Type::RefCell(inner) | Type::Guard(inner, _) => {
+                if let Type::Dyn(trait_name) = &**inner {
+                    return self.error_at(line, &format!("`{}` n'est pas pris en charge avec `dyn {}` : utilisez `Box<dyn {}>`", typ, trait_name, trait_name));
+                }
+                self.check_type(inner, line)
+            },
+            Type::Result(ok, err) => {
+                self.check_type(ok, line)?;
+                self.check_type(err, line)
+            },
+            Type::HashMap(key, _) | Type::HashSet(key) | Type::Entry(key, _) if !Self::is_hash_key(key) => {
+                self.error_at(line, &format!(
+                    "le type `{}` ne peut pas servir de clé : seuls les entiers, les booléens, les caractères et String sont pris en charge",
+                    self.display_type(key)))
+            },
+            Type::HashMap(key, value) | Type::Entry(key, value) => {
+                self.check_type(key, line)?;
+                self.check_type(value, line)
+            },
+            Type::HashSet(element) => self.check_type(element, line),
+            Type::Tuple(elements) => {
+                for element in elements {
+                    if let Type::Dyn(trait_name) = element {
+                        return self.error_at(line, &format!("le type `dyn {}` n'a pas de taille connue et ne peut pas être un élément de tuple", trait_name));
+                    }
+                    self.check_type(element, line)?;
+                }
+                Ok(())
+            },
+            Type::Array(element, _) | Type::Slice(element) => {
+                if let Type::Dyn(trait_name) = &**element {
+                    return self.error_at(line, &format!("le type `dyn {}` n'a pas de taille connue et ne peut pas être un élément de tableau", trait_name));
+                }
+                self.check_type(element, line)
+            },
+            Type::FnPtr(params, ret) | Type::Closure(params, ret) => {
+                for param in params {
+                    self.check_type(param, line)?;
+                }
+                self.check_type(ret, line)
+            },
+            _ => Ok(()),
+        }
+    }
+
+    // Un trait ne peut devenir un objet que si toutes ses méthodes peuvent être appelées via une vtable
+    pub fn check_object_safety(&self, trait_name: &str, line: usize) -> Result<(), usize> {
+        let trait_def = match self.traits.get(trait_name) {
+            Some(trait_def) => trait_def,
+            None => return self.error_at(line, &format!("Trait inconnu: {}", trait_name)),
+        };
+
+        let mut violations = Vec::new();
+        for method in &trait_def.methods {
+            let function = &method.function;
+            let has_receiver = function.params.first().is_some_and(|(name, _)| name == "self");
+            if !has_receiver {
+                violations.push(format!("la fonction associée `{}` (ligne {}) n'a pas de receveur `self`", function.name, function.line));
+                continue;
+            }
+
+            if !function.type_params.is_empty() {
+                violations.push(format!("la méthode `{}` (ligne {}) a des paramètres génériques", function.name, function.line));
+            }
+
+            let uses_self = function.params.iter().skip(1).any(|(_, typ)| Self::mentions(typ, "Self"))
+                || function.return_type.as_ref().is_some_and(|typ| Self::mentions(typ, "Self"));
+            if uses_self {
+                violations.push(format!("la méthode `{}` (ligne {}) utilise le type `Self` dans sa signature", function.name, function.line));
+            }
+        }
+
+        if violations.is_empty() {
+            return Ok(());
+        }
+
+        for violation in &violations {
+            self.error_handler.report_error(line, &format!(
+                "le trait `{}` ne peut pas être utilisé comme objet (`dyn {}`) : {}", trait_name, trait_name, violation));
+        }
+        Err(line)
+    }
+
+    // Les contraintes doivent désigner des traits connus
+    fn check_type_params(&self, type_params: &[TypeParam], line: usize) -> Result<(), usize> {
+        for param in type_params {
+            for bound in &param.bounds {
+                if !BUILTIN_TRAITS.contains(&bound.as_str()) && !self.traits.contains_key(bound) {
+                    return self.error_at(line, &format!("Trait inconnu dans la contrainte `{}: {}`", param.name, bound));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Enregistre les structures, traits, fonctions et méthodes d'un programme
+    pub fn collect_declarations(&mut self, program: &Program) -> Result<(), usize> {
+        for struct_def in &program.structs {
+            if let Some(origin) = &struct_def.instance_of {
+                self.instances.insert(struct_def.name.clone(), origin.clone());
+            }
+            if self.structs.insert(struct_def.name.clone(), struct_def.clone()).is_some() {
+                return self.error_at(struct_def.line, &format!("Structure définie plusieurs fois: {}", struct_def.name));
+            }
+        }
+        for enum_def in &program.enums {
+            if self.structs.contains_key(&enum_def.name) || self.enums.insert(enum_def.name.clone(), enum_def.clone()).is_some() {
+                return self.error_at(enum_def.line, &format!("Type défini plusieurs fois: {}", enum_def.name));
+            }
+        }
+
+        for trait_def in &program.traits {
+            if self.traits.insert(trait_def.name.clone(), trait_def.clone()).is_some() {
+                return self.error_at(trait_def.line, &format!("Trait défini plusieurs fois: {}", trait_def.name));
+            }
+        }
+
+        // #[derive(..)] : Debug et Clone sont engendrés par le générateur de code ; Copy est un marqueur,
+        // une structure ou une énumération étant toujours copiée octet par octet
+        for struct_def in &program.structs {
+            for trait_name in &struct_def.derives {
+                if !matches!(trait_name.as_str(), "Debug" | "Clone" | "Copy") {
+                    return self.error_at(struct_def.line, &format!(
+                        "#[derive({})] n'est pas pris en charge : seuls Debug, Clone et Copy peuvent être dérivés", trait_name));
+                }
+                if !self.trait_impls.insert((struct_def.name.clone(), trait_name.clone())) {
+                    return self.error_at(struct_def.line, &format!("trait `{}` dérivé plusieurs fois pour `{}`", trait_name, struct_def.name));
+                }
+            }
+        }
+        for enum_def in &program.enums {
+            for trait_name in &enum_def.derives {
+                if !matches!(trait_name.as_str(), "Debug" | "Clone" | "Copy") {
+                    return self.error_at(enum_def.line, &format!(
+                        "#[derive({})] n'est pas pris en charge : seuls Debug, Clone et Copy peuvent être dérivés", trait_name));
+                }
+                if !self.trait_impls.insert((enum_def.name.clone(), trait_name.clone())) {
+                    return self.error_at(enum_def.line, &format!("trait `{}` dérivé plusieurs fois pour `{}`", trait_name, enum_def.name));
+                }
+            }
+        }
+        // Une énumération Copy ne contient que des champs Copy
+        for enum_def in program.enums.iter().filter(|def| def.derives.iter().any(|name| name == "Copy")) {
+            if !enum_def.derives.iter().any(|name| name == "Clone") {
+                return self.error_at(enum_def.line, &format!("#[derive(Copy)] exige aussi Clone : `#[derive(Clone, Copy)]` pour `{}`", enum_def.name));
+            }
+            for (variant, fields) in &enum_def.variants {
+                if let Some(typ) = fields.iter().find(|typ| !self.satisfies(typ, "Copy")) {
+                    return self.error_at(enum_def.line, &format!(
+                        "#[derive(Copy)] impossible pour `{}` : la variante `{}` contient un `{}`, qui n'implémente pas Copy",
+                        enum_def.name, variant, self.display_type(typ)));
+                }
+            }
+        }
+        for struct_def in program.structs.iter().filter(|def| def.derives.iter().any(|name| name == "Copy")) {
+            if !struct_def.derives.iter().any(|name| name == "Clone") {
+                return self.error_at(struct_def.line, &format!("#[derive(Copy)] exige aussi Clone : `#[derive(Clone, Copy)]` pour `{}`", struct_def.name));
+            }
+            let field = struct_def.fields.iter().find(|(_, typ)| struct_def.type_params.is_empty() && !self.satisfies(typ, "Copy"));
+            if let Some((field, typ)) = field {
+                return self.error_at(struct_def.line, &format!(
+                    "#[derive(Copy)] impossible pour `{}` : le champ `{}` de type `{}` n'implémente pas Copy", struct_def.name, field, self.display_type(typ)));
+            }
+        }
+        // La copie en profondeur d'un Clone dérivé clone chacun de ses champs
+        for struct_def in program.structs.iter().filter(|def| def.derives.iter().any(|name| name == "Clone")) {
+            let field = struct_def.fields.iter().find(|(_, typ)| struct_def.type_params.is_empty() && !self.satisfies(typ, "Clone"));
+            if let Some((field, typ)) = field {
+                return self.error_at(struct_def.line, &format!(
+                    "#[derive(Clone)] impossible pour `{}` : le champ `{}` de type `{}` n'implémente pas Clone", struct_def.name, field, self.display_type(typ)));
+            }
+        }
+        for enum_def in program.enums.iter().filter(|def| def.derives.iter().any(|name| name == "Clone")) {
+            for (variant, fields) in &enum_def.variants {
+                if let Some(typ) = fields.iter().find(|typ| !self.satisfies(typ, "Clone")) {
+                    return self.error_at(enum_def.line, &format!(
+                        "#[derive(Clone)] impossible pour `{}` : la variante `{}` contient un `{}`, qui n'implémente pas Clone",
+                        enum_def.name, variant, self.display_type(typ)));
+                }
+            }
+        }
+
+        for struct_def in &program.structs {
+            self.check_type_params(&struct_def.type_params, struct_def.line)?;
+            self.type_params = struct_def.type_params.clone();
+            for (_, field_type) in &struct_def.fields {
+                self.check_type(field_type, struct_def.line)?;
+            }
+            self.type_params.clear();
+            self.check_recursive_struct(&struct_def.name, &mut Vec::new(), struct_def.line)?;
+        }
+        for enum_def in &program.enums {
+            for field_type in enum_def.variants.iter().flat_map(|(_, fields)| fields) {
+                self.check_type(field_type, enum_def.line)?;
+            }
+            self.check_recursive_struct(&enum_def.name, &mut Vec::new(), enum_def.line)?;
+        }
+
+        for function in &program.functions {
+            self.check_signature(function, &[])?;
+            let sig = FnSig {
+                label: function.name.clone(),
+                type_params: function.type_params.clone(),
+                params: function.params.iter().map(|(_, typ)| typ.clone()).collect(),
+                return_type: function.return_type.clone().unwrap_or(Type::Void),
+            };
+            if self.functions.insert(function.name.clone(), sig).is_some() {
+                return self.error_at(function.line, &format!("Fonction définie plusieurs fois: {}", function.name));
+            }
+        }
+
+        for impl_block in &program.impls {
+            self.register_impl(impl_block)?;
+        }
+
+        Ok(())
+    }
+
+    // Type implémenté par un bloc impl : structure (générique ou non) ou type primitif
+    pub fn impl_type(&self, impl_block: &ImplBlock) -> Result<Type, usize> {
+        if !impl_block.type_args.is_empty() {
+            let typ = Type::Generic(impl_block.type_name.clone(), impl_block.type_args.clone());
+            self.check_type(&typ, impl_block.line)?;
+            return Ok(typ);
+        }
+
+        match self.type_from_name(&impl_block.type_name) {
+            Some(typ) => {
+                self.check_type(&typ, impl_block.line)?;
+                Ok(typ)
+            },
+            None => self.error_at(impl_block.line, &format!("Type inconnu dans le bloc impl: {}", impl_block.type_name)),
+        }
+    }
+
+    pub fn register_impl(&mut self, impl_block: &ImplBlock) -> Result<(), usize> {
+        self.check_type_params(&impl_block.type_params, impl_block.line)?;
+        self.type_params = impl_block.type_params.clone();
+        let concrete = self.impl_type(impl_block);
+        self.type_params.clear();
+        let concrete = concrete?;
+
+        if let Some(trait_name) = &impl_block.trait_name {
+            let trait_def = match self.traits.get(trait_name) {
+                Some(trait_def) => trait_def.clone(),
+                None => return self.error_at(impl_block.line, &format!("Trait inconnu: {}", trait_name)),
+            };
+            if trait_name == "Drop" {
+                // Seule une structure possède un destructeur, et une valeur Copy n'en a jamais
+                if !matches!(concrete, Type::Named(_) | Type::Generic(_, _)) || !self.structs.contains_key(&impl_block.type_name) {
+                    return self.error_at(impl_block.line, &format!(
+                        "Drop ne peut être implémenté que pour une structure, pas pour `{}`", impl_block.type_name));
+                }
+                if self.trait_impls.contains(&(impl_block.type_name.clone(), "Copy".to_string())) {
+                    return self.error_at(impl_block.line, &format!(
+                        "le type `{}` implémente Copy : il ne peut pas implémenter Drop", impl_block.type_name));
+                }
+            }
+
+            for method in &impl_block.methods {
+                match trait_def.methods.iter().find(|m| m.function.name == method.name) {
+                    Some(declared) if declared.function.params.len() != method.params.len() => {
+                        return self.error_at(method.line, &format!(
+                            "la méthode `{}` a {} paramètre(s) mais sa déclaration dans le trait `{}` en a {}",
+                            method.name, method.params.len(), trait_name, declared.function.params.len()));
+                    },
+                    Some(_) => {},
+                    None => {
+                        return self.error_at(method.line, &format!("la méthode `{}` n'est pas membre du trait `{}`", method.name, trait_name));
+                    }
+                }
+            }
+
+            for declared in &trait_def.methods {
+                if impl_block.methods.iter().any(|m| m.name == declared.function.name) {
+                    continue;
+                }
+                if !declared.has_default {
+                    return self.error_at(impl_block.line, &format!(
+                        "méthode `{}` manquante dans l'implémentation de `{}` pour `{}`", declared.function.name, trait_name, impl_block.type_name));
+                }
+            }
+
+            if !self.trait_impls.insert((impl_block.type_name.clone(), trait_name.clone())) {
+                return self.error_at(impl_block.line, &format!(
+                    "implémentations conflictuelles du trait `{}` pour `{}`", trait_name, impl_block.type_name));
+            }
+
+            // Les méthodes par défaut deviennent des méthodes du type
+            let defaults: Vec<Function> = trait_def.methods.iter()
+                .filter(|m| m.has_default && !impl_block.methods.iter().any(|f| f.name == m.function.name))
+                .map(|m| m.function.clone())
+                .collect();
+            for function in defaults {
+                self.register_method(impl_block, &concrete, &function)?;
+            }
+        }
+
+        for function in &impl_block.methods {
+            self.register_method(impl_block, &concrete, function)?;
+        }
+
+        Ok(())
+    }
+
+    fn register_method(&mut self, impl_block: &ImplBlock, concrete: &Type, function: &Function) -> Result<(), usize> {
+        self.check_signature(function, &impl_block.type_params)?;
+
+        let type_name = &impl_block.type_name;
+        let trait_name = impl_block.trait_name.as_deref();
+        let mut type_params = impl_block.type_params.clone();
+        type_params.extend(function.type_params.iter().cloned());
+
+        let sig = FnSig {
+            label: Self::method_label(type_name, trait_name, &function.name),
+            type_params,
+            params: function.params.iter().map(|(_, typ)| Self::substitute_self(typ, concrete)).collect(),
+            return_type: Self::substitute_self(&function.return_type.clone().unwrap_or(Type::Void), concrete),
+        };
+        let has_self = function.params.first().is_some_and(|(name, _)| name == "self");
+        let inherent = trait_name.is_none();
+        let key = (type_name.to_string(), function.name.clone());
+
+        match self.methods.get(&key) {
+            Some(existing) if existing.inherent && inherent => {
+                return self.error_at(function.line, &format!("Méthode définie plusieurs fois: {}::{}", type_name, function.name));
+            },
+            // Une méthode inhérente est prioritaire sur une méthode de trait du même nom
+            Some(existing) if existing.inherent || !inherent => return Ok(()),
+            _ => {},
+        }
+
+        self.methods.insert(key, MethodInfo { sig, has_self, inherent, impl_type: concrete.clone() });
+        Ok(())
+    }
+
+    fn check_signature(&mut self, function: &Function, outer_params: &[TypeParam]) -> Result<(), usize> {
+        self.check_type_params(&function.type_params, function.line)?;
+        self.type_params = outer_params.to_vec();
+        self.type_params.extend(function.type_params.iter().cloned());
+
+        let mut result = Ok(());
+        for param_type in function.params.iter().map(|(_, typ)| typ).chain(function.return_type.iter()) {
+            result = self.check_type(param_type, function.line);
+            if result.is_err() {
+                break;
+            }
+        }
+
+        self.type_params.clear();
+        result
+    }
+
+    // Une structure ou une énumération qui se contient elle-même par valeur aurait une taille infinie
+    fn check_recursive_struct(&self, name: &str, path: &mut Vec<String>, line: usize) -> Result<(), usize> {
+        if path.iter().any(|n| n == name) {
+            return self.error_at(line, &format!(
+                "type récursif `{}` de taille infinie (utilisez une indirection comme Box<{}>)", path[0], path[0]));
+        }
+
+        let fields: Vec<&Type> = match (self.structs.get(name), self.enums.get(name)) {
+            (Some(def), _) => def.fields.iter().map(|(_, field_type)| field_type).collect(),
+            (None, Some(def)) => def.variants.iter().flat_map(|(_, fields)| fields).collect(),
+            (None, None) => return Ok(()),
+        };
+        path.push(name.to_string());
+        for field_type in fields {
+            for field_struct in Self::contained_structs(field_type) {
+                self.check_recursive_struct(&field_struct, path, line)?;
+            }
+        }
+        path.pop();
+
+        Ok(())
+    }
+
+    // Structures contenues par valeur dans un type (directement, dans un tuple ou dans un tableau)
+    fn contained_structs(typ: &Type) -> Vec<String> {
+        match typ {
+            Type::Named(name) | Type::Generic(name, _) => vec![name.clone()],
+            Type::Tuple(elements) => elements.iter().flat_map(Self::contained_structs).collect(),
+            Type::Array(element, _) | Type::Option(element) | Type::RefCell(element) => Self::contained_structs(element),
+            Type::Result(ok, err) => Self::contained_structs(ok).into_iter().chain(Self::contained_structs(err)).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    // Détermine le type statique d'une expression
+    pub fn expr_type(&self, expr: &Expr) -> Result<Type, usize> {
+        match expr {
+            Expr::Literal(Literal::Int(_)) => Ok(Type::I32),
+            Expr::Literal(Literal::Bool(_)) => Ok(Type::Bool),
+            Expr::Literal(Literal::String(_)) => Ok(Type::Ref(Box::new(Type::Str), false)),
+            Expr::Literal(Literal::Char(_)) => Ok(Type::Char),
+            // Un `Vec::new()` élément prend le type des autres éléments (`vec![vec![s], Vec::new()]`)
+            Expr::VecNew(elements) => match elements.iter().position(|element| !matches!(element, Expr::VecNew(inner) if inner.is_empty())) {
+                Some(first) => {
+                    let element_type = self.expr_type(&elements[first])?;
+                    for element in elements.iter().filter(|element| !matches!(element, Expr::VecNew(inner) if inner.is_empty())) {
+                        let other = self.expr_type(element)?;
+                        if !self.same_shape(&element_type, &other) {
+                            return self.error(&format!("types incompatibles dans vec! : `{}` et `{}`",
+                                self.display_type(&element_type), self.display_type(&other)));
+                        }
+                    }
+                    Ok(Type::Vec(Box::new(element_type)))
+                },
+                None => match elements.first() {
+                    Some(first) => Ok(Type::Vec(Box::new(self.expr_type(first)?))),
+                    None => Ok(Type::Vec(Box::new(Type::I32))),
+                },
+            },
+            Expr::VecRepeat(value, count) => {
+                let element_type = self.expr_type(value)?;
+                let count_type = self.expr_type(count)?;
+                if !Self::is_integer(&count_type) {
+                    return self.error(&format!("la longueur de `vec![valeur; n]` doit être un entier, pas `{}`", self.display_type(&count_type)));
+                }
+                if !self.satisfies(&element_type, "Clone") {
+                    return self.error(&format!("`vec![valeur; n]` exige que le type `{}` implémente Clone", self.display_type(&element_type)));
+                }
+                Ok(Type::Vec(Box::new(element_type)))
+            },
+            Expr::Format(_) => Ok(Type::String),
+            Expr::Write(destination, _) => match self.expr_type(destination)? {
+                Type::String => Ok(Type::Result(Box::new(Type::Void), Box::new(Type::Named(FMT_ERROR.to_string())))),
+                Type::Ref(inner, true) if matches!(&*inner, Type::String) || Self::is_formatter(&inner) => {
+                    Ok(Type::Result(Box::new(Type::Void), Box::new(Type::Named(FMT_ERROR.to_string()))))
+                },
+                other => self.error(&format!(
+                    "write! écrit dans une `String`, un `&mut String` ou un `&mut fmt::Formatter`, pas dans `{}`", self.display_type(&other))),
+            },
+            Expr::Literal(Literal::Array(elements)) => {
+                let element_type = match elements.first() {
+                    Some(first) => self.expr_type(first)?,
+                    None => Type::I32,
+                };
+                for element in elements.iter().skip(1) {
+                    let other = self.expr_type(element)?;
+                    if !self.same_shape(&element_type, &other) {
+                        return self.error(&format!("types incompatibles dans le tableau : `{}` et `{}`",
+                            self.display_type(&element_type), self.display_type(&other)));
+                    }
+                }
+                Ok(Type::Array(Box::new(element_type), elements.len()))
+            },
+            Expr::Literal(Literal::ArrayRepeat(value, length)) => {
+                let element_type = self.expr_type(value)?;
+                if *length > 1 && !self.satisfies(&element_type, "Copy") {
+                    return self.error(&format!("`[valeur; N]` exige que le type `{}` implémente Copy", self.display_type(&element_type)));
+                }
+                Ok(Type::Array(Box::new(element_type), *length))
+            },
+            Expr::Variable(name) => match self.variables.get(name) {
+                Some(var_type) => Ok(var_type.clone()),
+                None if self.enum_variant(name).is_some() => match self.enum_variant(name) {
+                    Some((enum_name, _, fields)) if fields.is_empty() => Ok(Type::Named(enum_name)),
+                    _ => self.error(&format!("la variante `{}` a des champs : construisez-la avec `{}(..)`", name, name)),
+                },
+                None if self.functions.contains_key(name) || name.contains("::") => self.function_value_type(name),
+                None => self.error(&format!("Variable non trouvée: {}", name)),
+            },
+            Expr::Binary(left, op, right) => {
+                let left_type = self.expr_type(left)?;
+                if Self::string_derefs(&left_type).is_some() {
+                    return self.string_operation(&left_type, op, &self.expr_type(right)?);
+                }
+                if let Type::Named(name) = &left_type {
+                    if self.is_type_param(name) {
+                        let (trait_name, symbol) = Self::operator_trait(op);
+                        if !self.satisfies(&left_type, trait_name) {
+                            let bound = match trait_name {
+                                "PartialEq" | "PartialOrd" => trait_name.to_string(),
+                                _ => format!("{}<Output = {}>", trait_name, name),
+                            };
+                            return self.error(&format!(
+                                "l'opérateur `{}` ne s'applique pas au type générique `{}` : ajoutez la contrainte `{}: {}`", symbol, name, name, bound));
+                        }
+                    }
+                }
+                // &i32 + i32 : l'opération porte sur la valeur désignée
+                let (value_type, _) = Self::scalar_pointee(&left_type);
+                match op {
+                    BinaryOp::Add | BinaryOp::Subtract | BinaryOp::Multiply | BinaryOp::Divide | BinaryOp::Modulo => Ok(value_type.clone()),
+                    _ => Ok(Type::Bool),
+                }
+            },
+            Expr::Unary(op, operand) => {
+                let operand_type = self.expr_type(operand)?;
+                match op {
+                    UnaryOp::Negate => Ok(operand_type),
+                    UnaryOp::Ref => Ok(Type::Ref(Box::new(operand_type), false)),
+                    UnaryOp::RefMut => Ok(Type::Ref(Box::new(operand_type), true)),
+                    UnaryOp::Deref => match operand_type {
+                        Type::Ref(inner, _) | Type::Box(inner) | Type::Rc(inner) | Type::Guard(inner, _) => Ok(*inner),
+                        other => self.error(&format!("le type `{}` ne peut pas être déréférencé", self.display_type(&other))),
+                    },
+                }
+            },
+            // Une variable locale masque la fonction de même nom : l'appel passe par sa valeur
+            Expr::FunctionCall(name, _, args) if self.variables.contains_key(name) => {
+                self.indirect_call_type(&self.variables[name], args)
+            },
+            Expr::Call(callee, args) => self.indirect_call_type(&self.expr_type(callee)?, args),
+            Expr::Closure(closure) => {
+                let unknown = Type::Named("_".to_string());
+                let params = closure.params.iter()
+                    .map(|(_, typ)| typ.as_ref().map_or_else(|| unknown.clone(), |typ| self.resolve_type(typ)))
+                    .collect();
+                let return_type = closure.return_type.as_ref().map_or(unknown, |typ| self.resolve_type(typ));
+                Ok(Type::Closure(params, Box::new(return_type)))
+            },
+            Expr::FunctionCall(name, type_args, args) => {
+                // Une collection vide enveloppée porte son type, fixé par la monomorphisation
+                if let ("Box::new" | "Rc::new" | "RefCell::new", [inner]) = (name.as_str(), type_args.as_slice()) {
+                    let inner = Box::new(self.resolve_type(inner));
+                    return Ok(match name.as_str() {
+                        "Box::new" => Type::Box(inner),
+                        "Rc::new" => Type::Rc(inner),
+                        _ => Type::RefCell(inner),
+                    });
+                }
+                if name == "Box::new" {
+                    return match args.first() {
+                        Some(arg) => Ok(Type::Box(Box::new(self.expr_type(arg)?))),
+                        None => self.error("Box::new attend un argument"),
+                    };
+                }
+                if matches!(name.as_str(), "Rc::new" | "RefCell::new" | "Rc::clone" | "Rc::strong_count") {
+                    let [arg] = args.as_slice() else {
+                        return self.error(&format!("{}() attend 1 argument, {} fourni(s)", name, args.len()));
+                    };
+                    let arg_type = self.expr_type(arg)?;
+                    return match (name.as_str(), arg_type) {
+                        ("Rc::new", value) => Ok(Type::Rc(Box::new(value))),
+                        ("RefCell::new", value) => Ok(Type::RefCell(Box::new(value))),
+                        ("Rc::clone", Type::Ref(inner, _)) if matches!(*inner, Type::Rc(_)) => Ok(*inner),
+                        ("Rc::strong_count", Type::Ref(inner, _)) if matches!(*inner, Type::Rc(_)) => Ok(Type::I32),
+                        (_, other) => self.error(&format!("{}() attend un `&Rc<T>`, trouvé `{}`", name, self.display_type(&other))),
+                    };
+                }
+                if VARIANTS.contains(&name.as_str()) {
+                    return self.variant_type(name, type_args, args);
+                }
+                if name == "drop" && !self.functions.contains_key(name) {
+                    // drop(valeur) du prélude : la valeur est déplacée puis détruite
+                    if args.len() != 1 {
+                        return self.error(&format!("drop() attend 1 argument, {} fourni(s)", args.len()));
+                    }
+                    self.expr_type(&args[0])?;
+                    return Ok(Type::Void);
+                }
+                if name == "HashMap::new" || name == "HashSet::new" {
+                    // Les paramètres de la table sont fixés par la monomorphisation, d'après le type attendu
+                    if !args.is_empty() {
+                        return self.error(&format!("{}() n'attend pas d'argument", name));
+                    }
+                    return match (name.as_str(), type_args.as_slice()) {
+                        ("HashMap::new", [key, value]) => Ok(Type::HashMap(Box::new(key.clone()), Box::new(value.clone()))),
+                        ("HashSet::new", [element]) => Ok(Type::HashSet(Box::new(element.clone()))),
+                        _ => self.error(&format!("annotations de type nécessaires : précisez le type de la table créée par {}()", name)),
+                    };
+                }
+                if name == "String::new" || name == "String::from" {
+                    let arity = if name == "String::new" { 0 } else { 1 };
+                    if args.len() != arity {
+                        return self.error(&format!("{}() attend {} argument(s), {} fourni(s)", name, arity, args.len()));
+                    }
+                    if let Some(arg) = args.first() {
+                        let arg_type = self.expr_type(arg)?;
+                        if !matches!(arg_type, Type::Ref(_, _)) || Self::string_derefs(&arg_type).is_none() {
+                            return self.error(&format!("String::from attend un `&str`, trouvé `{}`", self.display_type(&arg_type)));
+                        }
+                    }
+                    return Ok(Type::String);
+                }
+
+                if let Some((enum_name, _, fields)) = self.enum_variant(name) {
+                    if args.len() != fields.len() {
+                        return self.error(&format!("la variante `{}` attend {} champ(s), {} fourni(s)", name, fields.len(), args.len()));
+                    }
+                    for arg in args {
+                        self.expr_type(arg)?;
+                    }
+                    return Ok(Type::Named(enum_name));
+                }
+
+                let (sig, seed) = self.lookup_function(name)?;
+                if sig.type_params.is_empty() {
+                    return Ok(sig.return_type);
+                }
+
+                let arg_types = args.iter().map(|arg| self.expr_type(arg)).collect::<Result<Vec<_>, _>>()?;
+                let subst = self.infer_call(&sig, seed, type_args, &arg_types, None)?;
+                self.check_bounds(name, &sig.type_params, &subst)?;
+                Ok(Self::instantiate(&sig.return_type, &sig.type_params, &subst))
+            },
+            Expr::MethodCall(obj_expr, method_name, type_args, args) => {
+                let obj_type = self.expr_type(obj_expr)?;
+                match self.resolve_method(&obj_type, method_name)? {
+                    MethodTarget::Static(info) if info.sig.label.ends_with("__Drop__drop") => {
+                        self.error("appel explicite du destructeur `Drop::drop` interdit : utilisez `drop(valeur)`")
+                    },
+                    MethodTarget::Static(info) => {
+                        if info.sig.type_params.is_empty() {
+                            return Ok(info.sig.return_type);
+                        }
+                        let mut subst = HashMap::new();
+                        if let Err(message) = self.unify(Self::strip_refs(&info.sig.params[0]), Self::strip_refs(&obj_type), &info.sig.type_params, &mut subst) {
+                            return self.error(&message);
+                        }
+                        let arg_types = args.iter().map(|arg| self.expr_type(arg)).collect::<Result<Vec<_>, _>>()?;
+                        let params = FnSig { params: info.sig.params[1..].to_vec(), ..info.sig.clone() };
+                        let subst = self.infer_call(&params, subst, &[], &arg_types, None)?;
+                        Ok(Self::instantiate(&info.sig.return_type, &info.sig.type_params, &subst))
+                    },
+                    MethodTarget::Dynamic(trait_name, index) => {
+                        let declared = &self.traits[&trait_name].methods[index].function;
+                        Ok(declared.return_type.clone().unwrap_or(Type::Void))
+                    },
+                    MethodTarget::Builtin if Self::iterator_item(&obj_type, method_name).is_some() => {
+                        if method_name == "rev" && !Self::is_double_ended(obj_expr, &obj_type) {
+                            return self.error("rev() s'applique directement à une plage ou à une collection (`v.iter().rev()`), avant tout adaptateur");
+                        }
+                        let item = Self::iterator_item(&obj_type, method_name).unwrap();
+                        self.iterator_method_type(&item, method_name, type_args, args)
+                    },
+                    MethodTarget::Builtin if method_name == "map" => self.map_function(&obj_type, args),
+                    MethodTarget::Builtin => {
+                        let (params, result) = self.builtin_signature(&obj_type, method_name, type_args)?;
+                        if args.len() != params.len() {
+                            return self.error(&format!("{}() attend {} argument(s), {} fourni(s)", method_name, params.len(), args.len()));
+                        }
+                        for (position, (arg, param)) in args.iter().zip(&params).enumerate() {
+                            let arg_type = self.expr_type(arg)?;
+                            self.check_builtin_arg(Self::builtin_receiver(&obj_type, method_name), method_name, position, &arg_type, param)?;
+                        }
+                        Ok(result)
+                    },
+                }
+            },
+            Expr::Index(base, index) => {
+                let (base_type, _) = self.indexed_type(base)?;
+
+                // v[a..b] désigne une tranche, utilisable derrière une référence : &v[a..b] ;
+                // s[a..b] sur une chaîne désigne un str, découpé en octets
+                if let Expr::Range(start, end, _) = &**index {
+                    for bound in [start, end].into_iter().flatten() {
+                        let bound_type = self.expr_type(bound)?;
+                        if !Self::is_integer(&bound_type) {
+                            return self.error(&format!("les bornes d'une plage doivent être des entiers, trouvé `{}`", self.display_type(&bound_type)));
+                        }
+                    }
+                    return match base_type {
+                        Type::String | Type::Str => Ok(Type::Str),
+                        Type::Array(element, _) | Type::Vec(element) | Type::Slice(element) => Ok(Type::Slice(element)),
+                        _ => unreachable!(),
+                    };
+                }
+                let (Type::Array(element, _) | Type::Vec(element) | Type::Slice(element)) = base_type else {
+                    return self.error(&format!("le type `{}` ne peut pas être indexé par un entier : utilisez une plage (`&s[a..b]`)",
+                        self.display_type(&base_type)));
+                };
+
+                let index_type = self.expr_type(index)?;
+                if !Self::is_integer(&index_type) {
+                    return self.error(&format!("l'index doit être un entier, trouvé `{}`", self.display_type(&index_type)));
+                }
+                Ok(*element)
+            },
+            // a..b est une valeur, et un itérateur d'entiers : (0..n).map(|i| i * i).sum()
+            Expr::Range(Some(start), Some(end), inclusive) => Ok(Type::Range(Box::new(self.range_element(start, end)?), *inclusive)),
+            Expr::Range(_, _, _) => self.error("une plage ouverte n'est utilisable qu'entre crochets : `&v[a..]`"),
+            Expr::FieldAccess(base, field_name) => {
+                let (struct_type, _) = self.struct_of(base)?;
+                self.field_type(&struct_type, field_name)
+            },
+            Expr::StructLiteral(name, field_values) => {
+                let struct_type = self.resolve_type(&Type::Named(name.clone()));
+                let def = match &struct_type {
+                    Type::Named(struct_name) => match self.structs.get(struct_name) {
+                        Some(def) if !def.type_params.is_empty() => def,
+                        _ => return Ok(struct_type),
+                    },
+                    _ => return Ok(struct_type),
+                };
+
+                // Structure générique : les arguments se déduisent des valeurs des champs, sauf d'un
+                // `Vec::new()` dont le type d'élément vient du contexte
+                let mut subst = HashMap::new();
+                for (field_name, value) in field_values {
+                    if matches!(value, Expr::VecNew(elements) if elements.is_empty()) {
+                        continue;
+                    }
+                    if let Some((_, declared)) = def.fields.iter().find(|(name, _)| name == field_name) {
+                        let value_type = self.expr_type(value)?;
+                        if let Err(message) = self.unify(declared, &value_type, &def.type_params, &mut subst) {
+                            return self.error(&message);
+                        }
+                    }
+                }
+                let args = def.type_params.iter()
+                    .map(|param| subst.get(&param.name).cloned().unwrap_or_else(|| Type::Named("_".to_string())))
+                    .collect();
+                Ok(Type::Generic(def.name.clone(), args))
+            },
+            Expr::Tuple(elements) if elements.is_empty() => Ok(Type::Void),
+            Expr::Tuple(elements) => Ok(Type::Tuple(elements.iter().map(|element| self.expr_type(element)).collect::<Result<Vec<_>, _>>()?)),
+            Expr::TupleIndex(base, index) => {
+                let (elements, _) = self.tuple_of(base)?;
+                match elements.get(*index) {
+                    Some(element) => Ok(element.clone()),
+                    None => self.error(&format!("aucun champ `{}` dans le tuple `{}`", index, self.display_type(&Type::Tuple(elements.clone())))),
+                }
+            },
+            Expr::Assign(..) | Expr::CompoundAssign(..) => Ok(Type::Void),
+            Expr::Try(operand) => match self.expr_type(operand)? {
+                Type::Option(inner) | Type::Result(inner, _) => Ok(*inner),
+                other => self.error(&format!(
+                    "l'opérateur `?` s'applique à un `Result` ou à une `Option`, pas à `{}`", self.display_type(&other))),
+            },
+        }
+    }
+
+    // Type d'une variante construite ; sans arguments génériques explicites, ce que la valeur ne détermine
+    // pas reste `_` (la monomorphisation le déduit du contexte)
+    pub fn variant_type(&self, name: &str, type_args: &[Type], args: &[Expr]) -> Result<Type, usize> {
+        self.check_variant(name, type_args, args)?;
+        let mut types: Vec<Type> = type_args.iter().map(|typ| self.resolve_type(typ)).collect();
+        if types.is_empty() {
+            let unknown = Type::Named("_".to_string());
+            let payload = match args.first() {
+                Some(arg) => self.expr_type(arg)?,
+                None => unknown.clone(),
+            };
+            types = match name {
+                "Some" | "None" => vec![payload],
+                "Ok" => vec![payload, unknown],
+                _ => vec![unknown, payload],
+            };
+        }
+        Ok(match name {
+            "Some" | "None" => Type::Option(Box::new(types.remove(0))),
+            _ => Type::Result(Box::new(types[0].clone()), Box::new(types[1].clone())),
+        })
+    }
+
+    // `None` ne prend pas de valeur, les autres variantes une ; Option a un argument générique, Result deux
+    pub fn check_variant(&self, name: &str, type_args: &[Type], args: &[Expr]) -> Result<(), usize> {
+        let arity = if name == "None" { 0 } else { 1 };
+        if args.len() != arity {
+            return self.error(&format!("`{}` attend {} argument(s), {} fourni(s)", name, arity, args.len()));
+        }
+        let generic_count = if matches!(name, "Some" | "None") { 1 } else { 2 };
+        if !type_args.is_empty() && type_args.len() != generic_count {
+            return self.error(&format!("{} argument(s) générique(s) attendu(s), {} fourni(s)", generic_count, type_args.len()));
+        }
+        Ok(())
+    }
+
+    // Variante désignée par `Enum::Variante` (ou `Self::Variante` dans un bloc impl) : nom de
+    // l'énumération, rang de la variante et types de ses champs
+    pub fn enum_variant(&self, path: &str) -> Option<(String, usize, Vec<Type>)> {
+        let (type_name, variant) = path.split_once("::")?;
+        let enum_name = match (type_name, &self.self_type) {
+            ("Self", Some(Type::Named(name))) => name.as_str(),
+            _ => type_name,
+        };
+        let def = self.enums.get(enum_name)?;
+        let index = def.variants.iter().position(|(name, _)| name == variant)?;
+        Some((enum_name.to_string(), index, def.variants[index].1.clone()))
+    }
+
+    // Type d'une fonction nommée utilisée comme valeur : un pointeur de fonction
+    fn function_value_type(&self, name: &str) -> Result<Type, usize> {
+        let (sig, _) = self.lookup_function(name)?;
+        if !sig.type_params.is_empty() {
+            return self.error(&format!("la fonction générique `{}` ne peut pas être utilisée comme valeur", name));
+        }
+        let params = sig.params.iter().map(|param| self.resolve_type(param)).collect();
+        Ok(Type::FnPtr(params, Box::new(self.resolve_type(&sig.return_type))))
+    }
+
+    // Paramètres et type de retour d'une valeur appelable (pointeur de fonction ou fermeture,
+    // éventuellement derrière des références ou un Box)
+    pub fn callable_signature(&self, typ: &Type) -> Result<(Vec<Type>, Type), usize> {
+        match typ {
+            Type::FnPtr(params, return_type) | Type::Closure(params, return_type) => Ok((params.clone(), (**return_type).clone())),
+            Type::Ref(inner, _) | Type::Box(inner) | Type::Rc(inner) | Type::Guard(inner, _) => self.callable_signature(inner),
+            other => self.error(&format!("le type `{}` n'est pas une fonction et ne peut pas être appelé", self.display_type(other))),
+        }
+    }
+
+    fn indirect_call_type(&self, callee: &Type, args: &[Expr]) -> Result<Type, usize> {
+        let (params, return_type) = self.callable_signature(callee)?;
+        if args.len() != params.len() {
+            return self.error(&format!("la fonction `{}` attend {} argument(s), {} fourni(s)", self.display_type(callee), params.len(), args.len()));
+        }
+        Ok(return_type)
+    }
+
+    // Fonction passée à `map` d'une Option ou d'un Result : une fonction nommée ou une fermeture qui prend
+    // la valeur contenue ; renvoie le type produit
+    pub fn map_function(&self, obj_type: &Type, args: &[Expr]) -> Result<Type, usize> {
+        let receiver = Self::strip_refs(obj_type);
+        let payload = match receiver {
+            Type::Option(inner) | Type::Result(inner, _) => inner,
+            _ => return self.error(&format!("aucune méthode `map` pour le type `{}`", self.display_type(obj_type))),
+        };
+        let [function] = args else {
+            return self.error("map() attend une fonction ou une fermeture, par exemple `.map(double)` ou `.map(|x| x * 2)`");
+        };
+
+        let function_type = self.expr_type(function)?;
+        let (params, return_type) = self.callable_signature(&function_type)?;
+        if params.len() != 1 {
+            return self.error(&format!("la fonction passée à map() doit prendre un paramètre, elle en prend {}", params.len()));
+        }
+        if params[0] != **payload && params[0] != Type::Named("_".to_string()) {
+            return self.error(&format!("la fonction passée à map() attend `{}`, mais map() lui passe `{}`",
+                self.display_type(&params[0]), self.display_type(payload)));
+        }
+
+        let output = Box::new(return_type);
+        Ok(match receiver {
+            Type::Result(_, err) => Type::Result(output, err.clone()),
+            _ => Type::Option(output),
+        })
+    }
+
+    // Type des entiers d'une plage : celui de ses bornes, un littéral prenant le type de l'autre borne
+    fn range_element(&self, start: &Expr, end: &Expr) -> Result<Type, usize> {
+        let (start_type, end_type) = (self.expr_type(start)?, self.expr_type(end)?);
+        for bound_type in [&start_type, &end_type] {
+            if !Self::is_integer(bound_type) {
+                return self.error(&format!("les bornes d'une plage doivent être des entiers, trouvé `{}`", self.display_type(bound_type)));
+            }
+        }
+        let is_literal = |bound: &Expr| match bound {
+            Expr::Unary(UnaryOp::Negate, operand) => matches!(**operand, Expr::Literal(Literal::Int(_))),
+            _ => matches!(bound, Expr::Literal(Literal::Int(_))),
+        };
+        match (is_literal(start), is_literal(end)) {
+            (true, false) => Ok(end_type),
+            _ if start_type == end_type || is_literal(end) => Ok(start_type),
+            _ => self.error(&format!("les bornes d'une plage doivent avoir le même type, trouvé `{}` et `{}`",
+                self.display_type(&start_type), self.display_type(&end_type))),
+        }
+    }
+
+    // Éléments produits par un itérateur, ou par une plage utilisée comme itérateur par `method_name`
+    pub fn iterator_item(obj_type: &Type, method_name: &str) -> Option<Type> {
+        match obj_type {
+            Type::Iterator(item) => Some((**item).clone()),
+            Type::Range(element, _) if !RANGE_METHODS.contains(&method_name) => Some((**element).clone()),
+            _ => None,
+        }
+    }
+
+    // rev() exige un itérateur parcourable depuis la fin : une plage ou une collection, sans adaptateur
+    fn is_double_ended(obj_expr: &Expr, obj_type: &Type) -> bool {
+        match obj_expr {
+            _ if matches!(obj_type, Type::Range(_, _)) => true,
+            Expr::MethodCall(_, method_name, _, _) => matches!(IteratorMethod::from_name(method_name), Some(IteratorMethod::Source(_))),
+            _ => false,
+        }
+    }
+
+    // Méthodes du prélude Iterator (voir `iterators`) sur un itérateur d'éléments `item`
+    pub fn iterator_method_type(&self, item: &Type, method_name: &str, type_args: &[Type], args: &[Expr]) -> Result<Type, usize> {
+        let method = match IteratorMethod::from_name(method_name) {
+            Some(IteratorMethod::Adapter(adapter)) => IteratorMethod::Adapter(adapter),
+            Some(IteratorMethod::Consumer(consumer)) => IteratorMethod::Consumer(consumer),
+            _ => return self.error(&format!("aucune méthode `{}` pour le type `{}`", method_name, self.display_type(&Type::Iterator(Box::new(item.clone()))))),
+        };
+        if !type_args.is_empty() && !matches!(method, IteratorMethod::Consumer(Consumer::Collect | Consumer::Sum | Consumer::Product)) {
+            return self.error(&format!("{}() n'attend pas d'arguments génériques", method_name));
+        }
+        let arity = match method {
+            IteratorMethod::Adapter(Adapter::Enumerate | Adapter::Rev) | IteratorMethod::Consumer(Consumer::Sum | Consumer::Product | Consumer::Count
+                | Consumer::Min | Consumer::Max | Consumer::Collect) => 0,
+            IteratorMethod::Consumer(Consumer::Fold) => 2,
+            _ => 1,
+        };
+        if args.len() != arity {
+            return self.error(&format!("{}() attend {} argument(s), {} fourni(s)", method_name, arity, args.len()));
+        }
+        let iterator = |item: Type| Type::Iterator(Box::new(item));
+        let value = Self::scalar_pointee(item).0;
+
+        Ok(match method {
+            IteratorMethod::Adapter(Adapter::Map) => iterator(self.iterator_function(method_name, &args[0], std::slice::from_ref(item), None)?),
+            IteratorMethod::Adapter(Adapter::Filter) => {
+                self.iterator_function(method_name, &args[0], &[Type::Ref(Box::new(item.clone()), false)], Some(&Type::Bool))?;
+                iterator(item.clone())
+            },
+            IteratorMethod::Adapter(Adapter::Enumerate) => iterator(Type::Tuple(vec![Type::I32, item.clone()])),
+            IteratorMethod::Adapter(Adapter::Zip) => iterator(Type::Tuple(vec![item.clone(), self.iteration_type(&args[0])?])),
+            IteratorMethod::Adapter(Adapter::Rev) => iterator(item.clone()),
+            IteratorMethod::Adapter(Adapter::Take | Adapter::Skip | Adapter::StepBy) => {
+                let count_type = self.expr_type(&args[0])?;
+                if !Self::is_integer(&count_type) {
+                    return self.error(&format!("{}() attend un nombre d'éléments entier, trouvé `{}`", method_name, self.display_type(&count_type)));
+                }
+                iterator(item.clone())
+            },
+            IteratorMethod::Consumer(Consumer::Sum | Consumer::Product) => {
+                if !Self::is_integer(value) {
+                    return self.error(&format!("{}() exige des éléments entiers, trouvé `{}`", method_name, self.display_type(item)));
+                }
+                if let [target] = type_args {
+                    if target != value {
+                        return self.error(&format!("{}::<{}>() sur des éléments `{}` : les types doivent correspondre",
+                            method_name, self.display_type(target), self.display_type(item)));
+                    }
+                }
+                value.clone()
+            },
+            IteratorMethod::Consumer(Consumer::Count) => Type::I32,
+            IteratorMethod::Consumer(Consumer::Min | Consumer::Max) => {
+                if !Self::is_integer(value) && !matches!(value, Type::Bool | Type::Char) {
+                    return self.error(&format!("{}() n'est pris en charge que pour des entiers, booléens ou caractères, pas `{}`",
+                        method_name, self.display_type(item)));
+                }
+                Type::Option(Box::new(item.clone()))
+            },
+            IteratorMethod::Consumer(Consumer::Collect) => match type_args {
+                [] => Type::Vec(Box::new(item.clone())),
+                [Type::Vec(element)] if **element == Type::Named("_".to_string()) || **element == *item => Type::Vec(Box::new(item.clone())),
+                [Type::String] if *item == Type::Char => Type::String,
+                [target] => return self.error(&format!("impossible de collecter des éléments `{}` dans un `{}` : seuls Vec<T> et String (depuis des char) sont pris en charge",
+                    self.display_type(item), self.display_type(target))),
+                _ => return self.error("collect() attend un seul argument générique : `collect::<Vec<_>>()`"),
+            },
+            IteratorMethod::Consumer(Consumer::Fold) => {
+                let accumulator = self.expr_type(&args[0])?;
+                self.iterator_function(method_name, &args[1], &[accumulator.clone(), item.clone()], Some(&accumulator))?;
+                accumulator
+            },
+            IteratorMethod::Consumer(Consumer::Any | Consumer::All) => {
+                self.iterator_function(method_name, &args[0], std::slice::from_ref(item), Some(&Type::Bool))?;
+                Type::Bool
+            },
+            IteratorMethod::Consumer(Consumer::ForEach) => {
+                self.iterator_function(method_name, &args[0], std::slice::from_ref(item), None)?;
+                Type::Void
+            },
+            IteratorMethod::Source(_) => unreachable!(),
+        })
+    }
+
+    // Fonction passée à un adaptateur ou un consommateur : elle reçoit `params` et, si `output` est
+    // donné, doit le renvoyer ; renvoie le type qu'elle produit
+    fn iterator_function(&self, method_name: &str, function: &Expr, params: &[Type], output: Option<&Type>) -> Result<Type, usize> {
+        let function_type = self.expr_type(function)?;
+        let (declared, return_type) = self.callable_signature(&function_type)?;
+        if declared.len() != params.len() {
+            return self.error(&format!("la fonction passée à {}() doit prendre {} paramètre(s), elle en prend {}", method_name, params.len(), declared.len()));
+        }
+        let unknown = Type::Named("_".to_string());
+        for (declared, param) in declared.iter().zip(params) {
+            if declared != param && *declared != unknown {
+                return self.error(&format!("la fonction passée à {}() attend `{}`, mais {}() lui passe `{}`",
+                    method_name, self.display_type(declared), method_name, self.display_type(param)));
+            }
+        }
+        if let Some(output) = output {
+            let compatible = return_type == *output || return_type == unknown || (Self::is_integer(&return_type) && Self::is_integer(output));
+            if !compatible {
+                return self.error(&format!("la fonction passée à {}() doit renvoyer `{}`, elle renvoie `{}`",
+                    method_name, self.display_type(output), self.display_type(&return_type)));
+            }
+        }
+        Ok(return_type)
+    }
+
+    // Nombre de déréférencements menant de la valeur d'une chaîne (String, &String, &str...) à son
+    // en-tête (pointeur, longueur), ou None si le type n'est pas une chaîne
+    pub fn string_derefs(typ: &Type) -> Option<usize> {
+        if *typ == Type::String {
+            return Some(0);
+        }
+        match Self::pointer_target(typ)? {
+            Type::String | Type::Str => Some(0),
+            // La valeur d'un &&str pointe déjà sur le pointeur large (octets, longueur)
+            Type::Ref(str_type, _) if **str_type == Type::Str => Some(0),
+            inner if Self::pointer_target(inner).is_some() => Self::string_derefs(inner).map(|derefs| derefs + 1),
+            _ => None,
+        }
+    }
+
+    // Valeur désignée par une référence ou un pointeur (Box, Rc, Ref, RefMut), qui ont tous pour valeur
+    // l'adresse de la valeur désignée
+    fn pointer_target(typ: &Type) -> Option<&Type> {
+        match typ {
+            Type::Ref(inner, _) | Type::Box(inner) | Type::Rc(inner) | Type::Guard(inner, _) => Some(inner),
+            _ => None,
+        }
+    }
+
+    // Référence vers une chaîne : &str, &String, &&str...
+    fn is_str_ref(typ: &Type) -> bool {
+        matches!(typ, Type::Ref(_, _)) && Self::string_derefs(typ).is_some()
+    }
+
+    // Concaténation `String + &str` et comparaisons entre chaînes
+    fn string_operation(&self, left: &Type, op: &BinaryOp, right: &Type) -> Result<Type, usize> {
+        let shown = (self.display_type(left), self.display_type(right));
+        match op {
+            BinaryOp::Add if *left != Type::String => {
+                self.error(&format!("impossible de concaténer `{}` : l'opérande gauche doit être une `String` (utilisez `.to_string()`)", shown.0))
+            },
+            BinaryOp::Add if !matches!(right, Type::Ref(_, _)) || Self::string_derefs(right).is_none() => {
+                self.error(&format!("impossible d'ajouter `{}` à une `String` : attendu `&str`", shown.1))
+            },
+            BinaryOp::Add => Ok(Type::String),
+            BinaryOp::Equal | BinaryOp::NotEqual if Self::string_derefs(right).is_some() => Ok(Type::Bool),
+            BinaryOp::Less | BinaryOp::LessEqual | BinaryOp::Greater | BinaryOp::GreaterEqual if left == right => Ok(Type::Bool),
+            _ => self.error(&format!("opération impossible entre `{}` et `{}`", shown.0, shown.1)),
+        }
+    }
+
+    // Trait requis par un opérateur binaire sur un type générique, et son symbole
+    pub fn operator_trait(op: &BinaryOp) -> (&'static str, &'static str) {
+        match op {
+            BinaryOp::Add => ("Add", "+"),
+            BinaryOp::Subtract => ("Sub", "-"),
+            BinaryOp::Multiply => ("Mul", "*"),
+            BinaryOp::Divide => ("Div", "/"),
+            BinaryOp::Modulo => ("Rem", "%"),
+            BinaryOp::Equal => ("PartialEq", "=="),
+            BinaryOp::NotEqual => ("PartialEq", "!="),
+            BinaryOp::Less => ("PartialOrd", "<"),
+            BinaryOp::LessEqual => ("PartialOrd", "<="),
+            BinaryOp::Greater => ("PartialOrd", ">"),
+            BinaryOp::GreaterEqual => ("PartialOrd", ">="),
+        }
+    }
+
+    // Receveur d'une méthode intégrée : clone() s'applique au Box ou au Rc lui-même plutôt qu'à sa valeur
+    pub fn builtin_receiver<'t>(typ: &'t Type, method_name: &str) -> &'t Type {
+        match typ {
+            Type::Ref(inner, _) | Type::Guard(inner, _) if method_name == "clone" => Self::builtin_receiver(inner, method_name),
+            _ if method_name == "clone" => typ,
+            Type::Ref(inner, _) | Type::Box(inner) | Type::Rc(inner) | Type::Guard(inner, _) => Self::builtin_receiver(inner, method_name),
+            _ => typ,
+        }
+    }
+
+    pub fn strip_refs(typ: &Type) -> &Type {
+        match typ {
+            Type::Ref(inner, _) | Type::Box(inner) | Type::Rc(inner) | Type::Guard(inner, _) => Self::strip_refs(inner),
+            _ => typ,
+        }
+    }
+
+    // Scalaire (entier, booléen, caractère) désigné par des références ou des pointeurs, et nombre de
+    // déréférencements pour l'atteindre ; tout autre type est renvoyé tel quel
+    pub fn scalar_pointee(typ: &Type) -> (&Type, usize) {
+        let (mut pointee, mut derefs) = (typ, 0);
+        while let Some(inner) = Self::pointer_target(pointee) {
+            pointee = inner;
+            derefs += 1;
+        }
+        if Self::is_integer(pointee) || matches!(pointee, Type::Bool | Type::Char) {
+            (pointee, derefs)
+        } else {
+            (typ, 0)
+        }
+    }
+
+    // Structure désignée par une expression, à travers les références et les Box
+    pub fn struct_of(&self, expr: &Expr) -> Result<(Type, usize), usize> {
+        let mut typ = self.expr_type(expr)?;
+        let mut derefs = 0;
+
+        loop {
+            match typ {
+                Type::Named(ref name) | Type::Generic(ref name, _) if self.structs.contains_key(name) => return Ok((typ, derefs)),
+                Type::Ref(inner, _) | Type::Box(inner) | Type::Rc(inner) | Type::Guard(inner, _) => {
+                    typ = *inner;
+                    derefs += 1;
+                },
+                other => return self.error(&format!("le type `{}` n'a pas de champs", self.display_type(&other))),
+            }
+        }
+    }
+
+    // Tableau ou vecteur indexé par une expression, à travers les références et les Box
+    pub fn indexed_type(&self, expr: &Expr) -> Result<(Type, usize), usize> {
+        let mut typ = self.expr_type(expr)?;
+        let mut derefs = 0;
+
+        loop {
+            match typ {
+                Type::Array(_, _) | Type::Vec(_) | Type::Slice(_) | Type::String | Type::Str => return Ok((typ, derefs)),
+                // Un pointeur large est manipulé par son adresse : l'atteindre ne coûte pas de chargement
+                Type::Ref(inner, _) if derefs > 0 && matches!(*inner, Type::Slice(_) | Type::Str) => typ = *inner,
+                Type::Ref(inner, _) | Type::Box(inner) | Type::Rc(inner) | Type::Guard(inner, _) => {
+                    typ = *inner;
+                    derefs += 1;
+                },
+                other => return self.error(&format!("le type `{}` ne peut pas être indexé", self.display_type(&other))),
+            }
+        }
+    }
+
+    // Type des éléments produits par l'itérable d'une boucle for
+    pub fn iteration_type(&self, iterable: &Expr) -> Result<Type, usize> {
+        match self.expr_type(iterable)? {
+            Type::Array(element, _) | Type::Vec(element) | Type::Iterator(element) | Type::Range(element, _) => Ok(*element),
+            table @ (Type::HashMap(_, _) | Type::HashSet(_)) => Ok(Self::table_item(&table, None)),
+            Type::Ref(inner, mutable) => match *inner {
+                Type::Array(element, _) | Type::Vec(element) | Type::Slice(element) => Ok(Type::Ref(element, mutable)),
+                table @ (Type::HashMap(_, _) | Type::HashSet(_)) => Ok(Self::table_item(&table, Some(mutable))),
+                other => self.error(&format!("le type `&{}` n'est pas itérable", self.display_type(&other))),
+            },
+            other => self.error(&format!("le type `{}` n'est pas itérable", self.display_type(&other))),
+        }
+    }
+
+    // Élément du parcours d'une table : (clé, valeur) d'une HashMap ou élément d'un HashSet, par valeur
+    // ou à travers une référence (`&mut` ne rend modifiable que la valeur)
+    pub fn table_item(table: &Type, by_ref: Option<bool>) -> Type {
+        let reference = |typ: &Type, mutable: bool| match by_ref {
+            Some(by_ref) => Type::Ref(Box::new(typ.clone()), by_ref && mutable),
+            None => typ.clone(),
+        };
+        match table {
+            Type::HashMap(key, value) => Type::Tuple(vec![reference(key, false), reference(value, true)]),
+            Type::HashSet(element) => reference(element, false),
+            _ => unreachable!("table attendue : {}", table),
+        }
+    }
+
+    // Clé utilisable dans une table : comparée et hachée comme un entier de 64 bits ou comme une chaîne
+    pub fn is_hash_key(typ: &Type) -> bool {
+        match typ {
+            Type::I128 => false,
+            Type::Bool | Type::Char | Type::String => true,
+            // Trou ou `_` d'une annotation, fixé plus tard
+            Type::Named(name) => name.starts_with('_'),
+            _ => Self::is_integer(typ),
+        }
+    }
+
+    // Élément triable par sort() : comparé comme un entier, une chaîne (ordre des octets), ou champ par
+    // champ pour un tuple et une Option (None en premier)
+    pub fn is_sortable(typ: &Type) -> bool {
+        match typ {
+            Type::I128 => false,
+            Type::Bool | Type::Char | Type::String | Type::Str => true,
+            Type::Ref(inner, _) | Type::Option(inner) => Self::is_sortable(inner),
+            Type::Tuple(elements) => !elements.is_empty() && elements.iter().all(Self::is_sortable),
+            _ => Self::is_integer(typ),
+        }
+    }
+
+    // Types dont la valeur par défaut n'est faite que d'octets nuls (or_default())
+    fn zero_default(typ: &Type) -> bool {
+        Self::is_integer(typ) || matches!(typ, Type::Bool | Type::Char | Type::String | Type::Vec(_) | Type::Option(_)
+            | Type::HashMap(_, _) | Type::HashSet(_))
+    }
+
+    // Deux types de même forme, les entiers littéraux (i32) pouvant devenir un autre type entier
+    fn same_shape(&self, a: &Type, b: &Type) -> bool {
+        match (a, b) {
+            _ if a == b => true,
+            _ if Self::is_integer(a) && Self::is_integer(b) => true,
+            (Type::Tuple(a), Type::Tuple(b)) => a.len() == b.len() && a.iter().zip(b).all(|(a, b)| self.same_shape(a, b)),
+            (Type::Array(a, n), Type::Array(b, m)) => n == m && self.same_shape(a, b),
+            _ => false,
+        }
+    }
+
+    // Une valeur de type `source` convient-elle là où `target` est attendu ? Outre l'égalité, un type
+    // encore à déduire convient à tout type, et les coercitions réalisées par la génération de code
+    // s'appliquent : `&mut T` en `&T`, `&Vec<T>` ou `&[T; N]` en `&[T]`, `&String` en `&str`, `&Box<T>`
+    // ou `&&T` en `&T`, `&T` et `Box<T>` en objet trait, fonction nommée en fermeture
+    pub fn coerces_to(&self, source: &Type, target: &Type) -> bool {
+        let all = |sources: &[Type], targets: &[Type]| {
+            sources.len() == targets.len() && sources.iter().zip(targets).all(|(source, target)| self.coerces_to(source, target))
+        };
+        match (source, target) {
+            _ if source == target => true,
+            (Type::Named(name), _) | (_, Type::Named(name)) if name == "_" || Self::is_hole(name) => true,
+            // Une instance monomorphisée se compare à sa forme générique
+            (Type::Named(name), Type::Generic(_, _)) if self.instances.contains_key(name) => {
+                let (generic, args) = &self.instances[name];
+                self.coerces_to(&Type::Generic(generic.clone(), args.clone()), target)
+            },
+            (Type::Generic(_, _), Type::Named(name)) if self.instances.contains_key(name) => {
+                let (generic, args) = &self.instances[name];
+                self.coerces_to(source, &Type::Generic(generic.clone(), args.clone()))
+            },
+            (Type::Ref(source, source_mut), Type::Ref(target, target_mut)) => {
+                (*source_mut || !*target_mut) && (self.coerces_to(source, target) || self.pointee_coerces_to(source, target))
+            },
+            (Type::Box(source), Type::Box(target)) => self.coerces_to(source, target) || self.pointee_coerces_to(source, target),
+            (Type::Vec(source), Type::Vec(target)) | (Type::Option(source), Type::Option(target))
+            | (Type::Rc(source), Type::Rc(target)) | (Type::RefCell(source), Type::RefCell(target))
+            | (Type::HashSet(source), Type::HashSet(target)) | (Type::Slice(source), Type::Slice(target))
+            | (Type::Iterator(source), Type::Iterator(target)) => self.coerces_to(source, target),
+            (Type::Guard(source, source_mut), Type::Guard(target, target_mut)) => source_mut == target_mut && self.coerces_to(source, target),
+            (Type::Result(source_ok, source_err), Type::Result(target_ok, target_err))
+            | (Type::HashMap(source_ok, source_err), Type::HashMap(target_ok, target_err))
+            | (Type::Entry(source_ok, source_err), Type::Entry(target_ok, target_err)) => {
+                self.coerces_to(source_ok, target_ok) && self.coerces_to(source_err, target_err)
+            },
+            (Type::Array(source, n), Type::Array(target, m)) => n == m && self.coerces_to(source, target),
+            (Type::Range(source, a), Type::Range(target, b)) => a == b && self.coerces_to(source, target),
+            (Type::Tuple(sources), Type::Tuple(targets)) => all(sources, targets),
+            (Type::Generic(source_name, sources), Type::Generic(target_name, targets)) => source_name == target_name && all(sources, targets),
+            (Type::FnPtr(source_params, source_ret) | Type::Closure(source_params, source_ret), Type::Closure(target_params, target_ret))
+            | (Type::FnPtr(source_params, source_ret) | Type::Closure(source_params, source_ret), Type::FnPtr(target_params, target_ret)) => {
+                all(source_params, target_params) && self.coerces_to(source_ret, target_ret)
+            },
+            _ => false,
+        }
+    }
+
+    // Coercitions propres à la valeur désignée par un pointeur (`&source` attendu comme `&target`)
+    fn pointee_coerces_to(&self, source: &Type, target: &Type) -> bool {
+        match (source, target) {
+            (Type::Vec(source) | Type::Array(source, _), Type::Slice(target)) => self.coerces_to(source, target),
+            (Type::String, Type::Str) => true,
+            (Type::Box(pointee) | Type::Ref(pointee, _), _) if !matches!(target, Type::Dyn(_)) => self.coerces_to(pointee, target),
+            (_, Type::Dyn(trait_name)) => !matches!(source, Type::Dyn(_)) && self.satisfies(source, trait_name),
+            _ => false,
+        }
+    }
+
+    // Éléments du tuple désigné par une expression, à travers les références et les Box
+    pub fn tuple_of(&self, expr: &Expr) -> Result<(Vec<Type>, usize), usize> {
+        let mut typ = self.expr_type(expr)?;
+        let mut derefs = 0;
+
+        loop {
+            match typ {
+                Type::Tuple(elements) => return Ok((elements, derefs)),
+                Type::Ref(inner, _) | Type::Box(inner) | Type::Rc(inner) | Type::Guard(inner, _) => {
+                    typ = *inner;
+                    derefs += 1;
+                },
+                other => return self.error(&format!("le type `{}` n'est pas un tuple", self.display_type(&other))),
+            }
+        }
+    }
+
+    // Vérifie qu'un motif peut déstructurer une valeur du type donné
+    pub fn check_pattern(&self, pattern: &Pattern, typ: &Type) -> Result<(), usize> {
+        self.pattern_bindings(pattern, typ, None, &mut Vec::new())
+    }
+
+    // Vérifie un motif contre le type de la valeur filtrée et recueille le type de ses variables. Un
+    // motif non liant appliqué à une référence filtre la valeur pointée et lie ses variables par
+    // référence (`Some(x)` sur `&Option<T>` lie `x: &T`) ; `by_ref` donne alors la mutabilité
+    fn pattern_bindings(&self, pattern: &Pattern, typ: &Type, by_ref: Option<bool>, bindings: &mut Vec<(String, Type)>) -> Result<(), usize> {
+        match (pattern, typ) {
+            (Pattern::Binding(name, _), _) => {
+                let typ = match by_ref {
+                    Some(mutable) => Type::Ref(Box::new(typ.clone()), mutable),
+                    None => typ.clone(),
+                };
+                bindings.push((name.clone(), typ));
+                Ok(())
+            },
+            (Pattern::Wildcard, _) => Ok(()),
+            (Pattern::Or(alternatives), _) => {
+                for alternative in alternatives {
+                    self.pattern_bindings(alternative, typ, by_ref, bindings)?;
+                }
+                Ok(())
+            },
+            // &p filtre explicitement la valeur désignée : ses variables la lient par valeur
+            (Pattern::Ref(pattern), Type::Ref(inner, _)) => self.pattern_bindings(pattern, inner, None, bindings),
+            (Pattern::Ref(_), _) => self.error(&format!(
+                "le motif `{}` attend une référence, trouvé le type `{}`", pattern, self.display_type(typ))),
+            (_, Type::Ref(inner, mutable)) => {
+                let by_ref = Some(by_ref.unwrap_or(true) && *mutable);
+                self.pattern_bindings(pattern, inner, by_ref, bindings)
+            },
+            (Pattern::Tuple(patterns), Type::Void) if patterns.is_empty() => Ok(()),
+            (Pattern::Tuple(patterns), Type::Tuple(elements)) if patterns.len() == elements.len() => {
+                for (pattern, element) in patterns.iter().zip(elements) {
+                    self.pattern_bindings(pattern, element, by_ref, bindings)?;
+                }
+                Ok(())
+            },
+            (Pattern::Tuple(patterns), Type::Tuple(elements)) => self.error(&format!(
+                "le motif attend un tuple de {} élément(s), mais le type `{}` en a {}", patterns.len(), self.display_type(typ), elements.len())),
+            (Pattern::Tuple(patterns), _) => self.error(&format!(
+                "le motif attend un tuple de {} élément(s), trouvé le type `{}`", patterns.len(), self.display_type(typ))),
+            (Pattern::Variant(name, payload), _) if name.contains("::") => {
+                let fields = match self.enum_variant(name) {
+                    Some((enum_name, _, fields)) if *typ == Type::Named(enum_name.clone()) => fields,
+                    Some((enum_name, _, _)) => return self.error(&format!(
+                        "le motif `{}` attend une valeur de type `{}`, trouvé le type `{}`", pattern, enum_name, self.display_type(typ))),
+                    None => return self.error(&format!("variante inconnue dans le motif `{}`", pattern)),
+                };
+                match (payload.as_deref(), fields.as_slice()) {
+                    (None, []) => Ok(()),
+                    (Some(payload), [field]) => self.pattern_bindings(payload, field, by_ref, bindings),
+                    (Some(Pattern::Tuple(patterns)), fields) if patterns.len() == fields.len() && fields.len() > 1 => {
+                        for (pattern, field) in patterns.iter().zip(fields) {
+                            self.pattern_bindings(pattern, field, by_ref, bindings)?;
+                        }
+                        Ok(())
+                    },
+                    _ => self.error(&format!("le motif `{}` doit lier les {} champ(s) de sa variante", pattern, fields.len())),
+                }
+            },
+            (Pattern::Variant(name, payload), _) => {
+                let payload_type = match (name.as_str(), typ) {
+                    ("Some" | "None", Type::Option(inner)) => inner,
+                    ("Ok", Type::Result(ok, _)) => ok,
+                    ("Err", Type::Result(_, err)) => err,
+                    _ => {
+                        let expected = if matches!(name.as_str(), "Some" | "None") { "Option<_>" } else { "Result<_, _>" };
+                        return self.error(&format!(
+                            "le motif `{}` attend une valeur de type `{}`, trouvé le type `{}`", pattern, expected, self.display_type(typ)));
+                    },
+                };
+                match payload {
+                    Some(payload) => self.pattern_bindings(payload, payload_type, by_ref, bindings),
+                    None => Ok(()),
+                }
+            },
+            (Pattern::Int(_), typ) if Self::is_integer(typ) => Ok(()),
+            (Pattern::Bool(_), Type::Bool) | (Pattern::Char(_), Type::Char) => Ok(()),
+            (Pattern::Int(_) | Pattern::Bool(_) | Pattern::Char(_), _) => self.error(&format!(
+                "le motif littéral `{}` ne peut pas filtrer une valeur de type `{}`", pattern, self.display_type(typ))),
+        }
+    }
+
+    // Rend visibles les variables liées par un motif
+    pub fn bind_pattern(&mut self, pattern: &Pattern, typ: &Type) -> Result<(), usize> {
+        let mut bindings = Vec::new();
+        self.pattern_bindings(pattern, typ, None, &mut bindings)?;
+        for (name, typ) in bindings {
+            self.variables.insert(name, typ);
+        }
+        Ok(())
+    }
+
+    // Un `match` doit couvrir toutes les valeurs possibles par ses bras sans garde
+    pub fn check_exhaustive(&self, arms: &[MatchArm], typ: &Type) -> Result<(), usize> {
+        let rows = arms.iter().filter(|arm| arm.guard.is_none()).map(|arm| vec![&arm.pattern]).collect();
+        if self.exhaustive(rows, std::slice::from_ref(typ)) {
+            return Ok(());
+        }
+        self.error(&format!("`match` non exhaustif sur une valeur de type `{}` : ajoutez un bras `_ => ...`", self.display_type(typ)))
+    }
+
+    // Les lignes de motifs `rows` couvrent-elles toutes les valeurs de `types`, filtrées colonne par
+    // colonne ? Celles d'un booléen, d'une Option, d'un Result, d'une énumération ou d'un tuple se
+    // répartissent entre leurs constructeurs ; celles d'un autre type ne sont couvertes que par une
+    // variable ou `_`
+    fn exhaustive(&self, rows: Vec<Vec<&Pattern>>, types: &[Type]) -> bool {
+        let Some((mut typ, rest)) = types.split_first() else {
+            return !rows.is_empty();
+        };
+        // Sous une référence, les motifs filtrent la valeur désignée ; les alternatives sont dépliées
+        while let Type::Ref(inner, _) = typ {
+            typ = inner;
+        }
+        let mut expanded = Vec::new();
+        let mut pending = rows;
+        while let Some(mut row) = pending.pop() {
+            match row[0] {
+                Pattern::Ref(pattern) => {
+                    row[0] = pattern;
+                    pending.push(row);
+                },
+                Pattern::Or(alternatives) => for alternative in alternatives {
+                    let mut row = row.clone();
+                    row[0] = alternative;
+                    pending.push(row);
+                },
+                _ => expanded.push(row),
+            }
+        }
+
+        let constructors: Vec<(String, Vec<Type>)> = match typ {
+            Type::Bool => vec![("true".to_string(), Vec::new()), ("false".to_string(), Vec::new())],
+            Type::Option(inner) => vec![("None".to_string(), Vec::new()), ("Some".to_string(), vec![(**inner).clone()])],
+            Type::Result(ok, err) => vec![("Ok".to_string(), vec![(**ok).clone()]), ("Err".to_string(), vec![(**err).clone()])],
+            Type::Named(name) if self.enums.contains_key(name) => self.enums[name].variants.clone(),
+            Type::Tuple(elements) => vec![(String::new(), elements.clone())],
+            Type::Void => vec![(String::new(), Vec::new())],
+            _ => Vec::new(),
+        };
+        let catch_all = |pattern: &Pattern| matches!(pattern, Pattern::Binding(_, _) | Pattern::Wildcard);
+        if constructors.is_empty() || expanded.iter().all(|row| catch_all(row[0])) {
+            let defaults = expanded.into_iter().filter(|row| catch_all(row[0])).map(|row| row[1..].to_vec()).collect();
+            return self.exhaustive(defaults, rest);
+        }
+        constructors.iter().all(|(constructor, fields)| {
+            let specialized = expanded.iter().filter_map(|row| {
+                let mut heads = Self::constructor_fields(row[0], constructor, fields.len())?;
+                heads.extend_from_slice(&row[1..]);
+                Some(heads)
+            }).collect();
+            let types: Vec<Type> = fields.iter().chain(rest).cloned().collect();
+            self.exhaustive(specialized, &types)
+        })
+    }
+
+    // Motifs des champs du constructeur `constructor` (variante, booléen, ou "" pour un tuple) filtrés
+    // par `pattern`, ou None si celui-ci filtre un autre constructeur
+    fn constructor_fields<'p>(pattern: &'p Pattern, constructor: &str, arity: usize) -> Option<Vec<&'p Pattern>> {
+        const WILDCARD: &Pattern = &Pattern::Wildcard;
+        match pattern {
+            Pattern::Binding(_, _) | Pattern::Wildcard => Some(vec![WILDCARD; arity]),
+            Pattern::Bool(value) => (value.to_string() == constructor).then(Vec::new),
+            Pattern::Tuple(patterns) if constructor.is_empty() => Some(patterns.iter().collect()),
+            Pattern::Variant(name, payload) if name.rsplit("::").next() == Some(constructor) => Some(match payload.as_deref() {
+                None => Vec::new(),
+                Some(Pattern::Tuple(patterns)) if arity > 1 => patterns.iter().collect(),
+                Some(payload) => vec![payload],
+            }),
+            _ => None,
+        }
+    }
+
+    // Type d'un champ, après substitution des arguments d'une structure générique
+    pub fn field_type(&self, struct_type: &Type, field_name: &str) -> Result<Type, usize> {
+        let (name, args) = match struct_type {
+            Type::Generic(name, args) => (name, args.clone()),
+            Type::Named(name) => (name, Vec::new()),
+            other => return self.error(&format!("le type `{}` n'a pas de champs", self.display_type(other))),
+        };
+
+        let def = match self.structs.get(name) {
+            Some(def) => def,
+            None => return self.error(&format!("Structure inconnue: {}", name)),
+        };
+        let subst: HashMap<String, Type> = def.type_params.iter().map(|param| param.name.clone()).zip(args).collect();
+
+        match def.fields.iter().find(|(name, _)| name == field_name) {
+            Some((_, field_type)) => Ok(Self::substitute(field_type, &subst)),
+            None => self.error(&format!("la structure `{}` n'a pas de champ `{}`", self.display_type(struct_type), field_name)),
+        }
+    }
+
+    // Signature d'une fonction libre ou associée (Type::f), et les paramètres déjà fixés par le type
+    pub fn lookup_function(&self, name: &str) -> Result<(FnSig, HashMap<String, Type>), usize> {
+        // Fonction associée : Type::fonction
+        if let Some((type_name, member)) = name.split_once("::") {
+            let owner = match type_name {
+                "Self" => match &self.self_type {
+                    Some(self_type) => self_type.clone(),
+                    None => return self.error("`Self` n'est utilisable que dans un bloc impl ou un trait"),
+                },
+                _ => self.type_from_name(type_name).unwrap_or_else(|| Type::Named(type_name.to_string())),
+            };
+
+            // Paramètre générique : fonction fournie par l'une de ses contraintes de trait
+            if let Type::Named(param) = &owner {
+                if self.is_type_param(param) {
+                    return Ok((self.bound_method(param, member)?.sig, HashMap::new()));
+                }
+            }
+
+            let key = Self::type_key(&owner);
+            return match self.methods.get(&(key.clone(), member.to_string())) {
+                Some(info) => {
+                    // Self::f dans un bloc impl générique réutilise les paramètres du bloc
+                    let mut subst = HashMap::new();
+                    let _ = self.unify(&info.impl_type, &owner, &info.sig.type_params, &mut subst);
+                    Ok((info.sig.clone(), subst))
+                },
+                None if self.enums.contains_key(&key) => self.error(&format!(
+                    "l'énumération `{}` n'a ni variante ni fonction associée `{}`", key, member)),
+                None => self.error(&format!("Fonction associée inconnue: {}::{}", self.display_type(&owner), member)),
+            };
+        }
+
+        match self.functions.get(name) {
+            Some(sig) => Ok((sig.clone(), HashMap::new())),
+            None => self.error(&format!("Fonction inconnue: {}", name)),
+        }
+    }
+
+    // Méthode d'un paramètre générique, cherchée dans les traits de ses contraintes
+    fn bound_method(&self, param: &str, method_name: &str) -> Result<MethodInfo, usize> {
+        let concrete = Type::Named(param.to_string());
+        for bound in self.param_bounds(param) {
+            let Some(trait_def) = self.traits.get(&bound) else { continue };
+            if let Some(method) = trait_def.methods.iter().find(|m| m.function.name == method_name) {
+                let function = &method.function;
+                return Ok(MethodInfo {
+                    sig: FnSig {
+                        label: Self::method_label(param, Some(&bound), method_name),
+                        type_params: function.type_params.clone(),
+                        params: function.params.iter().map(|(_, typ)| Self::substitute_self(typ, &concrete)).collect(),
+                        return_type: Self::substitute_self(&function.return_type.clone().unwrap_or(Type::Void), &concrete),
+                    },
+                    has_self: function.params.first().is_some_and(|(name, _)| name == "self"),
+                    inherent: false,
+                    impl_type: concrete,
+                });
+            }
+        }
+
+        self.error(&format!(
+            "aucune méthode `{}` pour le paramètre générique `{}` : ajoutez une contrainte de trait qui la fournit", method_name, param))
+    }
+
+    // Paramètres et type du résultat des méthodes intégrées des vecteurs, tableaux, chaînes, caractères, Option et Result
+    pub fn builtin_signature(&self, obj_type: &Type, method_name: &str, type_args: &[Type]) -> Result<(Vec<Type>, Type), usize> {
+        let receiver = Self::builtin_receiver(obj_type, method_name).clone();
+        let index = Type::I32;
+        let str_ref = Type::Ref(Box::new(Type::Str), false);
+        if !type_args.is_empty() && method_name != "parse" {
+            return self.error(&format!("{}() n'attend pas d'arguments génériques", method_name));
+        }
+
+        if let Type::HashMap(key, _) | Type::HashSet(key) = &receiver {
+            if !Self::is_hash_key(key) {
+                return self.error(&format!(
+                    "le type `{}` ne peut pas servir de clé : seuls les entiers, les booléens, les caractères et String sont pris en charge",
+                    self.display_type(key)));
+            }
+        }
+
+        let signature = match (&receiver, method_name) {
+            (Type::Vec(_) | Type::Array(_, _) | Type::Slice(_), "len") => (vec![], Type::I32),
+            (Type::Range(element, _), "contains") => (vec![Type::Ref(element.clone(), false)], Type::Bool),
+            (Type::Range(_, _), "is_empty") => (vec![], Type::Bool),
+            (Type::Vec(_) | Type::Array(_, _) | Type::Slice(_), "is_empty") => (vec![], Type::Bool),
+            (Type::Vec(element) | Type::Array(element, _) | Type::Slice(element), "iter" | "iter_mut") => {
+                (vec![], Type::Iterator(Box::new(Type::Ref(element.clone(), method_name == "iter_mut"))))
+            },
+            // into_iter() parcourt par valeur, sauf à travers une référence
+            (Type::Vec(element) | Type::Array(element, _) | Type::Slice(element), "into_iter") => match obj_type {
+                Type::Ref(_, mutable) => (vec![], Type::Iterator(Box::new(Type::Ref(element.clone(), *mutable)))),
+                _ => (vec![], Type::Iterator(element.clone())),
+            },
+            (Type::Vec(element) | Type::Array(element, _) | Type::Slice(element), "get") => (vec![index], Type::Option(Box::new(Type::Ref(element.clone(), false)))),
+            (Type::Vec(element) | Type::Array(element, _) | Type::Slice(element), "contains") => {
+                if !self.satisfies(element, "PartialEq") {
+                    return self.error(&format!("contains() exige `{}: PartialEq`", self.display_type(element)));
+                }
+                (vec![Type::Ref(element.clone(), false)], Type::Bool)
+            },
+            (Type::Vec(element) | Type::Array(element, _) | Type::Slice(element), "sort") => {
+                if !Self::is_sortable(element) {
+                    return self.error(&format!(
+                        "sort() n'est pris en charge que pour des entiers, booléens, caractères, chaînes, et des tuples ou Option de tels types, pas `{}`",
+                        self.display_type(element)));
+                }
+                (vec![], Type::Void)
+            },
+            // La fonction de comparaison reçoit deux éléments empruntés
+            (Type::Vec(element) | Type::Array(element, _) | Type::Slice(element), "sort_by") => {
+                let element_ref = Type::Ref(element.clone(), false);
+                (vec![Type::Closure(vec![element_ref.clone(), element_ref], Box::new(Type::Named(ORDERING.to_string())))], Type::Void)
+            },
+            (Type::Vec(_) | Type::Array(_, _) | Type::Slice(_), "swap") => (vec![index.clone(), index], Type::Void),
+            (Type::Vec(_) | Type::Array(_, _) | Type::Slice(_), "reverse") => (vec![], Type::Void),
+            (Type::Vec(element), "push") => (vec![(**element).clone()], Type::Void),
+            (Type::Vec(element), "pop") => (vec![], Type::Option(element.clone())),
+            (Type::Vec(element), "insert") => (vec![index, (**element).clone()], Type::Void),
+            (Type::Vec(element), "remove") => (vec![index], (**element).clone()),
+            (Type::Vec(element), "extend") => (vec![Type::Vec(element.clone())], Type::Void),
+            (Type::Vec(_), "truncate") => (vec![index], Type::Void),
+            (Type::Vec(_), "clear") => (vec![], Type::Void),
+            (Type::String | Type::Str, "len") => (vec![], Type::I32),
+            (Type::String | Type::Str, "is_empty") => (vec![], Type::Bool),
+            (Type::String | Type::Str, "to_string") => (vec![], Type::String),
+            (Type::String, "push") => (vec![Type::Char], Type::Void),
+            (Type::String, "push_str") => (vec![str_ref.clone()], Type::Void),
+            (Type::String | Type::Str, "chars") => (vec![], Type::Iterator(Box::new(Type::Char))),
+            (Type::String | Type::Str, "bytes") => (vec![], Type::Iterator(Box::new(Type::U8))),
+            (Type::String | Type::Str, "split_whitespace") => (vec![], Type::Iterator(Box::new(str_ref.clone()))),
+            (Type::String | Type::Str, "split") => (vec![str_ref.clone()], Type::Iterator(Box::new(str_ref))),
+            (Type::String | Type::Str, "trim" | "trim_start" | "trim_end") => (vec![], str_ref),
+            (Type::String | Type::Str, "contains" | "starts_with" | "ends_with") => (vec![str_ref], Type::Bool),
+            (Type::String | Type::Str, "find") => (vec![str_ref], Type::Option(Box::new(Type::I32))),
+            (Type::String | Type::Str, "replace") => (vec![str_ref.clone(), str_ref], Type::String),
+            (Type::String | Type::Str, "to_uppercase" | "to_lowercase") => (vec![], Type::String),
+            (Type::String | Type::Str, "parse") => match type_args {
+                [target] if Self::is_integer(target) => {
+                    (vec![], Type::Result(Box::new(target.clone()), Box::new(Type::Named(PARSE_INT_ERROR.to_string()))))
+                },
+                [target] => return self.error(&format!("parse() n'est pris en charge que vers un type entier, pas `{}`", self.display_type(target))),
+                _ => return self.error("parse() exige le type cible : `parse::<i32>()` ou un type attendu (`let n: i32 = s.parse().unwrap();`)"),
+            },
+            (Type::Char, "is_digit") => (vec![Type::I32], Type::Bool),
+            (Type::Char, "is_alphabetic" | "is_numeric" | "is_alphanumeric" | "is_whitespace" | "is_uppercase" | "is_lowercase") => {
+                (vec![], Type::Bool)
+            },
+            (Type::Char, "to_ascii_uppercase" | "to_ascii_lowercase") => (vec![], Type::Char),
+            (Type::Option(_), "is_some" | "is_none") => (vec![], Type::Bool),
+            (Type::Option(inner), "unwrap") => (vec![], (**inner).clone()),
+            (Type::Option(inner), "expect") => (vec![str_ref], (**inner).clone()),
+            (Type::Option(inner), "unwrap_or") => (vec![(**inner).clone()], (**inner).clone()),
+            (Type::Result(_, _), "is_ok" | "is_err") => (vec![], Type::Bool),
+            (Type::Result(ok, _), "unwrap") => (vec![], (**ok).clone()),
+            (Type::Result(ok, _), "expect") => (vec![str_ref], (**ok).clone()),
+            (Type::Result(_, err), "unwrap_err") => (vec![], (**err).clone()),
+            (Type::Result(ok, _), "unwrap_or") => (vec![(**ok).clone()], (**ok).clone()),
+            (Type::HashMap(_, _) | Type::HashSet(_), "len") => (vec![], Type::I32),
+            (Type::HashMap(_, _) | Type::HashSet(_), "is_empty") => (vec![], Type::Bool),
+            (Type::HashMap(key, value), "insert") => (vec![(**key).clone(), (**value).clone()], Type::Option(value.clone())),
+            (Type::HashMap(key, value), "get" | "get_mut") => {
+                (vec![Type::Ref(key.clone(), false)], Type::Option(Box::new(Type::Ref(value.clone(), method_name == "get_mut"))))
+            },
+            (Type::HashMap(key, _), "contains_key") => (vec![Type::Ref(key.clone(), false)], Type::Bool),
+            (Type::HashMap(key, value), "remove") => (vec![Type::Ref(key.clone(), false)], Type::Option(value.clone())),
+            (Type::HashMap(key, value), "entry") => (vec![(**key).clone()], Type::Entry(key.clone(), value.clone())),
+            (Type::HashMap(key, value), "iter" | "iter_mut") => {
+                let entry = Type::Tuple(vec![Type::Ref(key.clone(), false), Type::Ref(value.clone(), method_name == "iter_mut")]);
+                (vec![], Type::Iterator(Box::new(entry)))
+            },
+            (Type::HashMap(key, _), "keys") => (vec![], Type::Iterator(Box::new(Type::Ref(key.clone(), false)))),
+            (Type::HashMap(_, value), "values" | "values_mut") => (vec![], Type::Iterator(Box::new(Type::Ref(value.clone(), method_name == "values_mut")))),
+            (Type::HashSet(element), "insert") => (vec![(**element).clone()], Type::Bool),
+            (Type::HashSet(element), "contains" | "remove") => (vec![Type::Ref(element.clone(), false)], Type::Bool),
+            (Type::HashSet(element), "iter") => (vec![], Type::Iterator(Box::new(Type::Ref(element.clone(), false)))),
+            // into_iter() parcourt par valeur, sauf à travers une référence
+            (Type::HashMap(_, _) | Type::HashSet(_), "into_iter") => match obj_type {
+                Type::Ref(_, mutable) => (vec![], Type::Iterator(Box::new(Self::table_item(&receiver, Some(*mutable))))),
+                _ => (vec![], Type::Iterator(Box::new(Self::table_item(&receiver, None)))),
+            },
+            (Type::Entry(_, value), "or_insert") => (vec![(**value).clone()], Type::Ref(value.clone(), true)),
+            (Type::Entry(_, value), "or_default") => {
+                if !Self::zero_default(value) {
+                    return self.error(&format!("or_default() n'est pris en charge que pour les entiers, String, Vec, Option et les tables, pas `{}`",
+                        self.display_type(value)));
+                }
+                (vec![], Type::Ref(value.clone(), true))
+            },
+            (_, "clone") if self.satisfies(&receiver, "Clone") => (vec![], receiver.clone()),
+            (_, "cmp") if Self::is_sortable(&receiver) => (vec![Type::Ref(Box::new(receiver.clone()), false)], Type::Named(ORDERING.to_string())),
+            (Type::Named(name), "reverse") if name == ORDERING => (vec![], receiver.clone()),
+            (Type::Named(name), "then") if name == ORDERING => (vec![receiver.clone()], receiver.clone()),
+            (Type::Named(name), _) if name == ORDERING && ORDERING_METHODS.contains(&method_name) => (vec![], Type::Bool),
+            (Type::RefCell(inner), "borrow") => (vec![], Type::Guard(inner.clone(), false)),
+            (Type::RefCell(inner), "borrow_mut") => (vec![], Type::Guard(inner.clone(), true)),
+            _ => return self.error(&format!("aucune méthode `{}` pour le type `{}`", method_name, self.display_type(obj_type))),
+        };
+
+        // Les méthodes qui modifient le vecteur exigent un accès mutable
+        if MUTATING_METHODS.contains(&method_name) {
+            if let Type::Ref(_, false) = obj_type {
+                return self.error(&format!("impossible d'appeler {}() à travers une référence `&` : utilisez `&mut`", method_name));
+            }
+        }
+        Ok(signature)
+    }
+
+    // Les index sont des entiers ; extend accepte un Vec ou un tableau, par valeur ou par référence ;
+    // push_str accepte un &str ou un &String, et les motifs de recherche aussi un char
+    fn check_builtin_arg(&self, receiver: &Type, method_name: &str, position: usize, arg_type: &Type, param: &Type) -> Result<(), usize> {
+        if let Type::HashMap(key, _) | Type::HashSet(key) = receiver {
+            return self.check_key_arg(key, method_name, position, arg_type, param);
+        }
+        let accepted = match (method_name, position) {
+            ("get" | "remove" | "truncate" | "swap", _) | ("insert", 0) => Self::is_integer(arg_type),
+            ("extend", _) => matches!(Self::strip_refs(arg_type), Type::Vec(_) | Type::Array(_, _) | Type::Slice(_)),
+            // Motif de recherche : un caractère ou une chaîne
+            ("contains" | "starts_with" | "ends_with" | "find" | "split", _) if *param == Type::Ref(Box::new(Type::Str), false) => {
+                *arg_type == Type::Char || Self::is_str_ref(arg_type)
+            },
+            ("contains", _) => matches!(arg_type, Type::Ref(_, _)),
+            ("push", _) if *param == Type::Char => *arg_type == Type::Char,
+            ("push_str" | "replace" | "expect", _) => Self::is_str_ref(arg_type),
+            ("is_digit", _) => Self::is_integer(arg_type),
+            // Même type de part et d'autre, à travers les références ; un entier encore ouvert convient à tout entier
+            ("cmp", _) => {
+                let (target, open) = (Self::strip_refs(arg_type), |typ: &Type| matches!(typ, Type::Named(name) if Self::is_hole(name)));
+                matches!(arg_type, Type::Ref(_, _))
+                    && (target == receiver || Self::is_integer(target) && Self::is_integer(receiver) && (open(target) || open(receiver)))
+            },
+            ("sort_by", _) => matches!(arg_type, Type::Closure(params, ret) | Type::FnPtr(params, ret)
+                if params.len() == 2 && matches!(&**ret, Type::Named(name) if name == ORDERING)),
+            ("then", _) => *arg_type == *param,
+            _ => true,
+        };
+        if !accepted {
+            return self.error(&format!("argument invalide pour {}() : attendu `{}`, trouvé `{}`",
+                method_name, self.display_type(param), self.display_type(arg_type)));
+        }
+        Ok(())
+    }
+
+    // Une clé passée par valeur (insert, entry) a le type des clés ; une clé cherchée est empruntée, et
+    // un &str convient pour une clé String. Les valeurs sont vérifiées par la monomorphisation, comme pour push().
+    fn check_key_arg(&self, key: &Type, method_name: &str, position: usize, arg_type: &Type, param: &Type) -> Result<(), usize> {
+        let key_like = |typ: &Type| matches!(key, Type::Named(_)) || self.same_shape(typ, key);
+        let accepted = match (param, position) {
+            (Type::Ref(_, _), 0) => match arg_type {
+                Type::Ref(inner, _) if key_like(inner) => true,
+                Type::Ref(_, _) => *key == Type::String && Self::string_derefs(arg_type) == Some(0),
+                _ => false,
+            },
+            (_, 0) => key_like(arg_type),
+            _ => true,
+        };
+        if !accepted {
+            return self.error(&format!("argument invalide pour {}() : attendu `{}`, trouvé `{}`",
+                method_name, self.display_type(param), self.display_type(arg_type)));
+        }
+        Ok(())
+    }
+
+    // Résout un appel de méthode en suivant les références/Box jusqu'au type qui la définit
+    pub fn resolve_method(&self, obj_type: &Type, method_name: &str) -> Result<MethodTarget, usize> {
+        let mut typ = obj_type.clone();
+
+        loop {
+            // clone() copie en profondeur toute valeur Clone : les Clone dérivés n'ont pas de méthode à appeler
+            if method_name == "clone" && !matches!(typ, Type::Ref(_, _) | Type::Guard(_, _)) && self.satisfies(&typ, "Clone") {
+                return Ok(MethodTarget::Builtin);
+            }
+            // cmp() compare deux valeurs triables ; un Ordering a ses propres méthodes
+            if method_name == "cmp" && !matches!(typ, Type::Ref(_, _)) && Self::is_sortable(&typ)
+                || matches!(&typ, Type::Named(name) if name == ORDERING) && ORDERING_METHODS.contains(&method_name) {
+                return Ok(MethodTarget::Builtin);
+            }
+            match &typ {
+                Type::Vec(_) | Type::Array(_, _) | Type::Slice(_) | Type::Option(_) | Type::Result(_, _) | Type::String | Type::Str | Type::Char
+                | Type::Iterator(_) | Type::Range(_, _) | Type::RefCell(_) | Type::HashMap(_, _) | Type::HashSet(_) | Type::Entry(_, _) => {
+                    return Ok(MethodTarget::Builtin);
+                },
+                Type::Ref(inner, _) | Type::Box(inner) | Type::Rc(inner) | Type::Guard(inner, _) => {
+                    if let Type::Dyn(trait_name) = &**inner {
+                        let trait_def = &self.traits[trait_name];
+                        return match trait_def.methods.iter().position(|m| m.function.name == *method_name) {
+                            Some(index) => Ok(MethodTarget::Dynamic(trait_name.clone(), index)),
+                            None => self.error(&format!("aucune méthode `{}` dans le trait `{}`", method_name, trait_name)),
+                        };
+                    }
+                    typ = (**inner).clone();
+                },
+                Type::Named(param) if self.is_type_param(param) => {
+                    return Ok(MethodTarget::Static(self.bound_method(param, method_name)?));
+                },
+                _ => {
+                    let key = Self::type_key(&typ);
+                    let shown = self.display_type(&typ);
+                    return match self.methods.get(&(key, method_name.to_string())) {
+                        Some(info) if info.has_self => Ok(MethodTarget::Static(info.clone())),
+                        Some(_) => self.error(&format!("`{}::{}` est une fonction associée, pas une méthode : appelez `{}::{}(...)`", shown, method_name, shown, method_name)),
+                        None => self.error(&format!("aucune méthode `{}` pour le type `{}`", method_name, self.display_type(obj_type))),
+                    };
+                },
+            }
+        }
+    }
+
+    // Vérifie une seule fois le corps d'une fonction générique, ses paramètres restant abstraits :
+    // seules les opérations garanties par les contraintes de traits sont permises
+    pub fn check_generic_function(&mut self, function: &Function, type_params: Vec<TypeParam>, self_type: Option<Type>) -> Result<(), usize> {
+        self.type_params = type_params;
+        self.self_type = self_type;
+        self.variables.clear();
+        self.current_line = function.line;
+
+        for (name, typ) in &function.params {
+            let typ = self.resolve_type(typ);
+            self.variables.insert(name.clone(), typ);
+        }
+
+        let mut result = Ok(());
+        for stmt in &function.body {
+            result = self.check_statement(stmt);
+            if result.is_err() {
+                break;
+            }
+        }
+
+        self.type_params.clear();
+        self.self_type = None;
+        self.variables.clear();
+        result
+    }
+
+    // Garde et corps d'un bras, une fois les variables de son motif liées
+    fn check_arm(&mut self, arm: &MatchArm) -> Result<(), usize> {
+        if let Some(guard) = &arm.guard {
+            self.check_expr(guard)?;
+        }
+        self.check_statement(&arm.body)
+    }
+
+    fn check_statement(&mut self, stmt: &Stmt) -> Result<(), usize> {
+        match stmt {
+            Stmt::Let(pattern, initializer, var_type, line) => {
+                self.current_line = *line;
+                let init_type = match initializer {
+                    Some(init) => Some(self.check_expr(init)?),
+                    None => None,
+                };
+                let var_type = match (var_type, init_type) {
+                    (Some(var_type), _) => {
+                        self.check_type(var_type, *line)?;
+                        self.resolve_type(var_type)
+                    },
+                    (None, Some(init_type)) => init_type,
+                    (None, None) => Type::I32,
+                };
+                self.bind_pattern(pattern, &var_type)?;
+            },
+            Stmt::Expression(expr, line) | Stmt::Return(Some(expr), line) => {
+                self.current_line = *line;
+                self.check_expr(expr)?;
+            },
+            Stmt::Return(None, _) | Stmt::Break(_) | Stmt::Continue(_) => {},
+            Stmt::Print(format, _, line) => {
+                self.current_line = *line;
+                self.check_format(format)?;
+            },
+            Stmt::Block(stmts) => {
+                let saved = self.variables.clone();
+                for stmt in stmts {
+                    self.check_statement(stmt)?;
+                }
+                self.variables = saved;
+            },
+            Stmt::If(condition, then_branch, else_branch, line) => {
+                self.current_line = *line;
+                self.check_expr(condition)?;
+                self.check_statement(then_branch)?;
+                if let Some(else_branch) = else_branch {
+                    self.check_statement(else_branch)?;
+                }
+            },
+            Stmt::While(condition, body, line) => {
+                self.current_line = *line;
+                self.check_expr(condition)?;
+                self.check_statement(body)?;
+            },
+            Stmt::Match(value, arms, line) => {
+                self.current_line = *line;
+                let value_type = self.check_expr(value)?;
+                for arm in arms {
+                    let saved = self.variables.clone();
+                    let result = self.bind_pattern(&arm.pattern, &value_type).and_then(|_| self.check_arm(arm));
+                    self.variables = saved;
+                    result?;
+                }
+                self.current_line = *line;
+                self.check_exhaustive(arms, &value_type)?;
+            },
+            Stmt::For(pattern, iterable, body, line) => {
+                self.current_line = *line;
+                self.check_expr(iterable)?;
+                let element_type = self.iteration_type(iterable)?;
+                let saved = self.variables.clone();
+                let result = self.bind_pattern(pattern, &element_type).and_then(|_| self.check_statement(body));
+                self.variables = saved;
+                result?;
+            },
+        }
+        Ok(())
+    }
+
+    // Étiquette de la méthode `fmt` d'une implémentation de Display ou Debug écrite par l'utilisateur ;
+    // None pour les traits dérivés ou fournis par le compilateur
+    pub fn user_fmt(&self, typ: &Type, trait_name: &str) -> Option<String> {
+        let Type::Named(name) = typ else { return None };
+        let derives = match (self.structs.get(name), self.enums.get(name)) {
+            (Some(def), _) => &def.derives,
+            (None, Some(def)) => &def.derives,
+            (None, None) => return None,
+        };
+        let derived = derives.iter().any(|derived| derived == trait_name) || matches!(name.as_str(), PARSE_INT_ERROR | FMT_ERROR);
+        if derived || !self.trait_impls.contains(&(name.clone(), trait_name.to_string())) {
+            return None;
+        }
+        Some(TypeChecker::method_label(name, Some(trait_name), "fmt"))
+    }
+
+    // {:?} exige Debug du type et, pour une structure ou une énumération dérivant Debug, de chacun de ses champs
+    pub fn check_debug(&self, typ: &Type, visited: &mut Vec<String>) -> Result<(), usize> {
+        if !self.satisfies(typ, "Debug") {
+            let hint = match typ {
+                Type::Named(_) => " : ajoutez #[derive(Debug)] à sa déclaration",
+                _ => "",
+            };
+            return self.error(&format!("le trait `Debug` n'est pas implémenté pour `{}`{}", self.display_type(typ), hint));
+        }
+        match typ {
+            Type::Named(name) if !visited.contains(name) && self.user_fmt(typ, "Debug").is_none() => {
+                visited.push(name.clone());
+                let fields: Vec<Type> = match (self.enums.get(name), self.structs.get(name)) {
+                    (Some(def), _) => def.variants.iter().flat_map(|(_, fields)| fields.clone()).collect(),
+                    (None, Some(def)) => def.fields.iter().map(|(_, field_type)| field_type.clone()).collect(),
+                    (None, None) => Vec::new(),
+                };
+                for field_type in fields {
+                    self.check_debug(&field_type, visited)?;
+                }
+                Ok(())
+            },
+            Type::Ref(inner, _) | Type::Box(inner) | Type::Vec(inner) | Type::Slice(inner) | Type::Array(inner, _) | Type::Option(inner)
+            | Type::Rc(inner) | Type::RefCell(inner) | Type::Guard(inner, _) => {
+                self.check_debug(inner, visited)
+            },
+            Type::Result(ok, err) | Type::HashMap(ok, err) => {
+                self.check_debug(ok, visited)?;
+                self.check_debug(err, visited)
+            },
+            Type::HashSet(element) => self.check_debug(element, visited),
+            Type::Tuple(elements) => elements.iter().try_for_each(|element| self.check_debug(element, visited)),
+            _ => Ok(()),
+        }
+    }
+
+    // Chaque emplacement du format exige de son argument le trait correspondant (contrainte pour un
+    // paramètre générique) ; une largeur ou une précision lue dans un argument est un entier
+    fn check_format(&self, format: &FormatArgs) -> Result<(), usize> {
+        let mut arg_types = Vec::new();
+        for arg in &format.args {
+            arg_types.push(self.check_expr(arg)?);
+        }
+        for piece in &format.pieces {
+            let Piece::Argument(index, spec) = piece else { continue };
+            for count in [&spec.width, &spec.precision].into_iter().flatten() {
+                if let Count::Argument(count) = count {
+                    if !Self::is_integer(&arg_types[*count]) {
+                        return self.error(&format!(
+                            "la largeur ou la précision doit être un entier, pas `{}`", self.display_type(&arg_types[*count])));
+                    }
+                }
+            }
+            let arg_type = &arg_types[*index];
+            if let Type::Named(name) = arg_type {
+                let bound = spec.kind.trait_name();
+                if self.is_type_param(name) && !self.satisfies(arg_type, bound) {
+                    return self.error(&format!(
+                        "le type générique `{}` ne peut pas être affiché avec {} : ajoutez la contrainte `{}: {}`",
+                        name, spec.kind.placeholder(), name, bound));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Type une expression après avoir vérifié chacune de ses sous-expressions
+    fn check_expr(&self, expr: &Expr) -> Result<Type, usize> {
+        match expr {
+            Expr::Binary(left, _, right) | Expr::CompoundAssign(left, _, right) => {
+                self.check_expr(left)?;
+                self.check_expr(right)?;
+            },
+            Expr::Unary(_, operand) => {
+                self.check_expr(operand)?;
+            },
+            Expr::FunctionCall(_, _, args) | Expr::VecNew(args) | Expr::Literal(Literal::Array(args)) | Expr::Tuple(args) => {
+                for arg in args {
+                    self.check_expr(arg)?;
+                }
+            },
+            Expr::MethodCall(obj_expr, _, _, args) | Expr::Call(obj_expr, args) => {
+                self.check_expr(obj_expr)?;
+                for arg in args {
+                    self.check_expr(arg)?;
+                }
+            },
+            Expr::Index(base, index) | Expr::Assign(base, index) | Expr::VecRepeat(base, index) => {
+                self.check_expr(base)?;
+                self.check_expr(index)?;
+            },
+            Expr::Range(start, end, _) => {
+                for bound in [start, end].into_iter().flatten() {
+                    self.check_expr(bound)?;
+                }
+            },
+            Expr::FieldAccess(base, _) | Expr::TupleIndex(base, _) | Expr::Literal(Literal::ArrayRepeat(base, _)) | Expr::Try(base) => {
+                self.check_expr(base)?;
+            },
+            Expr::StructLiteral(_, fields) => {
+                for (_, value) in fields {
+                    self.check_expr(value)?;
+                }
+            },
+            Expr::Format(format) => self.check_format(format)?,
+            Expr::Write(destination, format) => {
+                self.check_expr(destination)?;
+                self.check_format(format)?;
+            },
+            // Le corps d'une fermeture est vérifié dans chaque instance, une fois ses types connus
+            Expr::Literal(_) | Expr::Variable(_) | Expr::Closure(_) => {},
+        }
+        self.expr_type(expr)
+    }
+}
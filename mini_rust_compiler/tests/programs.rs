@@ -0,0 +1,1160 @@
+// Programmes d'exemple. Les programmes refusés passent par l'analyse en mémoire (lexer, parser,
+// typage, emprunts) ; les programmes acceptés sont compilés par le binaire construit par cargo puis
+// exécutés (sortie, code de sortie, rapport de fuites de mémoire), ce qui exige nasm et gcc : ces
+// tests sont ignorés par défaut et se lancent avec `cargo test -- --ignored`
+
+use std::fs;
+use std::process::Command;
+use mini_rust_compiler::borrow_checker::BorrowChecker;
+use mini_rust_compiler::error_handler::ErrorHandler;
+use mini_rust_compiler::lexer::Lexer;
+use mini_rust_compiler::monomorphizer::Monomorphizer;
+use mini_rust_compiler::parser::Parser;
+use tempfile::TempDir;
+
+struct Run {
+    stdout: String,
+    stderr: String,
+    code: Option<i32>,
+}
+
+// Compile puis exécute un programme qui doit être accepté
+fn run(source: &str) -> Run {
+    let dir = TempDir::new().expect("répertoire temporaire");
+    fs::write(dir.path().join("prog.rs"), source).expect("écriture du programme");
+    let output = Command::new(env!("CARGO_BIN_EXE_mini_rust_compiler"))
+        .arg("prog.rs")
+        .current_dir(dir.path())
+        .output()
+        .expect("lancement du compilateur");
+    assert!(output.status.success(), "compilation refusée :\n{}", String::from_utf8_lossy(&output.stderr));
+    let output = Command::new(dir.path().join("prog")).output().expect("lancement du programme");
+    Run {
+        stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+        stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        code: output.status.code(),
+    }
+}
+
+// Exécution réussie, sans fuite de mémoire, de sortie standard `expected`
+fn assert_output(source: &str, expected: &str) {
+    let run = run(source);
+    assert_eq!(run.code, Some(0), "sortie d'erreur :\n{}", run.stderr);
+    assert!(!run.stderr.contains("fuite de mémoire"), "{}", run.stderr);
+    assert_eq!(run.stdout, expected);
+}
+
+// Exécution interrompue par une panique (code 101) après la sortie standard `expected`, avec le
+// message `message` sur la sortie d'erreur
+fn assert_panics(source: &str, expected: &str, message: &str) {
+    let run = run(source);
+    assert_eq!(run.code, Some(101), "sortie d'erreur :\n{}", run.stderr);
+    assert!(run.stderr.contains(message), "{}", run.stderr);
+    assert_eq!(run.stdout, expected);
+}
+
+// Analyse un programme qui doit être refusé avant la génération de code ; renvoie ses messages d'erreur
+fn compile_error(source: &str) -> String {
+    let error_handler = ErrorHandler::new("prog.rs".to_string());
+    let accepted = Lexer::new(source, &error_handler).tokenize()
+        .and_then(|tokens| Parser::new(tokens, &error_handler).parse())
+        .and_then(|program| Monomorphizer::new(&error_handler).monomorphize(program))
+        .and_then(|program| BorrowChecker::new(&error_handler).check(&program));
+    assert!(accepted.is_err(), "programme accepté :\n{}", source);
+    error_handler.messages().join("\n")
+}
+
+#[test]
+#[ignore = "exige nasm et gcc"]
+fn references_read_and_write_through_borrows() {
+    assert_output("
+fn push_twice(v: &mut Vec<i32>, x: i32) {
+    v.push(x);
+    v.push(x);
+}
+fn longest<'a>(a: &'a str, b: &'a str) -> &'a str {
+    if a.len() >= b.len() { a } else { b }
+}
+fn main() {
+    let mut v = Vec::new();
+    push_twice(&mut v, 3);
+    let r = &v;
+    println!(\"{:?} {}\", r, r.len());
+    let mut n = 1;
+    let m = &mut n;
+    *m = *m + 41;
+    println!(\"{} {}\", n, longest(\"abc\", \"de\"));
+}
+", "[3, 3] 2\n42 abc\n");
+}
+
+#[test]
+fn borrow_checker_rejects_conflicting_borrows() {
+    let errors = compile_error("
+fn main() {
+    let mut v = vec![1, 2];
+    let first = &v[0];
+    v.push(3);
+    println!(\"{}\", first);
+}
+");
+    assert!(errors.contains("impossible d'emprunter `v` comme mutable, car il est aussi emprunté comme immuable (emprunt à la ligne 4) - Erreur à la ligne 5"), "{}", errors);
+
+    let errors = compile_error("
+fn main() {
+    let mut s = String::from(\"a\");
+    let a = &mut s;
+    let b = &mut s;
+    a.push('b');
+    b.push('c');
+}
+");
+    assert!(errors.contains("impossible d'emprunter `s` comme mutable plus d'une fois à la fois"), "{}", errors);
+}
+
+#[test]
+fn borrow_checker_rejects_dangling_and_immutable_borrows() {
+    let errors = compile_error("
+fn dangle() -> &String {
+    let s = String::from(\"x\");
+    &s
+}
+fn main() {}
+");
+    assert!(errors.contains("impossible de renvoyer une référence à la variable locale `s`"), "{}", errors);
+
+    let errors = compile_error("
+fn main() {
+    let s = String::from(\"a\");
+    s.push('b');
+}
+");
+    assert!(errors.contains("la variable `s` n'est pas déclarée `mut`"), "{}", errors);
+}
+
+#[test]
+#[ignore = "exige nasm et gcc"]
+fn format_specifiers_match_rust() {
+    let run = run("
+fn main() {
+    let name = \"ab\";
+    let x = 255;
+    let w = 6;
+    println!(\"[{:>5}] [{:<5}] [{:^6}] [{:*^7}]\", name, name, name, name);
+    println!(\"[{:05}] [{:+}] [{:x}] [{:#X}] [{:#b}] [{:o}]\", 42, 7, x, x, 5, 8);
+    println!(\"[{:<w$}] [{:>1$}] [{:.*}]\", 1, 3, 2, \"abcdef\", w = w);
+    println!(\"{0} {1} {0} {name} {x}\", \"a\", \"b\");
+    println!(\"{:?} {{}}\", vec![Some(1), None]);
+    let s = format!(\"{}-{}\", 1, \"deux\");
+    eprintln!(\"erreur {}\", s);
+    print!(\"{}\", s);
+}
+");
+    assert_eq!(run.code, Some(0));
+    assert_eq!(run.stdout, "\
+[   ab] [ab   ] [  ab  ] [**ab***]
+[00042] [+7] [ff] [0xFF] [0b101] [10]
+[1     ] [  3] [ab]
+a b a ab 255
+[Some(1), None] {}
+1-deux");
+    assert_eq!(run.stderr, "erreur 1-deux\n");
+}
+
+#[test]
+fn format_errors_are_reported_at_compile_time() {
+    let errors = compile_error("
+fn main() {
+    println!(\"{} {}\", 1);
+}
+");
+    assert!(errors.contains("2 emplacement(s) dans la chaîne de format, mais 1 argument(s) fourni(s) - Erreur à la ligne 3"), "{}", errors);
+}
+
+#[test]
+#[ignore = "exige nasm et gcc"]
+fn clone_copies_and_drops_run_in_reverse_order() {
+    assert_output("
+#[derive(Debug, Clone)]
+struct Player { name: String, scores: Vec<i32> }
+struct Noisy { name: String }
+impl Drop for Noisy {
+    fn drop(&mut self) { println!(\"drop {}\", self.name); }
+}
+fn take(p: Player) -> i32 { p.scores[2] }
+fn main() {
+    let a = Player { name: String::from(\"ana\"), scores: vec![1, 2] };
+    let mut b = a.clone();
+    b.scores.push(3);
+    b.name.push('!');
+    println!(\"{:?} {:?}\", a, b);
+    println!(\"{}\", take(b));
+    let _first = Noisy { name: String::from(\"un\") };
+    let _second = Noisy { name: String::from(\"deux\") };
+    let moved = Noisy { name: String::from(\"trois\") };
+    let keep = moved;
+    println!(\"fin {}\", keep.name);
+}
+", "\
+Player { name: \"ana\", scores: [1, 2] } Player { name: \"ana!\", scores: [1, 2, 3] }
+3
+fin trois
+drop trois
+drop deux
+drop un
+");
+}
+
+#[test]
+#[ignore = "exige nasm et gcc"]
+fn break_and_continue_drop_loop_values() {
+    assert_output("
+struct Noisy { name: i32 }
+impl Drop for Noisy {
+    fn drop(&mut self) { println!(\"drop {}\", self.name); }
+}
+fn main() {
+    let mut i = 0;
+    while i < 5 {
+        let _n = Noisy { name: i };
+        i = i + 1;
+        if i == 2 { continue; }
+        if i == 3 { break; }
+        println!(\"tour {}\", i);
+    }
+    println!(\"fin\");
+}
+", "tour 1\ndrop 0\ndrop 1\ndrop 2\nfin\n");
+}
+
+#[test]
+fn borrow_checker_rejects_use_after_move() {
+    let errors = compile_error("
+fn main() {
+    let a = String::from(\"x\");
+    let b = a;
+    println!(\"{} {}\", a, b);
+}
+");
+    assert!(errors.contains("emprunt de la valeur déplacée `a` (déplacée à la ligne 4) - Erreur à la ligne 5"), "{}", errors);
+
+    let errors = compile_error("
+fn eat(v: Vec<i32>) {}
+fn main() {
+    let v = vec![1];
+    for i in 0..2 {
+        eat(v);
+    }
+}
+");
+    assert!(errors.contains("utilisation de la valeur déplacée `v` (déplacée à la ligne 6, lors d'une itération précédente de la boucle)"), "{}", errors);
+}
+
+#[test]
+fn values_must_match_annotations_parameters_and_return_types() {
+    let errors = compile_error("
+fn main() {
+    let v: Vec<String> = vec![1, 2];
+}
+");
+    assert!(errors.contains("types incompatibles : attendu `Vec<String>`, trouvé `Vec<i32>` - Erreur à la ligne 3"), "{}", errors);
+
+    let errors = compile_error("
+fn main() {
+    let s = String::from(\"a\");
+    let t: Vec<i32> = s;
+}
+");
+    assert!(errors.contains("types incompatibles : attendu `Vec<i32>`, trouvé `String` - Erreur à la ligne 4"), "{}", errors);
+
+    let errors = compile_error("
+fn main() {
+    let y: i32 = true;
+}
+");
+    assert!(errors.contains("types incompatibles : attendu `i32`, trouvé `bool` - Erreur à la ligne 3"), "{}", errors);
+
+    let errors = compile_error("
+fn f(s: String) {}
+fn main() {
+    f(5);
+}
+");
+    assert!(errors.contains("types incompatibles : attendu `String`, trouvé `i32` - Erreur à la ligne 4"), "{}", errors);
+
+    let errors = compile_error("
+fn f() -> String {
+    5
+}
+fn main() {
+    f();
+}
+");
+    assert!(errors.contains("types incompatibles : attendu `String`, trouvé `i32` - Erreur à la ligne 3"), "{}", errors);
+
+    let errors = compile_error("
+struct Named { name: String }
+fn main() {
+    let mut n = Named { name: String::new() };
+    n.name = 5;
+}
+");
+    assert!(errors.contains("types incompatibles : attendu `String`, trouvé `i32` - Erreur à la ligne 5"), "{}", errors);
+}
+
+#[test]
+fn polymorphic_recursion_growing_types_is_rejected() {
+    let errors = compile_error("
+fn rec<T>(x: T, n: i32) -> i32 {
+    if n == 0 { 0 } else { rec((x, x), n - 1) }
+}
+fn main() {
+    rec(1, 3);
+}
+");
+    assert!(errors.contains("limite de taille des types (65536 nœuds) dépassée lors de l'instanciation de `rec`"), "{}", errors);
+}
+
+#[test]
+#[ignore = "exige nasm et gcc"]
+fn contains_compares_string_elements_by_content() {
+    assert_output("
+fn main() {
+    let v = vec![String::from(\"ab\"), String::from(\"cd\")];
+    let probe = String::from(\"cd\");
+    println!(\"{} {}\", v.contains(&probe), v.contains(&String::from(\"c\")));
+    let s = vec![\"x\", \"yz\"];
+    let y = String::from(\"yz\");
+    println!(\"{} {}\", s.contains(&&y[..]), s.contains(&\"w\"));
+    let words: [&str; 2] = [\"un\", \"deux\"];
+    println!(\"{}\", words[..].contains(&\"deux\"));
+    let a = 3;
+    let b = 3;
+    let refs = vec![&a];
+    let boxes = vec![Box::new(4), Box::new(5)];
+    println!(\"{} {}\", refs.contains(&&b), boxes.contains(&Box::new(5)));
+}
+", "true false\ntrue false\ntrue\ntrue true\n");
+}
+
+#[test]
+#[ignore = "exige nasm et gcc"]
+fn match_selects_the_first_matching_arm() {
+    assert_output("
+enum Shape {
+    Circle(i32),
+    Rect(i32, i32),
+    Empty,
+}
+fn area(s: &Shape) -> i32 {
+    match s {
+        Shape::Circle(r) => 3 * r * r,
+        Shape::Rect(w, h) => w * h,
+        Shape::Empty => 0,
+    }
+}
+fn classify(n: i32) -> String {
+    match n {
+        0 => String::from(\"zéro\"),
+        1 | 2 | 3 => String::from(\"petit\"),
+        x if x < 0 => String::from(\"négatif\"),
+        _ => String::from(\"grand\"),
+    }
+}
+fn main() {
+    let shapes = vec![Shape::Circle(2), Shape::Rect(3, 4), Shape::Empty];
+    for s in &shapes {
+        print!(\"{} \", area(s));
+    }
+    println!(\"{} {} {} {}\", classify(-4), classify(0), classify(2), classify(9));
+    let n: Result<i32, String> = Err(String::from(\"vide\"));
+    match n {
+        Ok(v) => println!(\"ok {}\", v),
+        Err(e) => println!(\"erreur {}\", e),
+    }
+    let o = Some(String::from(\"texte\"));
+    let len = match &o {
+        Some(s) => s.len(),
+        None => 0,
+    };
+    let mut stack = vec![1, 2, 3];
+    let mut total = 0;
+    while let Some(top) = stack.pop() {
+        match top {
+            2 => continue,
+            _ => total = total + top,
+        }
+    }
+    println!(\"{} {}\", len, total);
+}
+", "12 12 0 négatif zéro petit grand\nerreur vide\n5 4\n");
+}
+
+#[test]
+fn non_exhaustive_match_is_rejected() {
+    let errors = compile_error("
+fn main() {
+    let t = (true, Some(3));
+    match t {
+        (true, Some(n)) => println!(\"{}\", n),
+        (false, _) => println!(\"faux\"),
+    }
+}
+");
+    assert!(errors.contains("`match` non exhaustif sur une valeur de type `(bool, Option<i32>)` : ajoutez un bras `_ => ...`"), "{}", errors);
+}
+
+#[test]
+fn array_literal_length_must_match_its_type() {
+    let errors = compile_error("
+fn main() {
+    let a: [i32; 3] = [1, 2];
+}
+");
+    assert!(errors.contains("types incompatibles : attendu `[i32; 3]`, trouvé un tableau de 2 élément(s) - Erreur à la ligne 3"), "{}", errors);
+    let errors = compile_error("
+fn first(a: [i32; 2]) -> i32 { a[0] }
+fn main() {
+    println!(\"{}\", first([0; 3]));
+}
+");
+    assert!(errors.contains("types incompatibles : attendu `[i32; 2]`, trouvé un tableau de 3 élément(s) - Erreur à la ligne 4"), "{}", errors);
+}
+
+#[test]
+#[ignore = "exige nasm et gcc"]
+fn compound_assignment_updates_the_place_once() {
+    assert_output("
+use std::collections::HashMap;
+struct Counter {
+    hits: i32,
+}
+fn main() {
+    let mut m: HashMap<String, i32> = HashMap::new();
+    for w in \"le chat et le chien et le chat\".split(' ') {
+        *m.entry(w.to_string()).or_insert(0) += 1;
+    }
+    println!(\"{} {} {}\", m.get(\"le\").unwrap(), m.get(\"chat\").unwrap(), m.get(\"chien\").unwrap());
+    let mut count = 0;
+    let mut bump = || count += 1;
+    bump();
+    bump();
+    let mut v = vec![1, 2, 3];
+    for i in 0..v.len() {
+        v[i] *= 10;
+    }
+    v[2] -= 5;
+    let mut c = Counter { hits: 7 };
+    c.hits %= 4;
+    c.hits /= 2;
+    let mut total = 0;
+    for n in &v {
+        total += n;
+    }
+    let mut s = String::from(\"ab\");
+    s += \"cd\";
+    let t = String::from(\"ef\");
+    s += &t;
+    println!(\"{} {:?} {} {} {}\", count, v, c.hits, total, s);
+}
+", "3 2 1\n2 [10, 20, 25] 1 55 abcdef\n");
+}
+
+#[test]
+fn compound_assignment_needs_a_mutable_integer_place() {
+    let errors = compile_error("
+fn main() {
+    let mut b = true;
+    b += 1;
+}
+");
+    assert!(errors.contains("l'opérateur `+=` ne s'applique pas au type `bool` - Erreur à la ligne 4"), "{}", errors);
+    let errors = compile_error("
+fn main() {
+    let n = 1;
+    n -= 1;
+}
+");
+    assert!(errors.contains("impossible d'affecter deux fois à la variable immuable `n`"), "{}", errors);
+}
+
+#[test]
+fn stored_values_have_the_element_type() {
+    let cases = [
+        ("let mut v: Vec<i32> = Vec::new();\n    v.push(String::from(\"a\"));", "types incompatibles"),
+        ("let mut v = vec![1];\n    v.push(true);", "types incompatibles : attendu un entier, trouvé `bool`"),
+        ("let mut m: HashMap<i32, String> = HashMap::new();\n    m.insert(1, 2);", "types incompatibles"),
+    ];
+    for (body, expected) in cases {
+        let errors = compile_error(&format!("use std::collections::HashMap;\nfn main() {{\n    {}\n}}\n", body));
+        assert!(errors.contains(expected), "{}", errors);
+    }
+}
+
+#[test]
+fn format_arguments_are_typed_before_code_generation() {
+    let cases = [
+        ("println!(\"{:x}\", \"s\");", "le trait `LowerHex` n'est pas implémenté pour `&str`"),
+        ("println!(\"{}\", nope);", "Variable non trouvée: nope"),
+        ("let t = (1, 2);\n    println!(\"{}\", t.5);", "aucun champ `5` dans le tuple `(i32, i32)`"),
+        ("println!(\"{}\", 1 + true);", "l'opérateur `+` ne s'applique pas aux types `i32` et `bool`"),
+    ];
+    for (body, expected) in cases {
+        let errors = compile_error(&format!("fn main() {{\n    {}\n}}\n", body));
+        assert!(errors.contains(expected), "{}", errors);
+    }
+}
+
+#[test]
+#[ignore = "exige nasm et gcc"]
+fn trait_objects_dispatch_through_vtables() {
+    assert_output("
+trait Shape {
+    fn area(&self) -> i32;
+    fn name(&self) -> String {
+        String::from(\"forme\")
+    }
+}
+struct Square {
+    side: i32,
+}
+struct Rect {
+    w: i32,
+    h: i32,
+}
+impl Shape for Square {
+    fn area(&self) -> i32 {
+        self.side * self.side
+    }
+    fn name(&self) -> String {
+        String::from(\"carré\")
+    }
+}
+impl Shape for Rect {
+    fn area(&self) -> i32 {
+        self.w * self.h
+    }
+}
+fn describe(s: &dyn Shape) -> String {
+    format!(\"{} {}\", s.name(), s.area())
+}
+fn main() {
+    let mut shapes: Vec<Box<dyn Shape>> = Vec::new();
+    shapes.push(Box::new(Square { side: 3 }));
+    shapes.push(Box::new(Rect { w: 2, h: 5 }));
+    let mut total = 0;
+    for s in &shapes {
+        println!(\"{}\", describe(&**s));
+        total += s.area();
+    }
+    let r = Rect { w: 1, h: 7 };
+    let d: &dyn Shape = &r;
+    println!(\"{} {}\", total, d.area());
+}
+", "carré 9\nforme 10\n19 7\n");
+}
+
+#[test]
+#[ignore = "exige nasm et gcc"]
+fn generic_functions_and_structs_are_instantiated_per_type() {
+    assert_output("
+use std::fmt::Display;
+
+#[derive(Debug)]
+struct Pair<T> {
+    first: T,
+    second: T,
+}
+
+impl<T: PartialOrd + Copy> Pair<T> {
+    fn largest(&self) -> T {
+        if self.first > self.second {
+            self.first
+        } else {
+            self.second
+        }
+    }
+}
+
+fn show<T: Display>(label: &str, value: T) -> String {
+    format!(\"{}={}\", label, value)
+}
+
+fn swap<A, B>(pair: (A, B)) -> (B, A) {
+    (pair.1, pair.0)
+}
+
+fn main() {
+    let ints = Pair { first: 3, second: 9 };
+    let chars = Pair { first: 'z', second: 'a' };
+    println!(\"{} {}\", ints.largest(), chars.largest());
+    println!(\"{} {}\", show(\"n\", 42), show(\"s\", String::from(\"texte\")));
+    let (b, a) = swap((1, true));
+    println!(\"{} {}\", b, a);
+    let pairs: Vec<Pair<i64>> = vec![Pair { first: 1, second: 2 }];
+    println!(\"{} {:?} {:?}\", pairs[0].largest(), pairs[0], chars);
+}
+", "9 z\nn=42 s=texte\ntrue 1\n2 Pair { first: 1, second: 2 } Pair { first: 'z', second: 'a' }\n");
+}
+
+#[test]
+#[ignore = "exige nasm et gcc"]
+fn tuples_are_returned_indexed_and_destructured() {
+    assert_output("
+fn divmod(a: i32, b: i32) -> (i32, i32) {
+    (a / b, a % b)
+}
+
+fn label(n: i64) -> (String, i64, bool) {
+    (format!(\"n{}\", n), n * 2, n > 10)
+}
+
+fn main() {
+    let (q, r) = divmod(17, 5);
+    println!(\"{} {}\", q, r);
+    let t = label(21);
+    println!(\"{} {} {}\", t.0, t.1, t.2);
+    let ((x, _), mut y) = ((1, 'a'), 5);
+    y += x;
+    println!(\"{} {:?}\", y, label(3));
+    let mut p = (0, 0);
+    p.1 = 4;
+    p.0 += p.1;
+    let nested = (p, (true, \"fin\"));
+    println!(\"{} {} {}\", (nested.0).0, (nested.1).1, nested.1.0);
+}
+", "3 2\nn21 42 true\n6 (\"n3\", 6, false)\n4 fin true\n");
+}
+
+#[test]
+#[ignore = "exige nasm et gcc"]
+fn array_indexing_is_bounds_checked() {
+    assert_panics("
+fn sum(a: [i32; 4]) -> i32 {
+    let mut total = 0;
+    for x in a {
+        total += x;
+    }
+    total
+}
+
+fn main() {
+    let mut grid = [[0; 3]; 2];
+    grid[1][2] = 7;
+    let a = [1, 2, 3, 4];
+    println!(\"{} {} {:?}\", sum(a), a.len(), grid);
+    let i = a.len();
+    println!(\"{}\", a[i]);
+}
+", "10 4 [[0, 0, 0], [0, 0, 7]]\n", "panicked at prog.rs:16:\nindex out of bounds: the len is 4 but the index is 4");
+}
+
+#[test]
+#[ignore = "exige nasm et gcc"]
+fn vectors_grow_past_their_capacity() {
+    assert_panics("
+fn evens(n: i64) -> Vec<i64> {
+    let mut v = Vec::new();
+    let mut i: i64 = 0;
+    while i < n {
+        v.push(i * 2);
+        i += 1;
+    }
+    v
+}
+
+fn main() {
+    let mut a = Vec::new();
+    let mut b = evens(5);
+    for i in 0..100 {
+        a.push(i);
+        if i % 25 == 0 {
+            b.push(-i);
+        }
+    }
+    println!(\"{} {} {}\", a.len(), a[99], b.len());
+    println!(\"{:?}\", b);
+    let last = a.pop();
+    a.truncate(3);
+    a.insert(0, 42);
+    let removed = a.remove(1);
+    println!(\"{:?} {:?} {}\", last, a, removed);
+    println!(\"{}\", a.remove(7));
+}
+", "100 99 9\n[0, 2, 4, 6, 8, 0, -25, -50, -75]\nSome(99) [42, 1, 2] 0\n", "panicked at prog.rs:28:\nremoval index (is 7) should be < len (is 3)");
+}
+
+#[test]
+#[ignore = "exige nasm et gcc"]
+fn vectors_hold_any_element_type() {
+    assert_output("
+#[derive(Debug, Clone)]
+struct Item {
+    name: String,
+    qty: i32,
+}
+
+fn main() {
+    let mut grid: Vec<Vec<i32>> = vec![vec![0; 3]; 2];
+    grid[1][2] = 5;
+    grid.push(vec![7]);
+    grid[2].push(8);
+    println!(\"{:?} {}\", grid, grid[2].len());
+
+    let mut items = Vec::new();
+    items.push(Item { name: String::from(\"pomme\"), qty: 3 });
+    items.push(Item { name: String::from(\"poire\"), qty: 1 });
+    let copy = items.clone();
+    items[0].qty += 10;
+    items[1].name.push_str(\"s\");
+    println!(\"{:?}\", items);
+    println!(\"{} {}\", copy[0].qty, copy[1].name);
+
+    let mut flags: Vec<(bool, char)> = Vec::new();
+    flags.push((true, 'x'));
+    flags.push((false, 'y'));
+    let words = vec![vec![String::from(\"a\"), String::from(\"b\")], Vec::new()];
+    println!(\"{:?} {:?} {}\", flags, words, words[1].is_empty());
+}
+", "[[0, 0, 0], [0, 0, 5], [7, 8]] 2\n[Item { name: \"pomme\", qty: 13 }, Item { name: \"poires\", qty: 1 }]\n3 poire\n[(true, 'x'), (false, 'y')] [[\"a\", \"b\"], []] true\n");
+}
+
+#[test]
+#[ignore = "exige nasm et gcc"]
+fn slices_borrow_ranges_of_vectors_and_arrays() {
+    assert_panics("
+fn total(s: &[i32]) -> i32 {
+    let mut sum = 0;
+    for x in s {
+        sum += x;
+    }
+    sum
+}
+
+fn double(s: &mut [i32]) {
+    for i in 0..s.len() {
+        s[i] *= 2;
+    }
+}
+
+fn main() {
+    let mut v = vec![1, 2, 3, 4, 5];
+    let a = [10, 20, 30];
+    println!(\"{} {} {}\", total(&v[1..3]), total(&a), total(&v[..]));
+    double(&mut v[2..]);
+    println!(\"{:?} {:?} {:?}\", v, &a[..=1], &v[3..].get(0));
+    let tail = &v[4..];
+    println!(\"{} {}\", tail.len(), tail.is_empty());
+    let n = v.len();
+    println!(\"{:?}\", &v[2..n + 1]);
+}
+", "5 60 15\n[1, 2, 6, 8, 10] [10, 20] Some(8)\n1 false\n", "panicked at prog.rs:25:\nrange end index 6 out of range for slice of length 5");
+}
+
+#[test]
+#[ignore = "exige nasm et gcc"]
+fn strings_grow_concatenate_and_compare() {
+    assert_panics("
+fn greet(name: &str) -> String {
+    let mut s = String::from(\"bonjour \");
+    s.push_str(name);
+    s.push('!');
+    s
+}
+
+fn main() {
+    let a = greet(\"léa\");
+    let b = String::new() + \"abc\" + &a;
+    println!(\"{} {} {}\", a, a.len(), b.len());
+    println!(\"{} {} {}\", a == \"bonjour léa!\", String::from(\"abc\") < String::from(\"abd\"), b.is_empty());
+    let mut c = a.clone();
+    c.push_str(\" encore\");
+    println!(\"{} / {}\", a, c);
+    let s = String::from(\"héllo\");
+    println!(\"{}\", &s[0..2]);
+}
+", "bonjour léa! 13 16\ntrue true false\nbonjour léa! / bonjour léa! encore\n", "panicked at prog.rs:18:\nbyte index 2 is not a char boundary");
+}
+
+#[test]
+#[ignore = "exige nasm et gcc"]
+fn string_slices_are_values() {
+    assert_output("
+struct Entry<'a> {
+    key: &'a str,
+    rank: i32,
+}
+
+fn first_word(s: &str) -> &str {
+    match s.find(' ') {
+        Some(i) => &s[..i],
+        None => s,
+    }
+}
+
+fn pick<'a>(a: &'a str, b: &'a str, first: bool) -> &'a str {
+    if first {
+        a
+    } else {
+        b
+    }
+}
+
+fn main() {
+    let owned = String::from(\"salut le monde\");
+    let words: Vec<&str> = vec![\"un\", \"deux\", first_word(&owned)];
+    let e = Entry { key: pick(\"gauche\", \"droite\", false), rank: 2 };
+    println!(\"{:?} {} {}\", words, e.key, e.rank);
+    let s: &str = \"fixe\";
+    let t = s;
+    println!(\"{} {} {}\", s, t.len(), first_word(\"seul\"));
+    println!(\"{}\", \"tab\\tet \\\"guillemets\\\"\\\\\");
+}
+", "[\"un\", \"deux\", \"salut\"] droite 2\nfixe 4 seul\ntab\tet \"guillemets\"\\\n");
+}
+
+#[test]
+#[ignore = "exige nasm et gcc"]
+fn string_methods_split_trim_and_parse() {
+    assert_panics("
+fn main() {
+    let line = \"  12, 7 ,x, 30  \";
+    let mut total = 0;
+    let mut bad = Vec::new();
+    for part in line.trim().split(\",\") {
+        match part.trim().parse::<i32>() {
+            Ok(n) => total += n,
+            Err(e) => bad.push(format!(\"{}: {}\", part.trim(), e)),
+        }
+    }
+    println!(\"{} {:?}\", total, bad);
+    let word = \"Élan vital\";
+    let letters = word.chars().filter(|c| c.is_alphabetic()).count();
+    println!(\"{} {} {}\", letters, word.bytes().count(), word.to_uppercase());
+    println!(\"{} {} {:?}\", word.contains(\"vit\"), word.starts_with('É'), word.find(\"al\"));
+    let n: i32 = \"x1\".parse().unwrap();
+    println!(\"{}\", n);
+}
+", "49 [\"x: invalid digit found in string\"]\n9 11 ÉLAN VITAL\ntrue true Some(9)\n", "panicked at prog.rs:17:\ncalled `Result::unwrap()` on an `Err` value: ParseIntError { kind: InvalidDigit }");
+}
+
+#[test]
+#[ignore = "exige nasm et gcc"]
+fn user_display_impls_format_values() {
+    assert_output("
+use std::fmt;
+
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+enum Color {
+    Red,
+    Custom(u8, u8, u8),
+}
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, \"({}, {})\", self.x, self.y)
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Color::Red => write!(f, \"rouge\"),
+            Color::Custom(r, g, b) => write!(f, \"#{:02x}{:02x}{:02x}\", r, g, b),
+        }
+    }
+}
+
+fn main() {
+    let p = Point { x: 3, y: -4 };
+    let text = format!(\"{}\", p);
+    println!(\"{} [{:>10}] [{:<8}] {}\", p, p, Color::Red, text.len());
+    println!(\"{} {}\", Color::Custom(255, 8, 0), &p);
+    let b = Box::new(Point { x: 0, y: 1 });
+    println!(\"{}\", b);
+}
+", "(3, -4) [(3, -4)] [rouge] 7\n#ff0800 (3, -4)\n(0, 1)\n");
+}
+
+#[test]
+#[ignore = "exige nasm et gcc"]
+fn question_mark_propagates_none_and_err_out_of_main() {
+    let run = run("
+#[derive(Debug)]
+enum ConfigError {
+    Missing(String),
+    Invalid(i32),
+}
+
+fn lookup(key: &str) -> Option<i32> {
+    if key == \"port\" {
+        Some(8080)
+    } else if key == \"debug\" {
+        Some(-1)
+    } else {
+        None
+    }
+}
+
+fn first_even(v: &Vec<i32>) -> Option<i32> {
+    let first = v.get(0)?;
+    if first % 2 == 0 {
+        Some(*first)
+    } else {
+        None
+    }
+}
+
+fn setting(key: &str) -> Result<i32, ConfigError> {
+    let value = match lookup(key) {
+        Some(v) => v,
+        None => return Err(ConfigError::Missing(key.to_string())),
+    };
+    if value < 0 {
+        return Err(ConfigError::Invalid(value));
+    }
+    Ok(value)
+}
+
+fn total(keys: &[&str]) -> Result<i32, ConfigError> {
+    let mut sum = 0;
+    for key in keys {
+        sum += setting(key)?;
+    }
+    Ok(sum)
+}
+
+fn main() -> Result<(), ConfigError> {
+    println!(\"{:?} {:?} {:?}\", first_even(&vec![4, 1]), first_even(&vec![3]), first_even(&Vec::new()));
+    println!(\"{:?} {:?}\", total(&[\"port\", \"port\"]), total(&[\"port\", \"debug\"]));
+    println!(\"{}\", setting(\"port\").unwrap_or(0) + lookup(\"x\").unwrap_or(1));
+    let n = total(&[\"port\", \"nom\"])?;
+    println!(\"jamais {}\", n);
+    Ok(())
+}
+");
+    assert_eq!(run.code, Some(1), "sortie d'erreur :\n{}", run.stderr);
+    assert!(run.stderr.contains("Error: Missing(\"nom\")") && !run.stderr.contains("fuite de mémoire"), "{}", run.stderr);
+    assert_eq!(run.stdout, "Some(4) None None\nOk(16160) Err(Invalid(-1))\n8081\n");
+}
+
+#[test]
+#[ignore = "exige nasm et gcc"]
+fn closures_capture_and_function_pointers_call() {
+    assert_output("
+fn apply(f: fn(i32) -> i32, x: i32) -> i32 {
+    f(x)
+}
+
+fn twice<F: Fn(i32) -> i32>(f: F, x: i32) -> i32 {
+    f(f(x))
+}
+
+fn make_adder(n: i32) -> impl Fn(i32) -> i32 {
+    move |x| x + n
+}
+
+fn square(x: i32) -> i32 {
+    x * x
+}
+
+fn main() {
+    let offset = 10;
+    let add = |x: i32| x + offset;
+    println!(\"{} {} {}\", apply(square, 3), twice(add, 1), twice(square, 3));
+    let mut log = Vec::new();
+    let mut record = |s: &str| log.push(s.to_string());
+    record(\"a\");
+    record(\"b\");
+    println!(\"{:?}\", log);
+    let name = String::from(\"léa\");
+    let greet = move || format!(\"salut {}\", name);
+    println!(\"{} {}\", greet(), make_adder(5)(2));
+    let dec: fn(i32) -> i32 = |x| x - 1;
+    let ops = vec![square, dec];
+    let mut results = Vec::new();
+    for op in &ops {
+        results.push(op(6));
+    }
+    println!(\"{:?}\", results);
+}
+", "9 21 81\n[\"a\", \"b\"]\nsalut léa 7\n[36, 5]\n");
+}
+
+#[test]
+#[ignore = "exige nasm et gcc"]
+fn iterator_chains_are_fused_into_loops() {
+    assert_output("
+fn main() {
+    let v = vec![3, 8, 1, 6, 5];
+    let big: Vec<i32> = v.iter().filter(|x| **x > 2).map(|x| x * 10).collect();
+    let sum: i32 = v.iter().sum();
+    println!(\"{:?} {} {}\", big, sum, v.iter().any(|x| *x == 6));
+    for (i, x) in v.iter().enumerate().skip(1).take(2) {
+        print!(\"{}:{} \", i, x);
+    }
+    println!();
+    let words = vec![\"a\", \"bb\", \"ccc\"];
+    let total = words.iter().map(|w| w.len()).fold(0, |acc, n| acc + n);
+    for (x, w) in v.iter().zip(words.iter()) {
+        print!(\"{}{} \", w, x);
+    }
+    println!(\"{}\", total);
+    let count = v.iter().filter(|x| **x % 2 == 1).count();
+    let word: String = \"abc\".chars().rev().collect();
+    println!(\"{} {:?} {:?} {}\", count, v.iter().max(), v.iter().map(|&x| x - 4).min(), word);
+    let mut w = v.clone();
+    for x in w.iter_mut() {
+        *x *= 2;
+    }
+    println!(\"{:?} {}\", w, w.iter().all(|x| x % 2 == 0));
+}
+", "[30, 80, 60, 50] 23 true\n1:8 2:1 \na3 bb8 ccc1 6\n3 Some(8) Some(-3) cba\n[6, 16, 2, 12, 10] true\n");
+}
+
+#[test]
+#[ignore = "exige nasm et gcc"]
+fn ranges_are_inclusive_reversible_and_stepped() {
+    assert_panics("
+fn main() {
+    let mut s = 0;
+    for i in 1..=10 {
+        s += i;
+    }
+    print!(\"{} \", s);
+    for i in (0..5).rev() {
+        print!(\"{}\", i);
+    }
+    print!(\" \");
+    for i in (0..=20).step_by(5) {
+        print!(\"{},\", i);
+    }
+    println!();
+    let r = 2..6;
+    let inclusive = 1..=4;
+    let hits = (0..8).filter(|x| inclusive.contains(x)).count();
+    let total: i32 = inclusive.sum();
+    println!(\"{} {} {} {} {} {}\", r.is_empty(), r.contains(&6), hits, total, (10..0).rev().count(), (1..=3).rev().map(|x| x * x).sum::<i32>());
+    for i in r {
+        print!(\"{}\", i);
+    }
+    println!();
+    let v = vec![1, 2, 3, 4, 5, 6];
+    let picked: Vec<i32> = v.iter().rev().step_by(2).map(|&x| x).collect();
+    println!(\"{:?} {:?}\", picked, &v[1..=2]);
+    let step = picked.len() - 3;
+    for i in (0..3).step_by(step) {
+        println!(\"{}\", i);
+    }
+}
+", "55 43210 0,5,10,15,20,\nfalse false 4 10 0 14\n2345\n[6, 4, 2] [2, 3]\n", "panicked at prog.rs:29:\nassertion failed: step != 0");
+}
+
+#[test]
+#[ignore = "exige nasm et gcc"]
+fn smart_pointers_share_and_borrow_at_runtime() {
+    assert_panics("
+use std::rc::Rc;
+use std::cell::RefCell;
+
+#[derive(Debug)]
+enum List {
+    Cons(i32, Box<List>),
+    Nil,
+}
+
+fn sum(list: &List) -> i32 {
+    match list {
+        List::Cons(v, rest) => v + sum(rest),
+        List::Nil => 0,
+    }
+}
+
+struct Account {
+    balance: RefCell<i32>,
+}
+
+fn main() {
+    let list = List::Cons(1, Box::new(List::Cons(2, Box::new(List::Cons(3, Box::new(List::Nil))))));
+    println!(\"{} {:?}\", sum(&list), list);
+    let shared = Rc::new(Account { balance: RefCell::new(100) });
+    let other = Rc::clone(&shared);
+    *other.balance.borrow_mut() -= 30;
+    println!(\"{} {}\", shared.balance.borrow(), Rc::strong_count(&shared));
+    {
+        let third = shared.clone();
+        println!(\"{}\", Rc::strong_count(&third));
+    }
+    let log = Rc::new(RefCell::new(Vec::new()));
+    log.borrow_mut().push(String::from(\"a\"));
+    let view = Rc::clone(&log);
+    view.borrow_mut().push(String::from(\"b\"));
+    println!(\"{:?} {}\", log.borrow(), Rc::strong_count(&log));
+    let first = log.borrow();
+    let second = view.borrow_mut();
+    println!(\"{} {}\", first.len(), second.len());
+}
+", "6 Cons(1, Cons(2, Cons(3, Nil)))\n70 2\n3\n[\"a\", \"b\"] 2\n", "panicked at prog.rs:39:\nRefCell already borrowed");
+}
+
+#[test]
+#[ignore = "exige nasm et gcc"]
+fn hash_maps_and_sets_survive_removals_and_collisions() {
+    assert_output("
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+fn main() {
+    let mut m: HashMap<i32, String> = HashMap::new();
+    for i in 0..40 {
+        m.insert(i * 16, format!(\"v{}\", i));
+    }
+    println!(\"{}\", m.len());
+    for i in 0..40 {
+        if i % 3 == 0 {
+            m.remove(&(i * 16));
+        }
+    }
+    let mut found = 0;
+    for i in 0..40 {
+        match m.get(&(i * 16)) {
+            Some(v) => {
+                if *v != format!(\"v{}\", i) {
+                    println!(\"mauvaise valeur {}\", i);
+                }
+                found += 1;
+            }
+            None => {
+                if i % 3 != 0 {
+                    println!(\"clé perdue {}\", i);
+                }
+            }
+        }
+    }
+    println!(\"{} {}\", found, m.len());
+    println!(\"{} {}\", m.contains_key(&16), m.contains_key(&48));
+    m.insert(16, String::from(\"neuf\"));
+    println!(\"{:?}\", m.get(&16));
+
+    let mut compte: HashMap<String, i32> = HashMap::new();
+    for mot in \"a b a c b a\".split(' ') {
+        *compte.entry(mot.to_string()).or_insert(0) += 1;
+    }
+    println!(\"{:?} {:?} {:?}\", compte.get(\"a\"), compte.get(\"b\"), compte.get(\"z\"));
+
+    let mut vus = HashSet::new();
+    let mut doublons = 0;
+    for x in vec![3, 1, 3, 2, 1] {
+        if vus.insert(x) == false {
+            doublons += 1;
+        }
+    }
+    vus.remove(&1);
+    println!(\"{} {} {} {}\", vus.len(), doublons, vus.contains(&3), vus.contains(&1));
+}
+", "40\n26 26\ntrue false\nSome(\"neuf\")\nSome(3) Some(2) None\n2 2 true false\n");
+}